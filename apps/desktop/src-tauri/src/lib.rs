@@ -13,7 +13,10 @@ use tokio::sync::RwLock;
 
 // PTY module for interactive terminal support
 mod pty;
-pub use pty::{pty_create, pty_kill, pty_list, pty_read, pty_resize, pty_write, PtyState};
+pub use pty::{
+    pty_create, pty_get_scrollback, pty_healthcheck, pty_kill, pty_list, pty_read, pty_reattach,
+    pty_rename, pty_resize, pty_signal, pty_subscribe, pty_wait, pty_write, PtyState,
+};
 
 // System tray module
 mod tray;
@@ -284,6 +287,30 @@ async fn install_acp_agent(
                 )
                 .await?;
         }
+        DistributionType::Git => {
+            // For git, clone (or update) the repo at the pinned ref
+            let git = agent
+                .distribution
+                .git
+                .as_ref()
+                .ok_or_else(|| "Agent has no git distribution".to_string())?;
+
+            let checkout_dir = state
+                .binary_manager
+                .install_git(&agent_id, &version, git)
+                .await?;
+
+            state
+                .installation_state
+                .mark_installed(
+                    &agent_id,
+                    &version,
+                    DistributionType::Git,
+                    Some(checkout_dir.to_string_lossy().to_string()),
+                    None,
+                )
+                .await?;
+        }
     }
 
     state
@@ -296,10 +323,13 @@ async fn install_acp_agent(
 /// Uninstall an ACP agent.
 #[tauri::command]
 async fn uninstall_acp_agent(state: State<'_, AcpState>, agent_id: String) -> Result<(), String> {
-    // Get installed info to check if it's a binary
+    // Get installed info to check if it has files on disk
     if let Some(info) = state.installation_state.get_installed_info(&agent_id).await {
-        if info.dist_type == DistributionType::Binary {
-            // Remove binary files
+        if matches!(
+            info.dist_type,
+            DistributionType::Binary | DistributionType::Git
+        ) {
+            // Remove binary files or the git checkout
             state.binary_manager.uninstall(&agent_id).await?;
         }
     }
@@ -828,6 +858,8 @@ fn start_rust_server(
     tauri::async_runtime::block_on(rpc_state.set(app_state.clone()));
     println!("[rust-server] AppState shared with JSON-RPC handler");
 
+    server::register_health_probe("pty", pty::pty_health_probe_status);
+
     let addr = tauri::async_runtime::block_on(server::start_server_with_state(config, app_state))
         .map_err(|e| format!("Failed to start server: {e}"))?;
     println!("[rust-server] Server started on {addr}");
@@ -866,9 +898,16 @@ pub fn run() {
             pty_create,
             pty_write,
             pty_read,
+            pty_reattach,
+            pty_get_scrollback,
+            pty_subscribe,
+            pty_wait,
+            pty_rename,
             pty_resize,
+            pty_signal,
             pty_kill,
             pty_list,
+            pty_healthcheck,
             // Tray command so the frontend can push webhook configs
             update_tray_github_repos,
         ])
@@ -5,21 +5,190 @@
 //!
 //! This module enables xterm.js in the frontend to display real interactive
 //! terminals with proper ANSI escape code handling, cursor movement, etc.
+//!
+//! Each session has one background reader thread, so no command ever blocks
+//! on PTY output while holding the manager lock. Output is either polled
+//! with `pty_read`, which drains what the reader has buffered, or pushed to
+//! the UI once `pty_subscribe` (Tauri events) or `pty_reattach` (an IPC
+//! channel) is called. The reader keeps a bounded scrollback, so a reloaded
+//! UI can reattach to a running shell and replay what it missed, and stops
+//! when the session is killed. `pty_get_scrollback` returns the same history
+//! without attaching; its size is set per session by `pty_create`'s
+//! `scrollback_bytes`.
+//!
+//! A waiter thread per session holds the child process. When it exits the
+//! session is removed and `pty://{session_id}/exit` is emitted with its exit
+//! status; `pty_wait` returns the same status to a caller.
+//!
+//! Sessions can carry a label ("build", "server") given to `pty_create` or
+//! changed with `pty_rename`; `pty_list` falls back to the command when a
+//! session has none.
+//!
+//! `pty_signal` interrupts or stops the command running in a terminal
+//! without tearing the terminal down the way `pty_kill` does.
 
-use portable_pty::{native_pty_system, CommandBuilder, PtyPair, PtySize};
+use portable_pty::{native_pty_system, Child, ChildKiller, CommandBuilder, PtyPair, PtySize};
 use std::collections::HashMap;
-use std::io::{BufRead, BufReader, Read, Write};
-use std::sync::Arc;
+use std::io::{Read, Write};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::time::Duration;
 use tauri::async_runtime::Mutex as AsyncMutex;
-use tauri::State;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, State};
+
+/// Default size of a session's scrollback, and the upper bound on output
+/// buffered for `pty_read`.
+const SCROLLBACK_LIMIT_BYTES: usize = 256 * 1024;
+
+/// Largest scrollback `pty_create` accepts.
+const MAX_SCROLLBACK_BYTES: usize = 16 * 1024 * 1024;
+
+/// Receives streamed output. Returns `false` once the receiver is gone.
+pub type PtyOutputSink = Box<dyn FnMut(String) -> bool + Send>;
+
+/// Output state shared between a session and its streaming reader thread.
+#[derive(Default)]
+struct PtyOutput {
+    scrollback: String,
+    /// Size cap of `scrollback`, in bytes.
+    scrollback_limit: usize,
+    /// Output not yet taken by `pty_read`; only filled while no sink is set.
+    pending: String,
+    sink: Option<PtyOutputSink>,
+    /// The reader thread is running for the session.
+    reader_alive: bool,
+    /// The session was killed; the reader exits at its next chunk.
+    closed: bool,
+}
+
+impl PtyOutput {
+    fn push_chunk(&mut self, chunk: String) {
+        push_bounded(&mut self.scrollback, &chunk, self.scrollback_limit);
+        // A closed receiver only detaches the sink; keep reading so the
+        // scrollback stays current for the next reattach.
+        if let Some(sink) = self.sink.as_mut() {
+            if !sink(chunk) {
+                self.sink = None;
+            }
+            return;
+        }
+        push_bounded(&mut self.pending, &chunk, SCROLLBACK_LIMIT_BYTES);
+    }
+}
+
+/// Append `chunk`, dropping the oldest output past `limit` bytes.
+fn push_bounded(buffer: &mut String, chunk: &str, limit: usize) {
+    buffer.push_str(chunk);
+    truncate_front(buffer, limit);
+}
+
+/// Drop the oldest output of `buffer` past `limit` bytes.
+fn truncate_front(buffer: &mut String, limit: usize) {
+    if buffer.len() > limit {
+        let mut cut = buffer.len() - limit;
+        while !buffer.is_char_boundary(cut) {
+            cut += 1;
+        }
+        buffer.drain(..cut);
+    }
+}
+
+fn lock_output(output: &Mutex<PtyOutput>) -> std::sync::MutexGuard<'_, PtyOutput> {
+    output.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Clears `reader_alive` when the reader thread exits, including by panic.
+struct ReaderAliveGuard(Arc<Mutex<PtyOutput>>);
+
+impl Drop for ReaderAliveGuard {
+    fn drop(&mut self) {
+        lock_output(&self.0).reader_alive = false;
+    }
+}
+
+/// How a PTY session's child process ended.
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyExitStatus {
+    pub session_id: String,
+    pub exit_code: u32,
+    pub success: bool,
+}
+
+/// Exit status of a session's child, set once by its waiter thread.
+#[derive(Default)]
+pub struct PtyExit {
+    status: Mutex<Option<PtyExitStatus>>,
+    exited: Condvar,
+}
+
+impl PtyExit {
+    fn set(&self, status: PtyExitStatus) {
+        *self.status.lock().unwrap_or_else(|e| e.into_inner()) = Some(status);
+        self.exited.notify_all();
+    }
+
+    /// The exit status, once the child has exited.
+    pub fn status(&self) -> Option<PtyExitStatus> {
+        self.status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Block until the child exits.
+    pub fn wait(&self) -> PtyExitStatus {
+        let mut status = self.status.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if let Some(status) = status.as_ref() {
+                return status.clone();
+            }
+            status = self.exited.wait(status).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+/// Signals `pty_signal` can deliver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtySignal {
+    Interrupt,
+    Terminate,
+    Kill,
+}
+
+impl PtySignal {
+    /// Parse `SIGINT`/`SIGTERM`/`SIGKILL`, case-insensitively and with or
+    /// without the `SIG` prefix.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let upper = name.trim().to_ascii_uppercase();
+        match upper.strip_prefix("SIG").unwrap_or(&upper) {
+            "INT" => Ok(Self::Interrupt),
+            "TERM" => Ok(Self::Terminate),
+            "KILL" => Ok(Self::Kill),
+            _ => Err(format!(
+                "Unsupported signal: {name} (expected SIGINT, SIGTERM or SIGKILL)"
+            )),
+        }
+    }
+}
+
+/// Longest label `pty_rename` accepts.
+const MAX_LABEL_CHARS: usize = 64;
 
 /// A single PTY session with its reader/writer handles.
 pub struct PtySession {
     pub pty_pair: PtyPair,
     pub writer: Box<dyn Write + Send>,
-    pub reader: BufReader<Box<dyn Read + Send>>,
     pub cwd: String,
     pub command: String,
+    /// User-facing name; `None` shows the command instead.
+    pub label: Option<String>,
+    output: Arc<Mutex<PtyOutput>>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+    exit: Arc<PtyExit>,
+    /// Process id of the session's command (usually the shell).
+    #[cfg_attr(not(unix), allow(dead_code))]
+    pid: Option<u32>,
 }
 
 /// Manages multiple PTY sessions.
@@ -105,7 +274,7 @@ impl PtyManager {
         }
 
         // Spawn the command in the PTY
-        let _child = pty_pair
+        let child = pty_pair
             .slave
             .spawn_command(cmd)
             .map_err(|e| format!("Failed to spawn command in PTY: {e}"))?;
@@ -113,12 +282,32 @@ impl PtyManager {
         let session_id = format!("pty-{}", self.next_id);
         self.next_id += 1;
 
+        let output = Arc::new(Mutex::new(PtyOutput {
+            scrollback_limit: SCROLLBACK_LIMIT_BYTES,
+            reader_alive: true,
+            ..PtyOutput::default()
+        }));
+        let mut killer = child.clone_killer();
+        let pid = child.process_id();
+        let exit = Arc::new(PtyExit::default());
+        if let Err(error) = spawn_output_reader(&session_id, reader, output.clone())
+            .and_then(|()| spawn_exit_waiter(&session_id, child, exit.clone()))
+        {
+            lock_output(&output).closed = true;
+            let _ = killer.kill();
+            return Err(error);
+        }
+
         let session = PtySession {
             pty_pair,
             writer,
-            reader: BufReader::new(reader),
             cwd: working_dir,
             command: cmd_str.to_string(),
+            label: None,
+            output,
+            killer,
+            exit,
+            pid,
         };
 
         self.sessions.insert(session_id.clone(), session);
@@ -143,27 +332,85 @@ impl PtyManager {
         Ok(())
     }
 
-    /// Read available data from a PTY session.
+    /// Take the output buffered since the last read. Never blocks.
     pub fn read(&mut self, session_id: &str) -> Result<Option<String>, String> {
         let session = self
             .sessions
-            .get_mut(session_id)
+            .get(session_id)
             .ok_or_else(|| format!("PTY session not found: {session_id}"))?;
+        let mut output = lock_output(&session.output);
+        if output.sink.is_some() {
+            return Err(format!(
+                "PTY session {session_id} is streaming its output; use pty_reattach"
+            ));
+        }
+        if output.pending.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(std::mem::take(&mut output.pending)))
+    }
 
-        let data = session
-            .reader
-            .fill_buf()
-            .map_err(|e| format!("Failed to read from PTY: {e}"))?;
+    /// The session's recent output, oldest first, up to its scrollback size.
+    /// Unlike `read` this leaves every buffer untouched.
+    pub fn scrollback(&self, session_id: &str) -> Result<String, String> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| format!("PTY session not found: {session_id}"))?;
+        let scrollback = lock_output(&session.output).scrollback.clone();
+        Ok(scrollback)
+    }
 
-        if data.is_empty() {
-            return Ok(None);
+    /// Change how many bytes of output a session keeps, dropping the oldest
+    /// output already held past the new size.
+    pub fn set_scrollback_limit(&mut self, session_id: &str, bytes: usize) -> Result<(), String> {
+        if bytes > MAX_SCROLLBACK_BYTES {
+            return Err(format!(
+                "Scrollback of {bytes} bytes exceeds the {MAX_SCROLLBACK_BYTES}-byte limit"
+            ));
         }
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| format!("PTY session not found: {session_id}"))?;
+        let mut output = lock_output(&session.output);
+        output.scrollback_limit = bytes;
+        truncate_front(&mut output.scrollback, bytes);
+        Ok(())
+    }
 
-        let text = String::from_utf8_lossy(data).to_string();
-        let len = data.len();
-        session.reader.consume(len);
+    /// Stream a session's output to `sink`, replaying the scrollback first.
+    ///
+    /// The previous sink, if any, is dropped, as is output buffered for
+    /// `pty_read` (the replay covers it). A reader thread is started only
+    /// when the session's has died, so there is at most one reader per
+    /// session and calling this repeatedly is safe.
+    pub fn reattach(&mut self, session_id: &str, mut sink: PtyOutputSink) -> Result<(), String> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| format!("PTY session not found: {session_id}"))?;
 
-        Ok(Some(text))
+        // Replay and swap the sink under one lock so no chunk is missed or
+        // delivered twice.
+        let mut output = lock_output(&session.output);
+        if !output.scrollback.is_empty() && !sink(output.scrollback.clone()) {
+            return Err("PTY output receiver closed during scrollback replay".to_string());
+        }
+        output.pending.clear();
+        output.sink = Some(sink);
+        if output.reader_alive {
+            return Ok(());
+        }
+
+        let reader = session
+            .pty_pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| format!("Failed to clone PTY reader: {e}"))?;
+        output.reader_alive = true;
+        drop(output);
+        spawn_output_reader(session_id, reader, session.output.clone())
     }
 
     /// Resize a PTY session.
@@ -185,11 +432,113 @@ impl PtyManager {
             .map_err(|e| format!("Failed to resize PTY: {e}"))
     }
 
-    /// Kill/close a PTY session.
+    /// Kill/close a PTY session. Its child process is killed and its reader
+    /// thread exits without delivering further output.
     pub fn kill(&mut self, session_id: &str) -> Result<(), String> {
-        self.sessions
+        let mut session = self
+            .sessions
             .remove(session_id)
             .ok_or_else(|| format!("PTY session not found: {session_id}"))?;
+        {
+            let mut output = lock_output(&session.output);
+            output.closed = true;
+            output.sink = None;
+        }
+        // Already-exited children fail to kill; nothing to do then.
+        let _ = session.killer.kill();
+        Ok(())
+    }
+
+    /// Deliver `signal` to the command in the foreground of a session.
+    ///
+    /// SIGINT is sent as Ctrl-C (0x03) through the PTY, so the terminal
+    /// interrupts its foreground job exactly as a keypress would. SIGTERM and
+    /// SIGKILL go to the terminal's foreground process group, falling back to
+    /// the session's own process when none is known; the shell survives when
+    /// it is not the foreground job. They are only supported on Unix.
+    pub fn signal(&mut self, session_id: &str, signal: PtySignal) -> Result<(), String> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| format!("PTY session not found: {session_id}"))?;
+
+        if signal == PtySignal::Interrupt {
+            session
+                .writer
+                .write_all(b"\x03")
+                .and_then(|()| session.writer.flush())
+                .map_err(|e| format!("Failed to write to PTY: {e}"))?;
+            return Ok(());
+        }
+
+        #[cfg(unix)]
+        {
+            let number = match signal {
+                PtySignal::Terminate => libc::SIGTERM,
+                _ => libc::SIGKILL,
+            };
+            // A negative pid addresses the whole process group.
+            let target = match session.pty_pair.master.process_group_leader() {
+                Some(group) if group > 0 => -group,
+                _ => session
+                    .pid
+                    .and_then(|pid| libc::pid_t::try_from(pid).ok())
+                    .ok_or_else(|| format!("PTY session {session_id} has no process id"))?,
+            };
+            // SAFETY: kill(2) has no memory-safety preconditions.
+            if unsafe { libc::kill(target, number) } != 0 {
+                return Err(format!(
+                    "Failed to signal PTY session {session_id}: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        {
+            Err(format!(
+                "{signal:?} is only supported on Unix; use SIGINT or pty_kill"
+            ))
+        }
+    }
+
+    /// Exit state of a session's child, for waiting without holding the
+    /// manager lock.
+    pub fn exit_handle(&self, session_id: &str) -> Result<Arc<PtyExit>, String> {
+        self.sessions
+            .get(session_id)
+            .map(|session| session.exit.clone())
+            .ok_or_else(|| format!("PTY session not found: {session_id}"))
+    }
+
+    /// Drop a session whose child has exited. Its reader keeps delivering
+    /// the output still buffered in the PTY until EOF. Returns whether the
+    /// session was removed.
+    pub fn remove_exited(&mut self, session_id: &str) -> bool {
+        let exited = self
+            .sessions
+            .get(session_id)
+            .is_some_and(|session| session.exit.status().is_some());
+        exited && self.sessions.remove(session_id).is_some()
+    }
+
+    /// Set or clear (with `None` or a blank label) a session's label.
+    pub fn rename(&mut self, session_id: &str, label: Option<String>) -> Result<(), String> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| format!("PTY session not found: {session_id}"))?;
+        let label = label
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty());
+        if let Some(label) = &label {
+            if label.chars().count() > MAX_LABEL_CHARS {
+                return Err(format!(
+                    "PTY session label is longer than {MAX_LABEL_CHARS} characters"
+                ));
+            }
+        }
+        session.label = label;
         Ok(())
     }
 
@@ -201,11 +550,74 @@ impl PtyManager {
                 session_id: id.clone(),
                 command: session.command.clone(),
                 cwd: session.cwd.clone(),
+                label: session
+                    .label
+                    .clone()
+                    .unwrap_or_else(|| session.command.clone()),
             })
             .collect()
     }
 }
 
+/// Read `reader` until EOF or the session is killed, appending to the
+/// scrollback and forwarding each chunk to the current sink (or buffering it
+/// for `pty_read`).
+fn spawn_output_reader(
+    session_id: &str,
+    mut reader: Box<dyn Read + Send>,
+    output: Arc<Mutex<PtyOutput>>,
+) -> Result<(), String> {
+    let guard = ReaderAliveGuard(output.clone());
+    std::thread::Builder::new()
+        .name(format!("{session_id}-reader"))
+        .spawn(move || {
+            let _guard = guard;
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = match reader.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => n,
+                };
+                let chunk = String::from_utf8_lossy(&buf[..n]).to_string();
+                let mut output = lock_output(&output);
+                if output.closed {
+                    break;
+                }
+                output.push_chunk(chunk);
+            }
+        })
+        .map(|_| ())
+        .map_err(|e| format!("Failed to start PTY reader: {e}"))
+}
+
+/// Wait for `child` on its own thread and record how it exited.
+fn spawn_exit_waiter(
+    session_id: &str,
+    mut child: Box<dyn Child + Send + Sync>,
+    exit: Arc<PtyExit>,
+) -> Result<(), String> {
+    let id = session_id.to_string();
+    std::thread::Builder::new()
+        .name(format!("{session_id}-waiter"))
+        .spawn(move || {
+            let status = match child.wait() {
+                Ok(status) => PtyExitStatus {
+                    session_id: id,
+                    exit_code: status.exit_code(),
+                    success: status.success(),
+                },
+                Err(_) => PtyExitStatus {
+                    session_id: id,
+                    exit_code: 1,
+                    success: false,
+                },
+            };
+            exit.set(status);
+        })
+        .map(|_| ())
+        .map_err(|e| format!("Failed to start PTY exit waiter: {e}"))
+}
+
 impl Default for PtyManager {
     fn default() -> Self {
         Self::new()
@@ -218,6 +630,8 @@ pub struct PtySessionInfo {
     pub session_id: String,
     pub command: String,
     pub cwd: String,
+    /// The session's label, or its command when it has none.
+    pub label: String,
 }
 
 /// Shared PTY state for Tauri commands.
@@ -239,11 +653,125 @@ impl Default for PtyState {
     }
 }
 
+// ─── Health Probe ────────────────────────────────────────────────────────────
+
+/// How long the health probe waits for the trivial command to finish.
+const HEALTHCHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Marker echoed by the probe command so its output can be recognised.
+const HEALTHCHECK_MARKER: &str = "routa-pty-ok";
+
+/// Result of [`pty_health_probe`].
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct PtyHealth {
+    pub ok: bool,
+    pub detail: String,
+}
+
+/// Open a PTY, run a trivial command in it and check that it prints its
+/// marker and exits. Catches environments where portable-pty cannot work
+/// before a user opens their first terminal.
+pub fn pty_health_probe() -> PtyHealth {
+    match run_pty_probe() {
+        Ok(detail) => PtyHealth { ok: true, detail },
+        Err(detail) => PtyHealth { ok: false, detail },
+    }
+}
+
+/// [`pty_health_probe`] in the shape expected by `/healthz`.
+pub fn pty_health_probe_status() -> (bool, String) {
+    let health = pty_health_probe();
+    (health.ok, health.detail)
+}
+
+fn run_pty_probe() -> Result<String, String> {
+    let pty_pair = native_pty_system()
+        .openpty(PtySize {
+            rows: 24,
+            cols: 80,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|e| format!("Failed to open PTY: {e}"))?;
+
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = CommandBuilder::new("cmd.exe");
+        cmd.args(["/c", "echo", HEALTHCHECK_MARKER]);
+        cmd
+    } else {
+        let mut cmd = CommandBuilder::new("/bin/sh");
+        cmd.args(["-c", format!("echo {HEALTHCHECK_MARKER}").as_str()]);
+        cmd
+    };
+    if let Ok(cwd) = std::env::current_dir() {
+        cmd.cwd(cwd);
+    }
+
+    let mut child = pty_pair
+        .slave
+        .spawn_command(cmd)
+        .map_err(|e| format!("Failed to spawn command in PTY: {e}"))?;
+    // Drop our slave handle so the reader sees EOF once the child exits.
+    drop(pty_pair.slave);
+
+    let mut reader = pty_pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| format!("Failed to clone PTY reader: {e}"))?;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let mut buf = [0u8; 1024];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    output.extend_from_slice(&buf[..n]);
+                    if String::from_utf8_lossy(&output).contains(HEALTHCHECK_MARKER) {
+                        break;
+                    }
+                }
+            }
+        }
+        let _ = tx.send(output);
+    });
+
+    let output = match rx.recv_timeout(HEALTHCHECK_TIMEOUT) {
+        Ok(output) => output,
+        Err(_) => {
+            // Killing the child closes the PTY, which ends the reader thread.
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!(
+                "PTY produced no output within {}s",
+                HEALTHCHECK_TIMEOUT.as_secs()
+            ));
+        }
+    };
+    if !String::from_utf8_lossy(&output).contains(HEALTHCHECK_MARKER) {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err("PTY command exited without producing the expected output".to_string());
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for PTY command: {e}"))?;
+    if !status.success() {
+        return Err(format!("PTY command exited unsuccessfully: {status:?}"));
+    }
+    Ok("PTY opened, produced output and exited cleanly".to_string())
+}
+
 // ─── Tauri Commands ──────────────────────────────────────────────────────────
 
-/// Create a new PTY session.
+/// Create a new PTY session. When its process exits the session is removed
+/// and `pty://{session_id}/exit` is emitted with a [`PtyExitStatus`].
+/// `scrollback_bytes` sizes its scrollback (default 256 KiB, `0` keeps none).
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn pty_create(
+    app: AppHandle,
     state: State<'_, PtyState>,
     command: Option<String>,
     args: Option<Vec<String>>,
@@ -251,16 +779,69 @@ pub async fn pty_create(
     env: Option<HashMap<String, String>>,
     rows: Option<u16>,
     cols: Option<u16>,
+    label: Option<String>,
+    scrollback_bytes: Option<usize>,
 ) -> Result<String, String> {
+    if scrollback_bytes.is_some_and(|bytes| bytes > MAX_SCROLLBACK_BYTES) {
+        return Err(format!(
+            "scrollback_bytes exceeds the {MAX_SCROLLBACK_BYTES}-byte limit"
+        ));
+    }
     let mut manager = state.manager.lock().await;
-    manager.create(
+    let session_id = manager.create(
         command,
         args,
         cwd,
         env,
         rows.unwrap_or(24),
         cols.unwrap_or(80),
-    )
+    )?;
+    let configured = manager.rename(&session_id, label).and_then(|()| {
+        scrollback_bytes.map_or(Ok(()), |bytes| {
+            manager.set_scrollback_limit(&session_id, bytes)
+        })
+    });
+    if let Err(error) = configured {
+        let _ = manager.kill(&session_id);
+        return Err(error);
+    }
+    let exit = manager.exit_handle(&session_id)?;
+    drop(manager);
+
+    let manager = state.manager.clone();
+    let id = session_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let Ok(status) = tauri::async_runtime::spawn_blocking(move || exit.wait()).await else {
+            return;
+        };
+        manager.lock().await.remove_exited(&id);
+        let _ = app.emit(&format!("pty://{id}/exit"), status);
+    });
+    Ok(session_id)
+}
+
+/// Set a PTY session's label; a blank or missing label reverts to showing
+/// the command.
+#[tauri::command]
+pub async fn pty_rename(
+    state: State<'_, PtyState>,
+    session_id: String,
+    label: Option<String>,
+) -> Result<(), String> {
+    let mut manager = state.manager.lock().await;
+    manager.rename(&session_id, label)
+}
+
+/// Wait for a PTY session's process to exit.
+#[tauri::command]
+pub async fn pty_wait(
+    state: State<'_, PtyState>,
+    session_id: String,
+) -> Result<PtyExitStatus, String> {
+    let exit = state.manager.lock().await.exit_handle(&session_id)?;
+    tauri::async_runtime::spawn_blocking(move || exit.wait())
+        .await
+        .map_err(|e| format!("Failed to wait for PTY session: {e}"))
 }
 
 /// Write data to a PTY session.
@@ -284,6 +865,61 @@ pub async fn pty_read(
     manager.read(&session_id)
 }
 
+/// Push a PTY session's output to the UI as `pty://{session_id}/data` events
+/// carrying the text, replaying its scrollback first.
+#[tauri::command]
+pub async fn pty_subscribe(
+    app: AppHandle,
+    state: State<'_, PtyState>,
+    session_id: String,
+) -> Result<(), String> {
+    let event = format!("pty://{session_id}/data");
+    let mut manager = state.manager.lock().await;
+    manager.reattach(
+        &session_id,
+        Box::new(move |chunk| app.emit(&event, chunk).is_ok()),
+    )
+}
+
+/// A PTY session's recent output, for repainting a terminal without
+/// attaching to it.
+#[tauri::command]
+pub async fn pty_get_scrollback(
+    state: State<'_, PtyState>,
+    session_id: String,
+) -> Result<String, String> {
+    let manager = state.manager.lock().await;
+    manager.scrollback(&session_id)
+}
+
+/// Stream a PTY session's output over `on_output`, replaying its scrollback.
+/// Used both for the first attach and to resume a session after a UI reload.
+#[tauri::command]
+pub async fn pty_reattach(
+    state: State<'_, PtyState>,
+    session_id: String,
+    on_output: Channel<String>,
+) -> Result<(), String> {
+    let mut manager = state.manager.lock().await;
+    manager.reattach(
+        &session_id,
+        Box::new(move |chunk| on_output.send(chunk).is_ok()),
+    )
+}
+
+/// Send `signal` (`SIGINT`, `SIGTERM` or `SIGKILL`) to the command running
+/// in a PTY session, keeping the terminal open.
+#[tauri::command]
+pub async fn pty_signal(
+    state: State<'_, PtyState>,
+    session_id: String,
+    signal: String,
+) -> Result<(), String> {
+    let signal = PtySignal::parse(&signal)?;
+    let mut manager = state.manager.lock().await;
+    manager.signal(&session_id, signal)
+}
+
 /// Resize a PTY session.
 #[tauri::command]
 pub async fn pty_resize(
@@ -310,6 +946,14 @@ pub async fn pty_list(state: State<'_, PtyState>) -> Result<Vec<PtySessionInfo>,
     Ok(manager.list())
 }
 
+/// Run the PTY health probe.
+#[tauri::command]
+pub async fn pty_healthcheck() -> Result<PtyHealth, String> {
+    tauri::async_runtime::spawn_blocking(pty_health_probe)
+        .await
+        .map_err(|e| format!("PTY health probe failed: {e}"))
+}
+
 // ─── Unit Tests ──────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -372,6 +1016,31 @@ mod tests {
         let _ = manager.kill(&session2);
     }
 
+    #[test]
+    fn test_pty_manager_rename() {
+        let mut manager = PtyManager::new();
+        let session_id = manager.create(None, None, None, None, 24, 80).unwrap();
+        let label = |manager: &PtyManager| manager.list()[0].label.clone();
+        let command = manager.list()[0].command.clone();
+        assert_eq!(label(&manager), command, "Label defaults to the command");
+
+        manager
+            .rename(&session_id, Some("  server ".to_string()))
+            .unwrap();
+        assert_eq!(label(&manager), "server");
+
+        assert!(manager
+            .rename(&session_id, Some("x".repeat(MAX_LABEL_CHARS + 1)))
+            .is_err());
+        assert_eq!(label(&manager), "server");
+
+        manager.rename(&session_id, Some(" ".to_string())).unwrap();
+        assert_eq!(label(&manager), command);
+
+        assert!(manager.rename("nonexistent", None).is_err());
+        let _ = manager.kill(&session_id);
+    }
+
     #[test]
     fn test_pty_manager_write_read() {
         let mut manager = PtyManager::new();
@@ -482,4 +1151,214 @@ mod tests {
         // Clean up
         let _ = manager.kill(&session_id);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pty_manager_reattach_replays_scrollback_with_one_reader() {
+        let mut manager = PtyManager::new();
+        let session_id = manager
+            .create(
+                Some("/bin/sh".to_string()),
+                Some(vec!["-c".to_string(), "echo first; sleep 5".to_string()]),
+                None,
+                None,
+                24,
+                80,
+            )
+            .unwrap();
+
+        let (tx, rx) = mpsc::channel::<String>();
+        manager
+            .reattach(&session_id, Box::new(move |chunk| tx.send(chunk).is_ok()))
+            .unwrap();
+        let mut received = String::new();
+        while !received.contains("first") {
+            received.push_str(&rx.recv_timeout(Duration::from_secs(5)).unwrap());
+        }
+        assert!(manager.read(&session_id).is_err(), "polling is disabled");
+
+        // A second attach (e.g. after a reload) replays the scrollback and
+        // reuses the running reader.
+        drop(rx);
+        let (tx, rx) = mpsc::channel::<String>();
+        manager
+            .reattach(&session_id, Box::new(move |chunk| tx.send(chunk).is_ok()))
+            .unwrap();
+        let replay = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert!(replay.contains("first"), "replay: {replay}");
+        let session = manager.sessions.get(&session_id).unwrap();
+        assert!(lock_output(&session.output).reader_alive);
+
+        let _ = manager.kill(&session_id);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pty_manager_scrollback_keeps_recent_output_within_limit() {
+        let mut manager = PtyManager::new();
+        let session_id = manager
+            .create(
+                Some("/bin/sh".to_string()),
+                Some(vec![
+                    "-c".to_string(),
+                    "echo first; sleep 1; echo second-line; sleep 5".to_string(),
+                ]),
+                None,
+                None,
+                24,
+                80,
+            )
+            .unwrap();
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !manager.scrollback(&session_id).unwrap().contains("first") {
+            assert!(std::time::Instant::now() < deadline, "no output");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        // Retrieval does not consume output buffered for pty_read.
+        assert!(manager
+            .read(&session_id)
+            .unwrap()
+            .unwrap()
+            .contains("first"));
+
+        manager.set_scrollback_limit(&session_id, 8).unwrap();
+        assert!(manager.scrollback(&session_id).unwrap().len() <= 8);
+        while !manager.scrollback(&session_id).unwrap().contains("line") {
+            assert!(std::time::Instant::now() < deadline, "no second output");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        let scrollback = manager.scrollback(&session_id).unwrap();
+        assert!(scrollback.len() <= 8, "scrollback: {scrollback:?}");
+        assert!(!scrollback.contains("first"));
+
+        assert!(manager
+            .set_scrollback_limit(&session_id, MAX_SCROLLBACK_BYTES + 1)
+            .is_err());
+        assert!(manager.scrollback("nonexistent").is_err());
+        let _ = manager.kill(&session_id);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pty_manager_read_does_not_block_and_kill_stops_reader() {
+        let mut manager = PtyManager::new();
+        let session_id = manager
+            .create(
+                Some("/bin/sh".to_string()),
+                Some(vec!["-c".to_string(), "sleep 30".to_string()]),
+                None,
+                None,
+                24,
+                80,
+            )
+            .unwrap();
+
+        // An idle terminal has nothing buffered; reading returns at once.
+        let started = std::time::Instant::now();
+        assert_eq!(manager.read(&session_id).unwrap(), None);
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        let output = manager.sessions.get(&session_id).unwrap().output.clone();
+        assert!(lock_output(&output).reader_alive);
+        manager.kill(&session_id).unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while lock_output(&output).reader_alive && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(!lock_output(&output).reader_alive, "reader should stop");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pty_manager_reports_exit_status_and_removes_exited_session() {
+        let mut manager = PtyManager::new();
+        let session_id = manager
+            .create(
+                Some("/bin/sh".to_string()),
+                Some(vec!["-c".to_string(), "exit 3".to_string()]),
+                None,
+                None,
+                24,
+                80,
+            )
+            .unwrap();
+        let exit = manager.exit_handle(&session_id).unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || tx.send(exit.wait()));
+        let status = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(status.session_id, session_id);
+        assert_eq!(status.exit_code, 3);
+        assert!(!status.success);
+
+        assert!(manager.remove_exited(&session_id));
+        assert!(manager.list().is_empty());
+        assert!(manager.exit_handle(&session_id).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pty_manager_keeps_running_sessions() {
+        let mut manager = PtyManager::new();
+        let session_id = manager
+            .create(
+                Some("/bin/sh".to_string()),
+                Some(vec!["-c".to_string(), "sleep 30".to_string()]),
+                None,
+                None,
+                24,
+                80,
+            )
+            .unwrap();
+        assert!(manager.exit_handle(&session_id).unwrap().status().is_none());
+        assert!(!manager.remove_exited(&session_id));
+        assert_eq!(manager.list().len(), 1);
+        let _ = manager.kill(&session_id);
+    }
+
+    #[test]
+    fn test_pty_signal_parse() {
+        assert_eq!(PtySignal::parse("SIGINT"), Ok(PtySignal::Interrupt));
+        assert_eq!(PtySignal::parse("term"), Ok(PtySignal::Terminate));
+        assert_eq!(PtySignal::parse(" SigKill "), Ok(PtySignal::Kill));
+        assert!(PtySignal::parse("SIGHUP").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pty_manager_signal_stops_foreground_command() {
+        let mut manager = PtyManager::new();
+        for signal in [PtySignal::Interrupt, PtySignal::Terminate, PtySignal::Kill] {
+            let session_id = manager
+                .create(
+                    Some("sleep".to_string()),
+                    Some(vec!["30".to_string()]),
+                    None,
+                    None,
+                    24,
+                    80,
+                )
+                .unwrap();
+            let exit = manager.exit_handle(&session_id).unwrap();
+            // Let the child take over the terminal before signalling it.
+            std::thread::sleep(Duration::from_millis(200));
+            manager.signal(&session_id, signal).unwrap();
+
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || tx.send(exit.wait()));
+            let status = rx
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap_or_else(|_| panic!("{signal:?} did not stop the command"));
+            assert!(!status.success, "{signal:?}");
+            let _ = manager.kill(&session_id);
+        }
+        assert!(manager.signal("nonexistent", PtySignal::Interrupt).is_err());
+    }
+
+    #[test]
+    fn test_pty_health_probe() {
+        let health = pty_health_probe();
+        assert!(health.ok, "PTY health probe failed: {}", health.detail);
+    }
 }
@@ -51,6 +51,8 @@ pub enum AcpAction {
     EnsureNode,
     /// Download and cache uv (managed runtime) if not already present.
     EnsureUv,
+    /// Show where ACP agent data is stored.
+    Paths,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -120,9 +122,12 @@ pub async fn install(
         "binary" => {
             install_binary(state, agent_id, name, &version, &dist).await?;
         }
+        "git" => {
+            install_git(state, agent_id, name, &version, &dist).await?;
+        }
         other => {
             return Err(format!(
-                "Unknown distribution type '{other}'. Use npx, uvx, or binary."
+                "Unknown distribution type '{other}'. Use npx, uvx, binary, or git."
             ));
         }
     }
@@ -145,7 +150,10 @@ pub async fn uninstall(state: &AppState, agent_id: &str) -> Result<(), String> {
         .get_installed_info(agent_id)
         .await
     {
-        if info.dist_type == DistributionType::Binary {
+        if matches!(
+            info.dist_type,
+            DistributionType::Binary | DistributionType::Git
+        ) {
             state
                 .acp_binary_manager
                 .uninstall(agent_id)
@@ -330,6 +338,19 @@ pub async fn ensure_uv(state: &AppState) -> Result<(), String> {
     Ok(())
 }
 
+/// Print the resolved ACP data directory; fails in strict mode when only the
+/// fallback is available.
+pub fn paths() -> Result<(), String> {
+    let paths = AcpPaths::try_new()?;
+    print_json(&serde_json::json!({
+        "baseDir": paths.base_dir(),
+        "source": paths.source(),
+        "registryCache": paths.registry_cache_path(),
+        "installedState": paths.installed_state_path(),
+    }));
+    Ok(())
+}
+
 fn find_agent<'a>(
     registry: &'a serde_json::Value,
     agent_id: &str,
@@ -365,6 +386,9 @@ fn choose_dist_type(dist: &serde_json::Value) -> String {
     if dist.get("binary").is_some() {
         return "binary".into();
     }
+    if dist.get("git").is_some() {
+        return "git".into();
+    }
     "npx".into()
 }
 
@@ -471,6 +495,44 @@ async fn install_binary(
     Ok(())
 }
 
+async fn install_git(
+    state: &AppState,
+    agent_id: &str,
+    name: &str,
+    version: &str,
+    dist: &serde_json::Value,
+) -> Result<(), String> {
+    let git_config = dist
+        .get("git")
+        .ok_or_else(|| "No git distribution".to_string())?;
+
+    let git: routa_core::acp::GitDistribution = serde_json::from_value(git_config.clone())
+        .map_err(|e| format!("Invalid git config: {e}"))?;
+
+    println!("[acp install] Cloning {} for '{name}'…", git.repo);
+    let checkout = state
+        .acp_binary_manager
+        .install_git(agent_id, version, &git)
+        .await
+        .map_err(|e| format!("Git install failed: {e}"))?;
+
+    let checkout_str = checkout.to_string_lossy().to_string();
+    state
+        .acp_installation_state
+        .mark_installed(
+            agent_id,
+            version,
+            DistributionType::Git,
+            Some(checkout_str.clone()),
+            None,
+        )
+        .await
+        .map_err(|e| format!("State update failed: {e}"))?;
+
+    println!("[acp install] '{name}' cloned → {checkout_str}");
+    Ok(())
+}
+
 fn quick_check_installed(dist: &serde_json::Value, npx_ok: bool, uvx_ok: bool) -> bool {
     (dist.get("npx").is_some() && npx_ok) || (dist.get("uvx").is_some() && uvx_ok)
 }
@@ -486,6 +548,9 @@ fn dist_summary(dist: &serde_json::Value) -> Vec<String> {
     if dist.get("binary").is_some() {
         types.push("binary".to_string());
     }
+    if dist.get("git").is_some() {
+        types.push("git".to_string());
+    }
     types
 }
 
@@ -740,6 +805,7 @@ mod tests {
             args: vec![],
             description: "Preset".to_string(),
             env_bin_override: None,
+            env: Default::default(),
             resume: None,
         };
         let registry = serde_json::json!({
@@ -104,6 +104,12 @@ pub(crate) async fn call_security_specialist_via_acp(
     } else {
         output
     };
+    let output = state
+        .settings
+        .acp
+        .output_normalization
+        .apply(&output)
+        .into_owned();
 
     state.acp_manager.kill_session(&session_id).await;
 
@@ -4,13 +4,11 @@ use routa_core::workflow::specialist::SpecialistDef;
 
 use super::output::print_review_result;
 use super::shared::{
-    build_review_input_payload, load_dotenv, load_specialist_by_id, resolve_repo_root,
-    ReviewAnalyzeOptions, ReviewInputPayload, ReviewWorkerType,
+    build_review_input_payload, load_specialist_by_id, resolve_repo_root, ReviewAnalyzeOptions,
+    ReviewInputPayload, ReviewWorkerType,
 };
 
 pub async fn analyze(_state: &AppState, options: ReviewAnalyzeOptions<'_>) -> Result<(), String> {
-    load_dotenv();
-
     let repo_root = resolve_repo_root(options.repo_path)?;
     let payload =
         build_review_input_payload(&repo_root, options.base, options.head, options.rules_file)?;
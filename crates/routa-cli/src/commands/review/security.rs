@@ -12,16 +12,14 @@ use super::candidate_collector::build_security_review_payload;
 use super::dispatch::dispatch_security_specialists;
 use super::output::{print_pretty_json, print_review_result};
 use super::shared::{
-    load_dotenv, load_specialist_by_id, resolve_repo_root, ReviewAnalyzeOptions,
-    SecurityReviewPayload, SECURITY_REVIEW_HOME_DIR,
+    load_specialist_by_id, resolve_repo_root, ReviewAnalyzeOptions, SecurityReviewPayload,
+    SECURITY_REVIEW_HOME_DIR,
 };
 
 pub async fn security(state: &AppState, options: ReviewAnalyzeOptions<'_>) -> Result<(), String> {
     let full_path = routa_core::shell_env::full_path();
     std::env::set_var("PATH", full_path);
 
-    load_dotenv();
-
     let repo_root = resolve_repo_root(options.repo_path)?;
     let payload =
         build_security_review_payload(&repo_root, options.base, options.head, options.rules_file)?;
@@ -12,9 +12,6 @@ pub async fn run(
     specialist_dir: Option<&str>,
     trigger_payload: Option<&str>,
 ) -> Result<(), String> {
-    // Load .env / .env.local if present (for API keys, etc.)
-    load_dotenv();
-
     // Load the workflow definition
     let workflow = WorkflowDefinition::from_file(workflow_file)?;
 
@@ -131,7 +128,7 @@ pub async fn validate(workflow_file: &str) -> Result<(), String> {
 }
 
 /// Load .env and .env.local files for environment variables.
-fn load_dotenv() {
+pub(crate) fn load_dotenv() {
     // Try .env.local first (higher priority), then .env
     for filename in &[".env.local", ".env"] {
         let path = std::path::Path::new(filename);
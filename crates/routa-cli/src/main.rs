@@ -690,6 +690,7 @@ async fn main() {
                         let state = commands::init_state(&cli.db).await;
                         commands::acp::ensure_uv(&state).await
                     }
+                    AcpAction::Paths => commands::acp::paths(),
                 }
             }
 
@@ -980,6 +981,9 @@ async fn main() {
             Commands::Harness { action } => commands::harness::run(&cli.db, action).await,
 
             Commands::Workflow { action } => {
+                // Load .env / .env.local (API keys, ROUTA_* settings) before the
+                // state reads its settings.
+                commands::workflow::load_dotenv();
                 let state = commands::init_state(&cli.db).await;
                 match action {
                     WorkflowAction::Run {
@@ -1004,6 +1008,7 @@ async fn main() {
                 }
             }
             Commands::Review { action } => {
+                commands::review::shared::load_dotenv();
                 let state = commands::init_state(&cli.db).await;
                 match action {
                     ReviewAction::Analyze {
@@ -0,0 +1,327 @@
+//! Cache of downloaded agent archives, so reinstalling a version (after an
+//! uninstall, or on another workspace sharing the same base dir) can skip the
+//! download.
+//!
+//! Archives are kept under `AcpPaths::archive_cache_dir()` at
+//! `{agent}/{version}/{digest}/{filename}`, where `digest` is the registry's
+//! `sha256` when it has one and a hash of the archive URL otherwise. Only
+//! archives that extracted into a working install are stored. Reads refresh an
+//! entry's modification time, and stores evict the least recently used
+//! entries once the cache grows past its size cap (`AcpSettings`).
+//!
+//! Every hit is hashed again against the registry's `sha256`, so an entry
+//! corrupted or replaced on disk is evicted and downloaded again rather than
+//! installed. Archives without a published digest were unverified when they
+//! were downloaded and are reused as they are.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
+
+use super::binary_manager::sha256_file;
+use super::paths::AcpPaths;
+use super::registry_types::BinaryInfo;
+use crate::settings::AcpSettings;
+
+/// Default size cap for the archive cache.
+pub const DEFAULT_ARCHIVE_CACHE_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct ArchiveCache {
+    dir: PathBuf,
+    /// `None` when caching is disabled.
+    max_bytes: Option<u64>,
+}
+
+impl ArchiveCache {
+    pub fn new(dir: PathBuf, max_bytes: Option<u64>) -> Self {
+        Self {
+            dir,
+            max_bytes: max_bytes.filter(|max| *max > 0),
+        }
+    }
+
+    pub fn disabled(dir: PathBuf) -> Self {
+        Self::new(dir, None)
+    }
+
+    /// The cache under `paths`, sized by `settings`.
+    pub fn from_settings(paths: &AcpPaths, settings: &AcpSettings) -> Self {
+        Self::new(paths.archive_cache_dir(), settings.archive_cache_max_bytes)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_bytes.is_some()
+    }
+
+    /// Where the archive for `agent_id`/`version` is (or would be) cached.
+    pub fn entry_path(&self, agent_id: &str, version: &str, binary_info: &BinaryInfo) -> PathBuf {
+        let digest = binary_info
+            .sha256
+            .as_deref()
+            .map(str::trim)
+            .filter(|sha| !sha.is_empty() && sha.chars().all(|c| c.is_ascii_hexdigit()))
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_else(|| {
+                let hash = Sha256::digest(binary_info.archive.as_bytes());
+                hash.iter().map(|byte| format!("{byte:02x}")).collect()
+            });
+        self.dir
+            .join(path_segment(agent_id))
+            .join(path_segment(version))
+            .join(digest)
+            .join(path_segment(archive_filename(&binary_info.archive)))
+    }
+
+    /// Return the cached archive at `entry`, marking it as recently used.
+    /// With an `expected_sha256`, an entry that no longer matches it is
+    /// evicted and reported as a miss. Hashes the file, so call it off the
+    /// async runtime.
+    pub fn lookup(&self, entry: &Path, expected_sha256: Option<&str>) -> Option<PathBuf> {
+        if !self.is_enabled() || !entry.is_file() {
+            return None;
+        }
+        if let Some(expected) = expected_sha256.map(str::trim).filter(|sha| !sha.is_empty()) {
+            let matches = sha256_file(entry).map(|actual| actual.eq_ignore_ascii_case(expected));
+            if matches != Ok(true) {
+                tracing::warn!(
+                    "[AcpBinaryManager] Cached archive {:?} no longer matches sha256 {}; evicting it",
+                    entry,
+                    expected
+                );
+                self.remove(entry);
+                return None;
+            }
+        }
+        if let Ok(file) = std::fs::File::options().write(true).open(entry) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(entry.to_path_buf())
+    }
+
+    /// Move a downloaded archive into the cache at `entry`, then evict least
+    /// recently used entries until the cache fits its cap.
+    pub fn store(&self, archive: &Path, entry: &Path) -> Result<(), String> {
+        let Some(max_bytes) = self.max_bytes else {
+            return Ok(());
+        };
+        if let Some(parent) = entry.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create archive cache dir: {e}"))?;
+        }
+        if std::fs::rename(archive, entry).is_err() {
+            std::fs::copy(archive, entry).map_err(|e| format!("Failed to cache archive: {e}"))?;
+        }
+        if let Ok(file) = std::fs::File::options().write(true).open(entry) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        self.evict(max_bytes);
+        Ok(())
+    }
+
+    /// Drop a cached archive, e.g. one that no longer extracts.
+    pub fn remove(&self, entry: &Path) {
+        let _ = std::fs::remove_file(entry);
+        self.prune_empty_dirs(entry);
+    }
+
+    fn evict(&self, max_bytes: u64) {
+        let mut entries = Vec::new();
+        collect_files(&self.dir, &mut entries);
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        if total <= max_bytes {
+            return;
+        }
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, size, _) in entries {
+            if total <= max_bytes {
+                break;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                total = total.saturating_sub(size);
+                self.prune_empty_dirs(&path);
+                tracing::info!("[AcpBinaryManager] Evicted cached archive {:?}", path);
+            }
+        }
+    }
+
+    /// Remove now-empty directories between `removed` and the cache root.
+    fn prune_empty_dirs(&self, removed: &Path) {
+        let mut dir = removed.parent();
+        while let Some(current) = dir {
+            if current == self.dir || !current.starts_with(&self.dir) {
+                break;
+            }
+            if std::fs::remove_dir(current).is_err() {
+                break;
+            }
+            dir = current.parent();
+        }
+    }
+}
+
+/// File name of an archive URL, without query string.
+pub(super) fn archive_filename(url: &str) -> &str {
+    url.split('/')
+        .next_back()
+        .unwrap_or("archive")
+        .split('?')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("archive")
+}
+
+/// Make an id usable as a single path component.
+fn path_segment(value: &str) -> String {
+    let segment: String = value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '@' | '+') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match segment.as_str() {
+        "" | "." | ".." => "_".to_string(),
+        _ => segment,
+    }
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<(PathBuf, u64, SystemTime)>) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_files(&entry.path(), out);
+        } else if file_type.is_file() {
+            if let Ok(metadata) = entry.metadata() {
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                out.push((entry.path(), metadata.len(), modified));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn binary(archive: &str, sha256: Option<&str>) -> BinaryInfo {
+        BinaryInfo {
+            archive: archive.to_string(),
+            cmd: None,
+            sha256: sha256.map(str::to_string),
+        }
+    }
+
+    fn set_age(path: &Path, seconds_ago: u64) {
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(seconds_ago))
+            .unwrap();
+    }
+
+    #[test]
+    fn entry_path_is_keyed_by_agent_version_and_digest() {
+        let cache = ArchiveCache::new(PathBuf::from("/cache"), Some(1024));
+        let with_sha = cache.entry_path(
+            "codex",
+            "1.0.0",
+            &binary("https://example.com/dl/codex.tar.gz?x=1", Some("ABCDEF")),
+        );
+        assert_eq!(
+            with_sha,
+            PathBuf::from("/cache/codex/1.0.0/abcdef/codex.tar.gz")
+        );
+
+        let a = cache.entry_path("codex", "1.0.0", &binary("https://a/codex.zip", None));
+        let b = cache.entry_path("codex", "1.0.0", &binary("https://b/codex.zip", None));
+        assert_ne!(a, b);
+        assert!(cache
+            .entry_path("../x", "..", &binary("https://a/a.zip", None))
+            .starts_with("/cache/.._x/_/"));
+    }
+
+    #[test]
+    fn store_and_lookup_round_trip() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = ArchiveCache::new(temp.path().join("cache"), Some(1024));
+        let info = binary("https://example.com/tool.zip", None);
+        let entry = cache.entry_path("tool", "1.0.0", &info);
+        assert!(cache.lookup(&entry, None).is_none());
+
+        let download = temp.path().join("tool.zip");
+        std::fs::write(&download, b"archive").unwrap();
+        cache.store(&download, &entry).unwrap();
+        assert!(!download.exists());
+        assert_eq!(cache.lookup(&entry, None), Some(entry.clone()));
+
+        cache.remove(&entry);
+        assert!(cache.lookup(&entry, None).is_none());
+        assert!(!entry.parent().unwrap().exists());
+    }
+
+    #[test]
+    fn lookup_evicts_entries_that_no_longer_match_their_digest() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = ArchiveCache::new(temp.path().join("cache"), Some(1024));
+        let download = temp.path().join("tool.zip");
+        std::fs::write(&download, b"archive").unwrap();
+        let digest = sha256_file(&download).unwrap();
+        let info = binary("https://example.com/tool.zip", Some(&digest));
+        let entry = cache.entry_path("tool", "1.0.0", &info);
+        cache.store(&download, &entry).unwrap();
+
+        assert_eq!(cache.lookup(&entry, Some(&digest)), Some(entry.clone()));
+        std::fs::write(&entry, b"tampered").unwrap();
+        assert!(cache.lookup(&entry, Some(&digest)).is_none());
+        assert!(!entry.exists());
+    }
+
+    #[test]
+    fn store_evicts_least_recently_used_archives() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = ArchiveCache::new(temp.path().join("cache"), Some(25));
+        let entries: Vec<PathBuf> = ["a", "b", "c"]
+            .iter()
+            .map(|agent| cache.entry_path(agent, "1", &binary("https://x/a.zip", None)))
+            .collect();
+        for (i, entry) in entries.iter().take(2).enumerate() {
+            let download = temp.path().join(format!("{i}.zip"));
+            std::fs::write(&download, [0u8; 10]).unwrap();
+            cache.store(&download, entry).unwrap();
+        }
+        // `a` is older, but a lookup makes it the most recently used.
+        set_age(&entries[0], 60);
+        set_age(&entries[1], 30);
+        assert!(cache.lookup(&entries[0], None).is_some());
+
+        let download = temp.path().join("2.zip");
+        std::fs::write(&download, [0u8; 10]).unwrap();
+        cache.store(&download, &entries[2]).unwrap();
+
+        assert!(entries[0].exists());
+        assert!(!entries[1].exists());
+        assert!(entries[2].exists());
+    }
+
+    #[test]
+    fn disabled_cache_stores_nothing() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = ArchiveCache::disabled(temp.path().join("cache"));
+        let entry = cache.entry_path("tool", "1", &binary("https://x/t.zip", None));
+        let download = temp.path().join("t.zip");
+        std::fs::write(&download, b"archive").unwrap();
+        cache.store(&download, &entry).unwrap();
+        assert!(download.exists());
+        assert!(cache.lookup(&entry, None).is_none());
+        assert!(!ArchiveCache::new(temp.path().into(), Some(0)).is_enabled());
+    }
+}
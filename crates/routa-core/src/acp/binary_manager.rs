@@ -2,55 +2,416 @@
 //!
 //! Handles:
 //! - Downloading agent archives from URLs
-//! - Extracting ZIP, TAR.GZ, TAR.BZ2 formats
+//! - Verifying archives against the registry's SHA-256 digest
+//! - Extracting ZIP, TAR.GZ, TAR.BZ2, TAR.XZ, TAR.ZST formats
+//! - Checking the executable is a binary (ELF, Mach-O, PE) or a script, not
+//!   e.g. an HTML error page saved by a CDN
 //! - Setting executable permissions on Unix
 //! - Removing macOS quarantine attributes
+//! - Cancelling in-flight installs
+//! - Reusing cached archives on reinstall (see `archive_cache`)
 
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
 use tokio::sync::Mutex;
 
+use super::archive_cache::{archive_filename, ArchiveCache};
 use super::paths::AcpPaths;
-use super::registry_types::BinaryInfo;
+use super::registry_types::{BinaryInfo, GitDistribution};
+use crate::settings::AcpSettings;
 
 /// Manages binary agent downloads and extraction.
 pub struct AcpBinaryManager {
     paths: AcpPaths,
     /// Locks to prevent concurrent downloads of the same agent
     download_locks: Arc<Mutex<HashMap<String, Arc<Mutex<()>>>>>,
+    /// Cancellation flags for installs currently in flight, keyed by agent id
+    cancellations: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+    /// Archives kept from earlier installs
+    archive_cache: ArchiveCache,
+    /// Retries after a transient download failure (network error, 5xx, 429)
+    download_retries: u32,
+    /// Reject executables that are not a recognized binary or script
+    check_executable_format: bool,
+}
+
+const CANCELLED_MESSAGE: &str = "Installation cancelled";
+
+/// Retries after a transient download failure, unless configured otherwise.
+pub const DEFAULT_DOWNLOAD_RETRIES: u32 = 3;
+/// Backoff before the first retry; doubles with each further attempt.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// Upper bound on the backoff between attempts, before jitter.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// A failed download attempt. `retryable` marks failures another attempt
+/// may get past: network errors and 5xx/429 responses.
+struct DownloadFailure {
+    message: String,
+    retryable: bool,
+}
+
+impl DownloadFailure {
+    fn transient(message: String) -> Self {
+        Self {
+            message,
+            retryable: true,
+        }
+    }
+}
+
+impl From<String> for DownloadFailure {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            retryable: false,
+        }
+    }
+}
+
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Backoff before retry number `attempt` (1-based): exponential from
+/// `RETRY_BASE_DELAY`, capped at `RETRY_MAX_DELAY`, plus up to 50% jitter so
+/// parallel installs do not retry in lockstep.
+fn retry_delay(attempt: u32) -> Duration {
+    let backoff = RETRY_BASE_DELAY
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(RETRY_MAX_DELAY);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    backoff + backoff.mul_f64(f64::from(nanos % 1000) / 2000.0)
+}
+
+/// Stage of a binary install, as reported to an `InstallProgressFn`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstallPhase {
+    Downloading,
+    Extracting,
+    Finalizing,
+    /// Running the installed binary to check it works on this machine.
+    Verifying,
+}
+
+/// A progress report: the current phase and, when known, how far through it
+/// the install is (0–100). Downloads report a percentage only when the server
+/// sends a content length, and always report the bytes received so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallProgress {
+    pub phase: InstallPhase,
+    pub percent: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloaded_bytes: Option<u64>,
+    /// The archive's `Content-Length`, when the server sent one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+}
+
+impl InstallProgress {
+    pub fn new(phase: InstallPhase, percent: Option<u8>) -> Self {
+        Self {
+            phase,
+            percent,
+            downloaded_bytes: None,
+            total_bytes: None,
+        }
+    }
+
+    fn downloading(download: DownloadProgress) -> Self {
+        Self {
+            downloaded_bytes: Some(download.downloaded),
+            total_bytes: download.total,
+            ..Self::new(InstallPhase::Downloading, download.percent())
+        }
+    }
+}
+
+/// Bytes of an archive received so far, out of `total` when known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+impl DownloadProgress {
+    pub fn percent(&self) -> Option<u8> {
+        self.total
+            .map(|total| ((self.downloaded * 100) / total).min(100) as u8)
+    }
+}
+
+/// Without a content length, downloads report progress every this many bytes.
+const UNSIZED_PROGRESS_STEP_BYTES: u64 = 1024 * 1024;
+
+/// Callback receiving install progress; called from the installing task.
+pub type InstallProgressFn = Arc<dyn Fn(InstallProgress) + Send + Sync>;
+
+/// Archive formats `extract_archive` knows how to unpack, keyed by the
+/// canonical format name and the file suffixes that select it.
+pub const SUPPORTED_ARCHIVE_FORMATS: &[(&str, &[&str])] = &[
+    ("zip", &[".zip"]),
+    ("tar.gz", &[".tar.gz", ".tgz"]),
+    ("tar.bz2", &[".tar.bz2", ".tbz2"]),
+    ("tar.xz", &[".tar.xz", ".txz"]),
+    ("tar.zst", &[".tar.zst", ".tzst"]),
+    ("tar", &[".tar"]),
+];
+
+/// Resolve the canonical archive format for a (lowercased) archive path.
+pub fn archive_format(archive: &str) -> Option<&'static str> {
+    SUPPORTED_ARCHIVE_FORMATS
+        .iter()
+        .find(|(_, extensions)| extensions.iter().any(|ext| archive.ends_with(ext)))
+        .map(|(format, _)| *format)
 }
 
 impl AcpBinaryManager {
     /// Create a new binary manager.
     pub fn new(paths: AcpPaths) -> Self {
+        Self::with_settings(paths, &AcpSettings::from_env())
+    }
+
+    /// Create a binary manager configured by `settings`.
+    pub fn with_settings(paths: AcpPaths, settings: &AcpSettings) -> Self {
+        let archive_cache = ArchiveCache::from_settings(&paths, settings);
         Self {
             paths,
             download_locks: Arc::new(Mutex::new(HashMap::new())),
+            cancellations: Arc::new(Mutex::new(HashMap::new())),
+            archive_cache,
+            download_retries: DEFAULT_DOWNLOAD_RETRIES,
+            check_executable_format: settings.check_executable_format,
         }
     }
 
+    /// Turn the executable format check off (or back on) for agents that ship
+    /// something other than a native binary or a shebang script.
+    pub fn with_executable_check(mut self, enabled: bool) -> Self {
+        self.check_executable_format = enabled;
+        self
+    }
+
+    /// Retry transient download failures up to `retries` times (0 disables
+    /// retrying).
+    pub fn with_download_retries(mut self, retries: u32) -> Self {
+        self.download_retries = retries;
+        self
+    }
+
+    /// Replace the archive cache configured from the settings.
+    pub fn with_archive_cache(mut self, archive_cache: ArchiveCache) -> Self {
+        self.archive_cache = archive_cache;
+        self
+    }
+
+    /// Returns `true` if an install for `agent_id` is currently in flight.
+    pub async fn is_installing(&self, agent_id: &str) -> bool {
+        self.cancellations.lock().await.contains_key(agent_id)
+    }
+
+    /// Signal an in-flight install for `agent_id` to stop.
+    /// Returns `false` when no install is running for that agent.
+    pub async fn cancel_install(&self, agent_id: &str) -> bool {
+        match self.cancellations.lock().await.get(agent_id) {
+            Some(flag) => {
+                flag.store(true, Ordering::SeqCst);
+                tracing::info!("[AcpBinaryManager] Cancellation requested for {}", agent_id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether an error returned by `install_binary` came from cancellation.
+    pub fn is_cancelled_error(message: &str) -> bool {
+        message == CANCELLED_MESSAGE
+    }
+
     /// Download and install a binary agent.
     /// Returns the path to the executable.
+    ///
+    /// The archive is extracted into a staging directory that is only renamed
+    /// into place once extraction succeeds, so a cancelled or failed install
+    /// never leaves a half-written version directory behind.
     pub async fn install_binary(
         &self,
         agent_id: &str,
         version: &str,
         binary_info: &BinaryInfo,
     ) -> Result<PathBuf, String> {
-        // Get or create a lock for this agent
-        let lock = {
-            let mut locks = self.download_locks.lock().await;
-            locks
-                .entry(agent_id.to_string())
-                .or_insert_with(|| Arc::new(Mutex::new(())))
-                .clone()
-        };
+        self.install_binary_with_progress(agent_id, version, binary_info, None)
+            .await
+    }
+
+    /// `install_binary`, reporting each phase (and download percentage) to
+    /// `progress`.
+    pub async fn install_binary_with_progress(
+        &self,
+        agent_id: &str,
+        version: &str,
+        binary_info: &BinaryInfo,
+        progress: Option<InstallProgressFn>,
+    ) -> Result<PathBuf, String> {
+        // Hold the lock through cleanup as well, so a concurrent or retried
+        // install of the same agent never has its files deleted underneath it.
+        let lock = self.install_lock(agent_id).await;
+        let _guard = lock.lock().await;
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.cancellations
+            .lock()
+            .await
+            .insert(agent_id.to_string(), cancel.clone());
+
+        let result = self
+            .install_binary_inner(agent_id, version, binary_info, &cancel, progress.as_ref())
+            .await;
+
+        {
+            let mut cancellations = self.cancellations.lock().await;
+            if cancellations
+                .get(agent_id)
+                .is_some_and(|flag| Arc::ptr_eq(flag, &cancel))
+            {
+                cancellations.remove(agent_id);
+            }
+        }
+
+        if let Err(error) = &result {
+            // A `.part` file left by a failed download is resumed by the next
+            // install; only a cancelled or corrupt download starts over.
+            if Self::is_cancelled_error(error) || is_checksum_mismatch_error(error) {
+                let _ = tokio::fs::remove_dir_all(self.paths.agent_download_dir(agent_id, version))
+                    .await;
+            }
+            let _ = tokio::fs::remove_dir_all(Self::staging_dir(
+                &self.paths.agent_version_dir(agent_id, version),
+            ))
+            .await;
+        }
+        result
+    }
+
+    /// Install (or update) a git-distributed agent.
+    /// Returns the checkout directory, which `get_command` resolves the
+    /// entrypoint against.
+    ///
+    /// The repo is shallow-fetched at the pinned ref into the agent version
+    /// directory. Re-running against an existing checkout fetches and
+    /// force-checks-out the ref again, so a moved branch or re-pointed tag is
+    /// picked up without a fresh clone.
+    pub async fn install_git(
+        &self,
+        agent_id: &str,
+        version: &str,
+        git: &GitDistribution,
+    ) -> Result<PathBuf, String> {
+        crate::git::validate_remote_url(&git.repo)?;
 
-        // Hold the lock during download/extraction
+        let lock = self.install_lock(agent_id).await;
         let _guard = lock.lock().await;
 
         let install_dir = self.paths.agent_version_dir(agent_id, version);
+        let fresh = !install_dir.join(".git").exists();
+        let result = Self::sync_git_checkout(&install_dir, git, fresh).await;
+        if result.is_err() && fresh {
+            let _ = tokio::fs::remove_dir_all(&install_dir).await;
+        }
+        result.map(|()| install_dir)
+    }
+
+    async fn sync_git_checkout(
+        install_dir: &Path,
+        git: &GitDistribution,
+        fresh: bool,
+    ) -> Result<(), String> {
+        let repo = git.repo.trim();
+        if fresh {
+            let _ = tokio::fs::remove_dir_all(install_dir).await;
+            tokio::fs::create_dir_all(install_dir)
+                .await
+                .map_err(|e| format!("Failed to create install dir: {e}"))?;
+            Self::run_git(install_dir, &["init", "--quiet"]).await?;
+            Self::run_git(install_dir, &["remote", "add", "origin", repo]).await?;
+        } else {
+            Self::run_git(install_dir, &["remote", "set-url", "origin", repo]).await?;
+        }
+
+        let git_ref = git.git_ref.as_deref().unwrap_or("HEAD").trim();
+        if git_ref.is_empty() || git_ref.starts_with('-') {
+            return Err(format!("Invalid git ref: '{git_ref}'"));
+        }
+        tracing::info!(
+            "[AcpBinaryManager] Fetching {} at {} into {:?}",
+            repo,
+            git_ref,
+            install_dir
+        );
+        Self::run_git(
+            install_dir,
+            &["fetch", "--depth", "1", "--quiet", "origin", git_ref],
+        )
+        .await?;
+        Self::run_git(
+            install_dir,
+            &["checkout", "--force", "--quiet", "--detach", "FETCH_HEAD"],
+        )
+        .await
+    }
+
+    async fn run_git(dir: &Path, args: &[&str]) -> Result<(), String> {
+        let output = crate::git::git_tokio_command()
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run git: {e}"))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "git {} failed: {}",
+                args.first().copied().unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    /// The install itself; the caller holds the agent's install lock.
+    async fn install_binary_inner(
+        &self,
+        agent_id: &str,
+        version: &str,
+        binary_info: &BinaryInfo,
+        cancel: &Arc<AtomicBool>,
+        progress: Option<&InstallProgressFn>,
+    ) -> Result<PathBuf, String> {
+        let report = |phase, percent| {
+            if let Some(progress) = progress {
+                progress(InstallProgress::new(phase, percent));
+            }
+        };
+        let report_download = |download| {
+            if let Some(progress) = progress {
+                progress(InstallProgress::downloading(download));
+            }
+        };
+        Self::check_cancelled(cancel)?;
+
+        let install_dir = self.paths.agent_version_dir(agent_id, version);
+        let staging_dir = Self::staging_dir(&install_dir);
         let download_dir = self.paths.agent_download_dir(agent_id, version);
 
         // Check if already installed
@@ -63,34 +424,119 @@ impl AcpBinaryManager {
                 );
                 return Ok(exe);
             }
+            // A version dir without an executable is a leftover; replace it.
+            let _ = tokio::fs::remove_dir_all(&install_dir).await;
         }
 
         // Create directories
         tokio::fs::create_dir_all(&download_dir)
             .await
             .map_err(|e| format!("Failed to create download dir: {e}"))?;
-        tokio::fs::create_dir_all(&install_dir)
+        let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+        tokio::fs::create_dir_all(&staging_dir)
             .await
             .map_err(|e| format!("Failed to create install dir: {e}"))?;
 
-        // Download the archive
-        let archive_path = self
-            .download_archive(&binary_info.archive, &download_dir)
-            .await?;
+        // Reuse a cached archive, or download it
+        let cache_entry = self
+            .archive_cache
+            .entry_path(agent_id, version, binary_info);
+        let cached = {
+            let cache = self.archive_cache.clone();
+            let entry = cache_entry.clone();
+            let expected = binary_info.sha256.clone();
+            tokio::task::spawn_blocking(move || cache.lookup(&entry, expected.as_deref()))
+                .await
+                .map_err(|e| e.to_string())?
+        };
+        let from_cache = cached.is_some();
+        let mut archive_path = match cached {
+            Some(cached) => {
+                tracing::info!(
+                    "[AcpBinaryManager] Using cached archive {:?} for {} v{}",
+                    cached,
+                    agent_id,
+                    version
+                );
+                report(InstallPhase::Downloading, Some(100));
+                cached
+            }
+            None => {
+                report(InstallPhase::Downloading, Some(0));
+                self.download_archive(binary_info, &download_dir, cancel, &report_download)
+                    .await?
+            }
+        };
+        Self::check_cancelled(cancel)?;
 
         // Extract the archive
-        self.extract_archive(&archive_path, &install_dir).await?;
+        report(InstallPhase::Extracting, None);
+        if let Err(error) = self
+            .extract_archive(&archive_path, &staging_dir, cancel)
+            .await
+        {
+            if !from_cache || Self::is_cancelled_error(&error) {
+                return Err(error);
+            }
+            // A cached archive that no longer extracts is dropped and fetched again.
+            tracing::warn!(
+                "[AcpBinaryManager] Cached archive {:?} failed to extract ({}); downloading again",
+                archive_path,
+                error
+            );
+            self.archive_cache.remove(&cache_entry);
+            let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+            tokio::fs::create_dir_all(&staging_dir)
+                .await
+                .map_err(|e| format!("Failed to create install dir: {e}"))?;
+            report(InstallPhase::Downloading, Some(0));
+            archive_path = self
+                .download_archive(binary_info, &download_dir, cancel, &report_download)
+                .await?;
+            Self::check_cancelled(cancel)?;
+            report(InstallPhase::Extracting, None);
+            self.extract_archive(&archive_path, &staging_dir, cancel)
+                .await?;
+        }
+        let downloaded = archive_path != cache_entry;
+        Self::check_cancelled(cancel)?;
+        report(InstallPhase::Finalizing, None);
 
         // Find and prepare the executable
-        let exe_path = self
-            .find_executable(&install_dir, binary_info)
+        let staged_exe = self
+            .find_executable(&staging_dir, binary_info)
             .await
             .ok_or_else(|| "Could not find executable in extracted archive".to_string())?;
+        let exe_path = install_dir.join(
+            staged_exe
+                .strip_prefix(&staging_dir)
+                .map_err(|e| format!("Failed to resolve executable path: {e}"))?,
+        );
+
+        // Publish the fully extracted install
+        tokio::fs::rename(&staging_dir, &install_dir)
+            .await
+            .map_err(|e| format!("Failed to finalize install dir: {e}"))?;
 
         // Set executable permissions and remove quarantine
-        self.prepare_executable(&exe_path).await?;
+        if let Err(error) = self.prepare_executable(&exe_path).await {
+            // Don't leave an unusable install for the next call to find.
+            let _ = tokio::fs::remove_dir_all(&install_dir).await;
+            return Err(error);
+        }
 
-        // Clean up download directory
+        // Keep the archive for reinstalls, then clean up the download directory
+        if downloaded && self.archive_cache.is_enabled() {
+            let cache = self.archive_cache.clone();
+            let stored =
+                tokio::task::spawn_blocking(move || cache.store(&archive_path, &cache_entry))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|result| result);
+            if let Err(error) = stored {
+                tracing::warn!("[AcpBinaryManager] Failed to cache archive: {}", error);
+            }
+        }
         let _ = tokio::fs::remove_dir_all(&download_dir).await;
 
         tracing::info!(
@@ -102,130 +548,332 @@ impl AcpBinaryManager {
         Ok(exe_path)
     }
 
-    /// Download an archive from a URL.
-    async fn download_archive(&self, url: &str, download_dir: &Path) -> Result<PathBuf, String> {
-        tracing::info!("[AcpBinaryManager] Downloading from {}", url);
-
-        let response = reqwest::get(url)
+    /// Get or create the lock serializing installs of `agent_id`.
+    async fn install_lock(&self, agent_id: &str) -> Arc<Mutex<()>> {
+        self.download_locks
+            .lock()
             .await
-            .map_err(|e| format!("Failed to download: {e}"))?;
+            .entry(agent_id.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone()
+    }
 
-        if !response.status().is_success() {
-            return Err(format!(
-                "Download failed with status: {}",
-                response.status()
-            ));
+    fn staging_dir(install_dir: &Path) -> PathBuf {
+        let mut name = install_dir.file_name().unwrap_or_default().to_os_string();
+        name.push(".partial");
+        install_dir.with_file_name(name)
+    }
+
+    fn check_cancelled(cancel: &AtomicBool) -> Result<(), String> {
+        if cancel.load(Ordering::SeqCst) {
+            Err(CANCELLED_MESSAGE.to_string())
+        } else {
+            Ok(())
         }
+    }
 
-        // Determine filename from URL or Content-Disposition
-        let filename = url
-            .split('/')
-            .next_back()
-            .unwrap_or("archive")
-            .split('?')
-            .next()
-            .unwrap_or("archive");
+    /// Download `binary_info.archive` chunk by chunk, checking `cancel`
+    /// between chunks, then verify it against `binary_info.sha256`.
+    ///
+    /// Bytes go to a `.part` file that survives a failed attempt; the next
+    /// attempt asks for the rest with a `Range` request and appends it, or
+    /// starts over when the server sends the whole archive. A `416` whose
+    /// `Content-Range` length equals the `.part` size means the previous
+    /// attempt got every byte; the `.part` file is then verified and kept,
+    /// and only a digest mismatch starts over. The `.part` file only takes
+    /// the archive's name once its digest checks out.
+    ///
+    /// `on_progress` hears each whole-percent step, or every
+    /// `UNSIZED_PROGRESS_STEP_BYTES` when the size is unknown, and the final
+    /// byte count.
+    ///
+    /// Network errors and 5xx/429 responses are retried up to
+    /// `download_retries` times with exponential backoff; a retry resumes
+    /// from the `.part` file.
+    async fn download_archive(
+        &self,
+        binary_info: &BinaryInfo,
+        download_dir: &Path,
+        cancel: &AtomicBool,
+        on_progress: &(dyn Fn(DownloadProgress) + Send + Sync),
+    ) -> Result<PathBuf, String> {
+        let mut attempt = 0;
+        loop {
+            match Self::download_attempt(binary_info, download_dir, cancel, on_progress).await {
+                Ok(path) => return Ok(path),
+                Err(failure) if failure.retryable && attempt < self.download_retries => {
+                    attempt += 1;
+                    let delay = retry_delay(attempt);
+                    tracing::warn!(
+                        "[AcpBinaryManager] Download attempt {} of {} failed ({}); retrying in {:?}",
+                        attempt,
+                        self.download_retries + 1,
+                        failure.message,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    Self::check_cancelled(cancel)?;
+                }
+                Err(failure) => return Err(failure.message),
+            }
+        }
+    }
 
-        let archive_path = download_dir.join(filename);
+    async fn download_attempt(
+        binary_info: &BinaryInfo,
+        download_dir: &Path,
+        cancel: &AtomicBool,
+        on_progress: &(dyn Fn(DownloadProgress) + Send + Sync),
+    ) -> Result<PathBuf, DownloadFailure> {
+        let url = binary_info.archive.as_str();
+        let archive_path = download_dir.join(archive_filename(url));
+        let part_path = part_path(&archive_path);
+        let client = reqwest::Client::new();
 
-        let bytes = response
-            .bytes()
+        let mut offset = tokio::fs::metadata(&part_path)
             .await
-            .map_err(|e| format!("Failed to read response: {e}"))?;
+            .map(|meta| meta.len())
+            .unwrap_or(0);
+        let (mut response, offset) = loop {
+            let mut request = client.get(url);
+            if offset > 0 {
+                tracing::info!(
+                    "[AcpBinaryManager] Resuming download from {} at byte {}",
+                    url,
+                    offset
+                );
+                request = request.header(reqwest::header::RANGE, format!("bytes={offset}-"));
+            } else {
+                tracing::info!("[AcpBinaryManager] Downloading from {}", url);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| DownloadFailure::transient(format!("Failed to download: {e}")))?;
+
+            let status = response.status();
+            if offset > 0 && status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+                let length = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_RANGE)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(unsatisfied_range_length);
+                if length == Some(offset) {
+                    tracing::info!(
+                        "[AcpBinaryManager] {:?} already holds all {} bytes; verifying it",
+                        part_path,
+                        offset
+                    );
+                    match Self::finish_download(binary_info, &part_path, &archive_path).await {
+                        Ok(path) => {
+                            on_progress(DownloadProgress {
+                                downloaded: offset,
+                                total: Some(offset),
+                            });
+                            return Ok(path);
+                        }
+                        Err(error) if is_checksum_mismatch_error(&error) => {
+                            tracing::warn!("[AcpBinaryManager] {}", error);
+                        }
+                        Err(error) => return Err(error.into()),
+                    }
+                }
+                offset = 0;
+                continue;
+            }
+            if !status.is_success() {
+                let message = format!("Download failed with status: {status}");
+                return Err(if is_retryable_status(status) {
+                    DownloadFailure::transient(message)
+                } else {
+                    message.into()
+                });
+            }
+            let content_range = response
+                .headers()
+                .get(reqwest::header::CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            match resume_start(status, content_range.as_deref(), offset) {
+                Some(start) => break (response, start),
+                None if offset > 0 => offset = 0,
+                None => {
+                    return Err(format!(
+                        "Download failed: unexpected partial response ({})",
+                        content_range.as_deref().unwrap_or("no Content-Range")
+                    )
+                    .into())
+                }
+            }
+        };
+        if offset > 0 {
+            tracing::info!(
+                "[AcpBinaryManager] Server honored range; appending to {:?}",
+                part_path
+            );
+        }
 
-        tokio::fs::write(&archive_path, &bytes)
+        let mut file = if offset > 0 {
+            tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(&part_path)
+                .await
+        } else {
+            tokio::fs::File::create(&part_path).await
+        }
+        .map_err(|e| format!("Failed to write archive: {e}"))?;
+        let total = response
+            .content_length()
+            .filter(|len| *len > 0)
+            .map(|len| len + offset);
+        let mut download = DownloadProgress {
+            downloaded: offset,
+            total,
+        };
+        if total.is_none() || offset > 0 {
+            on_progress(download);
+        }
+        let mut reported = download;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| DownloadFailure::transient(format!("Failed to read response: {e}")))?
+        {
+            Self::check_cancelled(cancel)?;
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write archive: {e}"))?;
+            download.downloaded += chunk.len() as u64;
+            let due = match total {
+                Some(_) => download.percent() != reported.percent(),
+                None => download.downloaded - reported.downloaded >= UNSIZED_PROGRESS_STEP_BYTES,
+            };
+            if due {
+                reported = download;
+                on_progress(download);
+            }
+        }
+        if download != reported {
+            on_progress(download);
+        }
+        file.flush()
             .await
             .map_err(|e| format!("Failed to write archive: {e}"))?;
 
+        drop(file);
+
         tracing::info!(
             "[AcpBinaryManager] Downloaded {} bytes to {:?}",
-            bytes.len(),
-            archive_path
+            download.downloaded,
+            part_path
         );
-        Ok(archive_path)
+        Self::finish_download(binary_info, &part_path, &archive_path)
+            .await
+            .map_err(DownloadFailure::from)
+    }
+
+    /// Verify a complete `.part` file and give it the archive's name. The
+    /// `.part` file is deleted on a digest mismatch.
+    async fn finish_download(
+        binary_info: &BinaryInfo,
+        part_path: &Path,
+        archive_path: &Path,
+    ) -> Result<PathBuf, String> {
+        let expected = binary_info.sha256.clone();
+        let path = part_path.to_path_buf();
+        tokio::task::spawn_blocking(move || verify_archive_sha256(&path, expected.as_deref()))
+            .await
+            .map_err(|e| format!("Failed to verify archive: {e}"))??;
+        tokio::fs::rename(part_path, archive_path)
+            .await
+            .map_err(|e| format!("Failed to finalize archive: {e}"))?;
+        Ok(archive_path.to_path_buf())
     }
 
     /// Extract an archive to a directory.
-    async fn extract_archive(&self, archive_path: &Path, install_dir: &Path) -> Result<(), String> {
+    async fn extract_archive(
+        &self,
+        archive_path: &Path,
+        install_dir: &Path,
+        cancel: &Arc<AtomicBool>,
+    ) -> Result<(), String> {
         let archive_str = archive_path.to_string_lossy().to_lowercase();
         let archive_path = archive_path.to_path_buf();
         let install_dir = install_dir.to_path_buf();
+        let cancel = cancel.clone();
 
         // Run extraction in blocking task
         tokio::task::spawn_blocking(move || {
-            if archive_str.ends_with(".zip") {
-                Self::extract_zip(&archive_path, &install_dir)
-            } else if archive_str.ends_with(".tar.gz") || archive_str.ends_with(".tgz") {
-                Self::extract_tar_gz(&archive_path, &install_dir)
-            } else if archive_str.ends_with(".tar.bz2") || archive_str.ends_with(".tbz2") {
-                Self::extract_tar_bz2(&archive_path, &install_dir)
-            } else if archive_str.ends_with(".tar") {
-                Self::extract_tar(&archive_path, &install_dir)
-            } else {
-                // Assume it's a raw binary
-                let filename = archive_path.file_name().unwrap_or_default();
-                let dest = install_dir.join(filename);
-                std::fs::copy(&archive_path, &dest)
-                    .map_err(|e| format!("Failed to copy binary: {e}"))?;
-                Ok(())
+            match archive_format(&archive_str) {
+                Some("zip") => Self::extract_zip(&archive_path, &install_dir, &cancel),
+                Some("tar.gz") => Self::extract_tar_gz(&archive_path, &install_dir, &cancel),
+                Some("tar.bz2") => Self::extract_tar_bz2(&archive_path, &install_dir, &cancel),
+                Some("tar.xz") => Self::extract_tar_xz(&archive_path, &install_dir, &cancel),
+                Some("tar.zst") => Self::extract_tar_zst(&archive_path, &install_dir, &cancel),
+                Some("tar") => Self::extract_tar(&archive_path, &install_dir, &cancel),
+                _ => {
+                    // Assume it's a raw binary
+                    let filename = archive_path.file_name().unwrap_or_default();
+                    let dest = install_dir.join(filename);
+                    std::fs::copy(&archive_path, &dest)
+                        .map_err(|e| format!("Failed to copy binary: {e}"))?;
+                    Ok(())
+                }
             }
         })
         .await
         .map_err(|e| format!("Extract task failed: {e}"))?
     }
 
-    fn extract_zip(archive: &Path, dest: &Path) -> Result<(), String> {
+    fn extract_zip(archive: &Path, dest: &Path, cancel: &AtomicBool) -> Result<(), String> {
         let file = std::fs::File::open(archive).map_err(|e| format!("Failed to open zip: {e}"))?;
         let mut archive =
             zip::ZipArchive::new(file).map_err(|e| format!("Failed to read zip: {e}"))?;
-
-        for i in 0..archive.len() {
-            let mut file = archive
-                .by_index(i)
-                .map_err(|e| format!("Failed to read zip entry: {e}"))?;
-            let outpath = dest.join(file.mangled_name());
-
-            if file.name().ends_with('/') {
-                std::fs::create_dir_all(&outpath).ok();
-            } else {
-                if let Some(p) = outpath.parent() {
-                    std::fs::create_dir_all(p).ok();
-                }
-                let mut outfile = std::fs::File::create(&outpath)
-                    .map_err(|e| format!("Failed to create file: {e}"))?;
-                std::io::copy(&mut file, &mut outfile)
-                    .map_err(|e| format!("Failed to extract file: {e}"))?;
-            }
-        }
-        Ok(())
+        crate::archive::extract_zip(&mut archive, dest, || Self::check_cancelled(cancel))
     }
 
-    fn extract_tar_gz(archive: &Path, dest: &Path) -> Result<(), String> {
+    fn extract_tar_gz(archive: &Path, dest: &Path, cancel: &AtomicBool) -> Result<(), String> {
         let file =
             std::fs::File::open(archive).map_err(|e| format!("Failed to open tar.gz: {e}"))?;
         let gz = flate2::read::GzDecoder::new(file);
-        let mut tar = tar::Archive::new(gz);
-        tar.unpack(dest)
-            .map_err(|e| format!("Failed to extract tar.gz: {e}"))?;
-        Ok(())
+        crate::archive::extract_tar(tar::Archive::new(gz), dest, || {
+            Self::check_cancelled(cancel)
+        })
     }
 
-    fn extract_tar_bz2(archive: &Path, dest: &Path) -> Result<(), String> {
+    fn extract_tar_bz2(archive: &Path, dest: &Path, cancel: &AtomicBool) -> Result<(), String> {
         let file =
             std::fs::File::open(archive).map_err(|e| format!("Failed to open tar.bz2: {e}"))?;
         let bz2 = bzip2::read::BzDecoder::new(file);
-        let mut tar = tar::Archive::new(bz2);
-        tar.unpack(dest)
-            .map_err(|e| format!("Failed to extract tar.bz2: {e}"))?;
-        Ok(())
+        crate::archive::extract_tar(tar::Archive::new(bz2), dest, || {
+            Self::check_cancelled(cancel)
+        })
+    }
+
+    fn extract_tar_xz(archive: &Path, dest: &Path, cancel: &AtomicBool) -> Result<(), String> {
+        let file =
+            std::fs::File::open(archive).map_err(|e| format!("Failed to open tar.xz: {e}"))?;
+        let xz = xz2::read::XzDecoder::new(file);
+        crate::archive::extract_tar(tar::Archive::new(xz), dest, || {
+            Self::check_cancelled(cancel)
+        })
+    }
+
+    fn extract_tar_zst(archive: &Path, dest: &Path, cancel: &AtomicBool) -> Result<(), String> {
+        let file =
+            std::fs::File::open(archive).map_err(|e| format!("Failed to open tar.zst: {e}"))?;
+        let zst = zstd::stream::read::Decoder::new(file)
+            .map_err(|e| format!("Failed to read tar.zst: {e}"))?;
+        crate::archive::extract_tar(tar::Archive::new(zst), dest, || {
+            Self::check_cancelled(cancel)
+        })
     }
 
-    fn extract_tar(archive: &Path, dest: &Path) -> Result<(), String> {
+    fn extract_tar(archive: &Path, dest: &Path, cancel: &AtomicBool) -> Result<(), String> {
         let file = std::fs::File::open(archive).map_err(|e| format!("Failed to open tar: {e}"))?;
-        let mut tar = tar::Archive::new(file);
-        tar.unpack(dest)
-            .map_err(|e| format!("Failed to extract tar: {e}"))?;
-        Ok(())
+        crate::archive::extract_tar(tar::Archive::new(file), dest, || {
+            Self::check_cancelled(cancel)
+        })
     }
 
     /// Find the executable in the install directory.
@@ -239,7 +887,7 @@ impl AcpBinaryManager {
             // cmd might be "./codex-acp" or "codex-acp", strip the "./" prefix
             let exe_name = cmd.strip_prefix("./").unwrap_or(cmd);
             let direct = install_dir.join(exe_name);
-            if direct.exists() {
+            if resolve_entry_file(install_dir, &direct).is_some() {
                 return Some(direct);
             }
             // Search recursively
@@ -253,12 +901,14 @@ impl AcpBinaryManager {
 
         while let Ok(Some(entry)) = entries.next_entry().await {
             let path = entry.path();
-            if path.is_file() {
+            // A symlink is judged by its target, so `tool -> tool-1.2.3` is
+            // picked up like the versioned file itself.
+            if let Some(target) = resolve_entry_file(install_dir, &path) {
                 // Check if it's executable (on Unix) or has no extension (likely binary)
                 #[cfg(unix)]
                 {
                     use std::os::unix::fs::PermissionsExt;
-                    if let Ok(meta) = path.metadata() {
+                    if let Ok(meta) = target.metadata() {
                         if meta.permissions().mode() & 0o111 != 0 {
                             return Some(path);
                         }
@@ -281,9 +931,14 @@ impl AcpBinaryManager {
             if let Ok(mut entries) = tokio::fs::read_dir(&current).await {
                 while let Ok(Some(entry)) = entries.next_entry().await {
                     let path = entry.path();
-                    if path.is_dir() {
+                    // `file_type` does not follow symlinks, so linked
+                    // directories are never descended into.
+                    let is_dir = entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false);
+                    if is_dir {
                         stack.push(path);
-                    } else if path.file_name().map(|n| n == name).unwrap_or(false) {
+                    } else if path.file_name().map(|n| n == name).unwrap_or(false)
+                        && resolve_entry_file(dir, &path).is_some()
+                    {
                         return Some(path);
                     }
                 }
@@ -294,6 +949,13 @@ impl AcpBinaryManager {
 
     /// Prepare the executable (set permissions, remove quarantine).
     async fn prepare_executable(&self, _exe_path: &Path) -> Result<(), String> {
+        if self.check_executable_format {
+            let path = _exe_path.to_path_buf();
+            tokio::task::spawn_blocking(move || check_executable_format(&path))
+                .await
+                .map_err(|e| format!("Failed to check executable: {e}"))??;
+        }
+
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
@@ -320,7 +982,19 @@ impl AcpBinaryManager {
         Ok(())
     }
 
-    /// Uninstall a binary agent.
+    /// Remove one installed version of an agent, e.g. after it failed
+    /// verification.
+    pub async fn remove_version(&self, agent_id: &str, version: &str) -> Result<(), String> {
+        let version_dir = self.paths.agent_version_dir(agent_id, version);
+        if version_dir.exists() {
+            tokio::fs::remove_dir_all(&version_dir)
+                .await
+                .map_err(|e| format!("Failed to remove version directory: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Uninstall a binary or git agent.
     pub async fn uninstall(&self, agent_id: &str) -> Result<(), String> {
         let agent_dir = self.paths.agent_dir(agent_id);
         if agent_dir.exists() {
@@ -331,3 +1005,607 @@ impl AcpBinaryManager {
         Ok(())
     }
 }
+
+/// Upper bound on the `--version` run of `verify_executable`.
+pub const DEFAULT_VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Run `exe --version` and require it to exit successfully within `timeout`,
+/// catching binaries that cannot run here (wrong architecture, missing shared
+/// libraries). Returns the first line it printed, stdout before stderr, as the
+/// detected version.
+pub async fn verify_executable(exe: &Path, timeout: Duration) -> Result<Option<String>, String> {
+    let child = tokio::process::Command::new(exe)
+        .arg("--version")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {e}", exe.display()))?;
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| {
+            format!(
+                "{} --version did not exit within {}s",
+                exe.display(),
+                timeout.as_secs()
+            )
+        })?
+        .map_err(|e| format!("Failed to run {}: {e}", exe.display()))?;
+
+    let first_line = |bytes: &[u8]| {
+        String::from_utf8_lossy(bytes)
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+    };
+    if !output.status.success() {
+        return Err(format!(
+            "{} --version failed ({}){}",
+            exe.display(),
+            output.status,
+            first_line(&output.stderr)
+                .map(|line| format!(": {line}"))
+                .unwrap_or_default()
+        ));
+    }
+    Ok(first_line(&output.stdout).or_else(|| first_line(&output.stderr)))
+}
+
+/// The kind of executable `header` (a file's first bytes) starts like, if
+/// any: a native binary or a script with a shebang.
+pub fn executable_format(header: &[u8]) -> Option<&'static str> {
+    const MAGICS: &[(&[u8], &str)] = &[
+        (b"\x7fELF", "ELF"),
+        (&[0xfe, 0xed, 0xfa, 0xce], "Mach-O"),
+        (&[0xfe, 0xed, 0xfa, 0xcf], "Mach-O"),
+        (&[0xce, 0xfa, 0xed, 0xfe], "Mach-O"),
+        (&[0xcf, 0xfa, 0xed, 0xfe], "Mach-O"),
+        (&[0xca, 0xfe, 0xba, 0xbe], "Mach-O universal"),
+        (&[0xca, 0xfe, 0xba, 0xbf], "Mach-O universal"),
+        (b"MZ", "PE"),
+        (b"#!", "script"),
+    ];
+    MAGICS
+        .iter()
+        .find(|(magic, _)| header.starts_with(magic))
+        .map(|(_, format)| *format)
+}
+
+/// Fail unless `path` starts like an executable (see [`executable_format`]).
+fn check_executable_format(path: &Path) -> Result<(), String> {
+    let mut header = [0u8; 64];
+    let len = std::fs::File::open(path)
+        .and_then(|mut file| std::io::Read::read(&mut file, &mut header))
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let header = &header[..len];
+    if executable_format(header).is_some() {
+        return Ok(());
+    }
+    let text = String::from_utf8_lossy(header);
+    let looks_like = if text.trim_start().starts_with('<') {
+        "an HTML or XML document".to_string()
+    } else if len == 0 {
+        "an empty file".to_string()
+    } else {
+        format!("{:02x?}", &header[..len.min(8)])
+    };
+    Err(format!(
+        "{} is not an executable (it looks like {looks_like}); the download may be an \
+         error page. Set ROUTA_ACP_SKIP_EXECUTABLE_CHECK=1 to install it anyway",
+        path.display()
+    ))
+}
+
+/// Where an archive download in progress is written: `<archive>.part`.
+fn part_path(archive: &Path) -> PathBuf {
+    let mut name = archive.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    archive.with_file_name(name)
+}
+
+/// Byte offset the body of a response to `Range: bytes={requested}-` starts
+/// at: `requested` for a 206 covering it, 0 for a full 200 response, and
+/// `None` for a partial response that does not line up with the `.part` file.
+fn resume_start(
+    status: reqwest::StatusCode,
+    content_range: Option<&str>,
+    requested: u64,
+) -> Option<u64> {
+    if status != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Some(0);
+    }
+    let start = content_range?
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    (start == requested).then_some(start)
+}
+
+/// Full length from the `Content-Range: bytes */{length}` of a 416 response.
+fn unsatisfied_range_length(content_range: &str) -> Option<u64> {
+    content_range
+        .strip_prefix("bytes */")?
+        .trim()
+        .parse::<u64>()
+        .ok()
+}
+
+const CHECKSUM_MISMATCH_PREFIX: &str = "Checksum mismatch";
+
+/// Whether an install error came from an archive failing its sha256 check.
+fn is_checksum_mismatch_error(message: &str) -> bool {
+    message.starts_with(CHECKSUM_MISMATCH_PREFIX)
+}
+
+/// Check `archive` against the registry's SHA-256 digest, deleting it when
+/// the digest does not match. Without a digest the archive is accepted with
+/// a warning, since its integrity cannot be verified.
+pub fn verify_archive_sha256(archive: &Path, expected: Option<&str>) -> Result<(), String> {
+    let Some(expected) = expected.map(str::trim).filter(|sha| !sha.is_empty()) else {
+        tracing::warn!(
+            "[AcpBinaryManager] No sha256 published for {:?}; integrity is unverified",
+            archive
+        );
+        return Ok(());
+    };
+    let actual = sha256_file(archive)?;
+    if actual.eq_ignore_ascii_case(expected) {
+        return Ok(());
+    }
+    let _ = std::fs::remove_file(archive);
+    Err(format!(
+        "{CHECKSUM_MISMATCH_PREFIX} for {}: expected sha256 {}, got {}; the download was deleted",
+        archive.display(),
+        expected.to_ascii_lowercase(),
+        actual
+    ))
+}
+
+/// Lower-case hex SHA-256 digest of the file at `path`.
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open {} for hashing: {e}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = std::io::Read::read(&mut file, &mut buf)
+            .map_err(|e| format!("Failed to hash {}: {e}", path.display()))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// The regular file `path` refers to: `path` itself, or for a symlink its
+/// target, which must resolve to a file inside `install_dir`. `None` for
+/// anything else, including links that point outside the install.
+fn resolve_entry_file(install_dir: &Path, path: &Path) -> Option<PathBuf> {
+    let meta = std::fs::symlink_metadata(path).ok()?;
+    if meta.file_type().is_file() {
+        return Some(path.to_path_buf());
+    }
+    if !meta.file_type().is_symlink() {
+        return None;
+    }
+    let root = install_dir.canonicalize().ok()?;
+    let target = path.canonicalize().ok()?;
+    if !target.starts_with(&root) {
+        tracing::warn!(
+            "[AcpBinaryManager] Ignoring {:?}: symlink points outside {:?}",
+            path,
+            install_dir
+        );
+        return None;
+    }
+    target.is_file().then_some(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_format_matches_supported_suffixes() {
+        assert_eq!(archive_format("agent.zip"), Some("zip"));
+        assert_eq!(archive_format("agent.tar.gz"), Some("tar.gz"));
+        assert_eq!(archive_format("agent.tgz"), Some("tar.gz"));
+        assert_eq!(archive_format("agent.tbz2"), Some("tar.bz2"));
+        assert_eq!(archive_format("agent.tar"), Some("tar"));
+        assert_eq!(archive_format("agent.tar.xz"), Some("tar.xz"));
+        assert_eq!(archive_format("agent.txz"), Some("tar.xz"));
+        assert_eq!(archive_format("agent.tar.zst"), Some("tar.zst"));
+        assert_eq!(archive_format("agent.tzst"), Some("tar.zst"));
+        assert_eq!(archive_format("agent"), None);
+    }
+
+    fn tar_with_agent() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "bin/agent", &b"agent"[..])
+            .expect("append agent");
+        builder.into_inner().expect("finish tar")
+    }
+
+    #[test]
+    fn extracts_tar_xz_and_tar_zst_archives() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let tar = tar_with_agent();
+
+        let xz_path = temp.path().join("agent.tar.xz");
+        let mut xz = xz2::write::XzEncoder::new(Vec::new(), 6);
+        std::io::Write::write_all(&mut xz, &tar).expect("compress xz");
+        std::fs::write(&xz_path, xz.finish().expect("finish xz")).expect("write xz");
+
+        let zst_path = temp.path().join("agent.tzst");
+        let zst = zstd::stream::encode_all(&tar[..], 3).expect("compress zst");
+        std::fs::write(&zst_path, zst).expect("write zst");
+
+        for (archive, extract) in [
+            (
+                &xz_path,
+                AcpBinaryManager::extract_tar_xz
+                    as fn(&Path, &Path, &AtomicBool) -> Result<(), String>,
+            ),
+            (&zst_path, AcpBinaryManager::extract_tar_zst),
+        ] {
+            let dest = temp.path().join(format!(
+                "install-{}",
+                archive.file_name().unwrap().to_string_lossy()
+            ));
+            extract(archive, &dest, &AtomicBool::new(false)).expect("extract archive");
+            assert_eq!(
+                std::fs::read(dest.join("bin/agent")).expect("read agent"),
+                b"agent"
+            );
+        }
+    }
+
+    /// Serve `body` to every request on a local port and return the URL of
+    /// `name` on it.
+    async fn serve_archive(name: &str, body: Vec<u8>) -> String {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind archive server");
+        let addr = listener.local_addr().expect("server addr");
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let body = body.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => request.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    let head = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    );
+                    let _ = socket.write_all(head.as_bytes()).await;
+                    let _ = socket.write_all(&body).await;
+                });
+            }
+        });
+        format!("http://{addr}/{name}")
+    }
+
+    #[tokio::test]
+    async fn cancelled_install_leaves_no_install_dir_behind() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        let script = b"#!/bin/sh\necho agent\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(script.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "agent", &script[..])
+            .expect("append agent");
+        let archive = builder
+            .into_inner()
+            .and_then(|encoder| encoder.finish())
+            .expect("finish archive");
+        let info = BinaryInfo {
+            archive: serve_archive("agent.tar.gz", archive).await,
+            cmd: Some("./agent".to_string()),
+            sha256: None,
+        };
+
+        let temp = tempfile::tempdir().expect("tempdir");
+        let paths = AcpPaths::with_base_dir(temp.path().join("acp"));
+        let manager = Arc::new(
+            AcpBinaryManager::new(paths.clone())
+                .with_archive_cache(ArchiveCache::disabled(temp.path().join("cache")))
+                .with_download_retries(0),
+        );
+        let install_dir = paths.agent_version_dir("agent", "1.0.0");
+
+        // Cancel as soon as the install reaches `phase`.
+        for phase in [InstallPhase::Downloading, InstallPhase::Extracting] {
+            let cancel_at = {
+                let manager = manager.clone();
+                Arc::new(move |progress: InstallProgress| {
+                    if progress.phase == phase {
+                        let cancellations = manager.cancellations.try_lock().expect("flags");
+                        cancellations["agent"].store(true, Ordering::SeqCst);
+                    }
+                }) as InstallProgressFn
+            };
+            let error = manager
+                .install_binary_with_progress("agent", "1.0.0", &info, Some(cancel_at))
+                .await
+                .expect_err("install should be cancelled");
+            assert!(AcpBinaryManager::is_cancelled_error(&error), "{error}");
+            assert!(!install_dir.exists(), "cancelled at {phase:?}");
+            assert!(!AcpBinaryManager::staging_dir(&install_dir).exists());
+            assert!(!paths.agent_download_dir("agent", "1.0.0").exists());
+            assert!(!manager.is_installing("agent").await);
+        }
+
+        let exe = manager
+            .install_binary("agent", "1.0.0", &info)
+            .await
+            .expect("install after cancellations");
+        assert_eq!(exe, install_dir.join("agent"));
+    }
+
+    #[test]
+    fn download_progress_reports_bytes_and_percent() {
+        let sized = DownloadProgress {
+            downloaded: 25 * 1024 * 1024,
+            total: Some(100 * 1024 * 1024),
+        };
+        assert_eq!(sized.percent(), Some(25));
+        assert_eq!(
+            serde_json::to_value(InstallProgress::downloading(sized)).unwrap(),
+            serde_json::json!({
+                "phase": "downloading",
+                "percent": 25,
+                "downloadedBytes": 25 * 1024 * 1024,
+                "totalBytes": 100 * 1024 * 1024,
+            })
+        );
+
+        let unsized_download = DownloadProgress {
+            downloaded: 3,
+            total: None,
+        };
+        assert_eq!(unsized_download.percent(), None);
+        assert_eq!(
+            serde_json::to_value(InstallProgress::new(InstallPhase::Extracting, None)).unwrap(),
+            serde_json::json!({ "phase": "extracting", "percent": null })
+        );
+    }
+
+    #[test]
+    fn verify_archive_sha256_accepts_matching_digest_and_deletes_mismatch() {
+        // sha256("hello world")
+        const DIGEST: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+        let temp = tempfile::tempdir().expect("tempdir");
+        let archive = temp.path().join("agent.tar.gz");
+        std::fs::write(&archive, b"hello world").expect("write fixture");
+
+        assert_eq!(sha256_file(&archive).as_deref(), Ok(DIGEST));
+        assert_eq!(verify_archive_sha256(&archive, Some(DIGEST)), Ok(()));
+        assert_eq!(
+            verify_archive_sha256(&archive, Some(&DIGEST.to_ascii_uppercase())),
+            Ok(())
+        );
+        assert_eq!(verify_archive_sha256(&archive, None), Ok(()));
+        assert!(archive.exists());
+
+        let error = verify_archive_sha256(&archive, Some(&"0".repeat(64))).unwrap_err();
+        assert!(error.contains("Checksum mismatch"), "{error}");
+        assert!(error.contains(DIGEST), "{error}");
+        assert!(!archive.exists(), "mismatched archive should be deleted");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn find_executable_selects_symlink_to_versioned_binary() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().expect("tempdir");
+        let archive = temp.path().join("tool.tar.gz");
+        {
+            let encoder = flate2::write::GzEncoder::new(
+                std::fs::File::create(&archive).expect("create archive"),
+                flate2::Compression::default(),
+            );
+            let mut builder = tar::Builder::new(encoder);
+            let script = b"#!/bin/sh\necho tool-1.2.3\n";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(script.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, "tool-1.2.3", &script[..])
+                .expect("append binary");
+            let mut link = tar::Header::new_gnu();
+            link.set_entry_type(tar::EntryType::Symlink);
+            link.set_size(0);
+            link.set_mode(0o777);
+            link.set_cksum();
+            builder
+                .append_link(&mut link, "tool", "tool-1.2.3")
+                .expect("append symlink");
+            let mut outside = tar::Header::new_gnu();
+            outside.set_entry_type(tar::EntryType::Symlink);
+            outside.set_size(0);
+            outside.set_cksum();
+            builder
+                .append_link(&mut outside, "escape", "/bin/sh")
+                .expect("append escaping symlink");
+            builder
+                .into_inner()
+                .and_then(|encoder| encoder.finish())
+                .expect("finish archive");
+        }
+
+        let install_dir = temp.path().join("install");
+        std::fs::create_dir_all(&install_dir).expect("create install dir");
+        AcpBinaryManager::extract_tar_gz(&archive, &install_dir, &AtomicBool::new(false))
+            .expect("extract archive");
+
+        let manager = AcpBinaryManager::new(AcpPaths::with_base_dir(temp.path().join("acp")));
+        let info = BinaryInfo {
+            archive: "https://example.com/tool.tar.gz".to_string(),
+            cmd: Some("./tool".to_string()),
+            sha256: None,
+        };
+        let exe = manager
+            .find_executable(&install_dir, &info)
+            .await
+            .expect("symlinked executable should be found");
+        assert_eq!(exe, install_dir.join("tool"));
+        assert!(std::fs::symlink_metadata(&exe)
+            .expect("symlink metadata")
+            .file_type()
+            .is_symlink());
+        assert_ne!(
+            exe.metadata().expect("target").permissions().mode() & 0o111,
+            0
+        );
+        let output = std::process::Command::new(&exe)
+            .output()
+            .expect("run symlinked executable");
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "tool-1.2.3");
+
+        assert!(resolve_entry_file(&install_dir, &install_dir.join("escape")).is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn verify_executable_reports_version_and_failures() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().expect("tempdir");
+        let write_script = |name: &str, body: &str| {
+            let path = temp.path().join(name);
+            std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).expect("write script");
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+                .expect("chmod script");
+            path
+        };
+
+        let ok = write_script("ok", "echo 'tool 1.2.3'");
+        assert_eq!(
+            verify_executable(&ok, DEFAULT_VERIFY_TIMEOUT).await,
+            Ok(Some("tool 1.2.3".to_string()))
+        );
+
+        let failing = write_script("failing", "echo 'missing libfoo.so' >&2; exit 127");
+        let error = verify_executable(&failing, DEFAULT_VERIFY_TIMEOUT)
+            .await
+            .unwrap_err();
+        assert!(error.contains("missing libfoo.so"), "{error}");
+
+        let hanging = write_script("hanging", "sleep 5");
+        let error = verify_executable(&hanging, Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert!(error.contains("did not exit"), "{error}");
+    }
+
+    #[test]
+    fn resume_start_accepts_only_matching_partial_responses() {
+        use reqwest::StatusCode;
+
+        assert_eq!(resume_start(StatusCode::OK, None, 0), Some(0));
+        assert_eq!(
+            resume_start(StatusCode::OK, None, 4096),
+            Some(0),
+            "a full response restarts the download"
+        );
+        assert_eq!(
+            resume_start(
+                StatusCode::PARTIAL_CONTENT,
+                Some("bytes 4096-9999/10000"),
+                4096
+            ),
+            Some(4096)
+        );
+        assert_eq!(
+            resume_start(
+                StatusCode::PARTIAL_CONTENT,
+                Some("bytes 0-9999/10000"),
+                4096
+            ),
+            None
+        );
+        assert_eq!(resume_start(StatusCode::PARTIAL_CONTENT, None, 4096), None);
+        assert_eq!(unsatisfied_range_length("bytes */10000"), Some(10000));
+        assert_eq!(unsatisfied_range_length("bytes 0-99/10000"), None);
+        assert_eq!(unsatisfied_range_length("bytes */*"), None);
+        assert_eq!(
+            part_path(Path::new("/dl/agent.tar.gz")),
+            Path::new("/dl/agent.tar.gz.part")
+        );
+    }
+
+    #[test]
+    fn retries_only_transient_statuses_with_growing_backoff() {
+        use reqwest::StatusCode;
+
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::FORBIDDEN));
+
+        for attempt in 1..=3 {
+            let base = RETRY_BASE_DELAY * (1 << (attempt - 1));
+            let delay = retry_delay(attempt);
+            assert!(delay >= base && delay <= base * 3 / 2, "{delay:?}");
+        }
+        assert!(retry_delay(30) <= RETRY_MAX_DELAY * 3 / 2);
+    }
+
+    #[test]
+    fn executable_format_recognizes_binaries_and_scripts() {
+        assert_eq!(executable_format(b"\x7fELF\x02\x01"), Some("ELF"));
+        assert_eq!(
+            executable_format(&[0xcf, 0xfa, 0xed, 0xfe, 0x0c]),
+            Some("Mach-O")
+        );
+        assert_eq!(executable_format(b"MZ\x90\x00"), Some("PE"));
+        assert_eq!(executable_format(b"#!/usr/bin/env node\n"), Some("script"));
+        assert_eq!(executable_format(b"<!DOCTYPE html>"), None);
+        assert_eq!(executable_format(b""), None);
+    }
+
+    #[test]
+    fn check_executable_format_explains_html_pages() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let page = temp.path().join("agent");
+        std::fs::write(&page, "\n<html><body>403 Forbidden</body></html>").expect("write page");
+        let error = check_executable_format(&page).unwrap_err();
+        assert!(error.contains("HTML"), "{error}");
+        assert!(error.contains("ROUTA_ACP_SKIP_EXECUTABLE_CHECK"), "{error}");
+
+        let script = temp.path().join("tool");
+        std::fs::write(&script, "#!/bin/sh\necho ok\n").expect("write script");
+        assert_eq!(check_executable_format(&script), Ok(()));
+    }
+}
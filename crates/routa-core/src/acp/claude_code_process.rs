@@ -119,6 +119,9 @@ pub struct ClaudeCodeConfig {
     pub append_system_prompt: Option<String>,
     /// Optional allowlist for Claude built-in tools. Empty disables all built-ins.
     pub allowed_tools: Option<Vec<String>>,
+    /// Variables set on top of the inherited environment (the preset's API keys).
+    pub env: Vec<(String, String)>,
+    pub resource_limits: super::ResourceLimits,
 }
 
 impl Default for ClaudeCodeConfig {
@@ -131,6 +134,8 @@ impl Default for ClaudeCodeConfig {
             mcp_configs: Vec::new(),
             append_system_prompt: None,
             allowed_tools: None,
+            env: Vec::new(),
+            resource_limits: super::ResourceLimits::default(),
         }
     }
 }
@@ -247,6 +252,7 @@ impl ClaudeCodeProcess {
         cmd.current_dir(&self.config.cwd);
         cmd.env("PATH", crate::shell_env::full_path());
         cmd.env("NODE_NO_READLINE", "1");
+        cmd.envs(self.config.env.iter().map(|(key, value)| (key, value)));
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
@@ -254,11 +260,14 @@ impl ClaudeCodeProcess {
         #[cfg(windows)]
         cmd.as_std_mut().creation_flags(CREATE_NO_WINDOW);
 
+        self.config.resource_limits.apply(&mut cmd);
+
         tracing::info!(
-            "[ClaudeCode:{}] Spawning: {} -p --output-format stream-json ... (cwd: {})",
+            "[ClaudeCode:{}] Spawning: {} -p --output-format stream-json ... (cwd: {}, env: [{}])",
             self.config.display_name,
             resolved_command,
-            self.config.cwd
+            self.config.cwd,
+            super::process::redacted_env_list(&self.config.env)
         );
 
         let mut child = cmd.spawn().map_err(|e| {
@@ -0,0 +1,261 @@
+//! History-window policy for long-lived sessions.
+//!
+//! Session history grows with every `session/update`. When a policy is set,
+//! the oldest turns are dropped once the session exceeds the configured turn
+//! count or estimated token budget. The first turn, which carries the
+//! specialist/coordinator task context, is never dropped. Dropped turns are
+//! summarized by a single `history_truncated` entry placed after it.
+
+use serde::Serialize;
+
+/// Session update kind of the marker entry that summarizes dropped turns.
+pub const HISTORY_TRUNCATED_UPDATE: &str = "history_truncated";
+
+/// Rough characters-per-token ratio used for estimates.
+const CHARS_PER_TOKEN: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HistoryWindowPolicy {
+    pub max_turns: Option<usize>,
+    pub max_tokens: Option<usize>,
+}
+
+/// Estimated size of a session's history, reported in session info.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextSize {
+    pub turns: usize,
+    pub estimated_tokens: usize,
+    pub dropped_turns: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+}
+
+impl HistoryWindowPolicy {
+    pub fn is_active(&self) -> bool {
+        self.max_turns.is_some() || self.max_tokens.is_some()
+    }
+
+    /// Drop the oldest turns after the prefix until `history` fits the policy.
+    /// Returns the number of turns dropped.
+    pub fn apply(&self, session_id: &str, history: &mut Vec<serde_json::Value>) -> usize {
+        if !self.is_active() {
+            return 0;
+        }
+
+        let turns = turn_ranges(history);
+        // The first turn is the task-context prefix; only later turns are droppable.
+        let Some(prefix_end) = turns.first().map(|range| range.1) else {
+            return 0;
+        };
+        let droppable: Vec<_> = turns
+            .iter()
+            .skip(1)
+            .filter(|(start, _)| !is_truncation_marker(&history[*start]))
+            .copied()
+            .collect();
+
+        let mut total_tokens = estimate_tokens(history);
+        let mut remaining_turns = droppable.len();
+        let mut drop_until = prefix_end;
+        let mut dropped_turns = 0;
+        let mut dropped_tokens = 0;
+        for (start, end) in &droppable {
+            let over_turns = self.max_turns.is_some_and(|max| remaining_turns > max);
+            let over_tokens = self.max_tokens.is_some_and(|max| total_tokens > max);
+            // Always keep the latest turn, even if it alone exceeds the budget.
+            if !(over_turns || over_tokens) || remaining_turns <= 1 {
+                break;
+            }
+            let turn_tokens = estimate_tokens(&history[*start..*end]);
+            total_tokens = total_tokens.saturating_sub(turn_tokens);
+            dropped_tokens += turn_tokens;
+            remaining_turns -= 1;
+            dropped_turns += 1;
+            drop_until = *end;
+        }
+        if dropped_turns == 0 {
+            return 0;
+        }
+
+        let mut marker = None;
+        let removed: Vec<_> = history.drain(prefix_end..drop_until).collect();
+        for entry in removed {
+            if is_truncation_marker(&entry) {
+                marker = Some(entry);
+            }
+        }
+        let (previous_turns, previous_tokens) = marker
+            .as_ref()
+            .and_then(|entry| entry.get("update"))
+            .map(|update| {
+                (
+                    update["droppedTurns"].as_u64().unwrap_or(0) as usize,
+                    update["droppedEstimatedTokens"].as_u64().unwrap_or(0) as usize,
+                )
+            })
+            .unwrap_or((0, 0));
+        history.insert(
+            prefix_end,
+            serde_json::json!({
+                "sessionId": session_id,
+                "update": {
+                    "sessionUpdate": HISTORY_TRUNCATED_UPDATE,
+                    "droppedTurns": previous_turns + dropped_turns,
+                    "droppedEstimatedTokens": previous_tokens + dropped_tokens,
+                    "timestamp": chrono::Utc::now().to_rfc3339(),
+                }
+            }),
+        );
+        dropped_turns
+    }
+
+    /// Describe the current size of `history` against this policy.
+    pub fn context_size(&self, history: &[serde_json::Value]) -> ContextSize {
+        let turns = turn_ranges(history);
+        let dropped_turns = history
+            .iter()
+            .find(|entry| is_truncation_marker(entry))
+            .and_then(|entry| entry["update"]["droppedTurns"].as_u64())
+            .unwrap_or(0) as usize;
+        ContextSize {
+            turns: turns
+                .iter()
+                .filter(|(start, _)| !is_truncation_marker(&history[*start]))
+                .count(),
+            estimated_tokens: estimate_tokens(history),
+            dropped_turns,
+            max_turns: self.max_turns,
+            max_tokens: self.max_tokens,
+        }
+    }
+}
+
+/// Whether `entry` closes a turn (pushing it is a good time to apply the policy).
+pub fn is_turn_boundary(entry: &serde_json::Value) -> bool {
+    update_kind(entry) == Some("turn_complete")
+}
+
+/// Estimate the token count of `entries` from their serialized size.
+pub fn estimate_tokens(entries: &[serde_json::Value]) -> usize {
+    let chars: usize = entries
+        .iter()
+        .map(|entry| {
+            entry
+                .get("update")
+                .unwrap_or(entry)
+                .to_string()
+                .chars()
+                .count()
+        })
+        .sum();
+    chars.div_ceil(CHARS_PER_TOKEN)
+}
+
+fn update_kind(entry: &serde_json::Value) -> Option<&str> {
+    entry
+        .get("update")
+        .and_then(|update| update.get("sessionUpdate"))
+        .and_then(|kind| kind.as_str())
+}
+
+fn is_truncation_marker(entry: &serde_json::Value) -> bool {
+    update_kind(entry) == Some(HISTORY_TRUNCATED_UPDATE)
+}
+
+/// Split history into `[start, end)` turns. A turn starts at a `user_message`
+/// and ends after `turn_complete`; the truncation marker is its own turn.
+fn turn_ranges(history: &[serde_json::Value]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for (index, entry) in history.iter().enumerate() {
+        let kind = update_kind(entry);
+        let starts_turn = kind == Some("user_message") || kind == Some(HISTORY_TRUNCATED_UPDATE);
+        if starts_turn && index > start {
+            ranges.push((start, index));
+            start = index;
+        }
+        let ends_turn = kind == Some("turn_complete") || kind == Some(HISTORY_TRUNCATED_UPDATE);
+        if ends_turn {
+            ranges.push((start, index + 1));
+            start = index + 1;
+        }
+    }
+    if start < history.len() {
+        ranges.push((start, history.len()));
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(text: &str) -> Vec<serde_json::Value> {
+        vec![
+            serde_json::json!({ "sessionId": "s1", "update": { "sessionUpdate": "user_message", "content": { "text": text } } }),
+            serde_json::json!({ "sessionId": "s1", "update": { "sessionUpdate": "agent_message", "content": { "text": format!("reply to {text}") } } }),
+            serde_json::json!({ "sessionId": "s1", "update": { "sessionUpdate": "turn_complete" } }),
+        ]
+    }
+
+    fn texts(history: &[serde_json::Value]) -> Vec<String> {
+        history
+            .iter()
+            .filter(|entry| update_kind(entry) == Some("user_message"))
+            .map(|entry| {
+                entry["update"]["content"]["text"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn drops_oldest_turns_but_keeps_the_prefix() {
+        let policy = HistoryWindowPolicy {
+            max_turns: Some(2),
+            max_tokens: None,
+        };
+        let mut history: Vec<_> = ["context", "one", "two", "three", "four"]
+            .into_iter()
+            .flat_map(turn)
+            .collect();
+
+        assert_eq!(policy.apply("s1", &mut history), 2);
+        assert_eq!(texts(&history), vec!["context", "three", "four"]);
+        assert_eq!(update_kind(&history[3]), Some(HISTORY_TRUNCATED_UPDATE));
+
+        history.extend(turn("five"));
+        assert_eq!(policy.apply("s1", &mut history), 1);
+        assert_eq!(texts(&history), vec!["context", "four", "five"]);
+        let size = policy.context_size(&history);
+        assert_eq!(size.turns, 3);
+        assert_eq!(size.dropped_turns, 3);
+    }
+
+    #[test]
+    fn token_budget_keeps_latest_turn() {
+        let policy = HistoryWindowPolicy {
+            max_turns: None,
+            max_tokens: Some(1),
+        };
+        let mut history: Vec<_> = ["context", "one", "two"]
+            .into_iter()
+            .flat_map(turn)
+            .collect();
+
+        assert_eq!(policy.apply("s1", &mut history), 1);
+        assert_eq!(texts(&history), vec!["context", "two"]);
+    }
+
+    #[test]
+    fn inactive_policy_leaves_history_untouched() {
+        let mut history: Vec<_> = ["a", "b", "c"].into_iter().flat_map(turn).collect();
+        assert_eq!(HistoryWindowPolicy::default().apply("s1", &mut history), 0);
+        assert_eq!(history.len(), 9);
+    }
+}
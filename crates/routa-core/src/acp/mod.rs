@@ -6,7 +6,8 @@
 //! Architecture (matches the Next.js `AcpProcessManager`):
 //!   - `session/new`    → spawns a child process, sends `initialize` + `session/new`
 //!   - `session/prompt` → reuses the live process, sends `session/prompt`
-//!   - `session/cancel` → sends cancellation notification
+//!   - `session/cancel` → sends cancellation notification, kills the process if
+//!     the prompt keeps running past the grace period
 //!   - SSE GET          → subscribes to `broadcast` channel for `session/update` events
 //!
 //! **Claude Code** uses a different protocol (stream-json) instead of ACP.
@@ -16,38 +17,66 @@
 //! **Agent Trace**: All sessions record trace events to JSONL files for
 //! attribution tracking (which model/session/tool affected which files and when).
 
+pub mod archive_cache;
 pub mod binary_manager;
 pub mod claude_code_process;
 pub mod docker;
+pub mod history_window;
 pub mod installation_state;
 pub mod mcp_setup;
+pub mod output_normalization;
 pub mod paths;
+pub mod preset_env;
 pub mod process;
+pub mod process_pool;
+pub mod prompt_dedup;
 pub mod provider_adapter;
+pub mod provider_args;
+pub mod provider_settings;
 pub mod registry_fetch;
 pub mod registry_types;
+pub mod resource_limits;
 pub mod runtime_manager;
+pub mod session_activity;
+pub mod spawn_error;
 pub mod terminal_manager;
+pub mod transcript;
 pub mod warmup;
 
-pub use binary_manager::AcpBinaryManager;
+pub use binary_manager::{
+    verify_executable, AcpBinaryManager, DownloadProgress, InstallPhase, InstallProgress,
+    InstallProgressFn, DEFAULT_DOWNLOAD_RETRIES, DEFAULT_VERIFY_TIMEOUT,
+};
 pub use claude_code_process::{ClaudeCodeConfig, ClaudeCodeProcess};
+pub use history_window::{ContextSize, HistoryWindowPolicy};
 pub use installation_state::AcpInstallationState;
-pub use paths::AcpPaths;
-pub use registry_fetch::{fetch_registry, fetch_registry_json};
+pub use output_normalization::OutputNormalization;
+pub use paths::{AcpPaths, BaseDirSource};
+pub use process_pool::{AcpProcessPool, ProcessPoolConfig};
+pub use provider_settings::{EffectiveProviderSettings, ProviderSettings};
+pub use registry_fetch::{
+    check_registry_version, fetch_registry, fetch_registry_json, parse_registry_json,
+    registry_version_strict,
+};
 pub use registry_types::*;
+pub use resource_limits::ResourceLimits;
 pub use runtime_manager::{current_platform, AcpRuntimeManager, RuntimeInfo, RuntimeType};
+pub use transcript::{TranscriptStore, TranscriptTurn};
 pub use warmup::{AcpWarmupService, WarmupState, WarmupStatus};
 
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use tokio::sync::{broadcast, RwLock};
 
+use crate::settings::AcpSettings;
 use crate::trace::{Contributor, TraceConversation, TraceEventType, TraceRecord, TraceWriter};
 use process::AcpProcess;
+use prompt_dedup::{InFlightPrompts, PromptClaim};
+use session_activity::SessionActivity;
 
 #[cfg(windows)]
 pub(crate) const CREATE_NO_WINDOW: u32 = 0x0800_0000;
@@ -67,6 +96,98 @@ fn validate_session_cwd(cwd: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Launch argv for an ACP provider: preset args, Codex MCP overrides, the
+/// provider's default args from `settings`, the session's extra args and the
+/// model flag. See `provider_args`.
+fn launch_args(
+    settings: &AcpSettings,
+    provider_name: &str,
+    preset_args: &[String],
+    cwd: &str,
+    options: &SessionLaunchOptions,
+    model: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let mut overrides = Vec::new();
+    if matches!(provider_name, "codex" | "codex-acp") {
+        for override_arg in mcp_setup::codex_cli_overrides(cwd)? {
+            overrides.push("-c".to_string());
+            overrides.push(override_arg);
+        }
+    }
+    let defaults = provider_args::provider_default_args(provider_name, &settings.provider_args)?;
+    let session_args = options.provider_args.clone();
+    if let Some(args) = session_args.as_deref() {
+        provider_args::validate_args(args)?;
+    }
+    let args = provider_args::merge_args(preset_args, overrides, defaults, session_args, model);
+    tracing::info!(
+        "[AcpManager] Effective {} launch args: {:?}",
+        provider_name,
+        args
+    );
+    Ok(args)
+}
+
+/// Spawn an ACP agent and complete `initialize`, respawning up to
+/// `options.spawn_retries` times when the process exits before the handshake
+/// finishes. Returns the process, the initialize timeout used and the number
+/// of attempts.
+#[allow(clippy::too_many_arguments)]
+async fn spawn_and_initialize(
+    command: &str,
+    args: &[String],
+    env: &[(String, String)],
+    cwd: &str,
+    ntx: &broadcast::Sender<serde_json::Value>,
+    limits: ResourceLimits,
+    display_name: &str,
+    session_id: &str,
+    options: &SessionLaunchOptions,
+) -> Result<(AcpProcess, u64, u32), String> {
+    let retries = provider_settings::spawn_retries(options);
+    let initialize_timeout_ms = options
+        .initialize_timeout_ms
+        .unwrap_or_else(|| process::default_request_timeout_ms(command, "initialize"));
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let spawned = AcpProcess::spawn_with_env(
+            command,
+            &args,
+            env,
+            cwd,
+            ntx.clone(),
+            limits,
+            display_name,
+            session_id,
+        )
+        .await;
+        let error = match spawned {
+            Ok(process) => match process
+                .initialize_with_timeout(Some(initialize_timeout_ms))
+                .await
+            {
+                Ok(_) => return Ok((process, initialize_timeout_ms, attempt)),
+                Err(error) if !process.is_alive() => error,
+                Err(error) => return Err(error),
+            },
+            Err(error) if error.ends_with(process::PROCESS_DIED_DURING_STARTUP) => error,
+            Err(error) => return Err(error),
+        };
+        if attempt > retries {
+            return Err(error);
+        }
+        tracing::warn!(
+            "[AcpManager] {} exited before initialize (attempt {}/{}): {}; retrying",
+            display_name,
+            attempt,
+            retries + 1,
+            error
+        );
+    }
+}
+
 // ─── Session Record ─────────────────────────────────────────────────────
 
 /// Record of an active ACP session persisted for UI listing.
@@ -95,6 +216,9 @@ pub struct AcpSessionRecord {
     pub specialist_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub specialist_system_prompt: Option<String>,
+    /// Timeouts and retries in effect for this session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_settings: Option<EffectiveProviderSettings>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -103,6 +227,12 @@ pub struct SessionLaunchOptions {
     pub specialist_system_prompt: Option<String>,
     pub allowed_native_tools: Option<Vec<String>>,
     pub initialize_timeout_ms: Option<u64>,
+    /// Per-prompt timeout; defaults to 5 minutes for ACP agents.
+    pub prompt_timeout_ms: Option<u64>,
+    /// Respawn attempts when the agent exits before `initialize` completes.
+    pub spawn_retries: Option<u32>,
+    /// Args appended after the preset's and the provider's configured
+    /// defaults (`extraArgs` in `session/new`).
     pub provider_args: Option<Vec<String>>,
     pub acp_mcp_servers: Option<Vec<serde_json::Value>>,
 }
@@ -161,6 +291,18 @@ pub struct AcpManager {
     notification_channels: Arc<RwLock<HashMap<String, broadcast::Sender<serde_json::Value>>>>,
     /// Our sessionId → message history (session/update notifications)
     history: Arc<RwLock<HashMap<String, Vec<serde_json::Value>>>>,
+    /// Pre-spawned agent processes handed to new sessions (disabled by default)
+    process_pool: Arc<AcpProcessPool>,
+    /// Turn/token window applied to each session's history (unbounded by default)
+    history_window: HistoryWindowPolicy,
+    /// Prompts currently running, so double-submitted prompts share one turn
+    in_flight_prompts: Arc<InFlightPrompts>,
+    /// On-disk prompt/response transcripts replayed by `session/load`
+    transcripts: Arc<TranscriptStore>,
+    /// Last activity per session, for pruning idle sessions
+    activity: Arc<SessionActivity>,
+    /// Launch settings shared with the process pool
+    settings: Arc<AcpSettings>,
 }
 
 impl Default for AcpManager {
@@ -184,14 +326,31 @@ impl AcpManager {
     }
 
     pub fn new() -> Self {
+        Self::with_settings(&AcpSettings::from_env())
+    }
+
+    pub fn with_settings(settings: &AcpSettings) -> Self {
+        let settings = Arc::new(settings.clone());
+        let paths = AcpPaths::from_settings(&settings);
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             processes: Arc::new(RwLock::new(HashMap::new())),
             notification_channels: Arc::new(RwLock::new(HashMap::new())),
             history: Arc::new(RwLock::new(HashMap::new())),
+            process_pool: Arc::new(AcpProcessPool::new(settings.clone())),
+            history_window: settings.history_window,
+            in_flight_prompts: Arc::new(InFlightPrompts::default()),
+            transcripts: Arc::new(TranscriptStore::from_settings(&paths, &settings)),
+            activity: Arc::new(SessionActivity::default()),
+            settings,
         }
     }
 
+    /// The pool of pre-spawned agent processes.
+    pub fn process_pool(&self) -> &Arc<AcpProcessPool> {
+        &self.process_pool
+    }
+
     /// List all session records.
     pub async fn list_sessions(&self) -> Vec<AcpSessionRecord> {
         let sessions = self.sessions.read().await;
@@ -243,6 +402,7 @@ impl AcpManager {
 
         // Remove history
         history.remove(session_id);
+        self.activity.forget(session_id);
 
         Some(())
     }
@@ -263,6 +423,8 @@ impl AcpManager {
         if notification.get("childAgentId").is_some() {
             return;
         }
+        self.activity.touch(session_id);
+        let closes_turn = history_window::is_turn_boundary(&notification);
         let mut history = self.history.write().await;
         let entries = history.entry(session_id.to_string()).or_default();
         entries.push(notification);
@@ -271,6 +433,60 @@ impl AcpManager {
             let drain_count = entries.len() - 500;
             entries.drain(0..drain_count);
         }
+        if closes_turn {
+            let dropped = self.history_window.apply(session_id, entries);
+            if dropped > 0 {
+                tracing::info!(
+                    "[AcpManager] Dropped {} old turn(s) from session {} history",
+                    dropped,
+                    session_id
+                );
+            }
+        }
+    }
+
+    /// Prompt/response turns recorded for a session, oldest first. Survives
+    /// restarts, unlike the in-memory history.
+    pub async fn get_transcript(&self, session_id: &str) -> Vec<TranscriptTurn> {
+        let transcripts = self.transcripts.clone();
+        let session_id = session_id.to_string();
+        tokio::task::spawn_blocking(move || transcripts.read(&session_id))
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Delete a session's transcript file.
+    pub async fn remove_transcript(&self, session_id: &str) {
+        let transcripts = self.transcripts.clone();
+        let session_id = session_id.to_string();
+        let _ = tokio::task::spawn_blocking(move || transcripts.remove(&session_id)).await;
+    }
+
+    async fn append_transcript(&self, session_id: &str, turns: Vec<TranscriptTurn>) {
+        if !self.transcripts.is_enabled() {
+            return;
+        }
+        let transcripts = self.transcripts.clone();
+        let sid = session_id.to_string();
+        let result = tokio::task::spawn_blocking(move || transcripts.append(&sid, &turns))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result);
+        if let Err(error) = result {
+            tracing::warn!(
+                "[AcpManager] Failed to record transcript for session {}: {}",
+                session_id,
+                error
+            );
+        }
+    }
+
+    /// Estimated size of a session's in-memory history against the window policy.
+    pub async fn get_context_size(&self, session_id: &str) -> Option<ContextSize> {
+        let history = self.history.read().await;
+        history
+            .get(session_id)
+            .map(|entries| self.history_window.context_size(entries))
     }
 
     /// Broadcast a synthetic session/update event and persist it into in-memory history.
@@ -318,6 +534,22 @@ impl AcpManager {
         }
     }
 
+    /// Apply a `session/prompt` override of the prompt timeout to a live
+    /// session and return the settings now in effect. Launch-only settings
+    /// (initialize timeout, spawn retries) are ignored for running sessions.
+    pub async fn update_provider_settings(
+        &self,
+        session_id: &str,
+        overrides: &ProviderSettings,
+    ) -> Option<EffectiveProviderSettings> {
+        let mut sessions = self.sessions.write().await;
+        let settings = sessions.get_mut(session_id)?.provider_settings.as_mut()?;
+        if let Some(timeout_ms) = overrides.prompt_timeout_ms {
+            settings.prompt_timeout_ms = Some(timeout_ms);
+        }
+        Some(settings.clone())
+    }
+
     /// Create a new ACP session: spawn agent process, initialize, create session.
     /// Supports both static presets and registry-based agents.
     /// **Claude** uses stream-json protocol instead of ACP.
@@ -405,50 +637,52 @@ impl AcpManager {
         }
         let mcp_cleanup = mcp_setup.cleanup.clone();
 
-        let mut extra_args: Vec<String> = preset.args.clone();
-        if matches!(provider_name, "codex" | "codex-acp") {
-            for override_arg in mcp_setup::codex_cli_overrides(&cwd)? {
-                extra_args.push("-c".to_string());
-                extra_args.push(override_arg);
-            }
-        }
-        if let Some(provider_args) = options.provider_args.clone() {
-            extra_args.extend(provider_args);
-        }
-        if let Some(ref m) = model {
-            if !m.is_empty() {
-                extra_args.push("-m".to_string());
-                extra_args.push(m.clone());
-            }
-        }
+        let extra_args = launch_args(
+            &self.settings,
+            provider_name,
+            &preset.args,
+            &cwd,
+            &options,
+            model.as_deref(),
+        )?;
 
-        let preset_command = resolve_preset_command(&preset);
         let launch_result = async {
-            let process = AcpProcess::spawn(
+            let preset_command = resolve_launch_command(&preset).await?;
+            let preset_env =
+                preset_env::resolve_preset_env(&preset.env, &self.settings.env_passthrough);
+            let (process, initialize_timeout_ms, spawn_attempts) = spawn_and_initialize(
                 &preset_command,
-                &extra_args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+                &extra_args,
+                &preset_env,
                 &cwd,
-                ntx.clone(),
+                &ntx,
+                self.settings.resource_limits.for_provider(&preset.id),
                 &preset.name,
                 &session_id,
+                &options,
             )
             .await?;
 
-            process
-                .initialize_with_timeout(options.initialize_timeout_ms)
-                .await?;
-
             let resolved_provider_session_id =
                 provider_session_id.unwrap_or_else(|| session_id.clone());
             let acp_session_id = process
                 .load_session(&resolved_provider_session_id, &cwd, &acp_mcp_servers)
                 .await?;
 
-            Ok::<_, String>((process, acp_session_id))
+            Ok::<_, String>((
+                process,
+                acp_session_id,
+                EffectiveProviderSettings::acp(
+                    provider_name,
+                    &options,
+                    Some(initialize_timeout_ms),
+                    spawn_attempts,
+                ),
+            ))
         }
         .await;
 
-        let (process, acp_session_id) = match launch_result {
+        let (process, acp_session_id, provider_settings) = match launch_result {
             Ok(result) => result,
             Err(error) => {
                 if let Some(cleanup) = mcp_cleanup.as_ref() {
@@ -468,6 +702,7 @@ impl AcpManager {
             model.clone(),
             parent_session_id.clone(),
             &options,
+            provider_settings,
             AgentProcessType::Acp(Arc::new(process)),
             acp_session_id.clone(),
             ntx.clone(),
@@ -558,6 +793,7 @@ impl AcpManager {
         model: Option<String>,
         parent_session_id: Option<String>,
         options: &SessionLaunchOptions,
+        provider_settings: EffectiveProviderSettings,
         process_type: AgentProcessType,
         acp_session_id: String,
         ntx: broadcast::Sender<serde_json::Value>,
@@ -580,8 +816,10 @@ impl AcpManager {
             parent_session_id: parent_session_id.clone(),
             specialist_id: options.specialist_id.clone(),
             specialist_system_prompt: options.specialist_system_prompt.clone(),
+            provider_settings: Some(provider_settings),
         };
 
+        self.activity.register(&session_id);
         self.sessions
             .write()
             .await
@@ -636,19 +874,24 @@ impl AcpManager {
         validate_session_cwd(&cwd)?;
         let (ntx, _) = broadcast::channel::<serde_json::Value>(256);
 
-        let process = AcpProcess::spawn(
+        let (process, initialize_timeout_ms, spawn_attempts) = spawn_and_initialize(
             &command,
-            &args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+            &args,
+            &[],
             &cwd,
-            ntx.clone(),
+            &ntx,
+            self.settings.resource_limits.for_provider(&provider_name),
             &provider_name,
             &session_id,
+            &options,
         )
         .await?;
-
-        process
-            .initialize_with_timeout(options.initialize_timeout_ms)
-            .await?;
+        let provider_settings = EffectiveProviderSettings::acp(
+            &provider_name,
+            &options,
+            Some(initialize_timeout_ms),
+            spawn_attempts,
+        );
 
         let acp_session_id = process
             .new_session(&cwd, options.acp_mcp_servers.as_deref().unwrap_or(&[]))
@@ -662,6 +905,7 @@ impl AcpManager {
             model.clone(),
             parent_session_id.clone(),
             &options,
+            provider_settings,
             AgentProcessType::Acp(Arc::new(process)),
             acp_session_id.clone(),
             ntx.clone(),
@@ -697,19 +941,24 @@ impl AcpManager {
         validate_session_cwd(&cwd)?;
         let (ntx, _) = broadcast::channel::<serde_json::Value>(256);
 
-        let process = AcpProcess::spawn(
+        let (process, initialize_timeout_ms, spawn_attempts) = spawn_and_initialize(
             &command,
-            &args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+            &args,
+            &[],
             &cwd,
-            ntx.clone(),
+            &ntx,
+            self.settings.resource_limits.for_provider(&provider_name),
             &provider_name,
             &session_id,
+            &options,
         )
         .await?;
-
-        process
-            .initialize_with_timeout(options.initialize_timeout_ms)
-            .await?;
+        let provider_settings = EffectiveProviderSettings::acp(
+            &provider_name,
+            &options,
+            Some(initialize_timeout_ms),
+            spawn_attempts,
+        );
 
         let resolved_provider_session_id =
             provider_session_id.unwrap_or_else(|| session_id.clone());
@@ -730,6 +979,7 @@ impl AcpManager {
             model.clone(),
             parent_session_id.clone(),
             &options,
+            provider_settings,
             AgentProcessType::Acp(Arc::new(process)),
             acp_session_id.clone(),
             ntx.clone(),
@@ -790,6 +1040,7 @@ impl AcpManager {
         };
 
         // Check if this is Claude (uses stream-json protocol, not ACP)
+        let mut provider_settings = EffectiveProviderSettings::claude(provider_name, &options);
         let (process_type, acp_session_id, mcp_cleanup) = if provider_name == "claude" {
             // Use Claude Code stream-json protocol
             let config = ClaudeCodeConfig {
@@ -800,6 +1051,12 @@ impl AcpManager {
                 mcp_configs: claude_mcp_config.into_iter().collect(),
                 append_system_prompt: options.specialist_system_prompt.clone(),
                 allowed_tools: options.allowed_native_tools.clone(),
+                env: get_preset_by_id("claude")
+                    .map(|preset| {
+                        preset_env::resolve_preset_env(&preset.env, &self.settings.env_passthrough)
+                    })
+                    .unwrap_or_default(),
+                resource_limits: self.settings.resource_limits.for_provider("claude"),
             };
 
             let claude_process = ClaudeCodeProcess::spawn(config, ntx.clone()).await?;
@@ -831,55 +1088,83 @@ impl AcpManager {
             }
             let mcp_cleanup = mcp_setup.cleanup.clone();
 
-            // Build args: preset args + optional model flag
-            let mut extra_args: Vec<String> = preset.args.clone();
-            if matches!(provider_name, "codex" | "codex-acp") {
-                for override_arg in mcp_setup::codex_cli_overrides(&cwd)? {
-                    extra_args.push("-c".to_string());
-                    extra_args.push(override_arg);
-                }
-            }
-            if let Some(provider_args) = options.provider_args.clone() {
-                extra_args.extend(provider_args);
-            }
-            if let Some(ref m) = model {
-                if !m.is_empty() {
-                    // opencode (and future providers) accept -m <model>
-                    extra_args.push("-m".to_string());
-                    extra_args.push(m.clone());
-                }
-            }
+            let extra_args = launch_args(
+                &self.settings,
+                provider_name,
+                &preset.args,
+                &cwd,
+                &options,
+                model.as_deref(),
+            )?;
+
+            // A pre-spawned process only matches a plain launch: no extra
+            // args, model override, init timeout or session MCP servers.
+            let poolable = extra_args == preset.args
+                && acp_mcp_servers.is_empty()
+                && options.initialize_timeout_ms.is_none()
+                && self.process_pool.is_enabled_for(provider_name);
+            let warm = if poolable {
+                self.process_pool.checkout(provider_name, &cwd).await
+            } else {
+                None
+            };
 
-            let preset_command = resolve_preset_command(&preset);
             let launch_result = async {
-                let process = AcpProcess::spawn(
+                if let Some(warm) = warm {
+                    tracing::info!(
+                        "[AcpManager] Using pre-spawned {} process for session {}",
+                        provider_name,
+                        session_id
+                    );
+                    warm.process.rebind_session_id(&session_id);
+                    process_pool::forward_notifications(
+                        warm.notifications,
+                        ntx.clone(),
+                        session_id.clone(),
+                    );
+                    let settings = EffectiveProviderSettings::acp(provider_name, &options, None, 1);
+                    return Ok((warm.process, warm.acp_session_id, settings));
+                }
+
+                // Spawn and initialize the protocol, retrying early exits
+                let preset_command = resolve_launch_command(&preset).await?;
+                let preset_env =
+                    preset_env::resolve_preset_env(&preset.env, &self.settings.env_passthrough);
+                let (process, initialize_timeout_ms, spawn_attempts) = spawn_and_initialize(
                     &preset_command,
-                    &extra_args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+                    &extra_args,
+                    &preset_env,
                     &cwd,
-                    ntx.clone(),
+                    &ntx,
+                    self.settings.resource_limits.for_provider(&preset.id),
                     &preset.name,
                     &session_id,
+                    &options,
                 )
                 .await?;
 
-                // Initialize the protocol
-                process
-                    .initialize_with_timeout(options.initialize_timeout_ms)
-                    .await?;
-
                 // Create the agent session
                 let agent_session_id = process.new_session(&cwd, &acp_mcp_servers).await?;
 
-                Ok::<_, String>((process, agent_session_id))
+                let settings = EffectiveProviderSettings::acp(
+                    provider_name,
+                    &options,
+                    Some(initialize_timeout_ms),
+                    spawn_attempts,
+                );
+                Ok::<_, String>((process, agent_session_id, settings))
             }
             .await;
 
             match launch_result {
-                Ok((process, agent_session_id)) => (
-                    AgentProcessType::Acp(Arc::new(process)),
-                    agent_session_id,
-                    mcp_cleanup,
-                ),
+                Ok((process, agent_session_id, settings)) => {
+                    provider_settings = settings;
+                    (
+                        AgentProcessType::Acp(Arc::new(process)),
+                        agent_session_id,
+                        mcp_cleanup,
+                    )
+                }
                 Err(error) => {
                     if let Some(cleanup) = mcp_cleanup.as_ref() {
                         let summary = mcp_setup::cleanup_mcp_for_provider(cleanup).await;
@@ -899,6 +1184,7 @@ impl AcpManager {
             model.clone(),
             parent_session_id.clone(),
             &options,
+            provider_settings,
             process_type,
             acp_session_id.clone(),
             ntx.clone(),
@@ -917,9 +1203,37 @@ impl AcpManager {
     }
 
     /// Send a prompt to an existing session's agent process.
+    ///
+    /// An identical prompt already running for the session is not sent
+    /// again; this call waits for that turn and returns its result.
     pub async fn prompt(&self, session_id: &str, text: &str) -> Result<serde_json::Value, String> {
+        self.activity.touch(session_id);
+        let in_flight = match self.in_flight_prompts.claim(session_id, text) {
+            PromptClaim::Leader(in_flight) => in_flight,
+            PromptClaim::Duplicate(duplicate) => {
+                tracing::info!(
+                    "[AcpManager] Identical prompt already running for session {}, awaiting it",
+                    session_id
+                );
+                return duplicate.wait().await;
+            }
+        };
+        let result = self.prompt_turn(session_id, text).await;
+        in_flight.finish(&result);
+        result
+    }
+
+    async fn prompt_turn(&self, session_id: &str, text: &str) -> Result<serde_json::Value, String> {
         self.mark_first_prompt_sent(session_id).await;
 
+        let prompt_timeout_ms = self
+            .sessions
+            .read()
+            .await
+            .get(session_id)
+            .and_then(|record| record.provider_settings.as_ref())
+            .map(|settings| settings.prompt_timeout_ms)
+            .unwrap_or(Some(provider_settings::DEFAULT_PROMPT_TIMEOUT_MS));
         let (process, acp_session_id, preset_id, trace_writer) = {
             let processes = self.processes.read().await;
             let managed = processes
@@ -933,13 +1247,25 @@ impl AcpManager {
             )
         };
 
+        // The process spawned by `session/new` is reused for every prompt; once
+        // it has exited the agent-side session (and its context) is gone.
         let is_alive = match &process {
             AgentProcessType::Acp(p) => p.is_alive(),
             AgentProcessType::Claude(p) => p.is_alive(),
         };
 
         if !is_alive {
-            return Err(format!("Agent ({preset_id}) process is not running"));
+            let reason = match &process {
+                AgentProcessType::Acp(p) => p.exit_reason(),
+                AgentProcessType::Claude(_) => None,
+            };
+            return Err(format!(
+                "Agent ({preset_id}) process for session {session_id} is not running{}; \
+                 start a new session to continue",
+                reason
+                    .map(|reason| format!(": {reason}"))
+                    .unwrap_or_default()
+            ));
         }
 
         // Record UserMessage trace
@@ -957,6 +1283,13 @@ impl AcpManager {
 
         trace_writer.append_safe(&trace).await;
 
+        // Collect the agent's reply for the transcript from the same
+        // notifications SSE subscribers see, while the prompt runs.
+        let reply_collector = self
+            .subscribe(session_id)
+            .await
+            .map(transcript::ReplyCollector::spawn);
+
         tracing::info!(
             target: "routa_acp_prompt",
             session_id = %session_id,
@@ -967,9 +1300,24 @@ impl AcpManager {
         );
 
         let result = match &process {
-            AgentProcessType::Acp(p) => p.prompt(&acp_session_id, text).await,
+            AgentProcessType::Acp(p) => {
+                p.prompt_with_timeout(
+                    &acp_session_id,
+                    text,
+                    prompt_timeout_ms.unwrap_or(provider_settings::DEFAULT_PROMPT_TIMEOUT_MS),
+                )
+                .await
+            }
             AgentProcessType::Claude(p) => {
-                let stop_reason = p.prompt(text).await?;
+                let stop_reason = match prompt_timeout_ms {
+                    Some(timeout_ms) => tokio::time::timeout(
+                        std::time::Duration::from_millis(timeout_ms),
+                        p.prompt(text),
+                    )
+                    .await
+                    .map_err(|_| format!("Timeout waiting for prompt ({timeout_ms}ms)"))??,
+                    None => p.prompt(text).await?,
+                };
                 Ok(serde_json::json!({ "stopReason": stop_reason }))
             }
         };
@@ -990,17 +1338,40 @@ impl AcpManager {
             ),
         }
 
+        let mut turns = vec![TranscriptTurn::new("user", text)];
+        let reply = match reply_collector {
+            Some(collector) => collector.finish().await,
+            None => String::new(),
+        };
+        if !reply.is_empty() {
+            turns.push(TranscriptTurn::new("assistant", reply));
+        }
+        self.append_transcript(session_id, turns).await;
+
         result
     }
 
     /// Cancel the current prompt in a session.
+    ///
+    /// ACP agents get `session/cancel`; one whose prompt is still running after
+    /// the cancel grace period is killed in the background, ending the session's
+    /// process (later prompts report why).
     pub async fn cancel(&self, session_id: &str) {
-        let processes = self.processes.read().await;
-        if let Some(managed) = processes.get(session_id) {
-            match &managed.process {
-                AgentProcessType::Acp(p) => p.cancel(&managed.acp_session_id).await,
-                AgentProcessType::Claude(p) => p.cancel().await,
+        let (process, acp_session_id) = {
+            let processes = self.processes.read().await;
+            let Some(managed) = processes.get(session_id) else {
+                return;
+            };
+            (managed.process.clone(), managed.acp_session_id.clone())
+        };
+        match process {
+            AgentProcessType::Acp(p) => {
+                let grace = self.settings.cancel_grace;
+                tokio::spawn(async move {
+                    p.cancel_or_kill(&acp_session_id, grace).await;
+                });
             }
+            AgentProcessType::Claude(p) => p.cancel().await,
         }
     }
 
@@ -1030,6 +1401,30 @@ impl AcpManager {
         self.sessions.write().await.remove(session_id);
         // Remove notification channel
         self.notification_channels.write().await.remove(session_id);
+        self.activity.forget(session_id);
+    }
+
+    /// Kill sessions with no activity for longer than `ttl` and drop their
+    /// buffers. Sessions with a prompt still running are kept. Returns the
+    /// ids of the pruned sessions.
+    ///
+    /// Locks are taken one at a time (never across a kill), so sessions can
+    /// be created and prompted while a prune is running.
+    pub async fn prune_idle_sessions(&self, ttl: Duration) -> Vec<String> {
+        let mut pruned = Vec::new();
+        for session_id in self.activity.idle_longer_than(ttl) {
+            if self.in_flight_prompts.has_session(&session_id) {
+                self.activity.touch(&session_id);
+                continue;
+            }
+            if !self.activity.take_if_idle(&session_id, ttl) {
+                continue;
+            }
+            self.kill_session(&session_id).await;
+            self.history.write().await.remove(&session_id);
+            pruned.push(session_id);
+        }
+        pruned
     }
 
     /// Subscribe to SSE notifications for a session.
@@ -1080,7 +1475,21 @@ impl AcpManager {
     /// Send a prompt to Claude session and return immediately.
     /// The actual response is streamed via the broadcast channel.
     /// Use `subscribe()` to receive notifications.
+    ///
+    /// If an identical prompt is still running, nothing is sent; its output
+    /// already streams to every subscriber.
     pub async fn prompt_claude_async(&self, session_id: &str, text: &str) -> Result<(), String> {
+        self.activity.touch(session_id);
+        let in_flight = match self.in_flight_prompts.claim(session_id, text) {
+            PromptClaim::Leader(in_flight) => in_flight,
+            PromptClaim::Duplicate(_) => {
+                tracing::info!(
+                    "[AcpManager] Identical prompt already running for session {}, not resending",
+                    session_id
+                );
+                return Ok(());
+            }
+        };
         let processes = self.processes.read().await;
         let managed = processes
             .get(session_id)
@@ -1107,7 +1516,11 @@ impl AcpManager {
                 let process = Arc::clone(p);
                 let text = text.to_string();
                 tokio::spawn(async move {
-                    let _ = process.prompt(&text).await;
+                    let result = process
+                        .prompt(&text)
+                        .await
+                        .map(|stop_reason| serde_json::json!({ "stopReason": stop_reason }));
+                    in_flight.finish(&result);
                 });
                 Ok(())
             }
@@ -1148,6 +1561,11 @@ pub struct AcpPreset {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env_bin_override: Option<String>,
+    /// Variables set on the agent process; values may reference the parent
+    /// environment as `${VAR}` (see `preset_env`).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
     /// Resume/continuation capabilities for this provider.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1164,6 +1582,7 @@ pub fn get_presets() -> Vec<AcpPreset> {
             args: vec!["acp".to_string()],
             description: "OpenCode AI coding agent".to_string(),
             env_bin_override: Some("OPENCODE_BIN".to_string()),
+            env: preset_env::passthrough(&["ANTHROPIC_API_KEY", "OPENAI_API_KEY"]),
             resume: Some(ResumeCapability {
                 supported: true,
                 mode: "replay".to_string(),
@@ -1178,6 +1597,7 @@ pub fn get_presets() -> Vec<AcpPreset> {
             args: vec!["--experimental-acp".to_string()],
             description: "Google Gemini CLI".to_string(),
             env_bin_override: None,
+            env: preset_env::passthrough(&["GEMINI_API_KEY", "GOOGLE_API_KEY"]),
             resume: None,
         },
         AcpPreset {
@@ -1187,6 +1607,7 @@ pub fn get_presets() -> Vec<AcpPreset> {
             args: vec![],
             description: "OpenAI Codex CLI (codex-acp wrapper)".to_string(),
             env_bin_override: Some("CODEX_ACP_BIN".to_string()),
+            env: preset_env::passthrough(&["OPENAI_API_KEY"]),
             resume: Some(ResumeCapability {
                 supported: true,
                 mode: "both".to_string(),
@@ -1205,6 +1626,7 @@ pub fn get_presets() -> Vec<AcpPreset> {
             ],
             description: "GitHub Copilot CLI".to_string(),
             env_bin_override: Some("COPILOT_BIN".to_string()),
+            env: preset_env::passthrough(&["GH_TOKEN", "GITHUB_TOKEN"]),
             resume: None,
         },
         AcpPreset {
@@ -1214,6 +1636,7 @@ pub fn get_presets() -> Vec<AcpPreset> {
             args: vec!["--acp".to_string()],
             description: "Augment Code's AI agent".to_string(),
             env_bin_override: None,
+            env: HashMap::new(),
             resume: None,
         },
         AcpPreset {
@@ -1223,6 +1646,7 @@ pub fn get_presets() -> Vec<AcpPreset> {
             args: vec!["acp".to_string()],
             description: "Moonshot AI's Kimi CLI".to_string(),
             env_bin_override: None,
+            env: HashMap::new(),
             resume: None,
         },
         AcpPreset {
@@ -1232,6 +1656,7 @@ pub fn get_presets() -> Vec<AcpPreset> {
             args: vec!["acp".to_string()],
             description: "Amazon Kiro AI coding agent".to_string(),
             env_bin_override: Some("KIRO_BIN".to_string()),
+            env: HashMap::new(),
             resume: None,
         },
         AcpPreset {
@@ -1241,6 +1666,7 @@ pub fn get_presets() -> Vec<AcpPreset> {
             args: vec!["--acp".to_string(), "--experimental-mcp-load".to_string()],
             description: "Qoder AI coding agent".to_string(),
             env_bin_override: Some("QODER_BIN".to_string()),
+            env: HashMap::new(),
             resume: None,
         },
         AcpPreset {
@@ -1252,6 +1678,7 @@ pub fn get_presets() -> Vec<AcpPreset> {
             args: vec![],
             description: "Anthropic Claude Code (stream-json protocol)".to_string(),
             env_bin_override: Some("CLAUDE_BIN".to_string()),
+            env: preset_env::passthrough(&["ANTHROPIC_API_KEY"]),
             resume: Some(ResumeCapability {
                 supported: true,
                 mode: "replay".to_string(),
@@ -1326,20 +1753,22 @@ async fn get_registry_preset(id: &str) -> Result<AcpPreset, String> {
         .find(|a| a.id == id)
         .ok_or_else(|| format!("Agent '{id}' not found in registry"))?;
 
+    // Binary and git distributions launch from their install location
+    let installation_state = AcpInstallationState::new(AcpPaths::new());
+    let _ = installation_state.load().await;
+    let installed_path = installation_state
+        .get_installed_info(id)
+        .await
+        .and_then(|info| info.binary_path);
+
     // Build command from distribution
-    let (command, args) = if let Some(ref npx) = agent.distribution.npx {
-        let mut args = vec!["-y".to_string(), npx.package.clone()];
-        args.extend(npx.args.clone());
-        ("npx".to_string(), args)
-    } else if let Some(ref uvx) = agent.distribution.uvx {
-        let mut args = vec![uvx.package.clone()];
-        args.extend(uvx.args.clone());
-        ("uvx".to_string(), args)
-    } else {
-        return Err(format!(
-            "Agent '{id}' has no supported distribution (npx/uvx)"
-        ));
-    };
+    let AgentCommand {
+        command, args, env, ..
+    } = agent
+        .get_command(installed_path.as_deref())
+        .ok_or_else(|| {
+            format!("Agent '{id}' has no supported distribution (npx/uvx) and is not installed")
+        })?;
 
     Ok(AcpPreset {
         id: agent.id.clone(),
@@ -1348,6 +1777,7 @@ async fn get_registry_preset(id: &str) -> Result<AcpPreset, String> {
         args,
         description: agent.description,
         env_bin_override: None,
+        env,
         resume: None,
     })
 }
@@ -1365,6 +1795,92 @@ fn resolve_preset_command(preset: &AcpPreset) -> String {
     crate::shell_env::which(&preset.command).unwrap_or_else(|| preset.command.clone())
 }
 
+/// Resolve the command for `preset`, failing with an actionable message when
+/// it is an npx/uvx launcher whose runtime is neither on `PATH` nor managed.
+async fn resolve_launch_command(preset: &AcpPreset) -> Result<String, String> {
+    let command = resolve_preset_command(preset);
+    if command != preset.command {
+        return Ok(command);
+    }
+    let Some(runtime) = RuntimeType::for_command(&preset.command) else {
+        return Ok(command);
+    };
+    match AcpRuntimeManager::new(AcpPaths::new())
+        .get_managed_runtime(&runtime)
+        .await
+    {
+        Some(info) => Ok(info.path.to_string_lossy().to_string()),
+        None => Err(runtime.missing_message()),
+    }
+}
+
+/// The command line a session launch would use for an agent, for diagnostics.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchCommandInfo {
+    pub agent_id: String,
+    /// `acp` or `stream-json` (Claude Code).
+    pub protocol: &'static str,
+    /// Command from the preset/registry before resolution.
+    pub command: String,
+    /// Absolute command after `PATH`/override/managed-runtime resolution.
+    pub resolved_command: Option<String>,
+    pub args: Vec<String>,
+    /// Variables set on top of the inherited environment; secrets are redacted.
+    pub env: std::collections::BTreeMap<String, String>,
+    /// Why the command can't be resolved right now, if it can't.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Describe how `id` would be launched with the current installation and
+/// registry state, without spawning it.
+pub async fn describe_launch_command(id: &str) -> Result<LaunchCommandInfo, String> {
+    let preset = get_preset_by_id_with_registry(id).await?;
+    let (resolved_command, error) = match resolve_launch_command(&preset).await {
+        Ok(command) => (Some(command), None),
+        Err(error) => (None, Some(error)),
+    };
+    let env = process::launch_env_overrides(resolved_command.as_deref().unwrap_or(&preset.command))
+        .into_iter()
+        .chain(preset_env::resolve_preset_env(
+            &preset.env,
+            &AcpSettings::from_env().env_passthrough,
+        ))
+        .map(|(key, value)| {
+            let value = redact_env_value(&key, value);
+            (key, value)
+        })
+        .collect();
+
+    Ok(LaunchCommandInfo {
+        agent_id: preset.id.clone(),
+        protocol: if preset.id == "claude" {
+            "stream-json"
+        } else {
+            "acp"
+        },
+        command: preset.command,
+        resolved_command,
+        args: preset.args,
+        env,
+        error,
+    })
+}
+
+/// Hide values of variables that look like credentials.
+fn redact_env_value(key: &str, value: String) -> String {
+    let upper = key.to_ascii_uppercase();
+    let sensitive = ["KEY", "TOKEN", "SECRET", "PASSWORD", "CREDENTIAL", "AUTH"]
+        .iter()
+        .any(|marker| upper.contains(marker));
+    if sensitive && !value.is_empty() {
+        "[redacted]".to_string()
+    } else {
+        value
+    }
+}
+
 // ─── Utility Functions ─────────────────────────────────────────────────────
 
 /// Truncate content to a maximum length for storage in traces.
@@ -1382,14 +1898,30 @@ fn truncate_content(text: &str, max_len: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::{
-        get_preset_by_id_with_registry, get_presets, truncate_content, validate_session_cwd,
-        AcpManager, AcpSessionRecord,
+        get_preset_by_id_with_registry, get_presets, launch_args, redact_env_value,
+        session_activity::SessionActivity, truncate_content, validate_session_cwd, AcpManager,
+        AcpProcessPool, AcpSessionRecord, HistoryWindowPolicy, InFlightPrompts,
+        SessionLaunchOptions, TranscriptStore,
     };
     use std::collections::HashMap;
     use std::fs;
     use std::sync::Arc;
+    use std::time::Duration;
     use tokio::sync::RwLock;
 
+    #[test]
+    fn redact_env_value_hides_credentials_only() {
+        assert_eq!(
+            redact_env_value("OPENAI_API_KEY", "sk-123".to_string()),
+            "[redacted]"
+        );
+        assert_eq!(
+            redact_env_value("GITHUB_TOKEN", "ghp_abc".to_string()),
+            "[redacted]"
+        );
+        assert_eq!(redact_env_value("NODE_NO_READLINE", "1".to_string()), "1");
+    }
+
     #[test]
     fn static_presets_include_codex_acp_for_codex_alias() {
         let presets = get_presets();
@@ -1462,6 +1994,7 @@ mod tests {
                 parent_session_id: None,
                 specialist_id: None,
                 specialist_system_prompt: None,
+                provider_settings: None,
             },
         );
 
@@ -1471,6 +2004,49 @@ mod tests {
         assert!(session.first_prompt_sent);
     }
 
+    #[tokio::test]
+    async fn prune_idle_sessions_drops_idle_sessions_but_keeps_busy_ones() {
+        let manager = AcpManager::new();
+        for session_id in ["idle", "prompting", "active"] {
+            manager.sessions.write().await.insert(
+                session_id.to_string(),
+                AcpSessionRecord {
+                    session_id: session_id.to_string(),
+                    name: None,
+                    cwd: ".".to_string(),
+                    workspace_id: "default".to_string(),
+                    routa_agent_id: None,
+                    provider: Some("opencode".to_string()),
+                    role: Some("CRAFTER".to_string()),
+                    mode_id: None,
+                    model: None,
+                    created_at: chrono::Utc::now().to_rfc3339(),
+                    first_prompt_sent: false,
+                    parent_session_id: None,
+                    specialist_id: None,
+                    specialist_system_prompt: None,
+                    provider_settings: None,
+                },
+            );
+            manager.activity.register(session_id);
+            manager
+                .push_to_history(session_id, serde_json::json!({ "sessionId": session_id }))
+                .await;
+        }
+        let _running = manager.in_flight_prompts.claim("prompting", "long task");
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        manager
+            .push_to_history("active", serde_json::json!({ "sessionId": "active" }))
+            .await;
+
+        let pruned = manager.prune_idle_sessions(Duration::from_millis(20)).await;
+        assert_eq!(pruned, vec!["idle".to_string()]);
+        assert!(manager.get_session("idle").await.is_none());
+        assert!(manager.get_session_history("idle").await.is_none());
+        assert!(manager.get_session("prompting").await.is_some());
+        assert!(manager.get_session("active").await.is_some());
+    }
+
     #[tokio::test]
     async fn push_to_history_skips_parent_child_forwarding_noise() {
         let manager = AcpManager {
@@ -1478,6 +2054,12 @@ mod tests {
             processes: Arc::new(RwLock::new(HashMap::new())),
             notification_channels: Arc::new(RwLock::new(HashMap::new())),
             history: Arc::new(RwLock::new(HashMap::new())),
+            process_pool: Arc::new(AcpProcessPool::default()),
+            history_window: HistoryWindowPolicy::default(),
+            in_flight_prompts: Arc::new(InFlightPrompts::default()),
+            transcripts: Arc::new(TranscriptStore::new(std::path::PathBuf::new(), None)),
+            activity: Arc::new(SessionActivity::default()),
+            settings: Arc::new(AcpSettings::default()),
         };
 
         manager
@@ -1509,6 +2091,12 @@ mod tests {
                 tx,
             )]))),
             history: Arc::new(RwLock::new(HashMap::new())),
+            process_pool: Arc::new(AcpProcessPool::default()),
+            history_window: HistoryWindowPolicy::default(),
+            in_flight_prompts: Arc::new(InFlightPrompts::default()),
+            transcripts: Arc::new(TranscriptStore::new(std::path::PathBuf::new(), None)),
+            activity: Arc::new(SessionActivity::default()),
+            settings: Arc::new(AcpSettings::default()),
         };
 
         manager
@@ -1540,6 +2128,12 @@ mod tests {
             processes: Arc::new(RwLock::new(HashMap::new())),
             notification_channels: Arc::new(RwLock::new(HashMap::new())),
             history: Arc::new(RwLock::new(HashMap::new())),
+            process_pool: Arc::new(AcpProcessPool::default()),
+            history_window: HistoryWindowPolicy::default(),
+            in_flight_prompts: Arc::new(InFlightPrompts::default()),
+            transcripts: Arc::new(TranscriptStore::new(std::path::PathBuf::new(), None)),
+            activity: Arc::new(SessionActivity::default()),
+            settings: Arc::new(AcpSettings::default()),
         };
 
         manager
@@ -1583,4 +2177,108 @@ mod tests {
         assert_eq!(truncate_content("你好世界ABC", 3), "你好世");
         assert_eq!(truncate_content("短文本", 10), "短文本");
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn merged_launch_args_reach_the_spawned_agent() {
+        let cwd = tempfile::tempdir().expect("tempdir");
+        let cwd_str = cwd.path().to_str().expect("utf-8 cwd");
+        let settings = crate::settings::Settings::from_vars([(
+            "ROUTA_ACP_PROVIDER_ARGS_ARGS_TEST_PROVIDER",
+            "--default-flag",
+        )])
+        .acp;
+        // The "preset" records its positional args and exits.
+        let preset_args = vec![
+            "-c".to_string(),
+            "printf '%s\\n' \"$@\" > args.txt".to_string(),
+            "agent".to_string(),
+        ];
+        let options = SessionLaunchOptions {
+            provider_args: Some(vec!["--log-level".to_string(), "debug".to_string()]),
+            ..SessionLaunchOptions::default()
+        };
+        let args = launch_args(
+            &settings,
+            "args-test-provider",
+            &preset_args,
+            cwd_str,
+            &options,
+            Some("model-x"),
+        )
+        .expect("launch args");
+
+        let (tx, _rx) = tokio::sync::broadcast::channel(16);
+        let argv: Vec<&str> = args.iter().map(String::as_str).collect();
+        let _process = super::AcpProcess::spawn("sh", &argv, cwd_str, tx, "args-test", "s-1")
+            .await
+            .expect("spawn");
+        let recorded = cwd.path().join("args.txt");
+        for _ in 0..50 {
+            if fs::read_to_string(&recorded).is_ok_and(|text| text.ends_with("model-x\n")) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(
+            fs::read_to_string(&recorded).expect("args recorded"),
+            "--default-flag\n--log-level\ndebug\n-m\nmodel-x\n"
+        );
+
+        let rejected = SessionLaunchOptions {
+            provider_args: Some(vec!["$(whoami)".to_string()]),
+            ..SessionLaunchOptions::default()
+        };
+        assert!(launch_args(
+            &settings,
+            "args-test-provider",
+            &preset_args,
+            cwd_str,
+            &rejected,
+            None
+        )
+        .is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn transcript_keeps_replies_longer_than_the_notification_channel() {
+        let cwd = tempfile::tempdir().expect("tempdir");
+        let cwd_str = cwd.path().to_str().expect("utf-8 cwd");
+        // Streams 300 chunks, more than the channel holds, before answering.
+        let script = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  [ -z "$id" ] && continue
+  i=0
+  while [ $i -lt 300 ]; do
+    printf '{"jsonrpc":"2.0","method":"session/update","params":{"sessionId":"a","update":{"sessionUpdate":"agent_message_chunk","content":{"type":"text","text":"w%s "}}}}\n' "$i"
+    i=$((i + 1))
+  done
+  printf '{"jsonrpc":"2.0","id":%s,"result":{"stopReason":"end_turn"}}\n' "$id"
+done
+"#;
+        let (tx, _) = tokio::sync::broadcast::channel(256);
+        let process = super::AcpProcess::spawn(
+            "sh",
+            &["-c", script],
+            cwd_str,
+            tx.clone(),
+            "mock-agent",
+            "s-1",
+        )
+        .await
+        .expect("spawn");
+
+        let collector = transcript::ReplyCollector::spawn(tx.subscribe());
+        process
+            .prompt_with_timeout("a", "hello", 10_000)
+            .await
+            .expect("prompt");
+        let reply = collector.finish().await;
+        process.kill().await;
+
+        let expected: String = (0..300).map(|i| format!("w{i} ")).collect();
+        assert_eq!(reply, expected);
+    }
 }
@@ -0,0 +1,102 @@
+//! Normalization of collected agent output.
+//!
+//! Agents may emit CRLF or bare CR line endings, and some prefix their output
+//! (or individual chunks) with a UTF-8 byte-order mark. Both show up as
+//! artifacts once the chunks are joined and rendered. Callers that return
+//! collected output pass it through [`OutputNormalization::apply`]; callers
+//! that need the raw text use [`OutputNormalization::RAW`].
+
+use std::borrow::Cow;
+
+const BOM: char = '\u{feff}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputNormalization {
+    /// Remove every U+FEFF byte-order mark.
+    pub strip_bom: bool,
+    /// Rewrite CRLF and bare CR line endings to LF.
+    pub normalize_line_endings: bool,
+}
+
+impl Default for OutputNormalization {
+    fn default() -> Self {
+        Self {
+            strip_bom: true,
+            normalize_line_endings: true,
+        }
+    }
+}
+
+impl OutputNormalization {
+    /// Leave output untouched.
+    pub const RAW: Self = Self {
+        strip_bom: false,
+        normalize_line_endings: false,
+    };
+
+    /// Normalize `text`, borrowing it when nothing needs to change.
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let has_bom = self.strip_bom && text.contains(BOM);
+        let has_cr = self.normalize_line_endings && text.contains('\r');
+        if !has_bom && !has_cr {
+            return Cow::Borrowed(text);
+        }
+
+        let mut output = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                BOM if has_bom => {}
+                '\r' if has_cr => {
+                    if chars.peek() == Some(&'\n') {
+                        chars.next();
+                    }
+                    output.push('\n');
+                }
+                _ => output.push(c),
+            }
+        }
+        Cow::Owned(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_crlf_and_bom_prefixed_output() {
+        let normalization = OutputNormalization::default();
+        assert_eq!(
+            normalization.apply("\u{feff}line one\r\nline two\r\n"),
+            "line one\nline two\n"
+        );
+        // Chunks joined from separate messages can each carry a BOM.
+        assert_eq!(
+            normalization.apply("\u{feff}first\r\u{feff}second\r\n\r\nend"),
+            "first\nsecond\n\nend"
+        );
+        assert!(matches!(
+            normalization.apply("already clean\n"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn raw_and_partial_settings_keep_what_they_disable() {
+        let text = "\u{feff}a\r\nb";
+        assert_eq!(OutputNormalization::RAW.apply(text), text);
+
+        let keep_eol = OutputNormalization {
+            normalize_line_endings: false,
+            ..OutputNormalization::default()
+        };
+        assert_eq!(keep_eol.apply(text), "a\r\nb");
+
+        let keep_bom = OutputNormalization {
+            strip_bom: false,
+            ..OutputNormalization::default()
+        };
+        assert_eq!(keep_bom.apply(text), "\u{feff}a\nb");
+    }
+}
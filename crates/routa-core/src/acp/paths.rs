@@ -6,29 +6,94 @@
 //!   - Downloads: `{base}/.downloads/{agentId}/{version}/`
 //!   - Runtimes: `{base}/.runtimes/{runtime}/{version}/`
 //!   - Icons: `{base}/.icons/`
+//!   - Session transcripts: `{base}/.transcripts/{sessionId}.jsonl`
 //!   - Registry cache: `{base}/registry.json`
 //!   - Installed state: `{base}/installed.json`
+//!
+//! `{data_dir}` is the platform data-local directory. `ROUTA_ACP_DATA_DIR`
+//! replaces `{base}` outright. When the platform has no data-local directory
+//! the base falls back to `~/.routa/acp-agents` (or `.routa/acp-agents` under
+//! the working directory without a home) with a warning, or is refused when
+//! `ROUTA_ACP_DATA_DIR_STRICT` is set.
 
 use std::path::PathBuf;
+use std::sync::Once;
+
+use serde::Serialize;
+
+use crate::settings::AcpSettings;
+
+/// Where the ACP base directory came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BaseDirSource {
+    /// `ROUTA_ACP_DATA_DIR`
+    Env,
+    /// The platform data-local directory.
+    DataLocalDir,
+    /// No data-local directory; see the module docs.
+    Fallback,
+    /// `AcpPaths::with_base_dir`.
+    Custom,
+}
 
 /// ACP paths manager for local agent installation.
 #[derive(Debug, Clone)]
 pub struct AcpPaths {
     base_dir: PathBuf,
+    source: BaseDirSource,
 }
 
+static FALLBACK_WARNING: Once = Once::new();
+
 impl AcpPaths {
-    /// Create a new AcpPaths instance using the system data directory.
+    /// Create a new AcpPaths instance using the system data directory, or the
+    /// fallback (with a warning) when there is none, regardless of strict mode.
     pub fn new() -> Self {
-        let base_dir = dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("acp-agents");
-        Self { base_dir }
+        Self::from_settings(&AcpSettings::from_env())
+    }
+
+    /// Like `new`, but fails instead of falling back when
+    /// `ROUTA_ACP_DATA_DIR_STRICT` is set. Call once at startup.
+    pub fn try_new() -> Result<Self, String> {
+        Self::try_from_settings(&AcpSettings::from_env())
+    }
+
+    /// `new` with already-read settings.
+    pub fn from_settings(settings: &AcpSettings) -> Self {
+        Self::resolve(settings, false).expect("non-strict resolution always succeeds")
+    }
+
+    /// `try_new` with already-read settings.
+    pub fn try_from_settings(settings: &AcpSettings) -> Result<Self, String> {
+        Self::resolve(settings, settings.data_dir_strict)
+    }
+
+    fn resolve(settings: &AcpSettings, strict: bool) -> Result<Self, String> {
+        let (base_dir, source) = resolve_base_dir(
+            settings.data_dir.clone(),
+            dirs::data_local_dir(),
+            dirs::home_dir(),
+            strict,
+        )?;
+        if source == BaseDirSource::Fallback {
+            FALLBACK_WARNING.call_once(|| {
+                tracing::warn!(
+                    "[AcpPaths] No platform data directory; storing ACP agent data in {:?}. \
+                     Set ROUTA_ACP_DATA_DIR to choose a location.",
+                    base_dir
+                );
+            });
+        }
+        Ok(Self { base_dir, source })
     }
 
     /// Create AcpPaths with a custom base directory (for testing).
     pub fn with_base_dir(base_dir: PathBuf) -> Self {
-        Self { base_dir }
+        Self {
+            base_dir,
+            source: BaseDirSource::Custom,
+        }
     }
 
     /// Get the base directory for all ACP agent data.
@@ -36,6 +101,11 @@ impl AcpPaths {
         &self.base_dir
     }
 
+    /// Where `base_dir` came from.
+    pub fn source(&self) -> BaseDirSource {
+        self.source
+    }
+
     /// Get the directory for a specific agent.
     pub fn agent_dir(&self, agent_id: &str) -> PathBuf {
         self.base_dir.join(agent_id)
@@ -51,6 +121,16 @@ impl AcpPaths {
         self.base_dir.join(".downloads")
     }
 
+    /// Get the directory holding cached download archives.
+    pub fn archive_cache_dir(&self) -> PathBuf {
+        self.base_dir.join(".archive-cache")
+    }
+
+    /// Get the directory holding per-session prompt/response transcripts.
+    pub fn transcripts_dir(&self) -> PathBuf {
+        self.base_dir.join(".transcripts")
+    }
+
     /// Get the download directory for a specific agent version.
     pub fn agent_download_dir(&self, agent_id: &str, version: &str) -> PathBuf {
         self.downloads_dir().join(agent_id).join(version)
@@ -112,3 +192,68 @@ impl Default for AcpPaths {
         Self::new()
     }
 }
+
+fn resolve_base_dir(
+    env_override: Option<PathBuf>,
+    data_local_dir: Option<PathBuf>,
+    home_dir: Option<PathBuf>,
+    strict: bool,
+) -> Result<(PathBuf, BaseDirSource), String> {
+    if let Some(dir) = env_override.filter(|dir| !dir.as_os_str().is_empty()) {
+        return Ok((dir, BaseDirSource::Env));
+    }
+    if let Some(dir) = data_local_dir {
+        return Ok((dir.join("acp-agents"), BaseDirSource::DataLocalDir));
+    }
+    if strict {
+        return Err(
+            "No platform data directory for ACP agent data and ROUTA_ACP_DATA_DIR_STRICT is set; \
+             set ROUTA_ACP_DATA_DIR"
+                .to_string(),
+        );
+    }
+    let root = home_dir
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."));
+    Ok((
+        root.join(".routa").join("acp-agents"),
+        BaseDirSource::Fallback,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_under_home_without_data_dir_and_refuses_in_strict_mode() {
+        let (dir, source) =
+            resolve_base_dir(None, None, Some(PathBuf::from("/home/me")), false).unwrap();
+        assert_eq!(dir, PathBuf::from("/home/me/.routa/acp-agents"));
+        assert_eq!(source, BaseDirSource::Fallback);
+
+        let (dir, _) = resolve_base_dir(None, None, None, false).unwrap();
+        assert!(dir.ends_with(".routa/acp-agents"));
+        assert!(dir.is_absolute());
+
+        assert!(resolve_base_dir(None, None, Some(PathBuf::from("/home/me")), true).is_err());
+    }
+
+    #[test]
+    fn env_override_wins_over_data_dir() {
+        let (dir, source) = resolve_base_dir(
+            Some(PathBuf::from("/srv/acp")),
+            Some(PathBuf::from("/data")),
+            None,
+            true,
+        )
+        .unwrap();
+        assert_eq!(dir, PathBuf::from("/srv/acp"));
+        assert_eq!(source, BaseDirSource::Env);
+
+        let (dir, source) =
+            resolve_base_dir(None, Some(PathBuf::from("/data")), None, true).unwrap();
+        assert_eq!(dir, PathBuf::from("/data/acp-agents"));
+        assert_eq!(source, BaseDirSource::DataLocalDir);
+    }
+}
@@ -0,0 +1,103 @@
+//! Environment variables set on an agent process from its preset.
+//!
+//! A preset's `env` maps variable names to values that may reference the
+//! parent environment as `${VAR}`; built-in presets pass through the API
+//! keys their agent reads (`ANTHROPIC_API_KEY` for Claude Code, …). References
+//! are resolved with `shell_env::var`, so keys exported from the login shell
+//! reach agents even when the desktop app was started without them.
+//! Variables that resolve to an empty value are left unset.
+
+use std::collections::HashMap;
+
+/// A preset `env` passing `names` through from the parent environment.
+pub fn passthrough(names: &[&str]) -> HashMap<String, String> {
+    names
+        .iter()
+        .map(|name| (name.to_string(), format!("${{{name}}}")))
+        .collect()
+}
+
+/// Resolve a preset's `env`, plus the `extra_passthrough` variable names
+/// (`AcpSettings::env_passthrough`), into the variables to set on the agent
+/// process, sorted by name.
+pub fn resolve_preset_env(
+    env: &HashMap<String, String>,
+    extra_passthrough: &[String],
+) -> Vec<(String, String)> {
+    let mut merged = env.clone();
+    for name in extra_passthrough {
+        merged
+            .entry(name.clone())
+            .or_insert_with(|| format!("${{{name}}}"));
+    }
+    resolve_with(&merged, crate::shell_env::var)
+}
+
+fn resolve_with(
+    env: &HashMap<String, String>,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Vec<(String, String)> {
+    let mut resolved: Vec<(String, String)> = env
+        .iter()
+        .map(|(key, value)| (key.clone(), expand_env_value(value, &lookup)))
+        .filter(|(_, value)| !value.is_empty())
+        .collect();
+    resolved.sort();
+    resolved
+}
+
+/// Replace each `${VAR}` in `value` with `lookup(VAR)`, or nothing when it is
+/// unset. Text outside references, including an unterminated `${`, is kept.
+pub fn expand_env_value(value: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        out.push_str(&lookup(&after[..end]).unwrap_or_default());
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "ANTHROPIC_API_KEY" => Some("sk-ant".to_string()),
+            "HOST" => Some("example.com".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_references_from_lookup() {
+        assert_eq!(expand_env_value("${ANTHROPIC_API_KEY}", lookup), "sk-ant");
+        assert_eq!(
+            expand_env_value("https://${HOST}/v1?k=${MISSING}", lookup),
+            "https://example.com/v1?k="
+        );
+        assert_eq!(expand_env_value("literal", lookup), "literal");
+        assert_eq!(expand_env_value("broken ${HOST", lookup), "broken ${HOST");
+    }
+
+    #[test]
+    fn resolve_drops_unset_variables() {
+        let mut env = passthrough(&["ANTHROPIC_API_KEY", "OPENAI_API_KEY"]);
+        env.insert("BASE_URL".to_string(), "https://${HOST}".to_string());
+        assert_eq!(
+            resolve_with(&env, lookup),
+            vec![
+                ("ANTHROPIC_API_KEY".to_string(), "sk-ant".to_string()),
+                ("BASE_URL".to_string(), "https://example.com".to_string()),
+            ]
+        );
+    }
+}
@@ -7,15 +7,18 @@
 //!   4. `prompt(sid, text)`    — send "session/prompt" (5-min timeout), stream via SSE
 //!   5. `kill()`               — terminate the process
 //!
+//! `cancel_or_kill(sid, grace)` sends "session/cancel" and kills the process if
+//! the in-flight prompt has not settled within `grace`
+//! (`ROUTA_ACP_CANCEL_GRACE_MS`, default 5s), for agents stuck in a tool loop.
+//!
 //! Agent→client requests (permissions, fs, terminal) are handled in the background reader.
 //! Agent message notifications are traced to JSONL files for attribution tracking.
 
 use std::collections::HashMap;
-use std::io::ErrorKind;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 use std::path::Path;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -23,6 +26,11 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, ChildStdin};
 use tokio::sync::{broadcast, oneshot, Mutex};
 
+use super::provider_settings::DEFAULT_PROMPT_TIMEOUT_MS;
+use super::resource_limits::{is_allocation_failure, ResourceLimits};
+use super::spawn_error::{
+    SpawnErrorCode, COMMAND_NOT_FOUND_HINT, MISSING_INTERPRETER_HINT, PERMISSION_DENIED_HINT,
+};
 use super::terminal_manager::TerminalManager;
 #[cfg(windows)]
 use super::CREATE_NO_WINDOW;
@@ -33,6 +41,17 @@ use crate::trace::{
 /// Callback type for session/update notifications from the agent.
 pub type NotificationSender = broadcast::Sender<serde_json::Value>;
 
+/// Suffix of the spawn error returned when the agent exits right after launch.
+pub(crate) const PROCESS_DIED_DURING_STARTUP: &str = "process died during startup";
+
+/// How long a prompt may keep running after `session/cancel` before the agent
+/// process is killed.
+pub const DEFAULT_CANCEL_GRACE: Duration = Duration::from_secs(5);
+
+fn current_session_id(session_id: &std::sync::RwLock<String>) -> String {
+    session_id.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
 /// Type alias for the pending request map to avoid complex type repetition.
 type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<serde_json::Value, String>>>>>;
 
@@ -43,7 +62,17 @@ pub struct AcpProcess {
     pending: PendingMap,
     next_id: Arc<AtomicU64>,
     alive: Arc<AtomicBool>,
+    /// Why the process exited, when it was killed for exceeding a resource
+    /// limit or for ignoring `session/cancel`.
+    exit_error: Arc<std::sync::Mutex<Option<String>>>,
+    /// The child's exit status once it has exited on its own.
+    exit_status: Arc<std::sync::Mutex<Option<std::process::ExitStatus>>>,
     notification_tx: NotificationSender,
+    /// Number of `session/prompt` requests awaiting a response.
+    prompts_in_flight: Arc<AtomicUsize>,
+    /// Our session id, stamped on notifications, traces and agent requests;
+    /// rebound when a pre-spawned process is handed to a session.
+    session_id: Arc<std::sync::RwLock<String>>,
     display_name: String,
     /// The command used to spawn this process (e.g., "npx", "uvx", "opencode")
     command: String,
@@ -62,13 +91,40 @@ impl AcpProcess {
         notification_tx: NotificationSender,
         display_name: &str,
         our_session_id: &str,
+    ) -> Result<Self, String> {
+        Self::spawn_with_env(
+            command,
+            args,
+            &[],
+            cwd,
+            notification_tx,
+            ResourceLimits::default(),
+            display_name,
+            our_session_id,
+        )
+        .await
+    }
+
+    /// Spawn the agent process with `env` set on top of the inherited
+    /// environment (e.g. the preset's API keys), under `limits`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn spawn_with_env(
+        command: &str,
+        args: &[&str],
+        env: &[(String, String)],
+        cwd: &str,
+        notification_tx: NotificationSender,
+        limits: ResourceLimits,
+        display_name: &str,
+        our_session_id: &str,
     ) -> Result<Self, String> {
         tracing::info!(
-            "[AcpProcess:{}] Spawning: {} {} (cwd: {})",
+            "[AcpProcess:{}] Spawning: {} {} (cwd: {}, env: [{}])",
             display_name,
             command,
             args.join(" "),
             cwd,
+            redacted_env_list(env),
         );
 
         let cwd_path = Path::new(cwd);
@@ -92,8 +148,8 @@ impl AcpProcess {
         command_builder
             .args(args)
             .current_dir(cwd)
-            .env("PATH", crate::shell_env::full_path())
-            .env("NODE_NO_READLINE", "1")
+            .envs(launch_env_overrides(&resolved_command))
+            .envs(env.iter().map(|(key, value)| (key, value)))
             .stdin(std::process::Stdio::piped())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
@@ -103,32 +159,29 @@ impl AcpProcess {
             .as_std_mut()
             .creation_flags(CREATE_NO_WINDOW);
 
-        // codex-acp often returns only stopReason in session/prompt result.
-        // Enabling lightweight codex logs gives us process_output lines that
-        // include assistant deltas, which the CLI can aggregate as final output.
-        if resolved_command.ends_with("codex-acp") && std::env::var_os("RUST_LOG").is_none() {
-            command_builder.env(
-                "RUST_LOG",
-                "info,codex_acp::thread=info,codex_acp::codex_agent=info",
-            );
-        }
-
-        let mut child = command_builder.spawn().map_err(|e| match e.kind() {
-            ErrorKind::NotFound => {
-                let resolved_exists = Path::new(&resolved_command).exists();
-                if resolved_exists {
-                    format!(
-                        "Failed to execute '{command}' (resolved: '{resolved_command}'): {e}. The binary exists, but a required interpreter or wrapper target may be missing."
-                    )
-                } else {
-                    format!(
-                        "Failed to spawn '{command}' (resolved: '{resolved_command}'): {e}. Is it installed and in PATH?"
-                    )
+        limits.apply(&mut command_builder);
+
+        let mut child = command_builder.spawn().map_err(|e| {
+            match SpawnErrorCode::from_io_error_kind(e.kind()) {
+                SpawnErrorCode::CommandNotFound => {
+                    let resolved_exists = Path::new(&resolved_command).exists();
+                    if resolved_exists {
+                        format!(
+                            "Failed to execute '{command}' (resolved: '{resolved_command}'): {e}. {MISSING_INTERPRETER_HINT}"
+                        )
+                    } else {
+                        format!(
+                            "Failed to spawn '{command}' (resolved: '{resolved_command}'): {e}. {COMMAND_NOT_FOUND_HINT}"
+                        )
+                    }
                 }
+                SpawnErrorCode::PermissionDenied => format!(
+                    "Failed to spawn '{command}' (resolved: '{resolved_command}'): {e}. {PERMISSION_DENIED_HINT}"
+                ),
+                _ => format!(
+                    "Failed to spawn '{command}' (resolved: '{resolved_command}') from cwd '{cwd}': {e}"
+                ),
             }
-            _ => format!(
-                "Failed to spawn '{command}' (resolved: '{resolved_command}') from cwd '{cwd}': {e}"
-            ),
         })?;
 
         let stdin = child
@@ -142,22 +195,33 @@ impl AcpProcess {
         let stderr = child.stderr.take();
 
         let alive = Arc::new(AtomicBool::new(true));
+        let exit_error = Arc::new(std::sync::Mutex::new(None));
+        let exit_status = Arc::new(std::sync::Mutex::new(None));
+        let child = Arc::new(Mutex::new(Some(child)));
         let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
         let stdin = Arc::new(Mutex::new(stdin));
 
         let name = display_name.to_string();
+        let session_id = Arc::new(std::sync::RwLock::new(our_session_id.to_string()));
+        // Set when the agent reports a failed allocation, which is what lets
+        // a later crash be blamed on the memory limit.
+        let allocation_failed = Arc::new(AtomicBool::new(false));
 
         // Log stderr in background and forward to frontend as process_output
         if let Some(stderr) = stderr {
+            let allocation_failed = allocation_failed.clone();
             let name_clone = name.clone();
             let ntx_stderr = notification_tx.clone();
-            let our_sid_stderr = our_session_id.to_string();
+            let session_id_stderr = session_id.clone();
             let resolved_command_stderr = resolved_command.clone();
             tokio::spawn(async move {
                 let reader = BufReader::new(stderr);
                 let mut lines = reader.lines();
                 while let Ok(Some(line)) = lines.next_line().await {
                     if !line.trim().is_empty() {
+                        if is_allocation_failure(&line) {
+                            allocation_failed.store(true, Ordering::SeqCst);
+                        }
                         if should_ignore_process_stderr(
                             &resolved_command_stderr,
                             &name_clone,
@@ -171,7 +235,7 @@ impl AcpProcess {
                             "jsonrpc": "2.0",
                             "method": "session/update",
                             "params": {
-                                "sessionId": our_sid_stderr,
+                                "sessionId": current_session_id(&session_id_stderr),
                                 "update": {
                                     "sessionUpdate": "process_output",
                                     "source": "stderr",
@@ -188,11 +252,14 @@ impl AcpProcess {
 
         // Background stdout reader — dispatches responses, notifications, agent requests
         let alive_clone = alive.clone();
+        let exit_error_clone = exit_error.clone();
+        let exit_status_clone = exit_status.clone();
+        let child_clone = child.clone();
         let pending_clone = pending.clone();
         let ntx = notification_tx.clone();
         let stdin_clone = stdin.clone();
         let name_clone = name.clone();
-        let our_sid = our_session_id.to_string();
+        let session_id_clone = session_id.clone();
         let cwd_clone = cwd.to_string();
         let provider_clone = display_name.to_string();
 
@@ -207,15 +274,20 @@ impl AcpProcess {
             let mut pending_tool_calls: std::collections::HashMap<String, (String, bool)> =
                 std::collections::HashMap::new();
 
+            // Reassembles messages an agent pretty-prints or splits across writes
+            let mut frames = JsonFrameBuffer::default();
+
             while let Ok(Some(line)) = lines.next_line().await {
                 let line = line.trim().to_string();
                 if line.is_empty() {
                     continue;
                 }
+                let our_sid = current_session_id(&session_id_clone);
 
-                let msg: serde_json::Value = match serde_json::from_str(&line) {
-                    Ok(v) => v,
-                    Err(_) => {
+                let msg: serde_json::Value = match frames.push(&line) {
+                    JsonFrame::Complete(v) => v,
+                    JsonFrame::Pending => continue,
+                    JsonFrame::NotJson => {
                         // Try to find embedded JSON objects
                         if let Some(v) = try_parse_embedded_json(&line) {
                             v
@@ -507,6 +579,7 @@ impl AcpProcess {
             }
 
             // Flush any remaining buffered agent message content
+            let our_sid = current_session_id(&session_id_clone);
             if !agent_msg_buffer.is_empty() {
                 let record = TraceRecord::new(
                     &our_sid,
@@ -541,6 +614,31 @@ impl AcpProcess {
             }
 
             alive_clone.store(false, Ordering::SeqCst);
+            let limit_error = limit_exit_error(
+                &child_clone,
+                &limits,
+                allocation_failed.load(Ordering::SeqCst),
+                &name_clone,
+            )
+            .await;
+            if let Some(status) = child_clone
+                .lock()
+                .await
+                .as_mut()
+                .and_then(|child| child.try_wait().ok().flatten())
+            {
+                *exit_status_clone.lock().unwrap_or_else(|e| e.into_inner()) = Some(status);
+            }
+            if let Some(error) = &limit_error {
+                tracing::warn!("[AcpProcess:{}] {}", name_clone, error);
+                *exit_error_clone.lock().unwrap_or_else(|e| e.into_inner()) = Some(error.clone());
+            }
+            let exit_message =
+                limit_error.unwrap_or_else(|| format!("{name_clone} process exited"));
+            // Fail outstanding requests now instead of letting them run to their timeout.
+            for (_, tx) in pending_clone.lock().await.drain() {
+                let _ = tx.send(Err(exit_message.clone()));
+            }
             tracing::info!("[AcpProcess:{}] stdout reader finished", name_clone);
         });
 
@@ -548,29 +646,62 @@ impl AcpProcess {
         tokio::time::sleep(Duration::from_millis(300)).await;
 
         if !alive.load(Ordering::SeqCst) {
-            return Err(format!("{display_name} process died during startup"));
+            if let Some(error) = exit_error.lock().unwrap_or_else(|e| e.into_inner()).take() {
+                return Err(error);
+            }
+            return Err(format!("{display_name} {PROCESS_DIED_DURING_STARTUP}"));
         }
 
         tracing::info!("[AcpProcess:{}] Process started", display_name);
 
         Ok(Self {
             stdin,
-            child: Arc::new(Mutex::new(Some(child))),
+            child,
             pending,
             next_id: Arc::new(AtomicU64::new(1)),
             alive,
+            exit_error,
+            exit_status,
             notification_tx,
+            prompts_in_flight: Arc::new(AtomicUsize::new(0)),
+            session_id,
             display_name: display_name.to_string(),
             command: command.to_string(),
             _reader_handle: reader_handle,
         })
     }
 
+    /// Use `session_id` from now on for notifications, traces and agent
+    /// requests, e.g. when a pre-spawned process is checked out for a session.
+    pub fn rebind_session_id(&self, session_id: &str) {
+        *self.session_id.write().unwrap_or_else(|e| e.into_inner()) = session_id.to_string();
+    }
+
     /// Whether the process is still alive.
     pub fn is_alive(&self) -> bool {
         self.alive.load(Ordering::SeqCst)
     }
 
+    /// Why the process is no longer running: the resource-limit error, else
+    /// its exit status. `None` while alive, or when it was killed by us.
+    pub fn exit_reason(&self) -> Option<String> {
+        if self.is_alive() {
+            return None;
+        }
+        if let Some(error) = self
+            .exit_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+        {
+            return Some(error);
+        }
+        self.exit_status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .map(|status| format!("{} exited ({status})", self.display_name))
+    }
+
     /// Send a JSON-RPC request and wait for the response.
     pub async fn send_request(
         &self,
@@ -579,6 +710,14 @@ impl AcpProcess {
         timeout_ms: Option<u64>,
     ) -> Result<serde_json::Value, String> {
         if !self.is_alive() {
+            if let Some(error) = self
+                .exit_error
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone()
+            {
+                return Err(error);
+            }
             return Err(format!("{} process is not alive", self.display_name));
         }
 
@@ -607,20 +746,7 @@ impl AcpProcess {
                 .map_err(|e| format!("Flush {method}: {e}"))?;
         }
 
-        // Determine timeout based on method and command type
-        // npx/uvx agents may need longer timeout for first-time package download
-        let is_npx_or_uvx = self.command == "npx" || self.command == "uvx";
-        let default_timeout = match method {
-            "initialize" | "session/new" | "session/load" => {
-                if is_npx_or_uvx {
-                    120_000 // 2 min for npx/uvx (may need to download packages)
-                } else {
-                    15_000 // 15s for others
-                }
-            }
-            "session/prompt" => 300_000, // 5 min
-            _ => 30_000,
-        };
+        let default_timeout = default_request_timeout_ms(&self.command, method);
         let timeout_dur = Duration::from_millis(timeout_ms.unwrap_or(default_timeout));
 
         match tokio::time::timeout(timeout_dur, rx).await {
@@ -733,13 +859,25 @@ impl AcpProcess {
 
     /// Send a prompt to an existing session. 5-minute timeout.
     pub async fn prompt(&self, session_id: &str, text: &str) -> Result<serde_json::Value, String> {
+        self.prompt_with_timeout(session_id, text, DEFAULT_PROMPT_TIMEOUT_MS)
+            .await
+    }
+
+    /// Send a prompt with an explicit timeout.
+    pub async fn prompt_with_timeout(
+        &self,
+        session_id: &str,
+        text: &str,
+        timeout_ms: u64,
+    ) -> Result<serde_json::Value, String> {
+        let _in_flight = InFlightPrompt::start(&self.prompts_in_flight);
         self.send_request(
             "session/prompt",
             serde_json::json!({
                 "sessionId": session_id,
                 "prompt": [{ "type": "text", "text": text }]
             }),
-            Some(300_000),
+            Some(timeout_ms),
         )
         .await
     }
@@ -757,6 +895,33 @@ impl AcpProcess {
         let _ = stdin.flush().await;
     }
 
+    /// Cancel the in-flight prompt, killing the process if the prompt is still
+    /// running `grace` after `session/cancel` was sent. Returns whether the
+    /// process was killed.
+    pub async fn cancel_or_kill(&self, session_id: &str, grace: Duration) -> bool {
+        self.cancel(session_id).await;
+        let settled = tokio::time::timeout(grace, async {
+            while self.is_alive() && self.prompts_in_flight.load(Ordering::SeqCst) > 0 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .is_ok();
+        if settled {
+            return false;
+        }
+
+        let reason = format!(
+            "{} did not stop within {}ms of session/cancel and was killed",
+            self.display_name,
+            grace.as_millis()
+        );
+        tracing::warn!("[AcpProcess:{}] {}", self.display_name, reason);
+        *self.exit_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(reason.clone());
+        self.kill_with_reason(&reason).await;
+        true
+    }
+
     /// Get the notification broadcast sender (for subscribing to SSE).
     pub fn notification_sender(&self) -> &NotificationSender {
         &self.notification_tx
@@ -764,6 +929,10 @@ impl AcpProcess {
 
     /// Kill the agent process.
     pub async fn kill(&self) {
+        self.kill_with_reason("Process killed").await;
+    }
+
+    async fn kill_with_reason(&self, reason: &str) {
         self.alive.store(false, Ordering::SeqCst);
         if let Some(mut child) = self.child.lock().await.take() {
             tracing::info!("[AcpProcess:{}] Killing process", self.display_name);
@@ -772,11 +941,94 @@ impl AcpProcess {
         // Reject all pending requests
         let mut map = self.pending.lock().await;
         for (_, tx) in map.drain() {
-            let _ = tx.send(Err("Process killed".to_string()));
+            let _ = tx.send(Err(reason.to_string()));
         }
     }
 }
 
+/// Counts a `session/prompt` as in flight until dropped, including when the
+/// caller stops waiting for it.
+struct InFlightPrompt<'a>(&'a AtomicUsize);
+
+impl<'a> InFlightPrompt<'a> {
+    fn start(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for InFlightPrompt<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// After stdout closes, reap the child and report whether it was killed for
+/// exceeding `limits`. `None` if it exited otherwise, was killed by us, or
+/// does not exit promptly.
+async fn limit_exit_error(
+    child: &Mutex<Option<Child>>,
+    limits: &ResourceLimits,
+    allocation_failed: bool,
+    display_name: &str,
+) -> Option<String> {
+    let mut guard = child.lock().await;
+    let child = guard.as_mut()?;
+    let status = tokio::time::timeout(Duration::from_secs(2), child.wait())
+        .await
+        .ok()?
+        .ok()?;
+    let exceeded = limits.exceeded_by(&status, allocation_failed)?;
+    Some(limits.exceeded_error(display_name, exceeded))
+}
+
+/// Default timeout for a JSON-RPC `method` sent to an agent launched with
+/// `command`. npx/uvx agents may need longer for a first-time package download.
+pub fn default_request_timeout_ms(command: &str, method: &str) -> u64 {
+    let is_npx_or_uvx = command == "npx" || command == "uvx";
+    match method {
+        "initialize" | "session/new" | "session/load" => {
+            if is_npx_or_uvx {
+                120_000 // 2 min for npx/uvx (may need to download packages)
+            } else {
+                15_000 // 15s for others
+            }
+        }
+        "session/prompt" => DEFAULT_PROMPT_TIMEOUT_MS,
+        _ => 30_000,
+    }
+}
+
+/// `KEY=value` pairs for logging, with credential values redacted.
+pub(crate) fn redacted_env_list(env: &[(String, String)]) -> String {
+    env.iter()
+        .map(|(key, value)| format!("{key}={}", super::redact_env_value(key, value.clone())))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Environment variables set on top of the inherited environment when
+/// spawning `resolved_command`.
+pub fn launch_env_overrides(resolved_command: &str) -> Vec<(String, String)> {
+    let mut env = vec![
+        (
+            "PATH".to_string(),
+            crate::shell_env::full_path().to_string(),
+        ),
+        ("NODE_NO_READLINE".to_string(), "1".to_string()),
+    ];
+    // codex-acp often returns only stopReason in session/prompt result.
+    // Enabling lightweight codex logs gives us process_output lines that
+    // include assistant deltas, which the CLI can aggregate as final output.
+    if resolved_command.ends_with("codex-acp") && std::env::var_os("RUST_LOG").is_none() {
+        env.push((
+            "RUST_LOG".to_string(),
+            "info,codex_acp::thread=info,codex_acp::codex_agent=info".to_string(),
+        ));
+    }
+    env
+}
+
 /// Handle agent→client requests. Auto-approves permissions, handles fs ops.
 async fn handle_agent_request(
     method: &str,
@@ -853,6 +1105,62 @@ async fn handle_agent_request(
     }
 }
 
+/// Upper bound on a JSON message reassembled from several stdout lines.
+const MAX_JSON_FRAME_BYTES: usize = 16 * 1024 * 1024;
+
+/// Outcome of feeding one stdout line to a `JsonFrameBuffer`.
+#[derive(Debug, PartialEq)]
+enum JsonFrame {
+    /// A whole JSON value, from this line alone or joined with earlier ones.
+    Complete(serde_json::Value),
+    /// The line continues an unfinished value; wait for more.
+    Pending,
+    /// The line is not (the start of) a JSON value.
+    NotJson,
+}
+
+/// Joins stdout lines until they form a complete JSON value, for agents that
+/// pretty-print or chunk their messages. A line that breaks the value being
+/// assembled ends it (the partial value is dropped) and is parsed afresh.
+#[derive(Debug, Default)]
+struct JsonFrameBuffer {
+    partial: String,
+}
+
+impl JsonFrameBuffer {
+    fn push(&mut self, line: &str) -> JsonFrame {
+        if !self.partial.is_empty() {
+            self.partial.push('\n');
+            self.partial.push_str(line);
+            match serde_json::from_str::<serde_json::Value>(&self.partial) {
+                Ok(value) => {
+                    self.partial.clear();
+                    return JsonFrame::Complete(value);
+                }
+                Err(e) if e.is_eof() && self.partial.len() <= MAX_JSON_FRAME_BYTES => {
+                    return JsonFrame::Pending;
+                }
+                Err(_) => {
+                    tracing::debug!(
+                        "[AcpProcess] Dropping incomplete JSON message ({} bytes)",
+                        self.partial.len()
+                    );
+                    self.partial.clear();
+                }
+            }
+        }
+
+        match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(value) => JsonFrame::Complete(value),
+            Err(e) if e.is_eof() && (line.starts_with('{') || line.starts_with('[')) => {
+                self.partial.push_str(line);
+                JsonFrame::Pending
+            }
+            Err(_) => JsonFrame::NotJson,
+        }
+    }
+}
+
 /// Try to find and parse embedded JSON objects in a line.
 fn try_parse_embedded_json(line: &str) -> Option<serde_json::Value> {
     let mut depth = 0i32;
@@ -1011,8 +1319,13 @@ fn is_codex_otel_stderr(line: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{is_codex_otel_stderr, resolve_permission_option_id, should_ignore_process_stderr};
+    use super::{
+        is_codex_otel_stderr, redacted_env_list, resolve_permission_option_id,
+        should_ignore_process_stderr, AcpProcess, JsonFrame, JsonFrameBuffer,
+    };
     use serde_json::json;
+    use std::sync::Arc;
+    use std::time::Duration;
 
     #[test]
     fn ignores_codex_otel_stderr_noise() {
@@ -1059,4 +1372,183 @@ mod tests {
             Some("approved")
         );
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn exit_reason_reports_status_of_exited_agent() {
+        let cwd = tempfile::tempdir().expect("tempdir");
+        let (tx, _rx) = tokio::sync::broadcast::channel(16);
+        let process = AcpProcess::spawn(
+            "sh",
+            &["-c", "sleep 0.5; exit 3"],
+            cwd.path().to_str().expect("utf-8 cwd"),
+            tx,
+            "mock-agent",
+            "session-1",
+        )
+        .await
+        .expect("spawn mock agent");
+        assert_eq!(process.exit_reason(), None);
+
+        for _ in 0..50 {
+            if process.exit_reason().is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        let reason = process.exit_reason().expect("agent should have exited");
+        assert!(reason.contains("mock-agent exited"), "{reason}");
+        assert!(reason.contains('3'), "{reason}");
+    }
+
+    #[test]
+    fn json_frame_buffer_joins_split_response() {
+        let mut frames = JsonFrameBuffer::default();
+        assert_eq!(
+            frames.push(r#"{"jsonrpc":"2.0","id":3,"#),
+            JsonFrame::Pending
+        );
+        assert_eq!(
+            frames.push(r#""result":{"stopReason":"#),
+            JsonFrame::Pending
+        );
+        assert_eq!(
+            frames.push(r#""end_turn"}}"#),
+            JsonFrame::Complete(json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "result": { "stopReason": "end_turn" }
+            }))
+        );
+
+        // Pretty-printed messages are reassembled line by line.
+        for line in ["{", r#""id": 4,"#, r#""result": {}"#] {
+            assert_eq!(frames.push(line), JsonFrame::Pending);
+        }
+        assert_eq!(
+            frames.push("}"),
+            JsonFrame::Complete(json!({ "id": 4, "result": {} }))
+        );
+    }
+
+    #[test]
+    fn json_frame_buffer_recovers_from_broken_frames() {
+        let mut frames = JsonFrameBuffer::default();
+        assert_eq!(frames.push("Starting agent..."), JsonFrame::NotJson);
+        assert_eq!(frames.push(r#"{"id":5,"#), JsonFrame::Pending);
+        // A complete message on the next line ends the broken one.
+        assert_eq!(
+            frames.push(r#"{"id":6,"result":null}"#),
+            JsonFrame::Complete(json!({ "id": 6, "result": null }))
+        );
+        assert_eq!(
+            frames.push(r#"{"id":7,"result":1}"#),
+            JsonFrame::Complete(json!({ "id": 7, "result": 1 }))
+        );
+    }
+
+    /// A stdio agent that assigns its own session id and echoes back the id
+    /// each prompt was sent for.
+    #[cfg(unix)]
+    const MOCK_AGENT_SCRIPT: &str = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  [ -z "$id" ] && continue
+  case "$line" in
+    *'"session/new"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"sessionId":"agent-sess-42"}}\n' "$id" ;;
+    *'"session/prompt"'*)
+      sid=$(printf '%s' "$line" | sed -n 's/.*"sessionId":"\([^"]*\)".*/\1/p')
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"stopReason":"end_turn","promptSessionId":"%s"}}\n' "$id" "$sid" ;;
+    *)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{}}\n' "$id" ;;
+  esac
+done
+"#;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn prompt_uses_session_id_returned_by_agent() {
+        let cwd = tempfile::tempdir().expect("tempdir");
+        let cwd = cwd.path().to_str().expect("utf-8 cwd");
+        let (tx, _rx) = tokio::sync::broadcast::channel(16);
+        let process = AcpProcess::spawn(
+            "sh",
+            &["-c", MOCK_AGENT_SCRIPT],
+            cwd,
+            tx,
+            "mock-agent",
+            "routa-session",
+        )
+        .await
+        .expect("spawn mock agent");
+
+        process
+            .initialize_with_timeout(Some(5_000))
+            .await
+            .expect("initialize");
+        let session_id = process.new_session(cwd, &[]).await.expect("session/new");
+        assert_eq!(session_id, "agent-sess-42");
+
+        let result = process
+            .prompt_with_timeout(&session_id, "hello", 5_000)
+            .await
+            .expect("session/prompt");
+        assert_eq!(result["promptSessionId"], "agent-sess-42");
+        process.kill().await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cancel_kills_agent_that_keeps_prompting() {
+        let cwd = tempfile::tempdir().expect("tempdir");
+        let (tx, _rx) = tokio::sync::broadcast::channel(16);
+        // Reads requests but never answers, like an agent stuck in a tool loop.
+        let process = Arc::new(
+            AcpProcess::spawn(
+                "sh",
+                &["-c", "cat > /dev/null"],
+                cwd.path().to_str().expect("utf-8 cwd"),
+                tx,
+                "mock-agent",
+                "session-1",
+            )
+            .await
+            .expect("spawn mock agent"),
+        );
+        let grace = Duration::from_millis(200);
+
+        // Nothing in flight: cancel leaves the process running.
+        assert!(!process.cancel_or_kill("agent-sess", grace).await);
+        assert!(process.is_alive());
+
+        let prompt = tokio::spawn({
+            let process = process.clone();
+            async move {
+                process
+                    .prompt_with_timeout("agent-sess", "loop forever", 30_000)
+                    .await
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert!(process.cancel_or_kill("agent-sess", grace).await);
+        assert!(!process.is_alive());
+        let error = prompt.await.expect("join").expect_err("prompt should fail");
+        assert!(error.contains("session/cancel"), "{error}");
+        let reason = process.exit_reason().expect("exit reason");
+        assert!(reason.contains("was killed"), "{reason}");
+    }
+
+    #[test]
+    fn redacted_env_list_hides_credentials() {
+        let env = vec![
+            ("ANTHROPIC_API_KEY".to_string(), "sk-ant-secret".to_string()),
+            ("RUST_LOG".to_string(), "info".to_string()),
+        ];
+        assert_eq!(
+            redacted_env_list(&env),
+            "ANTHROPIC_API_KEY=[redacted], RUST_LOG=info"
+        );
+    }
 }
@@ -0,0 +1,424 @@
+//! Pool of pre-spawned ACP agent processes.
+//!
+//! Spawning an agent, sending `initialize` and `session/new` can take several
+//! seconds. When a provider has a pool size configured, idle processes that
+//! have already completed that handshake are kept per (provider, cwd) and
+//! handed to the next session that asks for the same pair.
+//!
+//! Only providers without session-scoped MCP setup are pooled: opencode, codex
+//! and qoder write MCP config containing the session id before spawning, and
+//! Claude uses the stream-json protocol instead of ACP.
+//!
+//! Pools are filled by `prewarm` (at server startup and when a workspace is
+//! opened) and topped up after each checkout, so the first session in a
+//! workspace doesn't cold-start either.
+//!
+//! A warm process is spawned with a placeholder session id. On checkout the
+//! process is rebound to the real id and its notifications are forwarded to
+//! the session's channel. Pooling is disabled until `ROUTA_ACP_WARM_POOL`
+//! configures a size.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{broadcast, Mutex};
+
+use super::process::AcpProcess;
+use crate::settings::AcpSettings;
+
+const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 600;
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Providers whose launch depends on per-session state and can't be pre-spawned.
+const UNPOOLABLE_PROVIDERS: &[&str] = &["claude", "opencode", "codex", "codex-acp", "qoder"];
+
+/// Per-provider pool sizes and idle timeout.
+#[derive(Debug, Clone)]
+pub struct ProcessPoolConfig {
+    pub sizes: HashMap<String, usize>,
+    pub idle_timeout: Duration,
+}
+
+impl Default for ProcessPoolConfig {
+    fn default() -> Self {
+        Self {
+            sizes: HashMap::new(),
+            idle_timeout: Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECS),
+        }
+    }
+}
+
+impl ProcessPoolConfig {
+    /// Target number of idle processes for `provider` (0 when disabled).
+    pub fn size_for(&self, provider: &str) -> usize {
+        if !supports_warm_start(provider) {
+            return 0;
+        }
+        self.sizes.get(provider).copied().unwrap_or(0)
+    }
+}
+
+/// Parse `provider=size` pairs separated by commas. Invalid entries are skipped.
+pub(crate) fn parse_pool_sizes(value: &str) -> HashMap<String, usize> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (provider, size) = entry.split_once('=')?;
+            let provider = provider.trim();
+            let size = size.trim().parse::<usize>().ok()?;
+            (!provider.is_empty() && size > 0).then(|| (provider.to_string(), size))
+        })
+        .collect()
+}
+
+/// Whether sessions for `provider` can be served by a pre-spawned process.
+pub fn supports_warm_start(provider: &str) -> bool {
+    let base_id = provider.strip_suffix("-registry").unwrap_or(provider);
+    !UNPOOLABLE_PROVIDERS.contains(&base_id)
+}
+
+/// An idle agent that has completed `initialize` and `session/new`.
+pub struct WarmProcess {
+    pub process: AcpProcess,
+    pub acp_session_id: String,
+    /// Subscribed at spawn so nothing the agent emits before checkout is lost.
+    pub notifications: broadcast::Receiver<serde_json::Value>,
+}
+
+#[derive(Default)]
+struct PoolEntry {
+    idle: Vec<WarmProcess>,
+    spawning: usize,
+    last_used: Option<Instant>,
+}
+
+type PoolKey = (String, String);
+
+/// Pre-spawned agent processes keyed by (provider, cwd).
+pub struct AcpProcessPool {
+    /// Pool sizes come from `warm_pool`; the rest applies to spawned processes.
+    settings: Arc<AcpSettings>,
+    entries: Arc<Mutex<HashMap<PoolKey, PoolEntry>>>,
+    maintenance_started: AtomicBool,
+}
+
+impl Default for AcpProcessPool {
+    fn default() -> Self {
+        Self::new(Arc::new(AcpSettings::default()))
+    }
+}
+
+impl AcpProcessPool {
+    pub fn new(settings: Arc<AcpSettings>) -> Self {
+        Self {
+            settings,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            maintenance_started: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_enabled_for(&self, provider: &str) -> bool {
+        self.settings.warm_pool.size_for(provider) > 0
+    }
+
+    /// Take a live warm process for `provider` in `cwd`, then top the pool back
+    /// up in the background. Returns `None` when the pool is empty or disabled.
+    pub async fn checkout(self: &Arc<Self>, provider: &str, cwd: &str) -> Option<WarmProcess> {
+        if !self.is_enabled_for(provider) {
+            return None;
+        }
+
+        let key = (provider.to_string(), cwd.to_string());
+        let (warm, dead) = {
+            let mut entries = self.entries.lock().await;
+            let entry = entries.entry(key).or_default();
+            entry.last_used = Some(Instant::now());
+            let mut dead = Vec::new();
+            let mut warm = None;
+            while let Some(candidate) = entry.idle.pop() {
+                if candidate.process.is_alive() {
+                    warm = Some(candidate);
+                    break;
+                }
+                dead.push(candidate);
+            }
+            (warm, dead)
+        };
+        for candidate in dead {
+            candidate.process.kill().await;
+        }
+
+        self.replenish(provider, cwd);
+        warm
+    }
+
+    /// Fill the pool of every configured provider for `cwd` in the background.
+    /// The pools count as used, so they are kept for the idle timeout even if
+    /// no session checks one out.
+    pub async fn prewarm(self: &Arc<Self>, cwd: &str) {
+        let providers: Vec<String> = self
+            .settings
+            .warm_pool
+            .sizes
+            .keys()
+            .filter(|provider| self.is_enabled_for(provider))
+            .cloned()
+            .collect();
+        if providers.is_empty() {
+            return;
+        }
+        {
+            let now = Instant::now();
+            let mut entries = self.entries.lock().await;
+            for provider in &providers {
+                let key = (provider.clone(), cwd.to_string());
+                entries.entry(key).or_default().last_used = Some(now);
+            }
+        }
+        for provider in &providers {
+            self.replenish(provider, cwd);
+        }
+    }
+
+    /// Spawn processes in the background until the pool for (`provider`, `cwd`)
+    /// reaches its configured size.
+    pub fn replenish(self: &Arc<Self>, provider: &str, cwd: &str) {
+        let target = self.settings.warm_pool.size_for(provider);
+        if target == 0 {
+            return;
+        }
+        self.start_maintenance();
+
+        let pool = Arc::clone(self);
+        let provider = provider.to_string();
+        let cwd = cwd.to_string();
+        tokio::spawn(async move {
+            let key = (provider.clone(), cwd.clone());
+            let missing = {
+                let mut entries = pool.entries.lock().await;
+                let entry = entries.entry(key.clone()).or_default();
+                let missing = target.saturating_sub(entry.idle.len() + entry.spawning);
+                entry.spawning += missing;
+                missing
+            };
+
+            for _ in 0..missing {
+                let spawned = spawn_warm_process(&pool.settings, &provider, &cwd).await;
+                let mut entries = pool.entries.lock().await;
+                let Some(entry) = entries.get_mut(&key) else {
+                    // Reaped while spawning; don't keep the process around.
+                    drop(entries);
+                    if let Ok(warm) = spawned {
+                        warm.process.kill().await;
+                    }
+                    continue;
+                };
+                entry.spawning = entry.spawning.saturating_sub(1);
+                match spawned {
+                    Ok(warm) => entry.idle.push(warm),
+                    Err(error) => {
+                        tracing::warn!(
+                            "[AcpProcessPool] Failed to pre-spawn {} in {}: {}",
+                            provider,
+                            cwd,
+                            error
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    /// Number of idle processes currently held for each (provider, cwd).
+    pub async fn idle_counts(&self) -> Vec<(String, String, usize)> {
+        let entries = self.entries.lock().await;
+        entries
+            .iter()
+            .map(|((provider, cwd), entry)| (provider.clone(), cwd.clone(), entry.idle.len()))
+            .collect()
+    }
+
+    /// Kill idle processes whose pool hasn't been used within the idle timeout,
+    /// drop dead ones, and refill the rest.
+    pub async fn maintain(self: &Arc<Self>) {
+        let now = Instant::now();
+        let mut to_kill = Vec::new();
+        let mut to_refill = HashSet::new();
+        {
+            let mut entries = self.entries.lock().await;
+            entries.retain(|(provider, cwd), entry| {
+                let expired = entry
+                    .last_used
+                    .map(|used| now.duration_since(used) >= self.settings.warm_pool.idle_timeout)
+                    .unwrap_or(true);
+                if expired && entry.spawning == 0 {
+                    to_kill.append(&mut entry.idle);
+                    return false;
+                }
+                let before = entry.idle.len();
+                let (alive, dead): (Vec<_>, Vec<_>) = entry
+                    .idle
+                    .drain(..)
+                    .partition(|warm| warm.process.is_alive());
+                entry.idle = alive;
+                to_kill.extend(dead);
+                if entry.idle.len() < before {
+                    to_refill.insert((provider.clone(), cwd.clone()));
+                }
+                true
+            });
+        }
+
+        for warm in to_kill {
+            warm.process.kill().await;
+        }
+        for (provider, cwd) in to_refill {
+            self.replenish(&provider, &cwd);
+        }
+    }
+
+    fn start_maintenance(self: &Arc<Self>) {
+        if self.maintenance_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let pool = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(MAINTENANCE_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let Some(pool) = pool.upgrade() else {
+                    break;
+                };
+                pool.maintain().await;
+            }
+        });
+    }
+}
+
+async fn spawn_warm_process(
+    settings: &AcpSettings,
+    provider: &str,
+    cwd: &str,
+) -> Result<WarmProcess, String> {
+    let preset = super::get_preset_by_id_with_registry(provider).await?;
+    let command = super::resolve_launch_command(&preset).await?;
+    let placeholder_session_id = format!("warm-{}", uuid::Uuid::new_v4());
+    let (ntx, notifications) = broadcast::channel::<serde_json::Value>(256);
+
+    let process = AcpProcess::spawn_with_env(
+        &command,
+        &preset.args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+        &super::preset_env::resolve_preset_env(&preset.env, &settings.env_passthrough),
+        cwd,
+        ntx,
+        settings.resource_limits.for_provider(&preset.id),
+        &preset.name,
+        &placeholder_session_id,
+    )
+    .await?;
+
+    let handshake = async {
+        process.initialize().await?;
+        process.new_session(cwd, &[]).await
+    }
+    .await;
+
+    match handshake {
+        Ok(acp_session_id) => {
+            tracing::info!(
+                "[AcpProcessPool] Pre-spawned {} in {} (agent session: {})",
+                provider,
+                cwd,
+                acp_session_id
+            );
+            Ok(WarmProcess {
+                process,
+                acp_session_id,
+                notifications,
+            })
+        }
+        Err(error) => {
+            process.kill().await;
+            Err(error)
+        }
+    }
+}
+
+/// Forward a warm process's notifications to the session's channel, rewriting
+/// the placeholder session id of anything emitted before the process was
+/// rebound to `session_id`.
+pub fn forward_notifications(
+    mut notifications: broadcast::Receiver<serde_json::Value>,
+    ntx: broadcast::Sender<serde_json::Value>,
+    session_id: String,
+) {
+    tokio::spawn(async move {
+        loop {
+            match notifications.recv().await {
+                Ok(mut notification) => {
+                    if let Some(params) = notification.get_mut("params") {
+                        if params.get("sessionId").is_some() {
+                            params["sessionId"] = serde_json::Value::String(session_id.clone());
+                        }
+                    }
+                    let _ = ntx.send(notification);
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pool_sizes_and_skips_invalid_entries() {
+        let sizes = parse_pool_sizes("gemini=2, copilot = 1,bad,zero=0,=3");
+        assert_eq!(sizes.get("gemini"), Some(&2));
+        assert_eq!(sizes.get("copilot"), Some(&1));
+        assert_eq!(sizes.len(), 2);
+    }
+
+    #[test]
+    fn session_scoped_providers_are_never_pooled() {
+        let config = ProcessPoolConfig {
+            sizes: parse_pool_sizes("opencode=2,codex-registry=1,gemini=1"),
+            ..ProcessPoolConfig::default()
+        };
+        assert_eq!(config.size_for("opencode"), 0);
+        assert_eq!(config.size_for("codex-registry"), 0);
+        assert_eq!(config.size_for("gemini"), 1);
+        assert_eq!(config.size_for("copilot"), 0);
+    }
+
+    #[tokio::test]
+    async fn prewarm_keeps_configured_pools_past_maintenance() {
+        let settings = AcpSettings {
+            warm_pool: ProcessPoolConfig {
+                sizes: parse_pool_sizes("gemini=1,opencode=1"),
+                ..ProcessPoolConfig::default()
+            },
+            ..AcpSettings::default()
+        };
+        let pool = Arc::new(AcpProcessPool::new(Arc::new(settings)));
+        pool.prewarm("/nonexistent/routa-prewarm").await;
+        pool.maintain().await;
+
+        let counts = pool.idle_counts().await;
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].0, "gemini");
+    }
+
+    #[tokio::test]
+    async fn checkout_is_empty_when_pool_disabled() {
+        let pool = Arc::new(AcpProcessPool::default());
+        assert!(pool.checkout("gemini", "/tmp").await.is_none());
+        assert!(pool.idle_counts().await.is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+//! In-flight deduplication of identical prompts.
+//!
+//! A double-submitted `session/prompt` would otherwise run the same turn
+//! twice. Prompts are keyed by session id and a hash of the prompt text; while
+//! a turn for a key is running, further identical prompts wait for its result
+//! instead of sending another prompt to the agent. The entry is removed when
+//! the turn completes (or the task running it is dropped).
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+
+type PromptResult = Result<serde_json::Value, String>;
+type PromptKey = (String, u64);
+
+#[derive(Default)]
+pub struct InFlightPrompts {
+    entries: Mutex<HashMap<PromptKey, watch::Receiver<Option<PromptResult>>>>,
+}
+
+/// Outcome of [`InFlightPrompts::claim`].
+pub enum PromptClaim {
+    /// No identical prompt is running; the caller runs the turn and reports
+    /// the result through the guard.
+    Leader(InFlightPrompt),
+    /// An identical prompt is already running.
+    Duplicate(DuplicatePrompt),
+}
+
+/// Held by the caller running a turn. Dropping it clears the entry.
+pub struct InFlightPrompt {
+    owner: Arc<InFlightPrompts>,
+    key: PromptKey,
+    result: watch::Sender<Option<PromptResult>>,
+    /// The receiver this guard registered, to tell its entry apart from one
+    /// a later identical prompt registered under the same key.
+    entry: watch::Receiver<Option<PromptResult>>,
+}
+
+/// Handle on a running identical prompt.
+pub struct DuplicatePrompt {
+    result: watch::Receiver<Option<PromptResult>>,
+}
+
+fn prompt_key(session_id: &str, text: &str) -> PromptKey {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    (session_id.to_string(), hasher.finish())
+}
+
+impl InFlightPrompts {
+    /// Register a prompt for `session_id`, or return the identical one
+    /// already running.
+    pub fn claim(self: &Arc<Self>, session_id: &str, text: &str) -> PromptClaim {
+        let key = prompt_key(session_id, text);
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(result) = entries.get(&key) {
+            return PromptClaim::Duplicate(DuplicatePrompt {
+                result: result.clone(),
+            });
+        }
+        let (tx, rx) = watch::channel(None);
+        entries.insert(key.clone(), rx.clone());
+        PromptClaim::Leader(InFlightPrompt {
+            owner: Arc::clone(self),
+            key,
+            result: tx,
+            entry: rx,
+        })
+    }
+
+    /// `true` when no prompt is in flight.
+    pub fn is_empty(&self) -> bool {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
+    /// `true` while any prompt for `session_id` is running.
+    pub fn has_session(&self, session_id: &str) -> bool {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .any(|(session, _)| session == session_id)
+    }
+}
+
+impl InFlightPrompt {
+    /// Hand the turn's result to any duplicates waiting on it. The entry is
+    /// cleared first, so a prompt arriving afterwards starts a new turn.
+    pub fn finish(self, result: &PromptResult) {
+        self.clear();
+        let _ = self.result.send(Some(result.clone()));
+    }
+
+    /// Remove this guard's entry. `finish` and `Drop` both call this; once
+    /// the entry is gone the key may belong to a newer leader, whose entry
+    /// is left alone.
+    fn clear(&self) {
+        let mut entries = self.owner.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries
+            .get(&self.key)
+            .is_some_and(|entry| entry.same_channel(&self.entry))
+        {
+            entries.remove(&self.key);
+        }
+    }
+}
+
+impl Drop for InFlightPrompt {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl DuplicatePrompt {
+    /// Wait for the original turn and return its result.
+    pub async fn wait(mut self) -> PromptResult {
+        loop {
+            if let Some(result) = self.result.borrow_and_update().clone() {
+                return result;
+            }
+            if self.result.changed().await.is_err() {
+                return Err("The identical prompt already in flight was cancelled".to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn duplicates_share_the_leader_result_until_it_finishes() {
+        let prompts = Arc::new(InFlightPrompts::default());
+        let PromptClaim::Leader(leader) = prompts.claim("s1", "fix the bug") else {
+            panic!("first prompt should lead");
+        };
+        let PromptClaim::Duplicate(duplicate) = prompts.claim("s1", "fix the bug") else {
+            panic!("identical prompt should be deduplicated");
+        };
+        assert!(matches!(
+            prompts.claim("s1", "something else"),
+            PromptClaim::Leader(_)
+        ));
+        assert!(matches!(
+            prompts.claim("s2", "fix the bug"),
+            PromptClaim::Leader(_)
+        ));
+
+        let waiter = tokio::spawn(duplicate.wait());
+        leader.finish(&Ok(serde_json::json!({ "stopReason": "end_turn" })));
+        let result = waiter.await.unwrap().unwrap();
+        assert_eq!(result["stopReason"], "end_turn");
+
+        assert!(prompts.is_empty());
+        assert!(matches!(
+            prompts.claim("s1", "fix the bug"),
+            PromptClaim::Leader(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn duplicates_fail_when_the_leader_is_dropped() {
+        let prompts = Arc::new(InFlightPrompts::default());
+        let PromptClaim::Leader(leader) = prompts.claim("s1", "go") else {
+            panic!("first prompt should lead");
+        };
+        let PromptClaim::Duplicate(duplicate) = prompts.claim("s1", "go") else {
+            panic!("identical prompt should be deduplicated");
+        };
+        drop(leader);
+        assert!(duplicate.wait().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn a_finished_leader_does_not_clear_its_successor() {
+        let prompts = Arc::new(InFlightPrompts::default());
+        let PromptClaim::Leader(first) = prompts.claim("s1", "go") else {
+            panic!("first prompt should lead");
+        };
+        // As `finish` does before the guard is dropped.
+        first.clear();
+        let PromptClaim::Leader(_second) = prompts.claim("s1", "go") else {
+            panic!("a prompt after the first finished should lead");
+        };
+        drop(first);
+
+        assert!(matches!(
+            prompts.claim("s1", "go"),
+            PromptClaim::Duplicate(_)
+        ));
+    }
+}
@@ -0,0 +1,196 @@
+//! Extra command-line arguments for ACP providers.
+//!
+//! Preset args are fixed; flags a user wants on every launch of a provider
+//! (a model name, a log level) come from `ROUTA_ACP_PROVIDER_ARGS_<PROVIDER>`
+//! instead (`AcpSettings::provider_args`), either whitespace-separated or a
+//! JSON array of strings for args containing spaces, and a single session can
+//! add more through `extraArgs` in `session/new`. The launch argv is the
+//! preset args, then provider-specific overrides, then the configured
+//! defaults, then the session's args, then the model flag.
+//!
+//! Agents are spawned from an argv, never through a shell, so metacharacters
+//! would not be interpreted; they are rejected anyway because an arg holding
+//! `;` or `$(...)` is almost always a value pasted from a shell command line
+//! and would reach the agent verbatim.
+
+use std::collections::HashMap;
+
+use crate::settings::provider_suffix;
+
+/// Characters refused in extra args.
+const SHELL_METACHARACTERS: &[char] = &[';', '|', '&', '$', '`', '<', '>', '\n', '\r', '\0'];
+
+/// Environment variable holding the default args for `provider`.
+pub fn env_var_name(provider: &str) -> String {
+    format!("ROUTA_ACP_PROVIDER_ARGS_{}", provider_suffix(provider))
+}
+
+/// Default args for `provider` from `configured`, the raw values keyed by
+/// provider suffix; empty when unset.
+pub fn provider_default_args(
+    provider: &str,
+    configured: &HashMap<String, String>,
+) -> Result<Vec<String>, String> {
+    match configured.get(&provider_suffix(provider)) {
+        Some(value) => {
+            parse_args(value).map_err(|e| format!("Invalid {}: {e}", env_var_name(provider)))
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Parse a whitespace-separated arg list, or a JSON array of strings.
+pub fn parse_args(value: &str) -> Result<Vec<String>, String> {
+    let value = value.trim();
+    let args = if value.starts_with('[') {
+        serde_json::from_str::<Vec<String>>(value)
+            .map_err(|e| format!("expected a JSON array of strings: {e}"))?
+    } else {
+        value.split_whitespace().map(str::to_string).collect()
+    };
+    validate_args(&args)?;
+    Ok(args)
+}
+
+/// Read `params.extraArgs`; absent or null means none.
+pub fn extra_args_from_params(params: &serde_json::Value) -> Result<Option<Vec<String>>, String> {
+    let Some(value) = params.get("extraArgs").filter(|v| !v.is_null()) else {
+        return Ok(None);
+    };
+    let args: Vec<String> = serde_json::from_value(value.clone())
+        .map_err(|_| "Invalid extraArgs: expected an array of strings".to_string())?;
+    validate_args(&args).map_err(|e| format!("Invalid extraArgs: {e}"))?;
+    Ok(Some(args))
+}
+
+/// Reject empty args and args containing shell metacharacters.
+pub fn validate_args(args: &[String]) -> Result<(), String> {
+    for arg in args {
+        if arg.trim().is_empty() {
+            return Err("arguments must not be empty".to_string());
+        }
+        if let Some(c) = arg.chars().find(|c| SHELL_METACHARACTERS.contains(c)) {
+            return Err(format!(
+                "argument {arg:?} contains shell metacharacter {c:?}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Assemble the launch argv from its parts, in the order described above.
+pub fn merge_args(
+    preset_args: &[String],
+    overrides: Vec<String>,
+    defaults: Vec<String>,
+    session_args: Option<Vec<String>>,
+    model: Option<&str>,
+) -> Vec<String> {
+    let mut args = preset_args.to_vec();
+    args.extend(overrides);
+    args.extend(defaults);
+    args.extend(session_args.unwrap_or_default());
+    if let Some(model) = model.filter(|m| !m.is_empty()) {
+        // opencode (and future providers) accept -m <model>
+        args.push("-m".to_string());
+        args.push(model.to_string());
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_var_name_normalizes_provider_ids() {
+        assert_eq!(
+            env_var_name("codex-acp"),
+            "ROUTA_ACP_PROVIDER_ARGS_CODEX_ACP"
+        );
+        assert_eq!(env_var_name("opencode"), "ROUTA_ACP_PROVIDER_ARGS_OPENCODE");
+    }
+
+    #[test]
+    fn default_args_are_looked_up_by_provider_suffix() {
+        let configured = HashMap::from([
+            ("CODEX_ACP".to_string(), "--verbose".to_string()),
+            ("GEMINI".to_string(), "--x;y".to_string()),
+        ]);
+        assert_eq!(
+            provider_default_args("codex-acp", &configured).unwrap(),
+            ["--verbose"]
+        );
+        assert!(provider_default_args("opencode", &configured)
+            .unwrap()
+            .is_empty());
+        let error = provider_default_args("gemini", &configured).unwrap_err();
+        assert!(
+            error.starts_with("Invalid ROUTA_ACP_PROVIDER_ARGS_GEMINI"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn parses_whitespace_and_json_lists() {
+        assert_eq!(
+            parse_args("  --model  gpt-5 --verbose ").unwrap(),
+            ["--model", "gpt-5", "--verbose"]
+        );
+        assert_eq!(
+            parse_args(r#"["--prompt-prefix", "be brief"]"#).unwrap(),
+            ["--prompt-prefix", "be brief"]
+        );
+        assert!(parse_args("").unwrap().is_empty());
+        assert!(parse_args("[1, 2]").is_err());
+        assert!(parse_args("--x; rm -rf /").is_err());
+    }
+
+    #[test]
+    fn validates_extra_args_from_params() {
+        assert_eq!(
+            extra_args_from_params(&serde_json::json!({})).unwrap(),
+            None
+        );
+        assert_eq!(
+            extra_args_from_params(&serde_json::json!({ "extraArgs": ["--log-level", "debug"] }))
+                .unwrap(),
+            Some(vec!["--log-level".to_string(), "debug".to_string()])
+        );
+        for invalid in [
+            serde_json::json!({ "extraArgs": "--log-level debug" }),
+            serde_json::json!({ "extraArgs": ["$(whoami)"] }),
+            serde_json::json!({ "extraArgs": ["a|b"] }),
+            serde_json::json!({ "extraArgs": [" "] }),
+        ] {
+            assert!(extra_args_from_params(&invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn merge_orders_preset_overrides_defaults_session_and_model() {
+        let args = merge_args(
+            &["acp".to_string()],
+            vec!["-c".to_string(), "key=value".to_string()],
+            vec!["--default".to_string()],
+            Some(vec!["--session".to_string()]),
+            Some("model-x"),
+        );
+        assert_eq!(
+            args,
+            [
+                "acp",
+                "-c",
+                "key=value",
+                "--default",
+                "--session",
+                "-m",
+                "model-x"
+            ]
+        );
+        assert_eq!(
+            merge_args(&["acp".to_string()], vec![], vec![], None, Some("")),
+            ["acp"]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Per-session provider timeout and retry settings.
+//!
+//! `session/new` and `session/prompt` may carry a `providerSettings` object to
+//! override the defaults for slow or flaky providers:
+//!
+//! ```json
+//! { "providerSettings": { "initializeTimeoutMs": 60000, "promptTimeoutMs": 900000, "spawnRetries": 2 } }
+//! ```
+//!
+//! Launch settings (`initializeTimeoutMs`, `spawnRetries`) apply when the
+//! request starts a process; `promptTimeoutMs` is stored on the session and
+//! used for every later prompt. The effective values are reported back as
+//! [`EffectiveProviderSettings`].
+
+use serde::{Deserialize, Serialize};
+
+use super::SessionLaunchOptions;
+
+/// Prompt timeout for ACP agents when none is requested.
+pub const DEFAULT_PROMPT_TIMEOUT_MS: u64 = 300_000;
+/// Upper bound on `spawnRetries`, so a broken provider cannot be respawned forever.
+pub const MAX_SPAWN_RETRIES: u32 = 5;
+
+/// Overrides requested by a client. Unset fields keep the defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProviderSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initialize_timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_timeout_ms: Option<u64>,
+    /// Extra spawn attempts when the process exits before `initialize` completes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spawn_retries: Option<u32>,
+}
+
+impl ProviderSettings {
+    /// Read `params.providerSettings`; absent means no overrides.
+    pub fn from_params(params: &serde_json::Value) -> Result<Self, String> {
+        let Some(value) = params.get("providerSettings").filter(|v| !v.is_null()) else {
+            return Ok(Self::default());
+        };
+        let settings: Self = serde_json::from_value(value.clone())
+            .map_err(|e| format!("Invalid providerSettings: {e}"))?;
+        if settings.initialize_timeout_ms == Some(0) || settings.prompt_timeout_ms == Some(0) {
+            return Err("Invalid providerSettings: timeouts must be greater than 0".to_string());
+        }
+        if settings
+            .spawn_retries
+            .is_some_and(|n| n > MAX_SPAWN_RETRIES)
+        {
+            return Err(format!(
+                "Invalid providerSettings: spawnRetries must be at most {MAX_SPAWN_RETRIES}"
+            ));
+        }
+        Ok(settings)
+    }
+
+    /// Copy these overrides onto launch options, keeping values already set there.
+    pub fn apply_to(&self, options: &mut SessionLaunchOptions) {
+        options.initialize_timeout_ms =
+            options.initialize_timeout_ms.or(self.initialize_timeout_ms);
+        options.prompt_timeout_ms = options.prompt_timeout_ms.or(self.prompt_timeout_ms);
+        options.spawn_retries = options.spawn_retries.or(self.spawn_retries);
+    }
+}
+
+/// Settings in effect for a session, stored on its record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveProviderSettings {
+    pub provider: String,
+    /// Timeout used for `initialize`; `None` when this session performed no
+    /// handshake (Claude stream-json, or a pre-spawned process).
+    pub initialize_timeout_ms: Option<u64>,
+    /// Timeout applied to each prompt; `None` means no limit.
+    pub prompt_timeout_ms: Option<u64>,
+    pub spawn_retries: u32,
+    /// Spawn attempts it took to start the session.
+    pub spawn_attempts: u32,
+}
+
+impl EffectiveProviderSettings {
+    /// Settings for an ACP process started with `options`.
+    pub fn acp(
+        provider: &str,
+        options: &SessionLaunchOptions,
+        initialize_timeout_ms: Option<u64>,
+        spawn_attempts: u32,
+    ) -> Self {
+        Self {
+            provider: provider.to_string(),
+            initialize_timeout_ms,
+            prompt_timeout_ms: Some(
+                options
+                    .prompt_timeout_ms
+                    .unwrap_or(DEFAULT_PROMPT_TIMEOUT_MS),
+            ),
+            spawn_retries: spawn_retries(options),
+            spawn_attempts,
+        }
+    }
+
+    /// Settings for a Claude stream-json process, which has no handshake and
+    /// no prompt timeout unless one is requested.
+    pub fn claude(provider: &str, options: &SessionLaunchOptions) -> Self {
+        Self {
+            provider: provider.to_string(),
+            initialize_timeout_ms: None,
+            prompt_timeout_ms: options.prompt_timeout_ms,
+            spawn_retries: spawn_retries(options),
+            spawn_attempts: 1,
+        }
+    }
+}
+
+/// Retries allowed by `options`, capped at [`MAX_SPAWN_RETRIES`].
+pub fn spawn_retries(options: &SessionLaunchOptions) -> u32 {
+    options.spawn_retries.unwrap_or(0).min(MAX_SPAWN_RETRIES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_validates_provider_settings() {
+        let params = serde_json::json!({
+            "providerSettings": { "promptTimeoutMs": 900000, "spawnRetries": 2 }
+        });
+        let settings = ProviderSettings::from_params(&params).unwrap();
+        assert_eq!(settings.prompt_timeout_ms, Some(900_000));
+        assert_eq!(settings.spawn_retries, Some(2));
+        assert_eq!(settings.initialize_timeout_ms, None);
+
+        assert_eq!(
+            ProviderSettings::from_params(&serde_json::json!({})).unwrap(),
+            ProviderSettings::default()
+        );
+        for invalid in [
+            serde_json::json!({ "providerSettings": { "spawnRetries": 99 } }),
+            serde_json::json!({ "providerSettings": { "promptTimeoutMs": 0 } }),
+            serde_json::json!({ "providerSettings": { "timeout": 5 } }),
+        ] {
+            assert!(ProviderSettings::from_params(&invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn apply_to_keeps_explicit_launch_options() {
+        let mut options = SessionLaunchOptions {
+            initialize_timeout_ms: Some(1_000),
+            ..SessionLaunchOptions::default()
+        };
+        ProviderSettings {
+            initialize_timeout_ms: Some(5_000),
+            prompt_timeout_ms: Some(60_000),
+            spawn_retries: Some(1),
+        }
+        .apply_to(&mut options);
+        assert_eq!(options.initialize_timeout_ms, Some(1_000));
+        assert_eq!(options.prompt_timeout_ms, Some(60_000));
+        assert_eq!(options.spawn_retries, Some(1));
+    }
+}
@@ -1,12 +1,78 @@
 //! ACP Registry fetch utilities (shared between CLI and HTTP server).
+//!
+//! The registry's top-level `version` is checked against the schema versions
+//! this code understands before the JSON is parsed into typed structs, so a
+//! future schema change produces a clear warning (or, in strict mode, an
+//! error) instead of a cryptic parse failure.
 
 use std::path::PathBuf;
 
 use super::paths::AcpPaths;
 use super::registry_types::AcpRegistry;
+use crate::settings::AcpSettings;
 
 const REGISTRY_URL: &str = "https://cdn.agentclientprotocol.com/registry/v1/latest/registry.json";
 
+/// Registry schema major versions the typed structs understand.
+pub const SUPPORTED_REGISTRY_MAJOR_VERSIONS: std::ops::RangeInclusive<u64> = 1..=1;
+
+/// Whether an unsupported registry version is an error rather than a warning.
+/// Registry loads have no state to take settings from, so this reads them
+/// when called.
+pub fn registry_version_strict() -> bool {
+    AcpSettings::from_env().registry_strict
+}
+
+/// Check a registry's top-level `version` against
+/// [`SUPPORTED_REGISTRY_MAJOR_VERSIONS`]. A missing version is accepted, as
+/// older registries did not carry one. An unsupported or unparseable version
+/// is logged, and rejected when `strict` is set.
+pub fn check_registry_version(version: Option<&str>, strict: bool) -> Result<(), String> {
+    let Some(version) = version else {
+        return Ok(());
+    };
+    let major = version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .next()
+        .and_then(|major| major.parse::<u64>().ok());
+    let problem = match major {
+        Some(major) if SUPPORTED_REGISTRY_MAJOR_VERSIONS.contains(&major) => return Ok(()),
+        Some(major) if major > *SUPPORTED_REGISTRY_MAJOR_VERSIONS.end() => format!(
+            "ACP registry schema version {version} is newer than supported (major {}-{})",
+            SUPPORTED_REGISTRY_MAJOR_VERSIONS.start(),
+            SUPPORTED_REGISTRY_MAJOR_VERSIONS.end()
+        ),
+        Some(_) => format!(
+            "ACP registry schema version {version} is older than supported (major {}-{})",
+            SUPPORTED_REGISTRY_MAJOR_VERSIONS.start(),
+            SUPPORTED_REGISTRY_MAJOR_VERSIONS.end()
+        ),
+        None => format!("ACP registry schema version '{version}' is not a valid version"),
+    };
+    if strict {
+        return Err(problem);
+    }
+    tracing::warn!("[ACP Registry] {problem}; entries may be misread");
+    Ok(())
+}
+
+/// Check the version of raw registry JSON, then parse it.
+pub fn parse_registry_json(json: serde_json::Value) -> Result<AcpRegistry, String> {
+    let version = json
+        .get("version")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    check_registry_version(version.as_deref(), registry_version_strict())?;
+    serde_json::from_value::<AcpRegistry>(json).map_err(|e| match &version {
+        Some(version) => {
+            format!("Failed to parse ACP registry JSON (schema version {version}): {e}")
+        }
+        None => format!("Failed to parse ACP registry JSON: {e}"),
+    })
+}
+
 fn registry_cache_path() -> PathBuf {
     AcpPaths::new().registry_cache_path()
 }
@@ -60,8 +126,7 @@ async fn fetch_live_registry_json() -> Result<serde_json::Value, String> {
 /// Fetch the live ACP registry from the CDN.
 pub async fn fetch_registry() -> Result<AcpRegistry, String> {
     let json = fetch_registry_json().await?;
-    serde_json::from_value::<AcpRegistry>(json)
-        .map_err(|e| format!("Failed to parse ACP registry JSON: {e}"))
+    parse_registry_json(json)
 }
 
 /// Fetch raw registry JSON value (useful when callers do not want typed structs).
@@ -73,3 +138,21 @@ pub async fn fetch_registry_json() -> Result<serde_json::Value, String> {
         }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_version_check_accepts_supported_and_flags_newer() {
+        assert!(check_registry_version(None, true).is_ok());
+        assert!(check_registry_version(Some("1.0.0"), true).is_ok());
+        assert!(check_registry_version(Some("v1.4"), true).is_ok());
+
+        assert!(check_registry_version(Some("2.0.0"), false).is_ok());
+        let error = check_registry_version(Some("2.0.0"), true).unwrap_err();
+        assert!(error.contains("newer than supported"), "{error}");
+        assert!(check_registry_version(Some("0.9.0"), true).is_err());
+        assert!(check_registry_version(Some("next"), true).is_err());
+    }
+}
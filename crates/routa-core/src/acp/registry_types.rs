@@ -6,6 +6,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::runtime_manager::RuntimeType;
+
 /// The root registry containing all available agents.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -39,7 +41,7 @@ pub struct AcpAgentEntry {
 }
 
 /// Distribution information for an agent.
-/// The actual registry uses npx/uvx/binary as optional fields.
+/// The actual registry uses npx/uvx/binary/git as optional fields.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AcpDistribution {
@@ -52,6 +54,9 @@ pub struct AcpDistribution {
     /// Binary distribution info (platform -> binary info)
     #[serde(default)]
     pub binary: Option<HashMap<String, BinaryInfo>>,
+    /// Git repository distribution info
+    #[serde(default)]
+    pub git: Option<GitDistribution>,
 }
 
 /// NPX distribution info.
@@ -76,6 +81,25 @@ pub struct UvxDistribution {
     pub env: HashMap<String, String>,
 }
 
+/// Git repository distribution info: the repo is cloned into the agent
+/// version directory and `cmd` is run from there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitDistribution {
+    pub repo: String,
+    /// Branch, tag, or commit to check out; the remote HEAD when absent.
+    #[serde(default, rename = "ref")]
+    pub git_ref: Option<String>,
+    /// Entrypoint. A path (`bin/agent`, `./run.sh`) is resolved inside the
+    /// clone; a bare name (`node`, `python3`) is looked up on PATH.
+    pub cmd: String,
+    /// Arguments; ones starting with `./` are resolved inside the clone.
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
 /// Distribution type for an agent (used internally).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -83,6 +107,7 @@ pub enum DistributionType {
     Npx,
     Uvx,
     Binary,
+    Git,
 }
 
 /// Binary distribution info for a specific platform.
@@ -99,6 +124,18 @@ pub struct BinaryInfo {
     pub sha256: Option<String>,
 }
 
+/// Command line for launching a registry agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentCommand {
+    pub command: String,
+    pub args: Vec<String>,
+    /// Runtime that must be available before `command` can run; `None` for
+    /// self-contained binaries.
+    pub runtime: Option<RuntimeType>,
+    /// Variables from the distribution's `env`, set on the agent process.
+    pub env: HashMap<String, String>,
+}
+
 /// Information about an installed agent.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -122,6 +159,17 @@ pub struct InstalledAgentsState {
     pub agents: HashMap<String, InstalledAgentInfo>,
 }
 
+impl DistributionType {
+    /// Runtime this distribution needs at launch time.
+    pub fn required_runtime(&self) -> Option<RuntimeType> {
+        match self {
+            DistributionType::Npx => Some(RuntimeType::Npx),
+            DistributionType::Uvx => Some(RuntimeType::Uvx),
+            DistributionType::Binary | DistributionType::Git => None,
+        }
+    }
+}
+
 impl AcpDistribution {
     /// Get the distribution type.
     pub fn dist_type(&self) -> Option<DistributionType> {
@@ -131,6 +179,8 @@ impl AcpDistribution {
             Some(DistributionType::Uvx)
         } else if self.binary.is_some() {
             Some(DistributionType::Binary)
+        } else if self.git.is_some() {
+            Some(DistributionType::Git)
         } else {
             None
         }
@@ -143,21 +193,65 @@ impl AcpAgentEntry {
         self.distribution.dist_type()
     }
 
-    /// Get the command to run this agent.
-    pub fn get_command(&self, binary_path: Option<&str>) -> Option<(String, Vec<String>)> {
+    /// Get the command to run this agent, along with the runtime it needs.
+    ///
+    /// `binary_path` is the installed binary for binary distributions and the
+    /// clone directory for git distributions.
+    pub fn get_command(&self, binary_path: Option<&str>) -> Option<AgentCommand> {
         if let Some(ref npx) = self.distribution.npx {
             let mut args = vec!["-y".to_string(), npx.package.clone()];
             args.extend(npx.args.clone());
-            return Some(("npx".to_string(), args));
+            return Some(AgentCommand {
+                command: "npx".to_string(),
+                args,
+                runtime: DistributionType::Npx.required_runtime(),
+                env: npx.env.clone(),
+            });
         }
         if let Some(ref uvx) = self.distribution.uvx {
             let mut args = vec![uvx.package.clone()];
             args.extend(uvx.args.clone());
-            return Some(("uvx".to_string(), args));
+            return Some(AgentCommand {
+                command: "uvx".to_string(),
+                args,
+                runtime: DistributionType::Uvx.required_runtime(),
+                env: uvx.env.clone(),
+            });
         }
         if self.distribution.binary.is_some() {
             let path = binary_path?;
-            return Some((path.to_string(), vec![]));
+            return Some(AgentCommand {
+                command: path.to_string(),
+                args: vec![],
+                runtime: None,
+                env: HashMap::new(),
+            });
+        }
+        if let Some(ref git) = self.distribution.git {
+            let checkout = std::path::Path::new(binary_path?);
+            let resolve = |value: &str| checkout.join(value).to_string_lossy().into_owned();
+            let command = if git.cmd.contains('/') || git.cmd.contains('\\') {
+                resolve(&git.cmd)
+            } else {
+                git.cmd.clone()
+            };
+            let args = git
+                .args
+                .iter()
+                .map(|arg| {
+                    if arg.starts_with("./") {
+                        resolve(arg)
+                    } else {
+                        arg.clone()
+                    }
+                })
+                .collect();
+            return Some(AgentCommand {
+                command,
+                args,
+                runtime: None,
+                env: git.env.clone(),
+            });
         }
         None
     }
@@ -187,3 +281,62 @@ impl AcpAgentEntry {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git_agent(cmd: &str, args: &[&str]) -> AcpAgentEntry {
+        serde_json::from_value(serde_json::json!({
+            "id": "git-agent",
+            "name": "Git Agent",
+            "distribution": {
+                "git": {
+                    "repo": "https://github.com/example/agent.git",
+                    "ref": "v1.2.0",
+                    "cmd": cmd,
+                    "args": args,
+                }
+            }
+        }))
+        .expect("git distribution should deserialize")
+    }
+
+    #[test]
+    fn git_distribution_parses_ref_and_dist_type() {
+        let agent = git_agent("bin/agent", &[]);
+        assert_eq!(agent.dist_type(), Some(DistributionType::Git));
+        let git = agent.distribution.git.as_ref().unwrap();
+        assert_eq!(git.git_ref.as_deref(), Some("v1.2.0"));
+    }
+
+    #[test]
+    fn git_command_resolves_relative_entrypoint_inside_checkout() {
+        let agent = git_agent("bin/agent", &["--acp"]);
+        let command = agent.get_command(Some("/agents/git-agent/1.2.0")).unwrap();
+        let expected = std::path::Path::new("/agents/git-agent/1.2.0").join("bin/agent");
+        assert_eq!(command.command, expected.to_string_lossy());
+        assert_eq!(command.args, vec!["--acp".to_string()]);
+        assert_eq!(command.runtime, None);
+    }
+
+    #[test]
+    fn git_command_keeps_bare_program_and_resolves_dot_slash_args() {
+        let agent = git_agent("node", &["./dist/index.js", "--stdio"]);
+        let command = agent.get_command(Some("/checkout")).unwrap();
+        let expected = std::path::Path::new("/checkout").join("./dist/index.js");
+        assert_eq!(command.command, "node");
+        assert_eq!(
+            command.args,
+            vec![
+                expected.to_string_lossy().into_owned(),
+                "--stdio".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn git_command_requires_checkout_path() {
+        assert!(git_agent("bin/agent", &[]).get_command(None).is_none());
+    }
+}
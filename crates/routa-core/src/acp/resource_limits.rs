@@ -0,0 +1,332 @@
+//! Per-provider resource limits for spawned agent processes (Unix only).
+//!
+//! A runaway agent can otherwise exhaust memory and hang the machine. On Unix
+//! the child gets `RLIMIT_AS` (address space) and, when configured,
+//! `RLIMIT_CPU` (CPU seconds) set via `setrlimit` right before `exec`. This
+//! is best-effort and platform-specific:
+//!   - limits are per process, so grandchildren an agent spawns each get
+//!     their own budget rather than sharing one;
+//!   - `RLIMIT_AS` counts virtual memory, not resident memory: runtimes that
+//!     reserve large ranges up front (V8 and wasm guard regions, the JVM, Go)
+//!     fail under caps far above what they actually use, so the default is
+//!     generous;
+//!   - `RLIMIT_CPU` is a budget for the process's whole life, and one process
+//!     serves every prompt of a session (or several sessions when pooled), so
+//!     there is no CPU limit unless one is configured;
+//!   - cgroups are not used, and nothing is applied on Windows.
+
+use std::collections::HashMap;
+
+use crate::settings::provider_suffix;
+
+/// Default address space cap, in MiB (64 GiB).
+pub const DEFAULT_MEMORY_LIMIT_MB: u64 = 64 * 1024;
+/// Grace between the soft CPU limit (`SIGXCPU`) and the hard one (`SIGKILL`).
+const CPU_HARD_LIMIT_GRACE_SECS: u64 = 30;
+
+/// Marker in errors for a process killed for exceeding a resource limit.
+pub const RESOURCE_LIMIT_EXCEEDED: &str = "exceeded its resource limit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Address space cap in bytes; `None` means unlimited.
+    pub max_address_space_bytes: Option<u64>,
+    /// CPU time cap in seconds; `None` means unlimited.
+    pub max_cpu_secs: Option<u64>,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_address_space_bytes: Some(DEFAULT_MEMORY_LIMIT_MB * 1024 * 1024),
+            max_cpu_secs: None,
+        }
+    }
+}
+
+/// Configured limits, in MiB and CPU seconds, with `0` disabling a limit.
+/// Overrides are keyed by provider suffix (see `settings::provider_suffix`);
+/// anything unset falls back to the defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceLimitConfig {
+    pub memory_limit_mb: Option<u64>,
+    pub cpu_limit_secs: Option<u64>,
+    pub memory_limit_mb_by_provider: HashMap<String, u64>,
+    pub cpu_limit_secs_by_provider: HashMap<String, u64>,
+}
+
+impl ResourceLimitConfig {
+    /// Limits for processes of `provider`, e.g. `codex-acp`.
+    pub fn for_provider(&self, provider: &str) -> ResourceLimits {
+        let suffix = provider_suffix(provider);
+        let defaults = ResourceLimits::default();
+        let memory_mb = self
+            .memory_limit_mb_by_provider
+            .get(&suffix)
+            .copied()
+            .or(self.memory_limit_mb);
+        let cpu_secs = self
+            .cpu_limit_secs_by_provider
+            .get(&suffix)
+            .copied()
+            .or(self.cpu_limit_secs);
+        ResourceLimits {
+            max_address_space_bytes: match memory_mb {
+                Some(0) => None,
+                Some(mb) => Some(mb.saturating_mul(1024 * 1024)),
+                None => defaults.max_address_space_bytes,
+            },
+            max_cpu_secs: match cpu_secs {
+                Some(0) => None,
+                Some(secs) => Some(secs),
+                None => defaults.max_cpu_secs,
+            },
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn forbid_new_privileges() -> std::io::Result<()> {
+    // SAFETY: PR_SET_NO_NEW_PRIVS takes no pointers.
+    if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn forbid_new_privileges() -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Which limit a process was killed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitExceeded {
+    Memory,
+    Cpu,
+}
+
+impl ResourceLimits {
+    /// No limits at all.
+    pub const UNLIMITED: Self = Self {
+        max_address_space_bytes: None,
+        max_cpu_secs: None,
+    };
+
+    /// Install the limits on `command`'s child, between `fork` and `exec`.
+    #[cfg(unix)]
+    pub fn apply(&self, command: &mut tokio::process::Command) {
+        if *self == Self::UNLIMITED {
+            return;
+        }
+        let limits = *self;
+        // SAFETY: the closure only calls getrlimit/setrlimit, which are
+        // async-signal-safe, and allocates nothing.
+        unsafe {
+            command.pre_exec(move || limits.set_for_current_process());
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn apply(&self, _command: &mut tokio::process::Command) {}
+
+    /// Like `apply`, and also stop the child from gaining privileges through
+    /// setuid binaries or file capabilities (`PR_SET_NO_NEW_PRIVS`, Linux
+    /// only). For untrusted scripts rather than agents, which may need
+    /// `sudo` and the like.
+    #[cfg(unix)]
+    pub fn apply_without_new_privileges(&self, command: &mut tokio::process::Command) {
+        let limits = *self;
+        // SAFETY: as in `apply`; prctl is a plain syscall.
+        unsafe {
+            command.pre_exec(move || {
+                limits.set_for_current_process()?;
+                forbid_new_privileges()
+            });
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn apply_without_new_privileges(&self, _command: &mut tokio::process::Command) {}
+
+    #[cfg(unix)]
+    fn set_for_current_process(&self) -> std::io::Result<()> {
+        use rlimit::Resource;
+
+        // Never raise a hard limit the parent already runs under.
+        let lower = |resource: Resource, soft: u64, hard: u64| -> std::io::Result<()> {
+            let (_, current_hard) = rlimit::getrlimit(resource)?;
+            rlimit::setrlimit(resource, soft.min(current_hard), hard.min(current_hard))
+        };
+        if let Some(bytes) = self.max_address_space_bytes {
+            lower(Resource::AS, bytes, bytes)?;
+        }
+        if let Some(secs) = self.max_cpu_secs {
+            lower(
+                Resource::CPU,
+                secs,
+                secs.saturating_add(CPU_HARD_LIMIT_GRACE_SECS),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Whether `status` means the process was killed for exceeding one of
+    /// these limits. `SIGXCPU` is unambiguous. Memory exhaustion under
+    /// `RLIMIT_AS` surfaces as a failed allocation followed by an abort or
+    /// segfault, which other bugs also cause, so it is only reported when
+    /// the process said an allocation failed (`allocation_failed`, see
+    /// `is_allocation_failure`).
+    #[cfg(unix)]
+    pub fn exceeded_by(
+        &self,
+        status: &std::process::ExitStatus,
+        allocation_failed: bool,
+    ) -> Option<LimitExceeded> {
+        use std::os::unix::process::ExitStatusExt;
+
+        match status.signal()? {
+            libc::SIGXCPU if self.max_cpu_secs.is_some() => Some(LimitExceeded::Cpu),
+            libc::SIGABRT | libc::SIGSEGV | libc::SIGBUS
+                if allocation_failed && self.max_address_space_bytes.is_some() =>
+            {
+                Some(LimitExceeded::Memory)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn exceeded_by(
+        &self,
+        _status: &std::process::ExitStatus,
+        _allocation_failed: bool,
+    ) -> Option<LimitExceeded> {
+        None
+    }
+
+    /// Error for a process named `display_name` killed for `exceeded`.
+    pub fn exceeded_error(&self, display_name: &str, exceeded: LimitExceeded) -> String {
+        let detail = match exceeded {
+            LimitExceeded::Memory => format!(
+                "memory (address space limit {} MiB)",
+                self.max_address_space_bytes.unwrap_or(0) / (1024 * 1024)
+            ),
+            LimitExceeded::Cpu => format!("CPU time (limit {}s)", self.max_cpu_secs.unwrap_or(0)),
+        };
+        format!("{display_name} {RESOURCE_LIMIT_EXCEEDED}: {detail}")
+    }
+}
+
+/// Whether a stderr line reports a failed memory allocation, in the words
+/// the common agent runtimes (Node, Rust, C/C++, Python, Go) use.
+pub fn is_allocation_failure(line: &str) -> bool {
+    const MARKERS: &[&str] = &[
+        "out of memory",
+        "cannot allocate memory",
+        "memory allocation of",
+        "bad_alloc",
+        "memoryerror",
+        "allocation failed",
+    ];
+    let line = line.to_ascii_lowercase();
+    MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_overrides_take_precedence_and_zero_disables() {
+        let config = ResourceLimitConfig {
+            memory_limit_mb: Some(512),
+            cpu_limit_secs: Some(600),
+            memory_limit_mb_by_provider: HashMap::from([("TEST_LIMITS_PROVIDER".to_string(), 0)]),
+            cpu_limit_secs_by_provider: HashMap::new(),
+        };
+
+        let limits = config.for_provider("test-limits.provider");
+        assert_eq!(limits.max_cpu_secs, Some(600));
+        assert_eq!(limits.max_address_space_bytes, None);
+
+        let limits = config.for_provider("gemini");
+        assert_eq!(limits.max_address_space_bytes, Some(512 * 1024 * 1024));
+        assert_eq!(
+            ResourceLimitConfig::default().for_provider("gemini"),
+            ResourceLimits::default()
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cpu_limit_kills_a_busy_process_with_a_distinct_error() {
+        let limits = ResourceLimits {
+            max_address_space_bytes: None,
+            max_cpu_secs: Some(1),
+        };
+        let mut command = tokio::process::Command::new("sh");
+        command.args(["-c", "while :; do :; done"]);
+        limits.apply(&mut command);
+        let status = command.status().await.expect("sh should run");
+
+        let exceeded = limits.exceeded_by(&status, false);
+        assert_eq!(exceeded, Some(LimitExceeded::Cpu));
+        let error = limits.exceeded_error("busy-agent", exceeded.unwrap());
+        assert!(error.contains(RESOURCE_LIMIT_EXCEEDED), "{error}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn reused_process_is_not_killed_by_cpu_time_from_earlier_prompts() {
+        let limits = ResourceLimitConfig::default().for_provider("gemini");
+        assert_eq!(limits.max_cpu_secs, None);
+
+        // Each stdin line stands in for one prompt turn on the same process.
+        let mut command = tokio::process::Command::new("sh");
+        command
+            .args([
+                "-c",
+                "while read -r _; do i=0; while [ $i -lt 300000 ]; do i=$((i+1)); done; done",
+            ])
+            .stdin(std::process::Stdio::piped());
+        limits.apply(&mut command);
+        let mut child = command.spawn().expect("sh should run");
+        let mut stdin = child.stdin.take().unwrap();
+        for _ in 0..5 {
+            tokio::io::AsyncWriteExt::write_all(&mut stdin, b"prompt\n")
+                .await
+                .unwrap();
+        }
+        drop(stdin);
+        let status = child.wait().await.unwrap();
+
+        assert!(status.success(), "{status:?}");
+        assert_eq!(limits.exceeded_by(&status, false), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn crashes_count_as_memory_only_after_a_failed_allocation() {
+        let limits = ResourceLimits::default();
+        let status = tokio::process::Command::new("sh")
+            .args(["-c", "kill -SEGV $$"])
+            .status()
+            .await
+            .expect("sh should run");
+
+        assert_eq!(limits.exceeded_by(&status, false), None);
+        assert_eq!(
+            limits.exceeded_by(&status, true),
+            Some(LimitExceeded::Memory)
+        );
+        assert_eq!(ResourceLimits::UNLIMITED.exceeded_by(&status, true), None);
+        assert!(is_allocation_failure(
+            "FATAL ERROR: Reached heap limit Allocation failed - JavaScript heap out of memory"
+        ));
+        assert!(is_allocation_failure(
+            "memory allocation of 1048576 bytes failed"
+        ));
+        assert!(!is_allocation_failure("Segmentation fault (core dumped)"));
+    }
+}
@@ -81,6 +81,30 @@ impl RuntimeType {
             RuntimeType::Uvx => "uvx",
         }
     }
+
+    /// The runtime a launcher command such as `npx` or `uvx` depends on.
+    pub fn for_command(command: &str) -> Option<Self> {
+        match command {
+            "node" => Some(RuntimeType::Node),
+            "npx" => Some(RuntimeType::Npx),
+            "uv" => Some(RuntimeType::Uv),
+            "uvx" => Some(RuntimeType::Uvx),
+            _ => None,
+        }
+    }
+
+    /// Actionable error shown when an agent needs this runtime but it is
+    /// neither on `PATH` nor managed by Routa.
+    pub fn missing_message(&self) -> String {
+        let requirement = match self {
+            RuntimeType::Node | RuntimeType::Npx => "Node.js",
+            RuntimeType::Uv | RuntimeType::Uvx => "uv (Python)",
+        };
+        format!(
+            "{requirement} is required for this agent (`{}` not found); install it or let Routa manage runtimes",
+            self.command_name()
+        )
+    }
 }
 
 // ─── Runtime Info ──────────────────────────────────────────────────────────
@@ -502,30 +526,13 @@ impl AcpRuntimeManager {
     fn extract_zip_sync(archive: &Path, dest: &Path) -> Result<(), String> {
         let f = std::fs::File::open(archive).map_err(|e| format!("open zip {archive:?}: {e}"))?;
         let mut z = zip::ZipArchive::new(f).map_err(|e| format!("read zip {archive:?}: {e}"))?;
-        for i in 0..z.len() {
-            let mut entry = z.by_index(i).map_err(|e| format!("zip entry {i}: {e}"))?;
-            let out = dest.join(entry.mangled_name());
-            if entry.name().ends_with('/') {
-                std::fs::create_dir_all(&out).ok();
-            } else {
-                if let Some(p) = out.parent() {
-                    std::fs::create_dir_all(p).ok();
-                }
-                let mut outf =
-                    std::fs::File::create(&out).map_err(|e| format!("create {out:?}: {e}"))?;
-                std::io::copy(&mut entry, &mut outf)
-                    .map_err(|e| format!("extract {out:?}: {e}"))?;
-            }
-        }
-        Ok(())
+        crate::archive::extract_zip(&mut z, dest, || Ok(()))
     }
 
     fn extract_tgz_sync(archive: &Path, dest: &Path) -> Result<(), String> {
         let f =
             std::fs::File::open(archive).map_err(|e| format!("open tar.gz {archive:?}: {e}"))?;
         let gz = flate2::read::GzDecoder::new(f);
-        tar::Archive::new(gz)
-            .unpack(dest)
-            .map_err(|e| format!("unpack tar.gz {archive:?}: {e}"))
+        crate::archive::extract_tar(tar::Archive::new(gz), dest, || Ok(()))
     }
 }
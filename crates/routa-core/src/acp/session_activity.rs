@@ -0,0 +1,83 @@
+//! Last-activity tracking for ACP sessions.
+//!
+//! A session is active when it is created, prompted, or its agent emits a
+//! `session/update`. The idle sweeper prunes sessions that have seen no
+//! activity for longer than `ROUTA_ACP_SESSION_TTL_SECS` (see
+//! [`crate::session_sweep`]).
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Default)]
+pub struct SessionActivity {
+    last_seen: Mutex<HashMap<String, Instant>>,
+}
+
+impl SessionActivity {
+    /// Start tracking a newly registered session.
+    pub fn register(&self, session_id: &str) {
+        self.entries()
+            .insert(session_id.to_string(), Instant::now());
+    }
+
+    /// Record activity on a tracked session. Unknown sessions are ignored,
+    /// so late notifications cannot resurrect a pruned session.
+    pub fn touch(&self, session_id: &str) {
+        if let Some(last_seen) = self.entries().get_mut(session_id) {
+            *last_seen = Instant::now();
+        }
+    }
+
+    pub fn forget(&self, session_id: &str) {
+        self.entries().remove(session_id);
+    }
+
+    /// Sessions with no activity for longer than `ttl`.
+    pub fn idle_longer_than(&self, ttl: Duration) -> Vec<String> {
+        self.entries()
+            .iter()
+            .filter(|(_, last_seen)| last_seen.elapsed() > ttl)
+            .map(|(session_id, _)| session_id.clone())
+            .collect()
+    }
+
+    /// Stop tracking `session_id` if it is still idle for longer than `ttl`.
+    /// Activity since `idle_longer_than` keeps the session.
+    pub fn take_if_idle(&self, session_id: &str, ttl: Duration) -> bool {
+        let mut entries = self.entries();
+        match entries.get(session_id) {
+            Some(last_seen) if last_seen.elapsed() > ttl => {
+                entries.remove(session_id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<String, Instant>> {
+        self.last_seen.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_idle_registered_sessions_are_taken() {
+        let activity = SessionActivity::default();
+        activity.register("stale");
+        activity.register("busy");
+        activity.touch("unknown");
+        std::thread::sleep(Duration::from_millis(20));
+        activity.touch("busy");
+
+        let ttl = Duration::from_millis(10);
+        assert_eq!(activity.idle_longer_than(ttl), vec!["stale".to_string()]);
+        assert!(!activity.take_if_idle("busy", ttl));
+        assert!(activity.take_if_idle("stale", ttl));
+        assert!(!activity.take_if_idle("stale", ttl));
+        assert!(!activity.take_if_idle("unknown", ttl));
+    }
+}
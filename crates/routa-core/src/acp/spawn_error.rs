@@ -0,0 +1,171 @@
+//! Classification of agent launch failures, so a client can act on the cause
+//! (offer to install a missing CLI, fix permissions, retry) rather than only
+//! show a message.
+//!
+//! Spawn and initialize errors travel through `AcpManager` as strings;
+//! `SpawnError::classify` recognizes the messages `AcpProcess` produces for
+//! each failure. Routes attach the result as the JSON-RPC error's `data`,
+//! e.g. `{ "code": "command-not-found", "detail": "Failed to spawn ..." }`.
+
+use std::io::ErrorKind;
+
+use serde::Serialize;
+
+use super::process::PROCESS_DIED_DURING_STARTUP;
+
+/// Hint appended when the agent binary cannot be found.
+pub(crate) const COMMAND_NOT_FOUND_HINT: &str = "Is it installed and in PATH?";
+/// Hint appended when the binary exists but its interpreter does not.
+pub(crate) const MISSING_INTERPRETER_HINT: &str =
+    "The binary exists, but a required interpreter or wrapper target may be missing.";
+/// Hint appended when the agent binary may not be executed.
+pub(crate) const PERMISSION_DENIED_HINT: &str = "Check that it is executable by this user.";
+
+/// Why an agent could not be launched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpawnErrorCode {
+    CommandNotFound,
+    PermissionDenied,
+    Timeout,
+    ProcessCrashed,
+    ProtocolError,
+    Unknown,
+}
+
+impl SpawnErrorCode {
+    /// Category of an OS error raised while spawning or talking to an agent.
+    pub fn from_io_error_kind(kind: ErrorKind) -> Self {
+        match kind {
+            ErrorKind::NotFound => Self::CommandNotFound,
+            ErrorKind::PermissionDenied => Self::PermissionDenied,
+            ErrorKind::TimedOut | ErrorKind::WouldBlock => Self::Timeout,
+            ErrorKind::BrokenPipe | ErrorKind::UnexpectedEof | ErrorKind::ConnectionReset => {
+                Self::ProcessCrashed
+            }
+            ErrorKind::InvalidData => Self::ProtocolError,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CommandNotFound => "command-not-found",
+            Self::PermissionDenied => "permission-denied",
+            Self::Timeout => "timeout",
+            Self::ProcessCrashed => "process-crashed",
+            Self::ProtocolError => "protocol-error",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// A classified launch failure with the raw error it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SpawnError {
+    pub code: SpawnErrorCode,
+    pub detail: String,
+}
+
+impl SpawnError {
+    /// Classify an error returned while creating or loading a session.
+    pub fn classify(detail: impl Into<String>) -> Self {
+        let detail = detail.into();
+        let lower = detail.to_ascii_lowercase();
+        let code = if detail.contains(COMMAND_NOT_FOUND_HINT)
+            || detail.contains(MISSING_INTERPRETER_HINT)
+        {
+            SpawnErrorCode::CommandNotFound
+        } else if detail.contains(PERMISSION_DENIED_HINT) || lower.contains("permission denied") {
+            SpawnErrorCode::PermissionDenied
+        } else if detail.contains("Timeout waiting for") || lower.contains("timed out") {
+            SpawnErrorCode::Timeout
+        } else if detail.ends_with(PROCESS_DIED_DURING_STARTUP)
+            || detail.contains("process is not alive")
+            || detail.contains(" exited (")
+            || detail.starts_with("Channel closed for")
+            || lower.contains("broken pipe")
+        {
+            SpawnErrorCode::ProcessCrashed
+        } else if detail.starts_with("ACP Error [") || detail.starts_with("No sessionId in") {
+            SpawnErrorCode::ProtocolError
+        } else {
+            SpawnErrorCode::Unknown
+        };
+        Self { code, detail }
+    }
+
+    /// JSON-RPC error `data` for this failure.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_io_error_kinds() {
+        assert_eq!(
+            SpawnErrorCode::from_io_error_kind(ErrorKind::NotFound),
+            SpawnErrorCode::CommandNotFound
+        );
+        assert_eq!(
+            SpawnErrorCode::from_io_error_kind(ErrorKind::PermissionDenied),
+            SpawnErrorCode::PermissionDenied
+        );
+        assert_eq!(
+            SpawnErrorCode::from_io_error_kind(ErrorKind::BrokenPipe),
+            SpawnErrorCode::ProcessCrashed
+        );
+        assert_eq!(
+            SpawnErrorCode::from_io_error_kind(ErrorKind::Other),
+            SpawnErrorCode::Unknown
+        );
+    }
+
+    #[test]
+    fn classifies_process_error_messages() {
+        let cases = [
+            (
+                format!("Failed to spawn 'codex-acp' (resolved: 'codex-acp'): No such file or directory (os error 2). {COMMAND_NOT_FOUND_HINT}"),
+                SpawnErrorCode::CommandNotFound,
+            ),
+            (
+                "Failed to spawn 'x' (resolved: '/x') from cwd '/': Permission denied (os error 13)"
+                    .to_string(),
+                SpawnErrorCode::PermissionDenied,
+            ),
+            (
+                "Timeout waiting for initialize (id=1, 10000ms)".to_string(),
+                SpawnErrorCode::Timeout,
+            ),
+            (
+                format!("OpenCode {PROCESS_DIED_DURING_STARTUP}"),
+                SpawnErrorCode::ProcessCrashed,
+            ),
+            (
+                "ACP Error [-32601]: Method not found".to_string(),
+                SpawnErrorCode::ProtocolError,
+            ),
+            ("Something else".to_string(), SpawnErrorCode::Unknown),
+        ];
+        for (detail, code) in cases {
+            assert_eq!(SpawnError::classify(detail.clone()).code, code, "{detail}");
+        }
+    }
+
+    #[test]
+    fn serializes_code_and_detail() {
+        let error = SpawnError::classify("ACP Error [-32600]: bad request");
+        assert_eq!(
+            error.to_json(),
+            serde_json::json!({
+                "code": "protocol-error",
+                "detail": "ACP Error [-32600]: bad request",
+            })
+        );
+        assert_eq!(error.code.as_str(), "protocol-error");
+    }
+}
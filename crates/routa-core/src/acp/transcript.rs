@@ -0,0 +1,325 @@
+//! Per-session prompt/response transcripts, so `session/load` can hand a
+//! reloaded client the conversation even when the agent itself cannot resume.
+//!
+//! Each session's transcript is a JSONL file under
+//! `AcpPaths::transcripts_dir()` named after the session id, holding one
+//! `{role, content, timestamp}` object per turn. Once a file grows past the
+//! size cap (`AcpSettings::transcript_max_bytes`) its oldest turns are
+//! dropped until it fits again.
+//!
+//! The assistant side of a turn is gathered by a `ReplyCollector` that reads
+//! the session's notifications while the prompt runs, so a reply longer than
+//! the notification channel's capacity is kept whole.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, oneshot};
+
+use super::paths::AcpPaths;
+use crate::settings::AcpSettings;
+
+/// Default size cap for one session's transcript.
+pub const DEFAULT_TRANSCRIPT_MAX_BYTES: u64 = 4 * 1024 * 1024;
+
+/// One prompt or response in a session transcript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptTurn {
+    /// `user` or `assistant`.
+    pub role: String,
+    pub content: String,
+    /// RFC 3339 time the turn was recorded.
+    pub timestamp: String,
+}
+
+impl TranscriptTurn {
+    pub fn new(role: &str, content: impl Into<String>) -> Self {
+        Self {
+            role: role.to_string(),
+            content: content.into(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TranscriptStore {
+    dir: PathBuf,
+    /// `None` when transcripts are disabled.
+    max_bytes: Option<u64>,
+}
+
+impl TranscriptStore {
+    pub fn new(dir: PathBuf, max_bytes: Option<u64>) -> Self {
+        Self {
+            dir,
+            max_bytes: max_bytes.filter(|max| *max > 0),
+        }
+    }
+
+    /// The store under `paths`, capped by `settings`.
+    pub fn from_settings(paths: &AcpPaths, settings: &AcpSettings) -> Self {
+        Self::new(paths.transcripts_dir(), settings.transcript_max_bytes)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_bytes.is_some()
+    }
+
+    /// Transcript file for `session_id`.
+    pub fn path(&self, session_id: &str) -> PathBuf {
+        let name: String = session_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{name}.jsonl"))
+    }
+
+    /// Append `turns` to the session's transcript, dropping its oldest turns
+    /// if the file outgrows the cap.
+    pub fn append(&self, session_id: &str, turns: &[TranscriptTurn]) -> Result<(), String> {
+        let Some(max_bytes) = self.max_bytes else {
+            return Ok(());
+        };
+        if turns.is_empty() {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create transcript dir: {e}"))?;
+        let path = self.path(session_id);
+        let mut data = String::new();
+        for turn in turns {
+            let line = serde_json::to_string(turn)
+                .map_err(|e| format!("Failed to serialize transcript turn: {e}"))?;
+            data.push_str(&line);
+            data.push('\n');
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open transcript {path:?}: {e}"))?;
+        file.write_all(data.as_bytes())
+            .map_err(|e| format!("Failed to write transcript {path:?}: {e}"))?;
+        drop(file);
+
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if size > max_bytes {
+            rotate(&path, max_bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Turns recorded for `session_id`, oldest first. Unreadable lines are
+    /// skipped.
+    pub fn read(&self, session_id: &str) -> Vec<TranscriptTurn> {
+        let Ok(content) = std::fs::read_to_string(self.path(session_id)) else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    pub fn remove(&self, session_id: &str) {
+        let _ = std::fs::remove_file(self.path(session_id));
+    }
+}
+
+/// Rewrite `path` without its oldest lines so it fits in `max_bytes`. The
+/// newest turn is always kept, even when it alone is over the cap.
+fn rotate(path: &Path, max_bytes: u64) -> Result<(), String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read transcript {path:?}: {e}"))?;
+    let lines: Vec<&str> = content.lines().filter(|l| !l.is_empty()).collect();
+    let mut kept = 0usize;
+    let mut size = 0u64;
+    for line in lines.iter().rev() {
+        let line_size = line.len() as u64 + 1;
+        if kept > 0 && size + line_size > max_bytes {
+            break;
+        }
+        size += line_size;
+        kept += 1;
+    }
+    let dropped = lines.len() - kept;
+    let mut rotated = lines[dropped..].join("\n");
+    rotated.push('\n');
+
+    let tmp = path.with_extension("jsonl.tmp");
+    std::fs::write(&tmp, rotated)
+        .map_err(|e| format!("Failed to write transcript {tmp:?}: {e}"))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to replace transcript: {e}"))?;
+    tracing::info!(
+        "[AcpManager] Rotated {} old turn(s) out of transcript {:?}",
+        dropped,
+        path
+    );
+    Ok(())
+}
+
+/// Text of an `agent_message_chunk` notification, as broadcast to session
+/// subscribers.
+pub fn agent_message_text(notification: &serde_json::Value) -> Option<&str> {
+    let update = notification
+        .get("params")
+        .unwrap_or(notification)
+        .get("update")?;
+    if update.get("sessionUpdate").and_then(|v| v.as_str()) != Some("agent_message_chunk") {
+        return None;
+    }
+    update
+        .get("content")
+        .and_then(|c| c.get("text"))
+        .and_then(|t| t.as_str())
+}
+
+/// Gathers the `agent_message_chunk` text of a session's notifications
+/// until `finish` is called.
+pub(crate) struct ReplyCollector {
+    done: oneshot::Sender<()>,
+    task: tokio::task::JoinHandle<String>,
+}
+
+impl ReplyCollector {
+    /// Start reading `notifications` in a background task. Subscribe before
+    /// sending the prompt so no chunk is missed.
+    pub(crate) fn spawn(mut notifications: broadcast::Receiver<serde_json::Value>) -> Self {
+        let (done, mut done_rx) = oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            let mut reply = String::new();
+            loop {
+                tokio::select! {
+                    biased;
+                    received = notifications.recv() => match received {
+                        Ok(notification) => {
+                            if let Some(chunk) = agent_message_text(&notification) {
+                                reply.push_str(chunk);
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            tracing::warn!(
+                                "[AcpManager] Reply collector fell {} notification(s) behind; \
+                                 the transcript turn is incomplete",
+                                skipped
+                            );
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    // The prompt has returned (or was abandoned): take what is
+                    // already queued and stop.
+                    _ = &mut done_rx => {
+                        while let Ok(notification) = notifications.try_recv() {
+                            if let Some(chunk) = agent_message_text(&notification) {
+                                reply.push_str(chunk);
+                            }
+                        }
+                        break;
+                    }
+                }
+            }
+            reply
+        });
+        Self { done, task }
+    }
+
+    /// Stop collecting and return the reply text gathered so far.
+    pub(crate) async fn finish(self) -> String {
+        let _ = self.done.send(());
+        self.task.await.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_and_read_round_trip() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = TranscriptStore::new(temp.path().join("transcripts"), Some(1024));
+        assert!(store.read("session-1").is_empty());
+
+        store
+            .append(
+                "session-1",
+                &[
+                    TranscriptTurn::new("user", "hello"),
+                    TranscriptTurn::new("assistant", "hi there"),
+                ],
+            )
+            .unwrap();
+        store
+            .append("session-1", &[TranscriptTurn::new("user", "again")])
+            .unwrap();
+
+        let turns = store.read("session-1");
+        let roles: Vec<_> = turns.iter().map(|t| t.role.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant", "user"]);
+        assert_eq!(turns[1].content, "hi there");
+        assert!(store
+            .path("../x")
+            .starts_with(temp.path().join("transcripts")));
+
+        store.remove("session-1");
+        assert!(store.read("session-1").is_empty());
+    }
+
+    #[test]
+    fn append_rotates_oldest_turns_past_the_cap() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = TranscriptStore::new(temp.path().to_path_buf(), Some(300));
+        for i in 0..10 {
+            store
+                .append("s", &[TranscriptTurn::new("user", format!("turn {i}"))])
+                .unwrap();
+        }
+
+        let turns = store.read("s");
+        assert!(turns.len() < 10);
+        assert_eq!(turns.last().unwrap().content, "turn 9");
+        assert!(std::fs::metadata(store.path("s")).unwrap().len() <= 300);
+
+        // A single turn over the cap is still kept.
+        store
+            .append("s", &[TranscriptTurn::new("assistant", "x".repeat(500))])
+            .unwrap();
+        assert_eq!(store.read("s").len(), 1);
+    }
+
+    #[test]
+    fn disabled_store_records_nothing() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = TranscriptStore::new(temp.path().to_path_buf(), Some(0));
+        assert!(!store.is_enabled());
+        store
+            .append("s", &[TranscriptTurn::new("user", "hello")])
+            .unwrap();
+        assert!(store.read("s").is_empty());
+    }
+
+    #[test]
+    fn agent_message_text_reads_chunks_only() {
+        let chunk = serde_json::json!({
+            "method": "session/update",
+            "params": {
+                "sessionId": "s",
+                "update": { "sessionUpdate": "agent_message_chunk", "content": { "text": "hi" } }
+            }
+        });
+        assert_eq!(agent_message_text(&chunk), Some("hi"));
+        let thought = serde_json::json!({
+            "update": { "sessionUpdate": "agent_thought_chunk", "content": { "text": "hm" } }
+        });
+        assert_eq!(agent_message_text(&thought), None);
+    }
+}
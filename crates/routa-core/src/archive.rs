@@ -0,0 +1,230 @@
+//! Archive extraction that refuses to write outside its destination.
+//!
+//! Agent binaries, runtimes, and skills are unpacked from archives fetched
+//! from registry and repository URLs, so entry names are untrusted. Every
+//! entry is checked before it is written: names with `..`, absolute paths, or
+//! drive prefixes are rejected (zip-slip), and the entry's parent directory
+//! is canonicalized so a symlink unpacked earlier cannot redirect a later
+//! entry outside the destination. Errors name the offending entry.
+
+use std::io::{Read, Seek};
+use std::path::{Component, Path, PathBuf};
+
+/// Where entry `name` belongs under `dest`, or an error when it would land
+/// outside it.
+pub fn entry_path(dest: &Path, name: &str) -> Result<PathBuf, String> {
+    let mut relative = PathBuf::new();
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(escape_error(name));
+            }
+        }
+    }
+    // Backslashes are separators in archives made on Windows.
+    if name.contains('\\') && name.split('\\').any(|part| part == "..") {
+        return Err(escape_error(name));
+    }
+    Ok(dest.join(relative))
+}
+
+/// Create `path`'s parent directories and check that, with symlinks
+/// resolved, they are inside `root` (the canonical destination).
+fn prepare_parent(root: &Path, path: &Path, name: &str) -> Result<(), String> {
+    if path == root {
+        return Ok(());
+    }
+    let Some(parent) = path.parent() else {
+        return Ok(());
+    };
+    std::fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create directory for '{name}': {e}"))?;
+    let parent = parent
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve directory for '{name}': {e}"))?;
+    if parent.starts_with(root) {
+        Ok(())
+    } else {
+        Err(escape_error(name))
+    }
+}
+
+fn escape_error(name: &str) -> String {
+    format!("Archive entry '{name}' escapes the extraction directory")
+}
+
+fn canonical_dest(dest: &Path) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dest)
+        .and_then(|()| dest.canonicalize())
+        .map_err(|e| format!("Failed to prepare {}: {e}", dest.display()))
+}
+
+/// Extract a zip archive into `dest`. `before_entry` runs before each entry
+/// and can stop the extraction (e.g. on cancellation) by returning an error.
+pub fn extract_zip<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    dest: &Path,
+    mut before_entry: impl FnMut() -> Result<(), String>,
+) -> Result<(), String> {
+    let root = canonical_dest(dest)?;
+    for i in 0..archive.len() {
+        before_entry()?;
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read zip entry: {e}"))?;
+        let name = file.name().to_string();
+        let outpath = entry_path(&root, &name)?;
+
+        if file.is_dir() {
+            prepare_parent(&root, &outpath, &name)?;
+            std::fs::create_dir_all(&outpath)
+                .map_err(|e| format!("Failed to create directory '{name}': {e}"))?;
+        } else {
+            prepare_parent(&root, &outpath, &name)?;
+            let mut outfile = std::fs::File::create(&outpath)
+                .map_err(|e| format!("Failed to create file '{name}': {e}"))?;
+            std::io::copy(&mut file, &mut outfile)
+                .map_err(|e| format!("Failed to extract file '{name}': {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Extract a tar archive into `dest`, keeping symlinks (whose targets are
+/// checked when something is read through them, not here). `before_entry`
+/// works as in `extract_zip`.
+pub fn extract_tar<R: Read>(
+    mut archive: tar::Archive<R>,
+    dest: &Path,
+    mut before_entry: impl FnMut() -> Result<(), String>,
+) -> Result<(), String> {
+    let root = canonical_dest(dest)?;
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read tar entries: {e}"))?;
+    for entry in entries {
+        before_entry()?;
+        let mut entry = entry.map_err(|e| format!("Failed to read tar entry: {e}"))?;
+        let name = entry
+            .path()
+            .map_err(|e| format!("Invalid tar entry path: {e}"))?
+            .to_string_lossy()
+            .to_string();
+        let outpath = entry_path(&root, &name)?;
+        prepare_parent(&root, &outpath, &name)?;
+        if entry.header().entry_type().is_hard_link() {
+            if let Some(target) = entry
+                .link_name()
+                .map_err(|e| format!("Invalid link in tar entry '{name}': {e}"))?
+            {
+                entry_path(&root, &target.to_string_lossy()).map_err(|_| escape_error(&name))?;
+            }
+        }
+        entry
+            .unpack_in(&root)
+            .map_err(|e| format!("Failed to extract '{name}': {e}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tar_with_entry(name: &str) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let data = b"evil";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        // `set_path` refuses `..`, so write the name into the header directly.
+        header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_cksum();
+        builder.append(&header, &data[..]).unwrap();
+        builder.into_inner().unwrap()
+    }
+
+    fn zip_with_entry(name: &str) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer
+            .start_file(name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut writer, b"evil").unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn entry_path_rejects_escaping_names() {
+        let dest = Path::new("/install");
+        assert_eq!(
+            entry_path(dest, "./bin/agent").unwrap(),
+            dest.join("bin/agent")
+        );
+        for name in ["../evil", "bin/../../evil", "/etc/passwd", "..\\evil"] {
+            let error = entry_path(dest, name).unwrap_err();
+            assert!(error.contains(name), "{error}");
+        }
+    }
+
+    #[test]
+    fn zip_slip_entry_is_rejected() {
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("install");
+        let mut archive =
+            zip::ZipArchive::new(std::io::Cursor::new(zip_with_entry("../evil"))).unwrap();
+
+        let error = extract_zip(&mut archive, &dest, || Ok(())).unwrap_err();
+        assert!(error.contains("../evil"), "{error}");
+        assert!(!temp.path().join("evil").exists());
+
+        let mut archive =
+            zip::ZipArchive::new(std::io::Cursor::new(zip_with_entry("bin/agent"))).unwrap();
+        extract_zip(&mut archive, &dest, || Ok(())).unwrap();
+        assert_eq!(std::fs::read(dest.join("bin/agent")).unwrap(), b"evil");
+    }
+
+    #[test]
+    fn tar_slip_entry_is_rejected() {
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("install");
+        let archive = tar::Archive::new(std::io::Cursor::new(tar_with_entry("../evil")));
+
+        let error = extract_tar(archive, &dest, || Ok(())).unwrap_err();
+        assert!(error.contains("../evil"), "{error}");
+        assert!(!temp.path().join("evil").exists());
+
+        let archive = tar::Archive::new(std::io::Cursor::new(tar_with_entry("./agent")));
+        extract_tar(archive, &dest, || Ok(())).unwrap();
+        assert_eq!(std::fs::read(dest.join("agent")).unwrap(), b"evil");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tar_entry_through_escaping_symlink_is_rejected() {
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("install");
+        let outside = temp.path().join("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut link = tar::Header::new_gnu();
+        link.set_entry_type(tar::EntryType::Symlink);
+        link.set_size(0);
+        link.set_cksum();
+        builder.append_link(&mut link, "link", &outside).unwrap();
+        let mut file = tar::Header::new_gnu();
+        file.set_size(4);
+        file.set_mode(0o644);
+        file.set_cksum();
+        builder
+            .append_data(&mut file, "link/evil", &b"evil"[..])
+            .unwrap();
+        let archive = tar::Archive::new(std::io::Cursor::new(builder.into_inner().unwrap()));
+
+        let error = extract_tar(archive, &dest, || Ok(())).unwrap_err();
+        assert!(error.contains("link/evil"), "{error}");
+        assert!(!outside.join("evil").exists());
+    }
+}
@@ -0,0 +1,190 @@
+//! Minimum client versions accepted at `initialize`.
+//!
+//! Both the ACP (`POST /api/acp`) and MCP (`/api/mcp`) endpoints read the
+//! `clientInfo` a client sends in `initialize`; when a minimum version is
+//! configured for that client, older versions are rejected with an upgrade
+//! message instead of failing later on a protocol mismatch. The minimums are
+//! parsed from `ROUTA_MIN_CLIENT_VERSION` by `crate::settings`.
+
+use serde::{Deserialize, Serialize};
+
+/// `clientInfo` from an `initialize` request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientInfo {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl ClientInfo {
+    /// Read `clientInfo` from `initialize` params; `None` when absent or unnamed.
+    pub fn from_initialize_params(params: &serde_json::Value) -> Option<Self> {
+        let info = params.get("clientInfo")?;
+        let name = info
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|name| !name.is_empty())?;
+        let version = info
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|version| !version.is_empty())
+            .map(str::to_string);
+        Some(Self {
+            name: name.to_string(),
+            version,
+        })
+    }
+}
+
+impl std::fmt::Display for ClientInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{} {}", self.name, version),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientVersionGate {
+    /// Minimum for clients without their own entry.
+    pub default_minimum: Option<String>,
+    /// Per-client minimums, keyed by `clientInfo.name`.
+    pub minimums: Vec<(String, String)>,
+}
+
+impl ClientVersionGate {
+    pub fn parse(value: &str) -> Self {
+        let mut gate = Self::default();
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match entry.split_once('=') {
+                Some((name, version)) if !name.trim().is_empty() => gate
+                    .minimums
+                    .push((name.trim().to_string(), version.trim().to_string())),
+                _ => gate.default_minimum = Some(entry.to_string()),
+            }
+        }
+        gate
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.default_minimum.is_some() || !self.minimums.is_empty()
+    }
+
+    /// Check `client` against its configured minimum. Clients that send no
+    /// `clientInfo`, or no version, are let through since there is nothing
+    /// to compare.
+    pub fn check(&self, client: Option<&ClientInfo>) -> Result<(), String> {
+        let Some(client) = client else {
+            return Ok(());
+        };
+        let minimum = self
+            .minimums
+            .iter()
+            .find(|(name, _)| *name == client.name)
+            .map(|(_, version)| version)
+            .or(self.default_minimum.as_ref());
+        let (Some(minimum), Some(version)) = (minimum, client.version.as_deref()) else {
+            return Ok(());
+        };
+        if compare_versions(version, minimum).is_lt() {
+            return Err(format!(
+                "Client {} {version} is no longer supported; upgrade to {minimum} or newer.",
+                client.name
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Compare dotted numeric versions (`v` prefix and pre-release/build suffixes
+/// ignored); missing components count as zero.
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    fn components(version: &str) -> Vec<u64> {
+        version
+            .trim()
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| {
+                part.chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+                    .parse()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    let (a, b) = (components(a), components(b));
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| {
+            a.get(i)
+                .copied()
+                .unwrap_or(0)
+                .cmp(&b.get(i).copied().unwrap_or(0))
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(std::cmp::Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(name: &str, version: Option<&str>) -> ClientInfo {
+        ClientInfo {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn reads_client_info_from_initialize_params() {
+        let params = serde_json::json!({
+            "clientInfo": { "name": " cursor ", "version": "1.4.0" }
+        });
+        assert_eq!(
+            ClientInfo::from_initialize_params(&params),
+            Some(client("cursor", Some("1.4.0")))
+        );
+        assert_eq!(
+            ClientInfo::from_initialize_params(&serde_json::json!({})),
+            None
+        );
+    }
+
+    #[test]
+    fn gate_rejects_clients_below_their_minimum() {
+        let gate = ClientVersionGate::parse("0.3.0, routa-desktop=1.2.0");
+        assert!(gate
+            .check(Some(&client("routa-desktop", Some("1.2.0"))))
+            .is_ok());
+        assert!(gate
+            .check(Some(&client("routa-desktop", Some("v1.10"))))
+            .is_ok());
+        let error = gate
+            .check(Some(&client("routa-desktop", Some("1.1.9"))))
+            .unwrap_err();
+        assert!(error.contains("upgrade to 1.2.0"), "{error}");
+
+        assert!(gate
+            .check(Some(&client("other", Some("0.2.9-beta"))))
+            .is_err());
+        assert!(gate.check(Some(&client("other", Some("0.3.0")))).is_ok());
+        assert!(gate.check(Some(&client("other", None))).is_ok());
+        assert!(gate.check(None).is_ok());
+    }
+
+    #[test]
+    fn empty_gate_accepts_everything() {
+        let gate = ClientVersionGate::parse("");
+        assert!(!gate.is_enabled());
+        assert!(gate.check(Some(&client("any", Some("0.0.1")))).is_ok());
+    }
+}
@@ -0,0 +1,238 @@
+//! Registry of in-flight clones, for `GET /api/clone/active`.
+//!
+//! Clone handlers register a clone before starting git and keep the returned
+//! [`ActiveCloneHandle`] for as long as it runs, updating its phase as
+//! progress arrives. Dropping the handle unregisters the clone, so finished,
+//! failed, and abandoned clones all leave the list as soon as their handler
+//! lets go. Only one clone per target path can be registered at a time.
+//!
+//! [`CloneTracker::cancel`] asks a running clone to stop; its handler waits
+//! on [`ActiveCloneHandle::cancelled`] and tears the clone down.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tokio::sync::watch;
+
+/// One clone as listed by `GET /api/clone/active`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveClone {
+    pub id: String,
+    pub url: String,
+    pub path: String,
+    /// `starting`, a git progress phase (`counting`, `receiving`, ...), or
+    /// `fetching` once the clone itself is done.
+    pub phase: String,
+    /// Progress through the current phase, when git reports one.
+    pub percent: Option<i32>,
+    /// RFC 3339 time the clone was registered.
+    pub started_at: String,
+}
+
+#[derive(Debug)]
+struct TrackedClone {
+    clone: ActiveClone,
+    cancel: watch::Sender<bool>,
+}
+
+type CloneMap = Arc<Mutex<HashMap<String, TrackedClone>>>;
+
+#[derive(Debug, Default)]
+pub struct CloneTracker {
+    clones: CloneMap,
+    next_id: AtomicU64,
+}
+
+impl CloneTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a clone of `url` into `path`. Returns `None` when a clone
+    /// into `path` is already running.
+    pub fn start(&self, url: &str, path: &str) -> Option<ActiveCloneHandle> {
+        let mut clones = lock(&self.clones);
+        if clones.values().any(|tracked| tracked.clone.path == path) {
+            return None;
+        }
+        let id = format!("clone-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        let (cancel, cancelled) = watch::channel(false);
+        clones.insert(
+            id.clone(),
+            TrackedClone {
+                clone: ActiveClone {
+                    id: id.clone(),
+                    url: url.to_string(),
+                    path: path.to_string(),
+                    phase: "starting".to_string(),
+                    percent: None,
+                    started_at: chrono::Utc::now().to_rfc3339(),
+                },
+                cancel,
+            },
+        );
+        Some(ActiveCloneHandle {
+            id,
+            clones: self.clones.clone(),
+            cancelled,
+        })
+    }
+
+    /// Ask the clone `id` to stop. Returns the clone, or `None` when no clone
+    /// with that id is running.
+    pub fn cancel(&self, id: &str) -> Option<ActiveClone> {
+        let clones = lock(&self.clones);
+        let tracked = clones.get(id)?;
+        tracked.cancel.send_replace(true);
+        Some(tracked.clone.clone())
+    }
+
+    /// Clones currently running, oldest first.
+    pub fn list(&self) -> Vec<ActiveClone> {
+        let mut clones: Vec<_> = lock(&self.clones)
+            .values()
+            .map(|tracked| tracked.clone.clone())
+            .collect();
+        clones.sort_by(|a, b| {
+            a.started_at
+                .cmp(&b.started_at)
+                .then_with(|| id_number(&a.id).cmp(&id_number(&b.id)))
+        });
+        clones
+    }
+}
+
+/// Keeps a clone listed until dropped.
+#[derive(Debug)]
+pub struct ActiveCloneHandle {
+    id: String,
+    clones: CloneMap,
+    cancelled: watch::Receiver<bool>,
+}
+
+impl ActiveCloneHandle {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn set_phase(&self, phase: &str, percent: Option<i32>) {
+        if let Some(tracked) = lock(&self.clones).get_mut(&self.id) {
+            tracked.clone.phase = phase.to_string();
+            tracked.clone.percent = percent;
+        }
+    }
+
+    /// Resolves once the clone has been cancelled.
+    pub async fn cancelled(&self) {
+        let mut cancelled = self.cancelled.clone();
+        if cancelled.wait_for(|cancelled| *cancelled).await.is_err() {
+            // The entry is only removed by dropping this handle.
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+impl Drop for ActiveCloneHandle {
+    fn drop(&mut self) {
+        lock(&self.clones).remove(&self.id);
+    }
+}
+
+fn lock(clones: &CloneMap) -> std::sync::MutexGuard<'_, HashMap<String, TrackedClone>> {
+    clones.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn id_number(id: &str) -> u64 {
+    id.rsplit('-')
+        .next()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_clones_until_their_handles_drop() {
+        let tracker = CloneTracker::new();
+        let first = tracker
+            .start("https://github.com/a/one", "/repos/a--one")
+            .unwrap();
+        let second = tracker
+            .start("https://github.com/a/two", "/repos/a--two")
+            .unwrap();
+        assert!(
+            tracker
+                .start("https://github.com/a/one", "/repos/a--one")
+                .is_none(),
+            "a path can only be cloned once at a time"
+        );
+
+        second.set_phase("receiving", Some(42));
+        let clones = tracker.list();
+        assert_eq!(clones.len(), 2);
+        assert_eq!(clones[0].id, first.id());
+        assert_eq!(clones[0].phase, "starting");
+        assert_eq!(clones[1].phase, "receiving");
+        assert_eq!(clones[1].percent, Some(42));
+
+        drop(first);
+        let clones = tracker.list();
+        assert_eq!(clones.len(), 1);
+        assert_eq!(clones[0].path, "/repos/a--two");
+        assert!(tracker
+            .start("https://github.com/a/one", "/repos/a--one")
+            .is_some());
+        assert_eq!(tracker.list().len(), 1, "dropped immediately");
+    }
+
+    #[tokio::test]
+    async fn cancel_wakes_only_the_cancelled_clone() {
+        let tracker = CloneTracker::new();
+        let wrong = tracker
+            .start("https://github.com/a/huge", "/repos/a--huge")
+            .unwrap();
+        let right = tracker
+            .start("https://github.com/a/small", "/repos/a--small")
+            .unwrap();
+
+        let cancelled = tracker.cancel(wrong.id()).unwrap();
+        assert_eq!(cancelled.path, "/repos/a--huge");
+        tokio::time::timeout(std::time::Duration::from_secs(1), wrong.cancelled())
+            .await
+            .expect("cancelled clone should be woken");
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(20), right.cancelled())
+                .await
+                .is_err(),
+            "other clones keep running"
+        );
+
+        drop(wrong);
+        assert!(tracker.cancel("clone-1").is_none());
+        assert_eq!(tracker.list().len(), 1);
+    }
+
+    #[test]
+    fn concurrent_clones_are_all_listed_and_removed() {
+        let tracker = Arc::new(CloneTracker::new());
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let tracker = tracker.clone();
+                std::thread::spawn(move || {
+                    tracker
+                        .start("https://github.com/o/r", &format!("/repos/{i}"))
+                        .unwrap()
+                })
+            })
+            .collect();
+        let handles: Vec<_> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+        assert_eq!(tracker.list().len(), 8);
+        drop(handles);
+        assert!(tracker.list().is_empty());
+    }
+}
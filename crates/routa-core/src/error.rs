@@ -23,6 +23,9 @@ pub enum ServerError {
 
     #[error("Not implemented: {0}")]
     NotImplemented(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
 }
 
 // ---------------------------------------------------------------------------
@@ -41,6 +44,7 @@ impl axum::response::IntoResponse for ServerError {
             ServerError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
             ServerError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
             ServerError::NotImplemented(msg) => (StatusCode::NOT_IMPLEMENTED, msg.clone()),
+            ServerError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
         };
 
         let body = serde_json::json!({ "error": message });
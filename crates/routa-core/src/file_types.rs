@@ -0,0 +1,207 @@
+//! File type detection shared by file search, read and diff features.
+//!
+//! Detection uses the extension first and falls back to sniffing the leading
+//! bytes, so extensionless scripts and images still get a usable type.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+/// Number of leading bytes inspected when sniffing content.
+const SNIFF_LEN: usize = 8192;
+
+/// How the UI should treat a file.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileKind {
+    Text,
+    Image,
+    Binary,
+}
+
+/// Detected type of a file.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileType {
+    pub mime_type: &'static str,
+    /// Syntax-highlighting hint (e.g. `rust`, `typescript`); `None` for non-code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<&'static str>,
+    pub kind: FileKind,
+}
+
+impl FileType {
+    const fn text(mime_type: &'static str, language: Option<&'static str>) -> Self {
+        Self {
+            mime_type,
+            language,
+            kind: FileKind::Text,
+        }
+    }
+
+    const fn image(mime_type: &'static str) -> Self {
+        Self {
+            mime_type,
+            language: None,
+            kind: FileKind::Image,
+        }
+    }
+
+    const fn binary(mime_type: &'static str) -> Self {
+        Self {
+            mime_type,
+            language: None,
+            kind: FileKind::Binary,
+        }
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.kind == FileKind::Text
+    }
+}
+
+/// Detect a file's type from its path alone (no I/O), e.g. for search results.
+pub fn detect_from_path(path: &Path) -> Option<FileType> {
+    let file_name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    match file_name.as_str() {
+        "dockerfile" => return Some(FileType::text("text/plain", Some("dockerfile"))),
+        "makefile" => return Some(FileType::text("text/plain", Some("makefile"))),
+        "cargo.lock" => return Some(FileType::text("text/plain", Some("toml"))),
+        _ => {}
+    }
+
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let file_type = match extension.as_str() {
+        "rs" => FileType::text("text/x-rust", Some("rust")),
+        "ts" | "mts" | "cts" => FileType::text("text/typescript", Some("typescript")),
+        "tsx" => FileType::text("text/typescript", Some("tsx")),
+        "js" | "mjs" | "cjs" => FileType::text("text/javascript", Some("javascript")),
+        "jsx" => FileType::text("text/javascript", Some("jsx")),
+        "json" => FileType::text("application/json", Some("json")),
+        "toml" => FileType::text("application/toml", Some("toml")),
+        "yaml" | "yml" => FileType::text("application/yaml", Some("yaml")),
+        "md" | "mdx" => FileType::text("text/markdown", Some("markdown")),
+        "html" | "htm" => FileType::text("text/html", Some("html")),
+        "css" => FileType::text("text/css", Some("css")),
+        "scss" => FileType::text("text/x-scss", Some("scss")),
+        "py" => FileType::text("text/x-python", Some("python")),
+        "go" => FileType::text("text/x-go", Some("go")),
+        "java" => FileType::text("text/x-java", Some("java")),
+        "kt" | "kts" => FileType::text("text/x-kotlin", Some("kotlin")),
+        "swift" => FileType::text("text/x-swift", Some("swift")),
+        "c" | "h" => FileType::text("text/x-c", Some("c")),
+        "cc" | "cpp" | "cxx" | "hpp" => FileType::text("text/x-c++", Some("cpp")),
+        "rb" => FileType::text("text/x-ruby", Some("ruby")),
+        "sh" | "bash" | "zsh" => FileType::text("text/x-shellscript", Some("shell")),
+        "sql" => FileType::text("application/sql", Some("sql")),
+        "xml" => FileType::text("application/xml", Some("xml")),
+        "txt" | "log" => FileType::text("text/plain", None),
+        "csv" => FileType::text("text/csv", None),
+        "svg" => FileType::image("image/svg+xml"),
+        "png" => FileType::image("image/png"),
+        "jpg" | "jpeg" => FileType::image("image/jpeg"),
+        "gif" => FileType::image("image/gif"),
+        "webp" => FileType::image("image/webp"),
+        "ico" => FileType::image("image/x-icon"),
+        "bmp" => FileType::image("image/bmp"),
+        "pdf" => FileType::binary("application/pdf"),
+        "zip" => FileType::binary("application/zip"),
+        "gz" | "tgz" => FileType::binary("application/gzip"),
+        "wasm" => FileType::binary("application/wasm"),
+        _ => return None,
+    };
+    Some(file_type)
+}
+
+/// Detect a file's type from its path and leading bytes.
+pub fn detect(path: &Path, content: &[u8]) -> FileType {
+    if let Some(file_type) = detect_from_path(path) {
+        return file_type;
+    }
+    sniff(content)
+}
+
+/// Guess a type from content when the path gives no hint.
+fn sniff(content: &[u8]) -> FileType {
+    let head = &content[..content.len().min(SNIFF_LEN)];
+    if head.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return FileType::image("image/png");
+    }
+    if head.starts_with(b"\xff\xd8\xff") {
+        return FileType::image("image/jpeg");
+    }
+    if head.starts_with(b"GIF87a") || head.starts_with(b"GIF89a") {
+        return FileType::image("image/gif");
+    }
+    if head.len() >= 12 && &head[..4] == b"RIFF" && &head[8..12] == b"WEBP" {
+        return FileType::image("image/webp");
+    }
+    if head.starts_with(b"%PDF-") {
+        return FileType::binary("application/pdf");
+    }
+    if head.contains(&0) || std::str::from_utf8(trim_partial_utf8(head)).is_err() {
+        return FileType::binary("application/octet-stream");
+    }
+    if let Some(first_line) = head.split(|b| *b == b'\n').next() {
+        if first_line.starts_with(b"#!") {
+            let interpreter = String::from_utf8_lossy(first_line);
+            let language = if interpreter.contains("python") {
+                Some("python")
+            } else if interpreter.contains("node") {
+                Some("javascript")
+            } else if interpreter.contains("sh") {
+                Some("shell")
+            } else {
+                None
+            };
+            return FileType::text("text/plain", language);
+        }
+    }
+    FileType::text("text/plain", None)
+}
+
+/// Drop a multi-byte UTF-8 sequence cut off by the sniff window.
+fn trim_partial_utf8(bytes: &[u8]) -> &[u8] {
+    match std::str::from_utf8(bytes) {
+        Ok(_) => bytes,
+        Err(error) if error.error_len().is_none() => &bytes[..error.valid_up_to()],
+        Err(_) => bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_rust_source_by_extension() {
+        let file_type = detect(Path::new("src/main.rs"), b"fn main() {}\n");
+        assert_eq!(file_type.mime_type, "text/x-rust");
+        assert_eq!(file_type.language, Some("rust"));
+        assert!(file_type.is_text());
+    }
+
+    #[test]
+    fn detects_png_by_extension_and_signature() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        assert_eq!(detect(Path::new("logo.png"), png).kind, FileKind::Image);
+
+        let sniffed = detect(Path::new("logo"), png);
+        assert_eq!(sniffed.mime_type, "image/png");
+        assert_eq!(sniffed.kind, FileKind::Image);
+    }
+
+    #[test]
+    fn sniffs_extensionless_text_files() {
+        let plain = detect(Path::new("LICENSE"), b"MIT License\n\nCopyright");
+        assert_eq!(plain.mime_type, "text/plain");
+        assert_eq!(plain.language, None);
+        assert!(plain.is_text());
+
+        let script = detect(Path::new("bin/run"), b"#!/usr/bin/env python3\nprint(1)\n");
+        assert_eq!(script.language, Some("python"));
+
+        let binary = detect(Path::new("blob"), b"\0\x01\x02binary");
+        assert_eq!(binary.kind, FileKind::Binary);
+    }
+}
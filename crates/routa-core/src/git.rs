@@ -79,6 +79,94 @@ pub fn parse_github_url(url: &str) -> Option<ParsedGitHubUrl> {
     None
 }
 
+/// A repository on any git host.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParsedGitUrl {
+    /// Lowercase host; keeps the port of an `http(s)://` URL that names one.
+    pub host: String,
+    pub owner: String,
+    pub repo: String,
+}
+
+impl ParsedGitUrl {
+    pub fn is_github(&self) -> bool {
+        self.host == "github.com"
+    }
+
+    /// HTTPS clone URL, e.g. `https://gitlab.com/group/project.git`.
+    pub fn https_url(&self) -> String {
+        format!("https://{}/{}/{}.git", self.host, self.owner, self.repo)
+    }
+}
+
+/// Parse a repository URL on any git host: `https://{host}/{owner}/{repo}`,
+/// `ssh://[user@]{host}[:port]/{owner}/{repo}`, `git@{host}:{owner}/{repo}.git`,
+/// or `{host}/{owner}/{repo}`. The `owner/repo` shorthand means GitHub.
+pub fn parse_git_url(url: &str) -> Option<ParsedGitUrl> {
+    let trimmed = url.trim();
+    if trimmed.contains('\\') || trimmed.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    let (host, path) = if let Some((scheme, rest)) = trimmed.split_once("://") {
+        let scheme = scheme.to_ascii_lowercase();
+        let (authority, path) = rest.split_once('/')?;
+        let authority = authority.rsplit('@').next()?;
+        let host = match scheme.as_str() {
+            "http" | "https" => authority,
+            // The SSH port is meaningless for the HTTPS clone URL.
+            "ssh" | "git" => authority.split(':').next()?,
+            _ => return None,
+        };
+        (host, path)
+    } else if let Some((user_host, path)) = trimmed.split_once(':') {
+        // scp-like `git@host:owner/repo.git`
+        let (_, host) = user_host.split_once('@')?;
+        (host, path)
+    } else if trimmed.matches('/').count() == 1 {
+        // GitHub fast path for the `owner/repo` shorthand.
+        let parsed = parse_github_url(trimmed)?;
+        return Some(ParsedGitUrl {
+            host: "github.com".to_string(),
+            owner: parsed.owner,
+            repo: parsed.repo,
+        });
+    } else {
+        let (host, path) = trimmed.split_once('/')?;
+        if !host.contains('.') {
+            return None;
+        }
+        (host, path)
+    };
+
+    let path = path.split(['?', '#']).next()?;
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    let owner = segments.next()?;
+    let repo = segments.next()?;
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    let is_valid_host = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | ':'));
+    if !is_valid_host || !is_repo_segment(owner) || !is_repo_segment(repo) {
+        return None;
+    }
+    Some(ParsedGitUrl {
+        host: host.to_ascii_lowercase(),
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+    })
+}
+
+fn is_repo_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment != "."
+        && segment != ".."
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
 /// Base directory for cloned repos.
 pub fn get_clone_base_dir() -> PathBuf {
     let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
@@ -122,6 +210,17 @@ pub fn is_bare_git_repository(repo_path: &str) -> bool {
         .is_some_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "true")
 }
 
+/// Whether `repo_path` was cloned with limited history (`--depth`).
+pub fn is_shallow_repository(repo_path: &str) -> bool {
+    git_command()
+        .args(["rev-parse", "--is-shallow-repository"])
+        .current_dir(repo_path)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "true")
+}
+
 pub fn get_current_branch(repo_path: &str) -> Option<String> {
     let output = git_command()
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
@@ -157,6 +256,182 @@ pub fn list_local_branches(repo_path: &str) -> Vec<String> {
         .unwrap_or_default()
 }
 
+/// Remote used by branch operations when the caller does not name one.
+pub const DEFAULT_REMOTE: &str = "origin";
+
+/// A configured git remote.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRemote {
+    pub name: String,
+    pub fetch_url: Option<String>,
+    pub push_url: Option<String>,
+}
+
+/// Parse `git remote -v` output into one entry per remote.
+pub fn parse_git_remotes(output: &str) -> Vec<GitRemote> {
+    let mut remotes: Vec<GitRemote> = Vec::new();
+    for line in output.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(name), Some(url), Some(kind)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        let index = match remotes.iter().position(|remote| remote.name == name) {
+            Some(index) => index,
+            None => {
+                remotes.push(GitRemote {
+                    name: name.to_string(),
+                    fetch_url: None,
+                    push_url: None,
+                });
+                remotes.len() - 1
+            }
+        };
+        match kind {
+            "(fetch)" => remotes[index].fetch_url = Some(url.to_string()),
+            "(push)" => remotes[index].push_url = Some(url.to_string()),
+            _ => {}
+        }
+    }
+    remotes
+}
+
+pub fn list_remotes(repo_path: &str) -> Vec<GitRemote> {
+    git_output_in_repo(repo_path, &["remote", "-v"])
+        .map(|output| parse_git_remotes(&output))
+        .unwrap_or_default()
+}
+
+/// Ensure `remote` is a configured remote of `repo_path`.
+pub fn validate_remote(repo_path: &str, remote: &str) -> Result<(), String> {
+    if remote.trim().is_empty() || remote.starts_with('-') {
+        return Err(format!("Invalid remote name: '{remote}'"));
+    }
+    if list_remotes(repo_path)
+        .iter()
+        .any(|candidate| candidate.name == remote)
+    {
+        Ok(())
+    } else {
+        Err(format!("Remote '{remote}' not found"))
+    }
+}
+
+fn validate_remote_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && !name.starts_with('.')
+        && !name.ends_with('.')
+        && !name.contains("..")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid remote name: '{name}'"))
+    }
+}
+
+/// Accept the URL forms git can fetch from: `http(s)://`, `ssh://`,
+/// `git://`, `file://` and scp-like `user@host:path`.
+pub fn validate_remote_url(url: &str) -> Result<(), String> {
+    let url = url.trim();
+    let invalid = || Err(format!("Invalid remote URL: '{url}'"));
+    if url.is_empty() || url.starts_with('-') || url.chars().any(char::is_whitespace) {
+        return invalid();
+    }
+
+    if let Some((scheme, rest)) = url.split_once("://") {
+        let known = matches!(scheme, "http" | "https" | "ssh" | "git" | "file");
+        let has_target = if scheme == "file" {
+            rest.starts_with('/')
+        } else {
+            rest.split('/').next().is_some_and(|host| !host.is_empty()) && rest.contains('/')
+        };
+        return if known && has_target {
+            Ok(())
+        } else {
+            invalid()
+        };
+    }
+
+    // scp-like syntax: [user@]host:path
+    match url.split_once(':') {
+        Some((host, path))
+            if !host.is_empty() && !host.contains('/') && !path.is_empty() && host.len() > 1 =>
+        {
+            Ok(())
+        }
+        _ => invalid(),
+    }
+}
+
+/// `git remote add <name> <url>`.
+pub fn add_remote(repo_path: &str, name: &str, url: &str) -> Result<(), String> {
+    validate_remote_name(name)?;
+    validate_remote_url(url)?;
+    if list_remotes(repo_path)
+        .iter()
+        .any(|remote| remote.name == name)
+    {
+        return Err(format!("Remote '{name}' already exists"));
+    }
+
+    let output = git_command()
+        .args(["remote", "add", "--", name, url.trim()])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// `git remote remove <name>`; fails when the remote does not exist.
+pub fn remove_remote(repo_path: &str, name: &str) -> Result<(), String> {
+    validate_remote(repo_path, name)?;
+
+    let output = git_command()
+        .args(["remote", "remove", "--", name])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Remote-tracking branches of a single `remote`, without the `remote/` prefix.
+pub fn list_remote_branches_for(repo_path: &str, remote: &str) -> Vec<String> {
+    let prefix = format!("{remote}/");
+    git_command()
+        .args([
+            "branch",
+            "-r",
+            "--format=%(refname:short)",
+            "--list",
+            &format!("{remote}/*"),
+        ])
+        .current_dir(repo_path)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .map(|l| l.trim())
+                .filter(|l| !l.is_empty() && !l.ends_with("/HEAD") && *l != remote)
+                .map(|l| l.strip_prefix(&prefix).unwrap_or(l).to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 pub fn list_remote_branches(repo_path: &str) -> Vec<String> {
     git_command()
         .args(["branch", "-r", "--format=%(refname:short)"])
@@ -241,6 +516,16 @@ pub fn fetch_remote(repo_path: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Fetch and prune a single remote.
+pub fn fetch_from_remote(repo_path: &str, remote: &str) -> bool {
+    git_command()
+        .args(["fetch", "--prune", remote])
+        .current_dir(repo_path)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
 pub fn pull_branch(repo_path: &str) -> Result<(), String> {
     let output = git_command()
         .args(["pull", "--ff-only"])
@@ -263,6 +548,11 @@ pub struct BranchStatus {
 }
 
 pub fn get_branch_status(repo_path: &str, branch: &str) -> BranchStatus {
+    get_branch_status_for_remote(repo_path, branch, DEFAULT_REMOTE)
+}
+
+/// Ahead/behind counts of `branch` relative to `remote/branch`.
+pub fn get_branch_status_for_remote(repo_path: &str, branch: &str, remote: &str) -> BranchStatus {
     let mut result = BranchStatus {
         ahead: 0,
         behind: 0,
@@ -270,7 +560,7 @@ pub fn get_branch_status(repo_path: &str, branch: &str) -> BranchStatus {
     };
 
     // Build the range string separately to ensure proper handling of branch names with slashes
-    let range = format!("{branch}...origin/{branch}");
+    let range = format!("{branch}...{remote}/{branch}");
 
     if let Ok(o) = git_command()
         .args(["rev-list", "--left-right", "--count", &range])
@@ -1349,6 +1639,122 @@ pub fn get_commit_list(
     Ok(commits)
 }
 
+/// Default number of commits returned by `get_commit_log_with_files`.
+pub const DEFAULT_COMMIT_LOG_LIMIT: usize = 50;
+/// Upper bound on the number of commits returned by `get_commit_log_with_files`.
+pub const MAX_COMMIT_LOG_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Default)]
+pub struct CommitLogFilter {
+    pub limit: Option<usize>,
+    pub since: Option<String>,
+    pub author: Option<String>,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitLogFile {
+    pub path: String,
+    pub added: i32,
+    pub removed: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitLogEntry {
+    pub sha: String,
+    pub author: String,
+    pub time: String,
+    pub message: String,
+    pub files: Vec<CommitLogFile>,
+}
+
+fn parse_commit_log_with_files(output: &str) -> Vec<CommitLogEntry> {
+    output
+        .split('\u{001e}')
+        .map(str::trim)
+        .filter(|record| !record.is_empty())
+        .filter_map(|record| {
+            let (header, stats_section) = record.split_once('\u{001d}')?;
+            let parts: Vec<&str> = header.split('\u{001f}').collect();
+            if parts.len() < 4 {
+                return None;
+            }
+
+            let files = stats_section
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .filter_map(|line| {
+                    let mut stat_parts = line.splitn(3, '\t');
+                    let added = stat_parts.next()?;
+                    let removed = stat_parts.next()?;
+                    let path = stat_parts.next()?;
+                    Some(CommitLogFile {
+                        path: path.to_string(),
+                        added: added.parse::<i32>().unwrap_or(0),
+                        removed: removed.parse::<i32>().unwrap_or(0),
+                    })
+                })
+                .collect();
+
+            Some(CommitLogEntry {
+                sha: parts[0].trim().to_string(),
+                author: parts[1].trim().to_string(),
+                time: parts[2].trim().to_string(),
+                message: parts[3].trim().to_string(),
+                files,
+            })
+        })
+        .collect()
+}
+
+/// Commit history with per-file line counts, parsed from `git log --numstat`.
+pub fn get_commit_log_with_files(
+    repo_path: &str,
+    filter: &CommitLogFilter,
+) -> Result<Vec<CommitLogEntry>, String> {
+    let limit = filter
+        .limit
+        .unwrap_or(DEFAULT_COMMIT_LOG_LIMIT)
+        .clamp(1, MAX_COMMIT_LOG_LIMIT)
+        .to_string();
+    let mut args = vec![
+        "log".to_string(),
+        "--format=%x1e%H%x1f%an%x1f%aI%x1f%B%x1d".to_string(),
+        "--numstat".to_string(),
+        "--no-renames".to_string(),
+        "-n".to_string(),
+        limit,
+    ];
+    if let Some(since) = filter.since.as_deref().filter(|v| !v.trim().is_empty()) {
+        args.push(format!("--since={since}"));
+    }
+    if let Some(author) = filter.author.as_deref().filter(|v| !v.trim().is_empty()) {
+        args.push(format!("--author={author}"));
+    }
+    if let Some(path) = filter.path.as_deref().filter(|v| !v.trim().is_empty()) {
+        validate_git_paths(&[path.to_string()])?;
+        args.push("--".to_string());
+        args.push(path.to_string());
+    }
+
+    let output = git_command()
+        .args(&args)
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    Ok(parse_commit_log_with_files(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RepoStatus {
@@ -1578,6 +1984,117 @@ pub fn get_repo_changes(repo_path: &str) -> RepoChanges {
     }
 }
 
+/// `git status` split into what is staged, what is only in the working tree
+/// and what is untracked, with the branch's position against its upstream.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuredGitStatus {
+    /// Current branch; `None` on a detached HEAD.
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: i32,
+    pub behind: i32,
+    pub staged: Vec<GitFileChange>,
+    pub unstaged: Vec<GitFileChange>,
+    pub untracked: Vec<String>,
+    pub conflicted: Vec<String>,
+    pub dirty: bool,
+}
+
+pub fn get_structured_status(repo_path: &str) -> Result<StructuredGitStatus, String> {
+    let output = git_command()
+        .args(["status", "--porcelain=v1", "--branch", "-uall"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(parse_structured_status(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `git status --porcelain=v1 --branch` output.
+pub fn parse_structured_status(output: &str) -> StructuredGitStatus {
+    let mut status = StructuredGitStatus::default();
+    for line in output.lines().filter(|line| line.len() >= 3) {
+        if let Some(header) = line.strip_prefix("## ") {
+            parse_status_branch_header(header, &mut status);
+            continue;
+        }
+
+        let code = &line[0..2];
+        let raw_path = line[3..].trim();
+        if code == "!!" {
+            continue;
+        }
+        if code == "??" {
+            status.untracked.push(raw_path.to_string());
+            continue;
+        }
+        if map_porcelain_status(code) == FileChangeStatus::Conflicted {
+            status.conflicted.push(raw_path.to_string());
+            continue;
+        }
+
+        let mut chars = code.chars();
+        let index_status = chars.next().unwrap_or(' ');
+        let worktree_status = chars.next().unwrap_or(' ');
+        if index_status != ' ' {
+            status.staged.extend(parse_git_status_porcelain(&format!(
+                "{index_status}  {raw_path}"
+            )));
+        }
+        if worktree_status != ' ' {
+            // Renames are recorded in the index; the worktree side is the new path.
+            let path = raw_path
+                .split_once(" -> ")
+                .map_or(raw_path, |(_, to)| to)
+                .to_string();
+            status.unstaged.push(GitFileChange {
+                path,
+                previous_path: None,
+                status: map_porcelain_status(&format!(" {worktree_status}")),
+            });
+        }
+    }
+    status.dirty = !(status.staged.is_empty()
+        && status.unstaged.is_empty()
+        && status.untracked.is_empty()
+        && status.conflicted.is_empty());
+    status
+}
+
+/// Parse the `## branch...upstream [ahead N, behind M]` header line.
+fn parse_status_branch_header(header: &str, status: &mut StructuredGitStatus) {
+    if header.starts_with("HEAD (no branch)") {
+        return;
+    }
+    let header = header
+        .strip_prefix("No commits yet on ")
+        .or_else(|| header.strip_prefix("Initial commit on "))
+        .unwrap_or(header);
+    let (refs, tracking) = match header.split_once(" [") {
+        Some((refs, tracking)) => (refs, tracking.trim_end_matches(']')),
+        None => (header, ""),
+    };
+    match refs.split_once("...") {
+        Some((branch, upstream)) => {
+            status.branch = Some(branch.to_string());
+            status.upstream = Some(upstream.to_string());
+        }
+        None => status.branch = Some(refs.trim().to_string()),
+    }
+    for part in tracking.split(", ") {
+        if let Some(n) = part.strip_prefix("ahead ") {
+            status.ahead = n.parse().unwrap_or(0);
+        } else if let Some(n) = part.strip_prefix("behind ") {
+            status.behind = n.parse().unwrap_or(0);
+        }
+    }
+}
+
 fn has_git_ref(repo_path: &str, git_ref: &str) -> bool {
     git_command()
         .args(["rev-parse", "--verify", git_ref])
@@ -1871,6 +2388,161 @@ pub fn get_repo_commit_diff(repo_path: &str, sha: &str) -> RepoCommitDiff {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeDiffCommit {
+    pub sha: String,
+    pub short_sha: String,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeDiff {
+    pub base: String,
+    pub head: String,
+    pub commits: Vec<RangeDiffCommit>,
+    pub files: Vec<RepoFileDiff>,
+    pub additions: i32,
+    pub deletions: i32,
+    /// Files whose patch was cut short (or dropped) to stay under the size cap.
+    pub truncated_files: Vec<String>,
+}
+
+/// Changes on `head` since it forked from `base` (`git diff base...head`),
+/// split into per-file patches. Once the patches add up to `max_patch_bytes`
+/// the rest are cut at a line boundary; line counts stay exact.
+pub fn get_range_diff(
+    repo_path: &str,
+    base: &str,
+    head: &str,
+    max_patch_bytes: usize,
+) -> Result<RangeDiff, String> {
+    if [base, head]
+        .iter()
+        .any(|rev| rev.trim().is_empty() || rev.starts_with('-'))
+    {
+        return Err(format!("Invalid diff range: '{base}...{head}'"));
+    }
+
+    let commits = git_output_at_path(
+        Path::new(repo_path),
+        &[
+            "log",
+            "--format=%H%x09%h%x09%s",
+            &format!("{base}..{head}"),
+            "--",
+        ],
+    )?
+    .lines()
+    .filter_map(|line| {
+        let mut parts = line.splitn(3, '\t');
+        Some(RangeDiffCommit {
+            sha: parts.next()?.to_string(),
+            short_sha: parts.next()?.to_string(),
+            summary: parts.next().unwrap_or_default().to_string(),
+        })
+    })
+    .collect();
+
+    let patch = git_output_at_path(
+        Path::new(repo_path),
+        &[
+            "--no-pager",
+            "diff",
+            "--no-ext-diff",
+            "--find-renames",
+            "--unified=3",
+            &format!("{base}...{head}"),
+            "--",
+        ],
+    )?;
+
+    let mut files = Vec::new();
+    let mut truncated_files = Vec::new();
+    let (mut additions, mut deletions) = (0, 0);
+    let mut remaining = max_patch_bytes;
+    for chunk in split_patch_by_file(&patch) {
+        let mut file = parse_file_patch(chunk);
+        additions += file.additions;
+        deletions += file.deletions;
+        if file.patch.len() > remaining {
+            let cut = file.patch[..remaining].rfind('\n').map_or(0, |i| i + 1);
+            file.patch.truncate(cut);
+            truncated_files.push(file.path.clone());
+        }
+        remaining -= file.patch.len();
+        files.push(file);
+    }
+
+    Ok(RangeDiff {
+        base: base.to_string(),
+        head: head.to_string(),
+        commits,
+        files,
+        additions,
+        deletions,
+        truncated_files,
+    })
+}
+
+/// Split a multi-file unified diff at each `diff --git` header.
+fn split_patch_by_file(patch: &str) -> Vec<&str> {
+    let mut starts: Vec<usize> = patch
+        .match_indices("diff --git ")
+        .filter(|(index, _)| *index == 0 || patch.as_bytes()[index - 1] == b'\n')
+        .map(|(index, _)| index)
+        .collect();
+    starts.push(patch.len());
+    starts
+        .windows(2)
+        .map(|bounds| patch[bounds[0]..bounds[1]].trim_end_matches('\n'))
+        .collect()
+}
+
+fn parse_file_patch(chunk: &str) -> RepoFileDiff {
+    let mut status = FileChangeStatus::Modified;
+    let mut path = None;
+    let mut previous_path = None;
+    for line in chunk.lines() {
+        if line.starts_with("@@") {
+            break;
+        }
+        if line.starts_with("new file mode") {
+            status = FileChangeStatus::Added;
+        } else if line.starts_with("deleted file mode") {
+            status = FileChangeStatus::Deleted;
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            status = FileChangeStatus::Renamed;
+            previous_path = Some(from.to_string());
+        } else if let Some(to) = line.strip_prefix("rename to ") {
+            path = Some(to.to_string());
+        } else if let Some(to) = line.strip_prefix("+++ b/") {
+            path = Some(to.to_string());
+        } else if let Some(from) = line.strip_prefix("--- a/") {
+            path.get_or_insert_with(|| from.to_string());
+        }
+    }
+    let path = path.unwrap_or_else(|| {
+        // Binary or mode-only changes have no ---/+++ lines.
+        chunk
+            .lines()
+            .next()
+            .and_then(|header| header.rsplit_once(" b/"))
+            .map(|(_, to)| to.to_string())
+            .unwrap_or_default()
+    });
+    let (additions, deletions) = count_diff_patch_lines(chunk);
+    RepoFileDiff {
+        path,
+        status,
+        previous_path,
+        patch: chunk.to_string(),
+        additions,
+        deletions,
+    }
+}
+
 fn git_output_at_path(repo_root: &Path, args: &[&str]) -> Result<String, String> {
     let output = git_command()
         .args(args)
@@ -2160,6 +2832,167 @@ fn load_changed_files_for_commit(
     Ok(files)
 }
 
+/// Largest file (in bytes) that `get_file_blame` will annotate.
+pub const MAX_BLAME_FILE_BYTES: u64 = 1024 * 1024;
+/// Maximum number of lines returned by `get_file_blame`.
+pub const MAX_BLAME_LINES: usize = 5000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlameLine {
+    pub line: u32,
+    pub commit: String,
+    pub author: String,
+    pub time: String,
+    pub summary: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileBlame {
+    pub file: String,
+    pub lines: Vec<BlameLine>,
+    pub total_lines: usize,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct BlameCommitMeta {
+    author: String,
+    time: String,
+    summary: String,
+}
+
+/// Parse `git blame --porcelain` output into per-line provenance records.
+///
+/// Porcelain output only repeats commit metadata the first time a commit is
+/// seen, so metadata is cached by sha and reused for later lines.
+pub fn parse_git_blame_porcelain(output: &str) -> Vec<BlameLine> {
+    let mut commits: HashMap<String, BlameCommitMeta> = HashMap::new();
+    let mut lines = Vec::new();
+    let mut current: Option<(String, u32)> = None;
+
+    for raw in output.lines() {
+        if let Some(content) = raw.strip_prefix('\t') {
+            if let Some((sha, line)) = current.take() {
+                let meta = commits.get(&sha).cloned().unwrap_or_default();
+                lines.push(BlameLine {
+                    line,
+                    commit: sha,
+                    author: meta.author,
+                    time: meta.time,
+                    summary: meta.summary,
+                    content: content.to_string(),
+                });
+            }
+            continue;
+        }
+
+        if current.is_none() {
+            let mut parts = raw.split(' ');
+            let sha = parts.next().unwrap_or_default();
+            let final_line = parts.nth(1).and_then(|value| value.parse::<u32>().ok());
+            if sha.len() == 40 && sha.chars().all(|c| c.is_ascii_hexdigit()) {
+                if let Some(final_line) = final_line {
+                    commits.entry(sha.to_string()).or_default();
+                    current = Some((sha.to_string(), final_line));
+                }
+            }
+            continue;
+        }
+
+        let Some((sha, _)) = current.as_ref() else {
+            continue;
+        };
+        let meta = commits.entry(sha.clone()).or_default();
+        if let Some(author) = raw.strip_prefix("author ") {
+            meta.author = author.to_string();
+        } else if let Some(time) = raw.strip_prefix("author-time ") {
+            meta.time = time
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default();
+        } else if let Some(summary) = raw.strip_prefix("summary ") {
+            meta.summary = summary.to_string();
+        }
+    }
+
+    lines
+}
+
+/// Run `git blame --porcelain` for a single file inside `repo_path`.
+///
+/// `file` must be relative and stay within the repository; files larger than
+/// `MAX_BLAME_FILE_BYTES` are rejected and only the first `MAX_BLAME_LINES`
+/// lines are annotated.
+pub fn get_file_blame(repo_path: &str, file: &str) -> Result<FileBlame, String> {
+    validate_git_paths(&[file.to_string()])?;
+
+    let repo_root = Path::new(repo_path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve repository path: {e}"))?;
+    let full_path = repo_root
+        .join(file)
+        .canonicalize()
+        .map_err(|_| format!("File not found: {file}"))?;
+    if !full_path.starts_with(&repo_root) {
+        return Err(format!(
+            "File paths must stay within the repository root: {file}"
+        ));
+    }
+    if !full_path.is_file() {
+        return Err(format!("File not found: {file}"));
+    }
+
+    let size = std::fs::metadata(&full_path)
+        .map_err(|e| format!("Failed to read file metadata: {e}"))?
+        .len();
+    if size > MAX_BLAME_FILE_BYTES {
+        return Err(format!(
+            "File is too large to blame ({size} bytes, limit {MAX_BLAME_FILE_BYTES})"
+        ));
+    }
+
+    let total_lines = std::fs::read(&full_path)
+        .map(|bytes| {
+            let newlines = bytes.iter().filter(|b| **b == b'\n').count();
+            if bytes.last().is_some_and(|b| *b != b'\n') {
+                newlines + 1
+            } else {
+                newlines
+            }
+        })
+        .map_err(|e| format!("Failed to read file: {e}"))?;
+    let truncated = total_lines > MAX_BLAME_LINES;
+
+    let range = format!("1,{MAX_BLAME_LINES}");
+    let mut args = vec!["blame", "--porcelain"];
+    if truncated {
+        args.extend(["-L", range.as_str()]);
+    }
+    args.extend(["--", file]);
+
+    let output = git_command()
+        .args(&args)
+        .current_dir(&repo_root)
+        .output()
+        .map_err(|e| format!("Failed to run git blame: {e}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    Ok(FileBlame {
+        file: file.to_string(),
+        lines: parse_git_blame_porcelain(&String::from_utf8_lossy(&output.stdout)),
+        total_lines,
+        truncated,
+    })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ClonedRepoInfo {
@@ -2320,7 +3153,7 @@ fn parse_discovered_skill(path: &Path) -> Option<DiscoveredSkill> {
 
 #[cfg(test)]
 mod status_tests {
-    use super::{parse_git_status_porcelain, FileChangeStatus};
+    use super::{parse_git_status_porcelain, parse_structured_status, FileChangeStatus};
 
     #[test]
     fn parse_git_status_porcelain_maps_statuses() {
@@ -2337,6 +3170,43 @@ mod status_tests {
         assert_eq!(files[4].status, FileChangeStatus::Untracked);
         assert_eq!(files[5].status, FileChangeStatus::Conflicted);
     }
+
+    #[test]
+    fn parse_structured_status_splits_index_and_worktree() {
+        let output = "## feature/x...origin/feature/x [ahead 2, behind 1]\nMM src/app.ts\nA  src/new.ts\nR  src/was.ts -> src/now.ts\n D src/gone.ts\n?? scratch.txt\nUU merge.txt\n";
+        let status = parse_structured_status(output);
+
+        assert_eq!(status.branch.as_deref(), Some("feature/x"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/feature/x"));
+        assert_eq!((status.ahead, status.behind), (2, 1));
+        let staged: Vec<&str> = status.staged.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(staged, ["src/app.ts", "src/new.ts", "src/now.ts"]);
+        assert_eq!(
+            status.staged[2].previous_path.as_deref(),
+            Some("src/was.ts")
+        );
+        let unstaged: Vec<&str> = status.unstaged.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(unstaged, ["src/app.ts", "src/gone.ts"]);
+        assert_eq!(status.unstaged[1].status, FileChangeStatus::Deleted);
+        assert_eq!(status.untracked, ["scratch.txt"]);
+        assert_eq!(status.conflicted, ["merge.txt"]);
+        assert!(status.dirty);
+    }
+
+    #[test]
+    fn parse_structured_status_handles_clean_and_detached_heads() {
+        let clean = parse_structured_status("## main\n");
+        assert_eq!(clean.branch.as_deref(), Some("main"));
+        assert_eq!(clean.upstream, None);
+        assert!(!clean.dirty);
+
+        let detached = parse_structured_status("## HEAD (no branch)\n");
+        assert_eq!(detached.branch, None);
+
+        let unborn = parse_structured_status("## No commits yet on main\n?? a.txt\n");
+        assert_eq!(unborn.branch.as_deref(), Some("main"));
+        assert!(unborn.dirty);
+    }
 }
 
 /// Extract YAML frontmatter from between `---` delimiters.
@@ -2595,6 +3465,247 @@ mod tests {
         assert!(parse_github_url(r"C:\tmp\repo").is_none());
     }
 
+    #[test]
+    fn parse_git_url_supports_other_hosts_and_ssh_forms() {
+        for (url, host, owner, repo) in [
+            (
+                "https://gitlab.com/group/project.git",
+                "gitlab.com",
+                "group",
+                "project",
+            ),
+            (
+                "https://GitLab.com/group/project/-/tree/main",
+                "gitlab.com",
+                "group",
+                "project",
+            ),
+            (
+                "git@gitlab.com:group/my.project.git",
+                "gitlab.com",
+                "group",
+                "my.project",
+            ),
+            (
+                "ssh://git@bitbucket.org/team/repo.git",
+                "bitbucket.org",
+                "team",
+                "repo",
+            ),
+            (
+                "ssh://git@git.example.com:2222/team/repo.git",
+                "git.example.com",
+                "team",
+                "repo",
+            ),
+            (
+                "https://token@git.example.com:8443/team/repo",
+                "git.example.com:8443",
+                "team",
+                "repo",
+            ),
+            (
+                "git.example.com/team/repo",
+                "git.example.com",
+                "team",
+                "repo",
+            ),
+            (
+                "https://github.com/phodal/routa.js",
+                "github.com",
+                "phodal",
+                "routa.js",
+            ),
+            ("phodal/routa-js", "github.com", "phodal", "routa-js"),
+        ] {
+            let parsed = parse_git_url(url).unwrap_or_else(|| panic!("{url} should parse"));
+            assert_eq!(
+                (
+                    parsed.host.as_str(),
+                    parsed.owner.as_str(),
+                    parsed.repo.as_str()
+                ),
+                (host, owner, repo),
+                "{url}"
+            );
+        }
+
+        let gitlab = parse_git_url("git@gitlab.com:group/project.git").unwrap();
+        assert!(!gitlab.is_github());
+        assert_eq!(gitlab.https_url(), "https://gitlab.com/group/project.git");
+        assert!(parse_git_url("phodal/routa-js").unwrap().is_github());
+
+        for url in [
+            r"C:\tmp\repo",
+            "C:/tmp/repo",
+            "ftp://example.com/team/repo",
+            "https://gitlab.com/group",
+            "https://gitlab.com/../repo",
+            "localhost/team/repo",
+        ] {
+            assert!(parse_git_url(url).is_none(), "{url}");
+        }
+    }
+
+    #[test]
+    fn parse_git_blame_porcelain_reuses_commit_metadata() {
+        let sha_a = "a".repeat(40);
+        let sha_b = "b".repeat(40);
+        let output = format!(
+            "{sha_a} 1 1 2\nauthor Alice\nauthor-mail <alice@example.com>\nauthor-time 1700000000\nauthor-tz +0000\nsummary feat: first\nfilename src/lib.rs\n\tfn one() {{}}\n\
+             {sha_a} 2 2\n\tfn two() {{}}\n\
+             {sha_b} 3 3 1\nauthor Bob\nauthor-time 1700003600\nsummary fix: third\nprevious {sha_a} src/lib.rs\nfilename src/lib.rs\n\tfn three() {{}}\n"
+        );
+
+        let lines = parse_git_blame_porcelain(&output);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].line, 1);
+        assert_eq!(lines[0].author, "Alice");
+        assert_eq!(lines[0].summary, "feat: first");
+        assert_eq!(lines[0].time, "2023-11-14T22:13:20+00:00");
+        assert_eq!(lines[1].commit, sha_a);
+        assert_eq!(lines[1].author, "Alice");
+        assert_eq!(lines[1].content, "fn two() {}");
+        assert_eq!(lines[2].commit, sha_b);
+        assert_eq!(lines[2].summary, "fix: third");
+    }
+
+    #[test]
+    fn parse_commit_log_with_files_reads_numstat_sections() {
+        let output = "\u{1e}abc123\u{1f}Alice\u{1f}2024-01-02T03:04:05+00:00\u{1f}feat: add parser\n\nbody line\n\u{1d}\n\n3\t1\tsrc/lib.rs\n-\t-\tassets/logo.png\n\
+                      \u{1e}def456\u{1f}Bob\u{1f}2024-01-01T00:00:00+00:00\u{1f}chore: init\u{1d}\n\n10\t0\tREADME.md\n";
+
+        let commits = parse_commit_log_with_files(output);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].sha, "abc123");
+        assert_eq!(commits[0].author, "Alice");
+        assert_eq!(commits[0].message, "feat: add parser\n\nbody line");
+        assert_eq!(
+            commits[0].files,
+            vec![
+                CommitLogFile {
+                    path: "src/lib.rs".to_string(),
+                    added: 3,
+                    removed: 1,
+                },
+                CommitLogFile {
+                    path: "assets/logo.png".to_string(),
+                    added: 0,
+                    removed: 0,
+                },
+            ]
+        );
+        assert_eq!(commits[1].files.len(), 1);
+        assert_eq!(commits[1].files[0].added, 10);
+    }
+
+    #[test]
+    fn parse_git_remotes_groups_fetch_and_push_urls() {
+        let output = "origin\tgit@github.com:me/repo.git (fetch)\n\
+                      origin\tgit@github.com:me/repo.git (push)\n\
+                      upstream\thttps://github.com/org/repo.git (fetch)\n\
+                      upstream\tno_push (push)\n";
+
+        let remotes = parse_git_remotes(output);
+        assert_eq!(remotes.len(), 2);
+        assert_eq!(remotes[0].name, "origin");
+        assert_eq!(
+            remotes[0].fetch_url.as_deref(),
+            Some("git@github.com:me/repo.git")
+        );
+        assert_eq!(remotes[1].name, "upstream");
+        assert_eq!(
+            remotes[1].fetch_url.as_deref(),
+            Some("https://github.com/org/repo.git")
+        );
+        assert_eq!(remotes[1].push_url.as_deref(), Some("no_push"));
+    }
+
+    #[test]
+    fn validate_remote_url_accepts_git_url_forms() {
+        for url in [
+            "https://github.com/org/repo.git",
+            "ssh://git@example.com/org/repo.git",
+            "git://example.com/repo",
+            "file:///srv/git/repo.git",
+            "git@github.com:org/repo.git",
+        ] {
+            assert!(validate_remote_url(url).is_ok(), "{url} should be valid");
+        }
+        for url in [
+            "",
+            "not a url",
+            "--upload-pack=evil",
+            "ftp://example.com/repo",
+            "https://",
+            "C:\\repo",
+        ] {
+            assert!(validate_remote_url(url).is_err(), "{url} should be invalid");
+        }
+    }
+
+    #[test]
+    fn get_file_blame_rejects_paths_outside_repo() {
+        let temp = tempdir().unwrap();
+        let repo = temp.path().to_string_lossy().to_string();
+
+        assert!(get_file_blame(&repo, "../etc/passwd").is_err());
+        assert!(get_file_blame(&repo, "/etc/passwd").is_err());
+        assert!(get_file_blame(&repo, "missing.txt").is_err());
+    }
+
+    #[test]
+    fn get_range_diff_splits_files_and_caps_patch_size() {
+        let temp = tempdir().unwrap();
+        let repo = temp.path().to_string_lossy().to_string();
+        let git = |args: &[&str]| {
+            let status = git_command()
+                .args(args)
+                .current_dir(temp.path())
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {args:?} failed");
+        };
+        git(&["init", "--quiet", "--initial-branch=main"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["config", "user.name", "Test"]);
+        fs::write(temp.path().join("keep.txt"), "one\n").unwrap();
+        fs::write(temp.path().join("gone.txt"), "bye\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "--quiet", "-m", "base"]);
+
+        git(&["checkout", "--quiet", "-b", "issue/abc"]);
+        fs::write(temp.path().join("keep.txt"), "one\ntwo\n").unwrap();
+        fs::remove_file(temp.path().join("gone.txt")).unwrap();
+        let big: String = (0..200).map(|i| format!("line {i}\n")).collect();
+        fs::write(temp.path().join("new.txt"), big).unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "--quiet", "-m", "task work"]);
+
+        let diff = get_range_diff(&repo, "main", "issue/abc", 1_000_000).unwrap();
+        assert_eq!(diff.commits.len(), 1);
+        assert_eq!(diff.commits[0].summary, "task work");
+        let status_of = |path: &str| {
+            diff.files
+                .iter()
+                .find(|file| file.path == path)
+                .map(|file| file.status.clone())
+        };
+        assert_eq!(status_of("keep.txt"), Some(FileChangeStatus::Modified));
+        assert_eq!(status_of("gone.txt"), Some(FileChangeStatus::Deleted));
+        assert_eq!(status_of("new.txt"), Some(FileChangeStatus::Added));
+        assert_eq!(diff.additions, 201);
+        assert_eq!(diff.deletions, 1);
+        assert!(diff.truncated_files.is_empty());
+
+        let capped = get_range_diff(&repo, "main", "issue/abc", 400).unwrap();
+        assert_eq!(capped.additions, 201);
+        assert!(capped.files.iter().map(|f| f.patch.len()).sum::<usize>() <= 400);
+        assert!(capped.truncated_files.contains(&"new.txt".to_string()));
+
+        assert!(get_range_diff(&repo, "--output=x", "issue/abc", 100).is_err());
+    }
+
     #[test]
     fn repo_dir_name_conversions_are_stable() {
         let dir = repo_to_dir_name("org", "project");
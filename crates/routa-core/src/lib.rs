@@ -14,10 +14,15 @@
 //! - `axum` — Enables `IntoResponse` impl on `ServerError` for use in axum handlers.
 
 pub mod acp;
+pub mod archive;
+pub mod client_gate;
+pub mod clone_policy;
+pub mod clone_tracker;
 pub mod codeowners;
 pub mod db;
 pub mod error;
 pub mod events;
+pub mod file_types;
 pub mod git;
 pub mod harness;
 pub mod harness_automation;
@@ -26,8 +31,11 @@ pub mod kanban;
 pub mod mcp;
 pub mod models;
 pub mod orchestration;
+pub mod role_providers;
 pub mod rpc;
 pub mod sandbox;
+pub mod session_sweep;
+pub mod settings;
 pub mod shell_env;
 pub mod skills;
 pub mod spec_detector;
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::validation::ValidationError;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum AgentRole {
     #[serde(rename = "ROUTA")]
@@ -122,6 +124,31 @@ pub struct Agent {
 }
 
 impl Agent {
+    /// Like [`Agent::new`], but rejects an empty `name`.
+    pub fn try_new(
+        id: String,
+        name: String,
+        role: AgentRole,
+        workspace_id: String,
+        parent_id: Option<String>,
+        model_tier: Option<ModelTier>,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<Self, ValidationError> {
+        let mut errors = ValidationError::new();
+        errors.require_non_empty("name", &name);
+        errors.into_result(|| {
+            Self::new(
+                id,
+                name,
+                role,
+                workspace_id,
+                parent_id,
+                model_tier,
+                metadata,
+            )
+        })
+    }
+
     pub fn new(
         id: String,
         name: String,
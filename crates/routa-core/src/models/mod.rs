@@ -11,6 +11,7 @@ pub mod message;
 pub mod note;
 pub mod schedule;
 pub mod task;
+pub mod validation;
 pub mod workspace;
 pub mod worktree;
 
@@ -26,5 +27,6 @@ pub use message::*;
 pub use note::*;
 pub use schedule::*;
 pub use task::*;
+pub use validation::*;
 pub use workspace::*;
 pub use worktree::*;
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::task::TaskStatus;
+use super::validation::ValidationError;
 
 pub const SPEC_NOTE_ID: &str = "spec";
 
@@ -99,6 +100,22 @@ impl Note {
         }
     }
 
+    /// Like [`Note::new_with_session`], but rejects an empty `title`.
+    pub fn try_new_with_session(
+        id: String,
+        title: String,
+        content: String,
+        workspace_id: String,
+        session_id: Option<String>,
+        metadata: Option<NoteMetadata>,
+    ) -> Result<Self, ValidationError> {
+        let mut errors = ValidationError::new();
+        errors.require_non_empty("title", &title);
+        errors.into_result(|| {
+            Self::new_with_session(id, title, content, workspace_id, session_id, metadata)
+        })
+    }
+
     /// Create a new note with session ID
     pub fn new_with_session(
         id: String,
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 use super::artifact::Artifact;
+use super::validation::ValidationError;
 
 /// Transport protocol for task sessions
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -519,6 +520,41 @@ pub struct Task {
 }
 
 impl Task {
+    /// Like [`Task::new`], but rejects an empty `title` or `objective`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        id: String,
+        title: String,
+        objective: String,
+        workspace_id: String,
+        session_id: Option<String>,
+        scope: Option<String>,
+        acceptance_criteria: Option<Vec<String>>,
+        verification_commands: Option<Vec<String>>,
+        test_cases: Option<Vec<String>>,
+        dependencies: Option<Vec<String>>,
+        parallel_group: Option<String>,
+    ) -> Result<Self, ValidationError> {
+        let mut errors = ValidationError::new();
+        errors.require_non_empty("title", &title);
+        errors.require_non_empty("objective", &objective);
+        errors.into_result(|| {
+            Self::new(
+                id,
+                title,
+                objective,
+                workspace_id,
+                session_id,
+                scope,
+                acceptance_criteria,
+                verification_commands,
+                test_cases,
+                dependencies,
+                parallel_group,
+            )
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: String,
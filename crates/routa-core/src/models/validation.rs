@@ -0,0 +1,111 @@
+//! Field-level validation errors raised by model constructors.
+//!
+//! `try_new` constructors collect every failing field into a
+//! [`ValidationError`] so MCP and HTTP callers can report all problems at
+//! once instead of silently substituting placeholder values.
+
+use serde::Serialize;
+
+use crate::error::ServerError;
+
+/// JSON-RPC error code for invalid method parameters.
+pub const INVALID_PARAMS_CODE: i64 = -32602;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationError {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationError {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Record an error for `field` when `value` is empty or whitespace-only.
+    pub fn require_non_empty(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.push(field, format!("{field} is required"));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// `Ok(value)` when no field errors were recorded, otherwise `Err(self)`.
+    pub fn into_result<T>(self, value: impl FnOnce() -> T) -> Result<T, Self> {
+        if self.is_empty() {
+            Ok(value())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// JSON-RPC `error` object (`-32602`) carrying the per-field error list.
+    pub fn to_json_rpc_error(&self) -> serde_json::Value {
+        serde_json::json!({
+            "code": INVALID_PARAMS_CODE,
+            "message": self.to_string(),
+            "data": { "errors": self.errors },
+        })
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let details = self
+            .errors
+            .iter()
+            .map(|error| error.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+        write!(f, "Invalid params: {details}")
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl From<ValidationError> for ServerError {
+    fn from(error: ValidationError) -> Self {
+        ServerError::BadRequest(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_every_missing_field() {
+        let mut errors = ValidationError::new();
+        errors.require_non_empty("title", "  ");
+        errors.require_non_empty("objective", "");
+        errors.require_non_empty("scope", "ok");
+
+        assert_eq!(errors.errors.len(), 2);
+        assert_eq!(
+            errors.to_string(),
+            "Invalid params: title is required; objective is required"
+        );
+        let rpc = errors.to_json_rpc_error();
+        assert_eq!(rpc["code"].as_i64(), Some(-32602));
+        assert_eq!(
+            rpc["data"]["errors"][1]["field"].as_str(),
+            Some("objective")
+        );
+        assert!(errors.into_result(|| ()).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use super::validation::ValidationError;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -51,3 +53,192 @@ impl Workspace {
         }
     }
 }
+
+/// `metadata` key holding the working directory new sessions default to.
+pub const DEFAULT_CWD_SETTING: &str = "defaultCwd";
+/// `metadata` key holding the provider new sessions default to.
+pub const DEFAULT_PROVIDER_SETTING: &str = "defaultProvider";
+/// `metadata` key holding extra session environment, as a JSON object.
+pub const ENV_SETTING: &str = "env";
+
+/// Per-workspace behavior settings, kept in `Workspace::metadata` so they
+/// need no schema change. The active branch lives on the workspace's
+/// codebase and is changed with `set_workspace_branch`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSettings {
+    pub default_cwd: Option<String>,
+    pub default_provider: Option<String>,
+    pub env: BTreeMap<String, String>,
+}
+
+impl WorkspaceSettings {
+    /// Settings stored in `workspace`; an unreadable `env` is treated as empty.
+    pub fn from_workspace(workspace: &Workspace) -> Self {
+        let non_empty = |key: &str| {
+            workspace
+                .metadata
+                .get(key)
+                .filter(|value| !value.trim().is_empty())
+                .cloned()
+        };
+        Self {
+            default_cwd: non_empty(DEFAULT_CWD_SETTING),
+            default_provider: non_empty(DEFAULT_PROVIDER_SETTING),
+            env: workspace
+                .metadata
+                .get(ENV_SETTING)
+                .and_then(|env| serde_json::from_str(env).ok())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Write these settings into `workspace.metadata`, dropping unset ones.
+    pub fn store(&self, workspace: &mut Workspace) {
+        let mut set = |key: &str, value: Option<String>| match value {
+            Some(value) => {
+                workspace.metadata.insert(key.to_string(), value);
+            }
+            None => {
+                workspace.metadata.remove(key);
+            }
+        };
+        set(DEFAULT_CWD_SETTING, self.default_cwd.clone());
+        set(DEFAULT_PROVIDER_SETTING, self.default_provider.clone());
+        set(
+            ENV_SETTING,
+            (!self.env.is_empty()).then(|| serde_json::to_string(&self.env).unwrap_or_default()),
+        );
+    }
+
+    /// Apply a partial settings object. A `null` clears a setting; `env` is
+    /// merged key by key, with `null` values removing a variable. Unknown
+    /// keys and malformed values are all reported together.
+    pub fn apply_patch(&mut self, patch: &serde_json::Value) -> Result<(), ValidationError> {
+        let mut errors = ValidationError::new();
+        let Some(patch) = patch.as_object() else {
+            errors.push("settings", "settings must be an object");
+            return Err(errors);
+        };
+        let mut updated = self.clone();
+        for (key, value) in patch {
+            match key.as_str() {
+                DEFAULT_CWD_SETTING => match optional_string(value) {
+                    Ok(Some(cwd)) if !std::path::Path::new(&cwd).is_absolute() => {
+                        errors.push(key, "defaultCwd must be an absolute path");
+                    }
+                    Ok(cwd) => updated.default_cwd = cwd,
+                    Err(message) => errors.push(key, format!("defaultCwd {message}")),
+                },
+                DEFAULT_PROVIDER_SETTING => match optional_string(value) {
+                    Ok(Some(provider)) if provider.chars().any(char::is_whitespace) => {
+                        errors.push(key, "defaultProvider must not contain whitespace");
+                    }
+                    Ok(provider) => updated.default_provider = provider,
+                    Err(message) => errors.push(key, format!("defaultProvider {message}")),
+                },
+                ENV_SETTING => {
+                    if value.is_null() {
+                        updated.env.clear();
+                        continue;
+                    }
+                    let Some(vars) = value.as_object() else {
+                        errors.push(key, "env must be an object of strings");
+                        continue;
+                    };
+                    for (name, value) in vars {
+                        if !is_env_var_name(name) {
+                            errors.push(key, format!("Invalid environment variable name: {name}"));
+                        } else if value.is_null() {
+                            updated.env.remove(name);
+                        } else if let Some(value) = value.as_str() {
+                            updated.env.insert(name.clone(), value.to_string());
+                        } else {
+                            errors.push(key, format!("env.{name} must be a string or null"));
+                        }
+                    }
+                }
+                _ => errors.push(key, format!("Unknown workspace setting: {key}")),
+            }
+        }
+        errors.into_result(|| *self = updated)
+    }
+}
+
+/// A trimmed string, `None` for null or blank, or an error for other types.
+fn optional_string(value: &serde_json::Value) -> Result<Option<String>, &'static str> {
+    match value {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(value) => {
+            let value = value.trim();
+            Ok((!value.is_empty()).then(|| value.to_string()))
+        }
+        _ => Err("must be a string or null"),
+    }
+}
+
+fn is_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip_through_metadata() {
+        let mut workspace = Workspace::new("ws".to_string(), "Workspace".to_string(), None);
+        let mut settings = WorkspaceSettings::from_workspace(&workspace);
+        assert_eq!(settings, WorkspaceSettings::default());
+
+        settings
+            .apply_patch(&serde_json::json!({
+                "defaultCwd": "/repos/app",
+                "defaultProvider": "claude",
+                "env": { "RUST_LOG": "debug", "API_URL": "http://localhost" }
+            }))
+            .unwrap();
+        settings.store(&mut workspace);
+        assert_eq!(workspace.metadata["defaultProvider"], "claude");
+
+        let mut reloaded = WorkspaceSettings::from_workspace(&workspace);
+        assert_eq!(reloaded, settings);
+
+        reloaded
+            .apply_patch(&serde_json::json!({
+                "defaultProvider": null,
+                "env": { "RUST_LOG": null }
+            }))
+            .unwrap();
+        reloaded.store(&mut workspace);
+        assert!(!workspace.metadata.contains_key("defaultProvider"));
+        let reloaded = WorkspaceSettings::from_workspace(&workspace);
+        assert_eq!(reloaded.default_cwd.as_deref(), Some("/repos/app"));
+        assert_eq!(reloaded.env.keys().collect::<Vec<_>>(), ["API_URL"]);
+    }
+
+    #[test]
+    fn invalid_patches_report_every_field_and_change_nothing() {
+        let mut settings = WorkspaceSettings {
+            default_provider: Some("claude".to_string()),
+            ..WorkspaceSettings::default()
+        };
+        let error = settings
+            .apply_patch(&serde_json::json!({
+                "defaultCwd": "relative/dir",
+                "defaultProvider": "codex",
+                "env": { "1BAD": "x", "OK": 1 },
+                "colour": "blue"
+            }))
+            .unwrap_err();
+        let fields: Vec<_> = error.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields.len(), 4, "{error}");
+        assert!(fields.contains(&"colour"));
+        assert_eq!(settings.default_provider.as_deref(), Some("claude"));
+        assert!(settings.apply_patch(&serde_json::json!("nope")).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Default provider per agent role, for `session/new` requests that name a
+//! `role` but no `provider`.
+//!
+//! A provider in the request always wins; roles without a mapping fall back
+//! to the global default provider. The mapping is parsed from
+//! `ROUTA_ROLE_PROVIDERS` by `crate::settings`.
+
+use std::collections::BTreeMap;
+
+use crate::models::agent::AgentRole;
+
+/// Where a session's provider came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderSource {
+    /// The request named a provider.
+    Request,
+    /// The role mapping supplied it.
+    Role,
+    /// Neither did; the global default applies.
+    Default,
+}
+
+impl ProviderSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Request => "request",
+            Self::Role => "role",
+            Self::Default => "default",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoleProviderMap {
+    /// Provider keyed by role name (`AgentRole::as_str`).
+    providers: BTreeMap<&'static str, String>,
+}
+
+impl RoleProviderMap {
+    /// Parse `ROLE=provider` pairs, skipping malformed entries and unknown roles.
+    pub fn parse(value: &str) -> Self {
+        let mut providers = BTreeMap::new();
+        for pair in value
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let Some((role, provider)) = pair.split_once('=') else {
+                tracing::warn!("[RoleProviders] Ignoring malformed entry {:?}", pair);
+                continue;
+            };
+            let provider = provider.trim();
+            match AgentRole::from_str(&role.trim().to_ascii_uppercase()) {
+                Some(role) if !provider.is_empty() => {
+                    providers.insert(role.as_str(), provider.to_string());
+                }
+                Some(_) => tracing::warn!("[RoleProviders] Ignoring empty provider in {:?}", pair),
+                None => tracing::warn!("[RoleProviders] Ignoring unknown role in {:?}", pair),
+            }
+        }
+        Self { providers }
+    }
+
+    /// The provider configured for `role`, if any.
+    pub fn provider_for(&self, role: &str) -> Option<&str> {
+        self.providers
+            .get(role.to_ascii_uppercase().as_str())
+            .map(String::as_str)
+    }
+
+    /// Resolve a session's provider: `requested` if given, else the mapping
+    /// for `role`, else `None` (the global default).
+    pub fn resolve(
+        &self,
+        requested: Option<String>,
+        role: Option<&str>,
+    ) -> (Option<String>, ProviderSource) {
+        if let Some(provider) = requested {
+            return (Some(provider), ProviderSource::Request);
+        }
+        match role.and_then(|role| self.provider_for(role)) {
+            Some(provider) => (Some(provider.to_string()), ProviderSource::Role),
+            None => (None, ProviderSource::Default),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pairs_and_skips_bad_entries() {
+        let map = RoleProviderMap::parse(" gate=claude, CRAFTER = codex ,BOGUS=x,ROUTA=,nonsense");
+        assert_eq!(map.provider_for("GATE"), Some("claude"));
+        assert_eq!(map.provider_for("crafter"), Some("codex"));
+        assert_eq!(map.provider_for("ROUTA"), None);
+        assert_eq!(map.provider_for("DEVELOPER"), None);
+        assert!(RoleProviderMap::parse("").is_empty());
+    }
+
+    #[test]
+    fn explicit_provider_wins_over_role_mapping() {
+        let map = RoleProviderMap::parse("GATE=claude");
+        assert_eq!(
+            map.resolve(Some("opencode".into()), Some("GATE")),
+            (Some("opencode".into()), ProviderSource::Request)
+        );
+        assert_eq!(
+            map.resolve(None, Some("GATE")),
+            (Some("claude".into()), ProviderSource::Role)
+        );
+        assert_eq!(
+            map.resolve(None, Some("CRAFTER")),
+            (None, ProviderSource::Default)
+        );
+        assert_eq!(map.resolve(None, None), (None, ProviderSource::Default));
+    }
+}
@@ -49,6 +49,7 @@ impl From<ServerError> for RpcError {
             ServerError::Database(msg) => RpcError::Internal(msg),
             ServerError::Internal(msg) => RpcError::Internal(msg),
             ServerError::NotImplemented(msg) => RpcError::Internal(msg),
+            ServerError::Unauthorized(msg) => RpcError::BadRequest(msg),
         }
     }
 }
@@ -0,0 +1,49 @@
+//! Defaults shared by the background sweepers that prune idle MCP and ACP
+//! sessions; the configured values live in `crate::settings`.
+
+use std::time::Duration;
+
+/// Idle time after which an MCP session is evicted.
+pub const DEFAULT_MCP_SESSION_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Idle time after which an ACP session is pruned.
+pub const DEFAULT_ACP_SESSION_TTL: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// How often a sweeper for sessions with the given TTL runs: `configured`
+/// (`Settings::session_sweep_interval`) when set, otherwise a quarter of the
+/// TTL between 1 and 60 seconds.
+pub fn sweep_interval(configured: Option<Duration>, ttl: Duration) -> Duration {
+    match configured.filter(|interval| !interval.is_zero()) {
+        Some(interval) => interval,
+        None => (ttl / 4).clamp(Duration::from_secs(1), Duration::from_secs(60)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_defaults_to_a_clamped_quarter_of_the_ttl() {
+        assert_eq!(
+            sweep_interval(None, Duration::from_secs(2)),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            sweep_interval(None, Duration::from_secs(120)),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            sweep_interval(None, DEFAULT_ACP_SESSION_TTL),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            sweep_interval(Some(Duration::ZERO), Duration::from_secs(120)),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            sweep_interval(Some(Duration::from_secs(5)), DEFAULT_ACP_SESSION_TTL),
+            Duration::from_secs(5)
+        );
+    }
+}
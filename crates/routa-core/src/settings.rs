@@ -0,0 +1,574 @@
+//! Runtime settings read from `ROUTA_*` environment variables.
+//!
+//! Every tunable is parsed here. `AppStateInner::new` reads the environment
+//! once and passes the result down; code that runs without a state reads it
+//! when called, so variables loaded later (e.g. from a `.env` file) apply to
+//! anything built afterwards. Unset or unparseable values fall back to the
+//! defaults listed.
+//!
+//! Server:
+//!   - `ROUTA_MAX_PROMPT_BYTES` → largest joined `session/prompt` text (default 1 MiB)
+//!   - `ROUTA_ADMIN_TOKEN` → bearer token required by operator endpoints such
+//!     as `GET /api/mcp/sessions`, which are disabled while it is unset
+//!   - `ROUTA_FILE_SEARCH_DEFAULT_LIMIT` / `ROUTA_FILE_SEARCH_MAX_LIMIT` →
+//!     file search result counts (default 20 / 200; the default never exceeds
+//!     the max)
+//!   - `ROUTA_FILE_SEARCH_TIMEOUT_MS` → file search walks stop and return
+//!     partial results after this long (default 5000)
+//!   - `ROUTA_CLONE_ALLOWED_HOSTS` → comma-separated hosts the clone
+//!     endpoints accept (default: github.com, gitlab.com, bitbucket.org; `*`
+//!     allows any host); a name check only, allowed hosts are not resolved
+//!   - `ROUTA_CLONE_STRICT=1` → also resolve the host, reject private
+//!     addresses, and pin the clone to the address that was checked
+//!   - `ROUTA_MIN_CLIENT_VERSION` → comma-separated `name=version` minimums
+//!     checked at ACP/MCP `initialize`; a bare `version` applies to every
+//!     client without its own entry (e.g. `0.3.0,routa-desktop=1.2.0`)
+//!
+//! MCP:
+//!   - `ROUTA_MCP_ENABLED_TOOLS` / `ROUTA_MCP_DISABLED_TOOLS` → comma-separated
+//!     tool names; disabled wins over enabled
+//!   - `ROUTA_MCP_SKILL_TOOLS=1` → expose runnable skills as `skill_<name>` tools
+//!   - `ROUTA_MCP_SESSION_TTL_SECS` → idle time before an MCP session is
+//!     closed (default 30 minutes, `0` disables)
+//!   - `ROUTA_MCP_TOOL_CACHE_TTL_MS` → how long results of idempotent list
+//!     tools are cached (default 5000, `0` disables)
+//!
+//! Session sweeping:
+//!   - `ROUTA_SESSION_SWEEP_INTERVAL_SECS` → how often each idle-session
+//!     sweeper runs (default: a quarter of its TTL, between 1 and 60 seconds)
+//!
+//! ACP sessions:
+//!   - `ROUTA_ACP_SESSION_TTL_SECS` → idle time after which an ACP session's
+//!     agent process is killed and its buffers dropped (default 2 hours, `0`
+//!     disables)
+//!   - `ROUTA_ROLE_PROVIDERS` → comma-separated `ROLE=provider` pairs choosing
+//!     the provider for `session/new` requests that give a role but no
+//!     provider, e.g. `GATE=claude,CRAFTER=codex`; role names are
+//!     case-insensitive and unknown roles are ignored with a warning
+//!   - `ROUTA_ACP_HISTORY_MAX_TURNS` / `ROUTA_ACP_HISTORY_MAX_TOKENS` → keep at
+//!     most this many turns, or an estimated history size under this many
+//!     tokens, after a session's first turn (default: unbounded)
+//!   - `ROUTA_ACP_OUTPUT_STRIP_BOM=0` / `ROUTA_ACP_OUTPUT_NORMALIZE_EOL=0` →
+//!     keep byte-order marks / CRLF and CR line endings in collected output
+//!   - `ROUTA_ACP_TRANSCRIPT_MAX_BYTES` → size cap of each session's
+//!     transcript (default 4 MiB, `0` disables transcripts)
+//!   - `ROUTA_ACP_CANCEL_GRACE_MS` → how long a cancelled prompt may keep
+//!     running before its agent process is killed (default 5000)
+//!
+//! ACP agent processes:
+//!   - `ROUTA_ACP_WARM_POOL` → pre-spawned processes per provider as
+//!     `provider=size` pairs, e.g. `gemini=2,copilot=1` (default: none)
+//!   - `ROUTA_ACP_WARM_POOL_IDLE_SECS` → reap a provider's pool after this long
+//!     without a checkout (default 600)
+//!   - `ROUTA_ACP_PROVIDER_ARGS_<PROVIDER>` → args added to every launch of
+//!     one provider, whitespace-separated or as a JSON array of strings;
+//!     checked when a session starts
+//!   - `ROUTA_ACP_ENV_PASSTHROUGH` → comma-separated variable names passed
+//!     through to every agent in addition to its preset's own
+//!   - `ROUTA_ACP_MEMORY_LIMIT_MB[_<PROVIDER>]` → virtual address space cap
+//!     for every provider, or for one (Unix only, default 64 GiB, `0`
+//!     disables)
+//!   - `ROUTA_ACP_CPU_LIMIT_SECS[_<PROVIDER>]` → CPU time cap for every
+//!     provider, or for one, counted over all of a process's prompts (Unix
+//!     only, default none)
+//!
+//! `<PROVIDER>` is the provider id upper-cased with non-alphanumerics
+//! replaced by `_`, e.g. `ROUTA_ACP_MEMORY_LIMIT_MB_CODEX_ACP`.
+//!
+//! ACP installs:
+//!   - `ROUTA_ACP_DATA_DIR` → directory for installed agents, transcripts and
+//!     caches (default: `acp-agents` under the platform data directory)
+//!   - `ROUTA_ACP_DATA_DIR_STRICT=1` → refuse to start instead of falling back
+//!     to `~/.routa/acp-agents` when there is no platform data directory
+//!   - `ROUTA_ACP_VERIFY_INSTALL=1` → run installed binaries with `--version`
+//!     unless an install request says otherwise
+//!   - `ROUTA_ACP_ARCHIVE_CACHE=0` → don't keep downloaded archives for
+//!     reinstalls
+//!   - `ROUTA_ACP_ARCHIVE_CACHE_MAX_BYTES` → archive cache size cap (default
+//!     1 GiB, `0` disables the cache)
+//!   - `ROUTA_ACP_SKIP_EXECUTABLE_CHECK=1` → install executables whose format
+//!     is not recognized
+//!   - `ROUTA_ACP_REGISTRY_STRICT=1` → reject registries with an unsupported
+//!     schema version instead of warning
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::acp::archive_cache::DEFAULT_ARCHIVE_CACHE_MAX_BYTES;
+use crate::acp::process::DEFAULT_CANCEL_GRACE;
+use crate::acp::process_pool::{parse_pool_sizes, ProcessPoolConfig};
+use crate::acp::resource_limits::ResourceLimitConfig;
+use crate::acp::transcript::DEFAULT_TRANSCRIPT_MAX_BYTES;
+use crate::acp::{HistoryWindowPolicy, OutputNormalization};
+use crate::client_gate::ClientVersionGate;
+use crate::clone_policy::CloneHostPolicy;
+use crate::role_providers::RoleProviderMap;
+use crate::session_sweep::{DEFAULT_ACP_SESSION_TTL, DEFAULT_MCP_SESSION_TTL};
+use crate::state::{
+    FileSearchLimits, McpToolConfig, DEFAULT_MAX_PROMPT_BYTES, DEFAULT_MCP_TOOL_CACHE_TTL,
+};
+
+const MEMORY_LIMIT_VAR: &str = "ROUTA_ACP_MEMORY_LIMIT_MB";
+const CPU_LIMIT_VAR: &str = "ROUTA_ACP_CPU_LIMIT_SECS";
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub max_prompt_bytes: usize,
+    pub admin_token: Option<String>,
+    pub file_search_limits: FileSearchLimits,
+    pub clone_host_policy: CloneHostPolicy,
+    pub client_version_gate: ClientVersionGate,
+    /// Providers used for `session/new` requests that give a role but no provider.
+    pub role_providers: RoleProviderMap,
+    /// Initial MCP tool configuration; `AppStateInner::mcp_tool_config` holds
+    /// the runtime copy.
+    pub mcp_tools: McpToolConfig,
+    /// `None` keeps idle MCP sessions forever.
+    pub mcp_session_ttl: Option<Duration>,
+    /// `None` keeps idle ACP sessions forever.
+    pub acp_session_ttl: Option<Duration>,
+    /// `None` derives each sweeper's interval from its TTL.
+    pub session_sweep_interval: Option<Duration>,
+    /// Zero disables the MCP tool result cache.
+    pub mcp_tool_cache_ttl: Duration,
+    pub acp: AcpSettings,
+}
+
+/// Settings for ACP agents and their processes, passed to `AcpManager`.
+#[derive(Debug, Clone)]
+pub struct AcpSettings {
+    pub history_window: HistoryWindowPolicy,
+    pub output_normalization: OutputNormalization,
+    /// `None` disables session transcripts.
+    pub transcript_max_bytes: Option<u64>,
+    pub cancel_grace: Duration,
+    pub warm_pool: ProcessPoolConfig,
+    /// Raw `ROUTA_ACP_PROVIDER_ARGS_<PROVIDER>` values, keyed by provider
+    /// suffix; see `provider_args`.
+    pub provider_args: HashMap<String, String>,
+    pub env_passthrough: Vec<String>,
+    pub resource_limits: ResourceLimitConfig,
+    pub registry_strict: bool,
+    /// Overrides the ACP base directory; see `AcpPaths`.
+    pub data_dir: Option<PathBuf>,
+    pub data_dir_strict: bool,
+    pub verify_install: bool,
+    /// Reject installed executables that are not a recognized binary or script.
+    pub check_executable_format: bool,
+    /// `None` disables the archive cache.
+    pub archive_cache_max_bytes: Option<u64>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::read(&Vars::default())
+    }
+}
+
+impl Settings {
+    pub fn from_env() -> Self {
+        Self::read(&Vars::new(routa_vars()))
+    }
+
+    /// Settings from `(name, value)` pairs; names not listed in the module
+    /// docs are ignored.
+    pub fn from_vars<K, V>(vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<OsString>,
+    {
+        Self::read(&Vars::new(vars))
+    }
+
+    fn read(vars: &Vars) -> Self {
+        let file_search_defaults = FileSearchLimits::default();
+        let max_limit = vars
+            .positive("ROUTA_FILE_SEARCH_MAX_LIMIT")
+            .unwrap_or(file_search_defaults.max_limit);
+        let file_search_limits = FileSearchLimits {
+            default_limit: vars
+                .positive("ROUTA_FILE_SEARCH_DEFAULT_LIMIT")
+                .unwrap_or(file_search_defaults.default_limit)
+                .min(max_limit),
+            max_limit,
+            walk_timeout: vars
+                .positive("ROUTA_FILE_SEARCH_TIMEOUT_MS")
+                .map_or(file_search_defaults.walk_timeout, Duration::from_millis),
+        };
+
+        let clone_hosts: Vec<String> = vars
+            .list("ROUTA_CLONE_ALLOWED_HOSTS")
+            .map(|host| host.to_ascii_lowercase())
+            .collect();
+        let clone_host_policy = CloneHostPolicy {
+            allowed_hosts: if clone_hosts.iter().any(|host| host == "*") {
+                None
+            } else if clone_hosts.is_empty() {
+                CloneHostPolicy::default().allowed_hosts
+            } else {
+                Some(clone_hosts)
+            },
+            strict: vars.flag("ROUTA_CLONE_STRICT"),
+        };
+
+        Self {
+            max_prompt_bytes: vars
+                .positive("ROUTA_MAX_PROMPT_BYTES")
+                .unwrap_or(DEFAULT_MAX_PROMPT_BYTES),
+            admin_token: vars
+                .get("ROUTA_ADMIN_TOKEN")
+                .map(str::trim)
+                .filter(|token| !token.is_empty())
+                .map(str::to_string),
+            file_search_limits,
+            clone_host_policy,
+            client_version_gate: vars
+                .get("ROUTA_MIN_CLIENT_VERSION")
+                .map(ClientVersionGate::parse)
+                .unwrap_or_default(),
+            role_providers: vars
+                .get("ROUTA_ROLE_PROVIDERS")
+                .map(RoleProviderMap::parse)
+                .unwrap_or_default(),
+            mcp_tools: McpToolConfig {
+                enabled: Some(vars.list("ROUTA_MCP_ENABLED_TOOLS").collect::<HashSet<_>>())
+                    .filter(|names| !names.is_empty()),
+                disabled: vars.list("ROUTA_MCP_DISABLED_TOOLS").collect(),
+                skill_tools: vars.flag("ROUTA_MCP_SKILL_TOOLS"),
+            },
+            mcp_session_ttl: vars.ttl("ROUTA_MCP_SESSION_TTL_SECS", DEFAULT_MCP_SESSION_TTL),
+            acp_session_ttl: vars.ttl("ROUTA_ACP_SESSION_TTL_SECS", DEFAULT_ACP_SESSION_TTL),
+            session_sweep_interval: vars
+                .positive("ROUTA_SESSION_SWEEP_INTERVAL_SECS")
+                .map(Duration::from_secs),
+            mcp_tool_cache_ttl: vars
+                .parse("ROUTA_MCP_TOOL_CACHE_TTL_MS")
+                .map_or(DEFAULT_MCP_TOOL_CACHE_TTL, Duration::from_millis),
+            acp: AcpSettings::read(vars),
+        }
+    }
+}
+
+impl Default for AcpSettings {
+    fn default() -> Self {
+        Self::read(&Vars::default())
+    }
+}
+
+impl AcpSettings {
+    pub fn from_env() -> Self {
+        Self::read(&Vars::new(routa_vars()))
+    }
+
+    fn read(vars: &Vars) -> Self {
+        Self {
+            history_window: HistoryWindowPolicy {
+                max_turns: vars.positive("ROUTA_ACP_HISTORY_MAX_TURNS"),
+                max_tokens: vars.positive("ROUTA_ACP_HISTORY_MAX_TOKENS"),
+            },
+            output_normalization: OutputNormalization {
+                strip_bom: vars.enabled("ROUTA_ACP_OUTPUT_STRIP_BOM"),
+                normalize_line_endings: vars.enabled("ROUTA_ACP_OUTPUT_NORMALIZE_EOL"),
+            },
+            transcript_max_bytes: Some(
+                vars.parse("ROUTA_ACP_TRANSCRIPT_MAX_BYTES")
+                    .unwrap_or(DEFAULT_TRANSCRIPT_MAX_BYTES),
+            )
+            .filter(|max| *max > 0),
+            cancel_grace: vars
+                .parse("ROUTA_ACP_CANCEL_GRACE_MS")
+                .map_or(DEFAULT_CANCEL_GRACE, Duration::from_millis),
+            warm_pool: ProcessPoolConfig {
+                sizes: vars
+                    .get("ROUTA_ACP_WARM_POOL")
+                    .map(parse_pool_sizes)
+                    .unwrap_or_default(),
+                idle_timeout: vars.parse("ROUTA_ACP_WARM_POOL_IDLE_SECS").map_or(
+                    ProcessPoolConfig::default().idle_timeout,
+                    Duration::from_secs,
+                ),
+            },
+            provider_args: vars
+                .with_prefix("ROUTA_ACP_PROVIDER_ARGS_")
+                .map(|(suffix, value)| (suffix.to_string(), value.to_string()))
+                .collect(),
+            env_passthrough: vars.list("ROUTA_ACP_ENV_PASSTHROUGH").collect(),
+            resource_limits: ResourceLimitConfig {
+                memory_limit_mb: vars.parse(MEMORY_LIMIT_VAR),
+                cpu_limit_secs: vars.parse(CPU_LIMIT_VAR),
+                memory_limit_mb_by_provider: vars.parsed_by_provider(MEMORY_LIMIT_VAR),
+                cpu_limit_secs_by_provider: vars.parsed_by_provider(CPU_LIMIT_VAR),
+            },
+            registry_strict: vars.flag("ROUTA_ACP_REGISTRY_STRICT"),
+            data_dir: vars.path("ROUTA_ACP_DATA_DIR"),
+            data_dir_strict: vars.flag("ROUTA_ACP_DATA_DIR_STRICT"),
+            verify_install: vars.flag("ROUTA_ACP_VERIFY_INSTALL"),
+            check_executable_format: !vars.flag("ROUTA_ACP_SKIP_EXECUTABLE_CHECK"),
+            archive_cache_max_bytes: Some(
+                vars.parse("ROUTA_ACP_ARCHIVE_CACHE_MAX_BYTES")
+                    .unwrap_or(DEFAULT_ARCHIVE_CACHE_MAX_BYTES),
+            )
+            .filter(|max| *max > 0 && vars.enabled("ROUTA_ACP_ARCHIVE_CACHE")),
+        }
+    }
+}
+
+/// The suffix naming `provider` in per-provider variables.
+pub fn provider_suffix(provider: &str) -> String {
+    provider
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn routa_vars() -> impl Iterator<Item = (String, OsString)> {
+    std::env::vars_os().filter_map(|(name, value)| {
+        name.into_string()
+            .ok()
+            .filter(|name| name.starts_with("ROUTA_"))
+            .map(|name| (name, value))
+    })
+}
+
+#[derive(Default)]
+struct Vars(HashMap<String, OsString>);
+
+impl Vars {
+    fn new<K, V>(vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<OsString>,
+    {
+        Self(
+            vars.into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+        )
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).and_then(|value| value.to_str())
+    }
+
+    /// A non-empty path; not required to be valid UTF-8.
+    fn path(&self, name: &str) -> Option<PathBuf> {
+        self.0
+            .get(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    }
+
+    fn parse<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.get(name).and_then(|value| value.trim().parse().ok())
+    }
+
+    /// A number that must be above zero to count as set.
+    fn positive<T: FromStr + Default + PartialOrd>(&self, name: &str) -> Option<T> {
+        self.parse(name).filter(|value| *value > T::default())
+    }
+
+    /// `1`/`true` turns the flag on; it is off otherwise.
+    fn flag(&self, name: &str) -> bool {
+        self.get(name)
+            .is_some_and(|value| matches!(value.trim(), "1" | "true" | "TRUE" | "True"))
+    }
+
+    /// `0`/`false` turns the setting off; it is on otherwise.
+    fn enabled(&self, name: &str) -> bool {
+        !self
+            .get(name)
+            .is_some_and(|value| matches!(value.trim(), "0" | "false" | "FALSE" | "False"))
+    }
+
+    /// Seconds from `name`, or `default`; `None` when set to `0`.
+    fn ttl(&self, name: &str, default: Duration) -> Option<Duration> {
+        match self.parse::<u64>(name) {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(default),
+        }
+    }
+
+    /// `(suffix, value)` for every variable named `{prefix}{suffix}`.
+    fn with_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.0.iter().filter_map(move |(name, value)| {
+            let suffix = name.strip_prefix(prefix).filter(|s| !s.is_empty())?;
+            Some((suffix, value.to_str()?))
+        })
+    }
+
+    /// Numeric `{name}_<PROVIDER>` overrides, keyed by provider suffix.
+    fn parsed_by_provider<T: FromStr>(&self, name: &str) -> HashMap<String, T> {
+        let prefix = format!("{name}_");
+        self.with_prefix(&prefix)
+            .filter_map(|(suffix, value)| Some((suffix.to_string(), value.trim().parse().ok()?)))
+            .collect()
+    }
+
+    /// The non-empty entries of a comma-separated list.
+    fn list(&self, name: &str) -> impl Iterator<Item = String> + '_ {
+        self.get(name)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(str::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_or_invalid_variables_use_the_defaults() {
+        let settings = Settings::default();
+        assert_eq!(settings.max_prompt_bytes, DEFAULT_MAX_PROMPT_BYTES);
+        assert_eq!(settings.mcp_tools, McpToolConfig::default());
+        assert_eq!(settings.file_search_limits, FileSearchLimits::default());
+        assert_eq!(settings.clone_host_policy, CloneHostPolicy::default());
+        assert!(!settings.client_version_gate.is_enabled());
+        assert_eq!(settings.role_providers, RoleProviderMap::default());
+
+        let settings = Settings::from_vars([
+            ("ROUTA_MAX_PROMPT_BYTES", "0"),
+            ("ROUTA_MCP_ENABLED_TOOLS", " , "),
+            ("ROUTA_ADMIN_TOKEN", "  "),
+            ("ROUTA_MCP_SESSION_TTL_SECS", "soon"),
+            ("ROUTA_MCP_TOOL_CACHE_TTL_MS", "-5"),
+            ("ROUTA_SESSION_SWEEP_INTERVAL_SECS", "0"),
+            ("ROUTA_FILE_SEARCH_TIMEOUT_MS", "-1"),
+            ("ROUTA_ACP_DATA_DIR", ""),
+            ("ROUTA_ACP_ARCHIVE_CACHE_MAX_BYTES", "big"),
+        ]);
+        assert_eq!(settings.max_prompt_bytes, DEFAULT_MAX_PROMPT_BYTES);
+        assert_eq!(settings.mcp_tools.enabled, None);
+        assert_eq!(settings.admin_token, None);
+        assert_eq!(settings.mcp_session_ttl, Some(DEFAULT_MCP_SESSION_TTL));
+        assert_eq!(settings.mcp_tool_cache_ttl, DEFAULT_MCP_TOOL_CACHE_TTL);
+        assert_eq!(settings.acp_session_ttl, Some(DEFAULT_ACP_SESSION_TTL));
+        assert_eq!(settings.session_sweep_interval, None);
+        assert_eq!(
+            settings.file_search_limits.walk_timeout,
+            FileSearchLimits::default().walk_timeout
+        );
+        assert_eq!(settings.acp.data_dir, None);
+        assert!(settings.acp.check_executable_format);
+        assert_eq!(settings.acp.cancel_grace, DEFAULT_CANCEL_GRACE);
+        assert_eq!(
+            settings.acp.transcript_max_bytes,
+            Some(DEFAULT_TRANSCRIPT_MAX_BYTES)
+        );
+        assert_eq!(
+            settings.acp.archive_cache_max_bytes,
+            Some(DEFAULT_ARCHIVE_CACHE_MAX_BYTES)
+        );
+        assert!(settings.acp.warm_pool.sizes.is_empty());
+        assert!(!settings.acp.history_window.is_active());
+        assert_eq!(settings.acp.resource_limits, ResourceLimitConfig::default());
+        assert_eq!(
+            settings.acp.output_normalization,
+            OutputNormalization::default()
+        );
+    }
+
+    #[test]
+    fn parses_set_values() {
+        let settings = Settings::from_vars([
+            ("ROUTA_MAX_PROMPT_BYTES", " 4096 "),
+            ("ROUTA_MCP_DISABLED_TOOLS", "delete_task, ,list_notes"),
+            ("ROUTA_MCP_SKILL_TOOLS", "true"),
+            ("ROUTA_ACP_REGISTRY_STRICT", "1"),
+            ("ROUTA_ACP_DATA_DIR", "/srv/acp"),
+            ("ROUTA_ACP_DATA_DIR_STRICT", "true"),
+            ("ROUTA_ACP_VERIFY_INSTALL", "yes"),
+            ("ROUTA_ACP_SKIP_EXECUTABLE_CHECK", "1"),
+            ("ROUTA_ACP_ARCHIVE_CACHE", "0"),
+            ("ROUTA_ACP_ARCHIVE_CACHE_MAX_BYTES", "4096"),
+            ("ROUTA_MCP_SESSION_TTL_SECS", "0"),
+            ("ROUTA_MCP_TOOL_CACHE_TTL_MS", "0"),
+            ("ROUTA_ACP_SESSION_TTL_SECS", " 15 "),
+            ("ROUTA_SESSION_SWEEP_INTERVAL_SECS", "5"),
+            ("ROUTA_ACP_WARM_POOL", "gemini=2"),
+            ("ROUTA_ACP_WARM_POOL_IDLE_SECS", "30"),
+            ("ROUTA_ACP_HISTORY_MAX_TURNS", "12"),
+            ("ROUTA_ACP_HISTORY_MAX_TOKENS", "soon"),
+            ("ROUTA_FILE_SEARCH_MAX_LIMIT", "50"),
+            ("ROUTA_FILE_SEARCH_DEFAULT_LIMIT", "80"),
+            ("ROUTA_ACP_OUTPUT_STRIP_BOM", "false"),
+            ("ROUTA_ACP_CANCEL_GRACE_MS", "250"),
+            ("ROUTA_ACP_TRANSCRIPT_MAX_BYTES", "0"),
+            ("ROUTA_ACP_ENV_PASSTHROUGH", "HTTPS_PROXY, ,NO_PROXY"),
+            ("ROUTA_ACP_PROVIDER_ARGS_OPENCODE", "--log-level debug"),
+            ("ROUTA_CLONE_ALLOWED_HOSTS", "GitHub.com, git.example.com"),
+            ("ROUTA_MIN_CLIENT_VERSION", "routa-desktop=1.2.0"),
+            ("ROUTA_ROLE_PROVIDERS", "gate=claude"),
+            ("ROUTA_ACP_MEMORY_LIMIT_MB", "512"),
+            ("ROUTA_ACP_MEMORY_LIMIT_MB_CODEX_ACP", "0"),
+            ("ROUTA_ACP_CPU_LIMIT_SECS_GEMINI", "lots"),
+        ]);
+        assert_eq!(settings.max_prompt_bytes, 4096);
+        assert_eq!(settings.file_search_limits.max_limit, 50);
+        assert_eq!(settings.file_search_limits.default_limit, 50);
+        assert_eq!(
+            settings.clone_host_policy.allowed_hosts,
+            Some(vec![
+                "github.com".to_string(),
+                "git.example.com".to_string()
+            ])
+        );
+        assert_eq!(
+            settings.client_version_gate,
+            ClientVersionGate::parse("routa-desktop=1.2.0")
+        );
+        assert_eq!(settings.role_providers.provider_for("GATE"), Some("claude"));
+        let any_host = Settings::from_vars([("ROUTA_CLONE_ALLOWED_HOSTS", "*")]);
+        assert_eq!(any_host.clone_host_policy.allowed_hosts, None);
+        assert!(!settings.mcp_tools.is_enabled("delete_task"));
+        assert!(settings.mcp_tools.is_enabled("list_tasks"));
+        assert_eq!(settings.mcp_tools.disabled.len(), 2);
+        assert!(settings.mcp_tools.skill_tools);
+        assert!(settings.acp.registry_strict);
+        assert_eq!(settings.acp.data_dir, Some(PathBuf::from("/srv/acp")));
+        assert!(settings.acp.data_dir_strict);
+        assert!(!settings.acp.verify_install);
+        assert!(!settings.acp.check_executable_format);
+        assert_eq!(settings.acp.archive_cache_max_bytes, None);
+        let capped = Settings::from_vars([("ROUTA_ACP_ARCHIVE_CACHE_MAX_BYTES", "4096")]);
+        assert_eq!(capped.acp.archive_cache_max_bytes, Some(4096));
+        assert_eq!(settings.mcp_session_ttl, None);
+        assert!(settings.mcp_tool_cache_ttl.is_zero());
+        assert_eq!(settings.acp_session_ttl, Some(Duration::from_secs(15)));
+        assert_eq!(
+            settings.session_sweep_interval,
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(settings.acp.warm_pool.size_for("gemini"), 2);
+        assert_eq!(settings.acp.warm_pool.idle_timeout, Duration::from_secs(30));
+        assert_eq!(settings.acp.history_window.max_turns, Some(12));
+        assert_eq!(settings.acp.history_window.max_tokens, None);
+        assert!(!settings.acp.output_normalization.strip_bom);
+        assert_eq!(settings.acp.cancel_grace, Duration::from_millis(250));
+        assert_eq!(settings.acp.transcript_max_bytes, None);
+        assert_eq!(settings.acp.env_passthrough, ["HTTPS_PROXY", "NO_PROXY"]);
+        assert_eq!(
+            settings.acp.provider_args,
+            HashMap::from([("OPENCODE".to_string(), "--log-level debug".to_string())])
+        );
+        assert!(settings.acp.output_normalization.normalize_line_endings);
+        let limits = &settings.acp.resource_limits;
+        assert_eq!(limits.memory_limit_mb, Some(512));
+        assert_eq!(limits.memory_limit_mb_by_provider["CODEX_ACP"], 0);
+        assert!(limits.cpu_limit_secs_by_provider.is_empty());
+        assert_eq!(provider_suffix("codex-acp"), "CODEX_ACP");
+    }
+}
@@ -6,12 +6,15 @@
 //! - Linux: depends on the desktop environment
 //!
 //! This module recovers the user's login-shell PATH so we can find
-//! CLI tools like `opencode`, `claude`, `gemini`, etc.
+//! CLI tools like `opencode`, `claude`, `gemini`, etc., and the other
+//! variables the login shell exports (API keys set in `~/.zshrc`, …).
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 static FULL_PATH: OnceLock<String> = OnceLock::new();
+static LOGIN_ENV: OnceLock<HashMap<String, String>> = OnceLock::new();
 
 /// Platform-specific PATH separator.
 #[cfg(windows)]
@@ -66,6 +69,43 @@ fn resolve_full_path() -> String {
     result
 }
 
+/// Look up an environment variable, falling back to the login shell's
+/// environment when this process didn't inherit it. The login environment
+/// is read once and cached.
+pub fn var(name: &str) -> Option<String> {
+    if let Ok(value) = std::env::var(name) {
+        return Some(value);
+    }
+    LOGIN_ENV.get_or_init(resolve_login_env).get(name).cloned()
+}
+
+#[cfg(not(windows))]
+fn resolve_login_env() -> HashMap<String, String> {
+    let login_shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+    let Ok(output) = std::process::Command::new(&login_shell)
+        .args(["-l", "-c", "env"])
+        .output()
+    else {
+        return HashMap::new();
+    };
+    if !output.status.success() {
+        return HashMap::new();
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .filter(|(key, _)| {
+            !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[cfg(windows)]
+fn resolve_login_env() -> HashMap<String, String> {
+    HashMap::new()
+}
+
 /// Unix: try running the user's login shell to get $PATH.
 #[cfg(not(windows))]
 fn resolve_unix_shell_path() -> Option<String> {
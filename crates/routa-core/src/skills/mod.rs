@@ -8,17 +8,26 @@
 //! ---
 //! name: skill-name
 //! description: What this skill does.
+//! tags: [review, rust]
 //! metadata:
 //!   short-description: Brief label
+//! entry: scripts/run.py        # optional, relative to the skill directory
+//! input-schema:                # optional JSON Schema for `entry`'s input
+//!   type: object
+//!   properties:
+//!     path: { type: string }
 //! ---
 //!
 //! Full instructions for the agent...
 //! ```
+//!
+//! Skills with both `entry` and `input-schema` can be exposed as MCP tools.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::RwLock;
+use tokio::sync::watch;
 
 /// YAML frontmatter parsed from a SKILL.md file.
 #[derive(Debug, Deserialize)]
@@ -30,17 +39,25 @@ struct SkillFrontmatter {
     #[serde(default)]
     compatibility: Option<String>,
     #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
     metadata: SkillFrontmatterMetadata,
+    #[serde(default)]
+    entry: Option<String>,
+    #[serde(default, rename = "input-schema", alias = "inputSchema")]
+    input_schema: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Default, Deserialize)]
 struct SkillFrontmatterMetadata {
     #[serde(default, rename = "short-description")]
     short_description: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
 }
 
 /// A discovered skill definition.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SkillDefinition {
     pub name: String,
@@ -53,8 +70,50 @@ pub struct SkillDefinition {
     pub license: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub compatibility: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     #[serde(default)]
     pub metadata: HashMap<String, String>,
+    /// Script run when the skill is invoked as a tool, relative to the
+    /// directory containing SKILL.md.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry: Option<String>,
+    /// JSON Schema describing the input passed to `entry`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_schema: Option<serde_json::Value>,
+}
+
+/// Criteria for narrowing the skill list. Empty fields match everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillFilter {
+    /// Provider the skill must work with, matched case-insensitively against
+    /// the `compatibility` frontmatter. Skills that declare no compatibility
+    /// are treated as working with every provider.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compatibility: Option<String>,
+    /// Tag the skill must carry (case-insensitive).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl SkillDefinition {
+    pub fn matches(&self, filter: &SkillFilter) -> bool {
+        if let Some(provider) = filter.compatibility.as_deref() {
+            let provider = provider.to_lowercase();
+            if let Some(compatibility) = self.compatibility.as_deref() {
+                if !compatibility.to_lowercase().contains(&provider) {
+                    return false;
+                }
+            }
+        }
+        if let Some(tag) = filter.tag.as_deref() {
+            if !self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                return false;
+            }
+        }
+        true
+    }
 }
 
 /// Well-known directory patterns where skills can be found.
@@ -72,6 +131,8 @@ const SKILL_FILENAME: &str = "SKILL.md";
 /// In-memory registry for discovered skills.
 pub struct SkillRegistry {
     skills: RwLock<HashMap<String, SkillDefinition>>,
+    /// Bumped whenever a reload changes the set of skills.
+    changes: watch::Sender<u64>,
 }
 
 impl Default for SkillRegistry {
@@ -84,6 +145,7 @@ impl SkillRegistry {
     pub fn new() -> Self {
         Self {
             skills: RwLock::new(HashMap::new()),
+            changes: watch::channel(0).0,
         }
     }
 
@@ -112,12 +174,23 @@ impl SkillRegistry {
         }
 
         let count = discovered.len();
+        let mut changed = false;
         if let Ok(mut skills) = self.skills.write() {
+            changed = *skills != discovered;
             *skills = discovered;
         }
+        if changed {
+            self.changes.send_modify(|generation| *generation += 1);
+        }
         tracing::info!("Discovered {} skills", count);
     }
 
+    /// Subscribe to skill-set changes; the receiver is notified after every
+    /// reload that adds, removes or modifies a skill.
+    pub fn subscribe_changes(&self) -> watch::Receiver<u64> {
+        self.changes.subscribe()
+    }
+
     /// Get a skill by name.
     pub fn get_skill(&self, name: &str) -> Option<SkillDefinition> {
         self.skills.read().ok().and_then(|s| s.get(name).cloned())
@@ -130,6 +203,17 @@ impl SkillRegistry {
             .map(|s| s.values().cloned().collect())
             .unwrap_or_default()
     }
+
+    /// List discovered skills matching `filter`, sorted by name.
+    pub fn list_skills_filtered(&self, filter: &SkillFilter) -> Vec<SkillDefinition> {
+        let mut skills: Vec<_> = self
+            .list_skills()
+            .into_iter()
+            .filter(|skill| skill.matches(filter))
+            .collect();
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+        skills
+    }
 }
 
 /// Recursively discover SKILL.md files in a directory (max 2 levels deep).
@@ -217,6 +301,12 @@ fn parse_skill_file(path: &Path) -> Option<SkillDefinition> {
     if let Some((frontmatter_str, body)) = extract_frontmatter(&raw) {
         if let Ok(fm) = serde_yaml::from_str::<SkillFrontmatter>(&frontmatter_str) {
             let short_desc = fm.metadata.short_description.filter(|s| !s.is_empty());
+            let mut tags = fm.tags;
+            for tag in fm.metadata.tags {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
 
             return Some(SkillDefinition {
                 name: fm.name,
@@ -226,7 +316,10 @@ fn parse_skill_file(path: &Path) -> Option<SkillDefinition> {
                 source: path.to_string_lossy().to_string(),
                 license: fm.license,
                 compatibility: fm.compatibility,
+                tags,
                 metadata: HashMap::new(),
+                entry: fm.entry.filter(|entry| !entry.trim().is_empty()),
+                input_schema: fm.input_schema,
             });
         }
     }
@@ -257,6 +350,75 @@ fn parse_skill_file(path: &Path) -> Option<SkillDefinition> {
         source: path.to_string_lossy().to_string(),
         license: None,
         compatibility: None,
+        tags: Vec::new(),
         metadata: HashMap::new(),
+        entry: None,
+        input_schema: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(compatibility: Option<&str>, tags: &[&str]) -> SkillDefinition {
+        SkillDefinition {
+            name: "demo".to_string(),
+            description: "Demo".to_string(),
+            short_description: None,
+            content: String::new(),
+            source: "SKILL.md".to_string(),
+            license: None,
+            compatibility: compatibility.map(str::to_string),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            metadata: HashMap::new(),
+            entry: None,
+            input_schema: None,
+        }
+    }
+
+    #[test]
+    fn filter_matches_compatibility_and_tag() {
+        let filter = SkillFilter {
+            compatibility: Some("Claude".to_string()),
+            tag: Some("review".to_string()),
+        };
+
+        assert!(skill(Some("claude, opencode"), &["Review"]).matches(&filter));
+        assert!(skill(None, &["review"]).matches(&filter));
+        assert!(!skill(Some("codex"), &["review"]).matches(&filter));
+        assert!(!skill(Some("claude"), &["docs"]).matches(&filter));
+        assert!(skill(Some("codex"), &[]).matches(&SkillFilter::default()));
+    }
+
+    #[test]
+    fn parses_entry_and_input_schema_and_signals_changes() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let skill_dir = dir.path().join(".agents/skills/lint");
+        std::fs::create_dir_all(&skill_dir).expect("create skill dir");
+        std::fs::write(
+            skill_dir.join(SKILL_FILENAME),
+            "---\nname: lint\ndescription: Lint files\nentry: run.sh\ninput-schema:\n  type: object\n  properties:\n    path: { type: string }\n---\nRun the linter.\n",
+        )
+        .expect("write SKILL.md");
+
+        let registry = SkillRegistry::new();
+        let changes = registry.subscribe_changes();
+        registry.reload(&dir.path().to_string_lossy());
+
+        let skill = registry.get_skill("lint").expect("skill discovered");
+        assert_eq!(skill.entry.as_deref(), Some("run.sh"));
+        assert_eq!(
+            skill.input_schema,
+            Some(serde_json::json!({
+                "type": "object",
+                "properties": { "path": { "type": "string" } }
+            }))
+        );
+        assert!(changes.has_changed().unwrap());
+
+        let generation = *changes.borrow();
+        registry.reload(&dir.path().to_string_lossy());
+        assert_eq!(*changes.borrow(), generation);
+    }
+}
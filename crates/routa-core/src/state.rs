@@ -1,21 +1,242 @@
 //! Shared application state for the axum server.
 
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 use crate::acp::{
     docker::{DockerDetector, DockerProcessManager},
     AcpBinaryManager, AcpInstallationState, AcpManager, AcpPaths, AcpRuntimeManager,
     AcpWarmupService,
 };
+use crate::clone_tracker::CloneTracker;
 use crate::db::Database;
 use crate::events::EventBus;
 use crate::sandbox::SandboxManager;
+use crate::settings::Settings;
 use crate::skills::SkillRegistry;
 use crate::store::{
     AcpSessionStore, AgentStore, ArtifactStore, CodebaseStore, ConversationStore, KanbanStore,
     NoteStore, ScheduleStore, TaskStore, WorkspaceStore, WorktreeStore,
 };
 
+/// Default upper bound (in bytes) on the joined text of a `session/prompt` request.
+pub const DEFAULT_MAX_PROMPT_BYTES: usize = 1024 * 1024;
+
+/// Result limits for `/api/files/search`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileSearchLimits {
+    /// Used when the request has no `limit`.
+    pub default_limit: usize,
+    /// Requested limits above this are clamped.
+    pub max_limit: usize,
+    /// Directory walks stop after this long and return partial results.
+    pub walk_timeout: Duration,
+}
+
+impl Default for FileSearchLimits {
+    fn default() -> Self {
+        Self {
+            default_limit: 20,
+            max_limit: 200,
+            walk_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl FileSearchLimits {
+    /// Effective limit for a request, and whether it was clamped to the max.
+    pub fn resolve(&self, requested: Option<usize>) -> (usize, bool) {
+        match requested {
+            Some(limit) if limit > self.max_limit => (self.max_limit, true),
+            Some(limit) => (limit, false),
+            None => (self.default_limit, false),
+        }
+    }
+}
+
+/// Which MCP tools are exposed. Everything is enabled unless an allow-list is
+/// set; `disabled` always wins over `enabled`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct McpToolConfig {
+    /// When set, only these tools are exposed.
+    pub enabled: Option<HashSet<String>>,
+    pub disabled: HashSet<String>,
+    /// Expose skills that declare an entry script and input schema as
+    /// `skill_<name>` tools.
+    pub skill_tools: bool,
+}
+
+impl McpToolConfig {
+    pub fn is_enabled(&self, name: &str) -> bool {
+        if self.disabled.contains(name) {
+            return false;
+        }
+        match &self.enabled {
+            Some(enabled) => enabled.contains(name),
+            None => true,
+        }
+    }
+}
+
+/// Default lifetime of a cached MCP tool result.
+pub const DEFAULT_MCP_TOOL_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Short-lived cache of MCP tool results, keyed by tool name plus normalized
+/// arguments. The MCP tool executor decides which tools are cached and which
+/// mutations invalidate them; the TTL bounds how stale a result can get when
+/// data changes outside MCP (e.g. through the REST API).
+#[derive(Debug, Default)]
+pub struct McpToolResultCache {
+    /// `None` disables caching.
+    ttl: Option<Duration>,
+    entries: Mutex<HashMap<String, CachedToolResult>>,
+}
+
+#[derive(Debug)]
+struct CachedToolResult {
+    tool: String,
+    stored_at: Instant,
+    value: serde_json::Value,
+}
+
+impl McpToolResultCache {
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            ttl: ttl.filter(|ttl| !ttl.is_zero()),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.ttl.is_some()
+    }
+
+    /// The cached result for `key`, unless it has expired.
+    pub fn get(&self, key: &str) -> Option<serde_json::Value> {
+        let ttl = self.ttl?;
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match entries.get(key) {
+            Some(entry) if entry.stored_at.elapsed() < ttl => Some(entry.value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Cache `value` as the result of `tool` for `key`, dropping expired entries.
+    pub fn insert(&self, tool: &str, key: String, value: serde_json::Value) {
+        let Some(ttl) = self.ttl else {
+            return;
+        };
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, entry| entry.stored_at.elapsed() < ttl);
+        entries.insert(
+            key,
+            CachedToolResult {
+                tool: tool.to_string(),
+                stored_at: Instant::now(),
+                value,
+            },
+        );
+    }
+
+    /// Drop every cached result of a tool for which `affected` returns true.
+    pub fn invalidate(&self, mut affected: impl FnMut(&str) -> bool) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|_, entry| !affected(&entry.tool));
+    }
+}
+
+/// How long a provider command's availability is trusted.
+pub const DEFAULT_COMMAND_AVAILABILITY_TTL: Duration = Duration::from_secs(60);
+
+/// Whether provider commands (`opencode`, `npx`, …) are on `PATH`, keyed by
+/// command, so `_providers/list` doesn't look every preset up on each refresh.
+#[derive(Debug)]
+pub struct CommandAvailabilityCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, bool)>>,
+}
+
+impl Default for CommandAvailabilityCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMMAND_AVAILABILITY_TTL)
+    }
+}
+
+impl CommandAvailabilityCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cached availability of `command`, unless it has expired.
+    pub fn get(&self, command: &str) -> Option<bool> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(command)
+            .filter(|(checked_at, _)| checked_at.elapsed() < self.ttl)
+            .map(|(_, available)| *available)
+    }
+
+    pub fn insert(&self, command: &str, available: bool) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(command.to_string(), (Instant::now(), available));
+    }
+
+    /// Drop every cached result; returns how many there were.
+    pub fn clear(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let count = entries.len();
+        entries.clear();
+        count
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Every cached result, expired ones included, sorted by command.
+    pub fn entries(&self) -> Vec<CommandAvailabilityEntry> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut listed: Vec<_> = entries
+            .iter()
+            .map(|(command, (checked_at, available))| {
+                let age = checked_at.elapsed();
+                CommandAvailabilityEntry {
+                    command: command.clone(),
+                    available: *available,
+                    age_ms: age.as_millis() as u64,
+                    expired: age >= self.ttl,
+                }
+            })
+            .collect();
+        listed.sort_by(|a, b| a.command.cmp(&b.command));
+        listed
+    }
+}
+
+/// A cached command lookup, as listed by `GET /api/acp/providers/cache`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandAvailabilityEntry {
+    pub command: String,
+    pub available: bool,
+    /// Time since the command was looked up.
+    pub age_ms: u64,
+    /// Older than the TTL; the next provider list looks it up again.
+    pub expired: bool,
+}
+
 /// Docker state for managing Docker-based agent execution.
 #[derive(Default)]
 pub struct DockerState {
@@ -47,14 +268,39 @@ pub struct AppStateInner {
     pub acp_warmup_service: AcpWarmupService,
     pub docker_state: DockerState,
     pub sandbox_manager: SandboxManager,
+    /// Configuration read from `ROUTA_*` variables; see `crate::settings`.
+    pub settings: Settings,
+    /// MCP tool enable/disable configuration, seeded from `settings` and
+    /// adjustable at runtime via `PATCH /api/mcp/tools`.
+    pub mcp_tool_config: RwLock<McpToolConfig>,
+    /// Results of idempotent MCP read tools; see `McpToolResultCache`.
+    pub mcp_tool_cache: McpToolResultCache,
+    /// Provider command lookups for `_providers/list`.
+    pub command_availability: CommandAvailabilityCache,
+    /// Clones in progress, listed by `GET /api/clone/active`.
+    pub clone_tracker: CloneTracker,
+}
+
+impl AppStateInner {
+    /// Whether the MCP tool `name` is enabled by the current configuration.
+    pub fn mcp_tool_enabled(&self, name: &str) -> bool {
+        self.mcp_tool_config
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_enabled(name)
+    }
 }
 
 pub type AppState = Arc<AppStateInner>;
 
 impl AppStateInner {
     pub fn new(db: Database) -> Self {
-        let acp_paths = AcpPaths::new();
-        let acp_binary_manager = AcpBinaryManager::new(acp_paths.clone());
+        Self::with_settings(db, Settings::from_env())
+    }
+
+    pub fn with_settings(db: Database, settings: Settings) -> Self {
+        let acp_paths = AcpPaths::from_settings(&settings.acp);
+        let acp_binary_manager = AcpBinaryManager::with_settings(acp_paths.clone(), &settings.acp);
         let acp_installation_state = AcpInstallationState::new(acp_paths.clone());
         let acp_runtime_manager = AcpRuntimeManager::new(acp_paths.clone());
         let acp_warmup_service = AcpWarmupService::new(acp_paths.clone());
@@ -71,7 +317,7 @@ impl AppStateInner {
             conversation_store: ConversationStore::new(db.clone()),
             acp_session_store: AcpSessionStore::new(db.clone()),
             skill_registry: SkillRegistry::new(),
-            acp_manager: AcpManager::new(),
+            acp_manager: AcpManager::with_settings(&settings.acp),
             event_bus: EventBus::new(),
             db,
             acp_paths,
@@ -81,6 +327,11 @@ impl AppStateInner {
             acp_warmup_service,
             docker_state: DockerState::default(),
             sandbox_manager: SandboxManager::new(),
+            mcp_tool_config: RwLock::new(settings.mcp_tools.clone()),
+            mcp_tool_cache: McpToolResultCache::new(Some(settings.mcp_tool_cache_ttl)),
+            command_availability: CommandAvailabilityCache::default(),
+            clone_tracker: CloneTracker::new(),
+            settings,
         }
     }
 }
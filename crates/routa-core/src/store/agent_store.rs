@@ -148,6 +148,20 @@ impl AgentStore {
             .await
     }
 
+    /// Delete every agent in a workspace. Returns the number of rows removed.
+    pub async fn delete_by_workspace(&self, workspace_id: &str) -> Result<usize, ServerError> {
+        let ws_id = workspace_id.to_string();
+        self.db
+            .with_conn_async(move |conn| {
+                let removed = conn.execute(
+                    "DELETE FROM agents WHERE workspace_id = ?1",
+                    rusqlite::params![ws_id],
+                )?;
+                Ok(removed)
+            })
+            .await
+    }
+
     pub async fn update_status(
         &self,
         agent_id: &str,
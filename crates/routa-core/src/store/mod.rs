@@ -21,3 +21,26 @@ pub use schedule_store::ScheduleStore;
 pub use task_store::TaskStore;
 pub use workspace_store::WorkspaceStore;
 pub use worktree_store::WorktreeStore;
+
+/// Default number of results returned by the task/note `search` methods.
+pub const DEFAULT_SEARCH_LIMIT: usize = 50;
+/// Upper bound on results returned by the task/note `search` methods.
+pub const MAX_SEARCH_LIMIT: usize = 200;
+
+/// Clamp a caller-supplied search limit into `1..=MAX_SEARCH_LIMIT`.
+pub fn clamp_search_limit(limit: Option<usize>) -> usize {
+    limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT)
+}
+
+/// Build a case-insensitive SQL `LIKE` pattern (escape char `\`) matching
+/// `query` anywhere in a column.
+pub(crate) fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .trim()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
@@ -93,6 +93,39 @@ impl NoteStore {
             .await
     }
 
+    /// Notes whose title or content contains `query` (case-insensitive),
+    /// most recently updated first. `workspace_id = None` searches every
+    /// existing workspace.
+    pub async fn search(
+        &self,
+        query: &str,
+        workspace_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Note>, ServerError> {
+        let pattern = super::like_pattern(query);
+        let ws_id = workspace_id.map(str::to_string);
+        let limit = limit as i64;
+        self.db
+            .with_conn_async(move |conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT id, workspace_id, session_id, title, content, type, task_status,
+                     assigned_agent_ids, parent_note_id, linked_task_id, custom_metadata, created_at, updated_at
+                     FROM notes
+                     WHERE (?1 IS NULL OR workspace_id = ?1)
+                       AND workspace_id IN (SELECT id FROM workspaces)
+                       AND (title LIKE ?2 ESCAPE '\\' OR content LIKE ?2 ESCAPE '\\')
+                     ORDER BY updated_at DESC LIMIT ?3",
+                )?;
+                let rows = stmt
+                    .query_map(rusqlite::params![ws_id, pattern, limit], |row| {
+                        Ok(row_to_note(row))
+                    })?
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(rows)
+            })
+            .await
+    }
+
     pub async fn list_by_type(
         &self,
         workspace_id: &str,
@@ -129,6 +162,20 @@ impl NoteStore {
             .await
     }
 
+    /// Delete every note in a workspace, including the spec note. Returns the number of rows removed.
+    pub async fn delete_by_workspace(&self, workspace_id: &str) -> Result<usize, ServerError> {
+        let ws_id = workspace_id.to_string();
+        self.db
+            .with_conn_async(move |conn| {
+                let removed = conn.execute(
+                    "DELETE FROM notes WHERE workspace_id = ?1",
+                    rusqlite::params![ws_id],
+                )?;
+                Ok(removed)
+            })
+            .await
+    }
+
     pub async fn ensure_spec(&self, workspace_id: &str) -> Result<Note, ServerError> {
         if let Some(note) = self.get(SPEC_NOTE_ID, workspace_id).await? {
             return Ok(note);
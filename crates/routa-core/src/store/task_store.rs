@@ -185,6 +185,44 @@ impl TaskStore {
             .await
     }
 
+    /// Tasks whose title or objective contains `query` (case-insensitive),
+    /// most recently updated first. `workspace_id = None` searches every
+    /// existing workspace.
+    pub async fn search(
+        &self,
+        query: &str,
+        workspace_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Task>, ServerError> {
+        let pattern = super::like_pattern(query);
+        let ws_id = workspace_id.map(str::to_string);
+        let limit = limit as i64;
+        self.db
+            .with_conn_async(move |conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT id, title, objective, comment, scope, acceptance_criteria, verification_commands, test_cases,
+                     assigned_to, status, board_id, column_id, position, priority, labels, assignee,
+                     assigned_provider, assigned_role, assigned_specialist_id, assigned_specialist_name,
+                     trigger_session_id, github_id, github_number, github_url, github_repo, github_state,
+                     github_synced_at, last_sync_error, dependencies, parallel_group, workspace_id, session_id, creation_source,
+                     session_ids, lane_sessions, lane_handoffs, completion_summary, verification_verdict,
+                     verification_report, codebase_ids, context_search_spec, worktree_id, created_at, updated_at
+                     FROM tasks
+                     WHERE (?1 IS NULL OR workspace_id = ?1)
+                       AND workspace_id IN (SELECT id FROM workspaces)
+                       AND (title LIKE ?2 ESCAPE '\\' OR objective LIKE ?2 ESCAPE '\\')
+                     ORDER BY updated_at DESC LIMIT ?3",
+                )?;
+                let rows = stmt
+                    .query_map(rusqlite::params![ws_id, pattern, limit], |row| {
+                        Ok(row_to_task(row))
+                    })?
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(rows)
+            })
+            .await
+    }
+
     pub async fn list_by_session(&self, session_id: &str) -> Result<Vec<Task>, ServerError> {
         let sid = session_id.to_string();
         self.db
@@ -303,6 +341,40 @@ impl TaskStore {
             })
             .await
     }
+
+    /// Delete a task only if it belongs to `workspace_id`. Returns whether a
+    /// task was removed.
+    pub async fn delete_in_workspace(
+        &self,
+        task_id: &str,
+        workspace_id: &str,
+    ) -> Result<bool, ServerError> {
+        let id = task_id.to_string();
+        let ws_id = workspace_id.to_string();
+        self.db
+            .with_conn_async(move |conn| {
+                let removed = conn.execute(
+                    "DELETE FROM tasks WHERE id = ?1 AND workspace_id = ?2",
+                    rusqlite::params![id, ws_id],
+                )?;
+                Ok(removed > 0)
+            })
+            .await
+    }
+
+    /// Delete every task in a workspace. Returns the number of rows removed.
+    pub async fn delete_by_workspace(&self, workspace_id: &str) -> Result<usize, ServerError> {
+        let ws_id = workspace_id.to_string();
+        self.db
+            .with_conn_async(move |conn| {
+                let removed = conn.execute(
+                    "DELETE FROM tasks WHERE workspace_id = ?1",
+                    rusqlite::params![ws_id],
+                )?;
+                Ok(removed)
+            })
+            .await
+    }
 }
 
 use rusqlite::Row;
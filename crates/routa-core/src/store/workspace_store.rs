@@ -1,6 +1,7 @@
 use chrono::Utc;
 use rusqlite::OptionalExtension;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::db::Database;
 use crate::error::ServerError;
@@ -8,11 +9,22 @@ use crate::models::workspace::{Workspace, WorkspaceStatus};
 
 pub struct WorkspaceStore {
     db: Database,
+    /// Bumped on every delete so callers caching "workspace exists" can
+    /// detect that their cache may be stale.
+    generation: AtomicU64,
 }
 
 impl WorkspaceStore {
     pub fn new(db: Database) -> Self {
-        Self { db }
+        Self {
+            db,
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Current deletion generation; changes whenever a workspace is deleted.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
     }
 
     pub async fn save(&self, workspace: &Workspace) -> Result<(), ServerError> {
@@ -123,7 +135,8 @@ impl WorkspaceStore {
 
     pub async fn delete(&self, id: &str) -> Result<(), ServerError> {
         let id = id.to_string();
-        self.db
+        let result = self
+            .db
             .with_conn_async(move |conn| {
                 conn.execute(
                     "DELETE FROM workspaces WHERE id = ?1",
@@ -131,7 +144,9 @@ impl WorkspaceStore {
                 )?;
                 Ok(())
             })
-            .await
+            .await;
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        result
     }
 
     pub async fn ensure_default(&self) -> Result<Workspace, ServerError> {
@@ -254,4 +269,20 @@ mod tests {
         let loaded = store.get("ws-3").await.expect("get should succeed");
         assert!(loaded.is_none());
     }
+
+    #[tokio::test]
+    async fn delete_bumps_generation() {
+        let store = setup().await;
+        let ws = Workspace::new("ws-3".to_string(), "Doomed".to_string(), None);
+        store.save(&ws).await.expect("save should succeed");
+
+        let before = store.generation();
+        store.delete("ws-3").await.expect("delete should succeed");
+        assert_ne!(store.generation(), before);
+        assert!(store
+            .get("ws-3")
+            .await
+            .expect("get should succeed")
+            .is_none());
+    }
 }
@@ -4,21 +4,37 @@
 //! GET  /api/acp/registry?id=x      - Get specific agent details
 //! POST /api/acp/registry           - Force refresh registry cache
 //!
-//! POST   /api/acp/install          - Install an agent
+//! POST   /api/acp/install          - Install an agent. With `verify: true` (or
+//!                                    `ROUTA_ACP_VERIFY_INSTALL=1`) a binary is run with
+//!                                    `--version` afterwards and removed again if that fails
+//!                                    (unless `uninstallOnVerifyFailure: false`)
 //! DELETE /api/acp/install          - Uninstall an agent
+//! DELETE /api/acp/install?agentId=x - Cancel an in-flight binary install
+//! POST   /api/acp/install/manifest - Install several agents, streaming aggregated SSE progress
+//!
+//! GET    /api/acp/capabilities     - Supported archive formats, platform and distribution types
+//! GET    /api/acp/paths            - Resolved ACP data directory and where it came from
+//!
+//! GET    /api/acp/agents/{id}/launch-command - Command, args and env a launch would use
 
 use axum::{
-    extract::{Query, State},
+    body::Bytes,
+    extract::{Path, Query, State},
+    response::sse::{Event, Sse},
     routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::pin::Pin;
 
 use crate::acp::{get_presets, AcpPaths, DistributionType, RuntimeType, WarmupStatus};
 use crate::error::ServerError;
 use crate::shell_env;
 use crate::state::AppState;
 
+type SseStream = Pin<Box<dyn tokio_stream::Stream<Item = Result<Event, Infallible>> + Send>>;
+
 /// ACP Registry URL
 const ACP_REGISTRY_URL: &str =
     "https://cdn.agentclientprotocol.com/registry/v1/latest/registry.json";
@@ -27,8 +43,12 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/registry", get(get_registry).post(refresh_registry))
         .route("/install", post(install_agent).delete(uninstall_agent))
+        .route("/install/manifest", post(install_manifest))
         .route("/runtime", get(get_runtime_status).post(ensure_runtime))
         .route("/warmup", get(get_warmup_status).post(warmup_agent))
+        .route("/capabilities", get(get_capabilities))
+        .route("/paths", get(get_paths))
+        .route("/agents/{id}/launch-command", get(get_launch_command))
 }
 
 // ─── Types ─────────────────────────────────────────────────────────────────
@@ -92,10 +112,48 @@ struct InstallRequest {
     agent_id: String,
     #[serde(rename = "distributionType")]
     distribution_type: Option<String>,
+    /// Run an installed binary with `--version` before reporting success.
+    /// Defaults to `ROUTA_ACP_VERIFY_INSTALL`.
+    verify: Option<bool>,
+    /// Remove the installed version when verification fails (default: true).
+    #[serde(rename = "uninstallOnVerifyFailure")]
+    uninstall_on_verify_failure: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestInstallRequest {
+    agents: Vec<InstallRequest>,
+    /// Stop at the first failure instead of installing the remaining agents.
+    #[serde(default)]
+    stop_on_error: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CancelInstallQuery {
+    #[serde(rename = "agentId")]
+    agent_id: Option<String>,
 }
 
 // ─── Handlers ──────────────────────────────────────────────────────────────
 
+/// GET /api/acp/agents/{id}/launch-command - Show the resolved launch command
+/// without spawning the agent, so it can be reproduced in a terminal.
+async fn get_launch_command(
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let info = crate::acp::describe_launch_command(&id)
+        .await
+        .map_err(|e| {
+            if e.contains("not found") {
+                ServerError::NotFound(e)
+            } else {
+                ServerError::Internal(e)
+            }
+        })?;
+    Ok(Json(serde_json::json!(info)))
+}
+
 /// GET /api/acp/registry - List all agents with installation status
 async fn get_registry(
     State(state): State<AppState>,
@@ -117,6 +175,7 @@ async fn get_registry(
             let status = get_agent_status(&state, &agent, npx_available, uvx_available).await;
             return Ok(Json(serde_json::json!({
                 "agent": agent,
+                "registryVersion": registry.version,
                 "available": status.available,
                 "installed": status.installed,
                 "uninstallable": status.uninstallable,
@@ -137,6 +196,7 @@ async fn get_registry(
         .map(|agent| agent.id.clone())
         .collect();
     let mut agents_with_status = Vec::new();
+    let registry_version = registry.version;
     for agent in registry.agents {
         let dist_types = get_distribution_types(&agent.distribution);
         let status = get_agent_status(&state, &agent, npx_available, uvx_available).await;
@@ -182,6 +242,7 @@ async fn get_registry(
 
     Ok(Json(serde_json::json!({
         "agents": agents_with_status,
+        "registryVersion": registry_version,
         "platform": detect_platform(),
         "runtimeAvailability": {
             "npx": npx_available,
@@ -205,18 +266,34 @@ async fn install_agent(
             ServerError::NotFound(format!("Agent '{}' not found in registry", req.agent_id))
         })?;
 
+    install_registry_agent(&state, &agent, &req, None)
+        .await
+        .map(Json)
+}
+
+/// Install one registry agent, reporting binary download/extract progress to
+/// `progress`. Returns the JSON body of `POST /api/acp/install`.
+async fn install_registry_agent(
+    state: &AppState,
+    agent: &RegistryAgent,
+    request: &InstallRequest,
+    progress: Option<crate::acp::InstallProgressFn>,
+) -> Result<serde_json::Value, ServerError> {
+    let distribution_type = request.distribution_type.clone();
     let dist_types = get_distribution_types(&agent.distribution);
     let npx_available = shell_env::which("npx").is_some();
     let uvx_available = shell_env::which("uv").is_some();
 
     // Determine distribution type to use
-    let dist_type = req.distribution_type.unwrap_or_else(|| {
+    let dist_type = distribution_type.unwrap_or_else(|| {
         if dist_types.contains(&"npx".to_string()) && npx_available {
             "npx".to_string()
         } else if dist_types.contains(&"uvx".to_string()) && uvx_available {
             "uvx".to_string()
         } else if dist_types.contains(&"binary".to_string()) {
             "binary".to_string()
+        } else if dist_types.contains(&"git".to_string()) {
+            "git".to_string()
         } else {
             "npx".to_string()
         }
@@ -224,7 +301,7 @@ async fn install_agent(
 
     tracing::info!(
         "[ACP Install] Installing agent: {} via {}",
-        req.agent_id,
+        agent.id,
         dist_type
     );
 
@@ -259,28 +336,22 @@ async fn install_agent(
 
             state
                 .acp_installation_state
-                .mark_installed(
-                    &req.agent_id,
-                    &version,
-                    DistributionType::Npx,
-                    None,
-                    package,
-                )
+                .mark_installed(&agent.id, &version, DistributionType::Npx, None, package)
                 .await
                 .map_err(|e| ServerError::Internal(format!("Failed to save state: {e}")))?;
 
             // Trigger background warmup to pre-cache the npm package
             state
                 .acp_warmup_service
-                .warmup_in_background(&req.agent_id)
+                .warmup_in_background(&agent.id)
                 .await;
 
-            Ok(Json(serde_json::json!({
+            Ok(serde_json::json!({
                 "success": true,
-                "agentId": req.agent_id,
+                "agentId": agent.id,
                 "distributionType": dist_type,
                 "message": format!("Agent '{}' configured for npx (warmup started)", agent.name)
-            })))
+            }))
         }
         "uvx" => {
             // Ensure we have a uv / uvx runtime (managed download if system uv absent)
@@ -306,28 +377,22 @@ async fn install_agent(
 
             state
                 .acp_installation_state
-                .mark_installed(
-                    &req.agent_id,
-                    &version,
-                    DistributionType::Uvx,
-                    None,
-                    package,
-                )
+                .mark_installed(&agent.id, &version, DistributionType::Uvx, None, package)
                 .await
                 .map_err(|e| ServerError::Internal(format!("Failed to save state: {e}")))?;
 
             // Trigger background warmup to pre-cache the Python package
             state
                 .acp_warmup_service
-                .warmup_in_background(&req.agent_id)
+                .warmup_in_background(&agent.id)
                 .await;
 
-            Ok(Json(serde_json::json!({
+            Ok(serde_json::json!({
                 "success": true,
-                "agentId": req.agent_id,
+                "agentId": agent.id,
                 "distributionType": dist_type,
                 "message": format!("Agent '{}' configured for uvx (warmup started)", agent.name)
-            })))
+            }))
         }
         "binary" => {
             // For binary, download and extract
@@ -345,15 +410,60 @@ async fn install_agent(
 
             let exe_path = state
                 .acp_binary_manager
-                .install_binary(&req.agent_id, &version, &binary_info)
+                .install_binary_with_progress(&agent.id, &version, &binary_info, progress.clone())
                 .await
-                .map_err(|e| ServerError::Internal(format!("Binary installation failed: {e}")))?;
+                .map_err(|e| {
+                    if crate::acp::AcpBinaryManager::is_cancelled_error(&e) {
+                        ServerError::Conflict(format!(
+                            "Installation of '{}' was cancelled",
+                            agent.id
+                        ))
+                    } else {
+                        ServerError::Internal(format!("Binary installation failed: {e}"))
+                    }
+                })?;
+
+            let verify = request.verify.unwrap_or(state.settings.acp.verify_install);
+            let detected_version = if verify {
+                if let Some(progress) = &progress {
+                    progress(crate::acp::InstallProgress::new(
+                        crate::acp::InstallPhase::Verifying,
+                        None,
+                    ));
+                }
+                match crate::acp::verify_executable(&exe_path, crate::acp::DEFAULT_VERIFY_TIMEOUT)
+                    .await
+                {
+                    Ok(detected) => detected,
+                    Err(e) => {
+                        if request.uninstall_on_verify_failure.unwrap_or(true) {
+                            if let Err(remove_error) = state
+                                .acp_binary_manager
+                                .remove_version(&agent.id, &version)
+                                .await
+                            {
+                                tracing::warn!(
+                                    "[ACP Install] Failed to remove unverified {}: {}",
+                                    agent.id,
+                                    remove_error
+                                );
+                            }
+                        }
+                        return Err(ServerError::Internal(format!(
+                            "Agent '{}' was installed but failed verification: {e}",
+                            agent.id
+                        )));
+                    }
+                }
+            } else {
+                None
+            };
 
             let exe_path_str = exe_path.to_string_lossy().to_string();
             state
                 .acp_installation_state
                 .mark_installed(
-                    &req.agent_id,
+                    &agent.id,
                     &version,
                     DistributionType::Binary,
                     Some(exe_path_str.clone()),
@@ -362,13 +472,53 @@ async fn install_agent(
                 .await
                 .map_err(|e| ServerError::Internal(format!("Failed to save state: {e}")))?;
 
-            Ok(Json(serde_json::json!({
+            let mut result = serde_json::json!({
                 "success": true,
-                "agentId": req.agent_id,
+                "agentId": agent.id,
                 "distributionType": dist_type,
                 "installedPath": exe_path_str,
+                "verified": verify,
                 "message": format!("Agent '{}' binary installed successfully", agent.name)
-            })))
+            });
+            if let Some(detected_version) = detected_version {
+                result["detectedVersion"] = serde_json::json!(detected_version);
+            }
+            Ok(result)
+        }
+        "git" => {
+            // For git, clone (or update) the repo at the pinned ref
+            let git_config = agent.distribution.get("git").ok_or_else(|| {
+                ServerError::BadRequest("Agent has no git distribution".to_string())
+            })?;
+            let git: crate::acp::GitDistribution = serde_json::from_value(git_config.clone())
+                .map_err(|e| ServerError::Internal(format!("Failed to parse git info: {e}")))?;
+
+            let checkout_dir = state
+                .acp_binary_manager
+                .install_git(&agent.id, &version, &git)
+                .await
+                .map_err(|e| ServerError::Internal(format!("Git installation failed: {e}")))?;
+
+            let checkout_dir_str = checkout_dir.to_string_lossy().to_string();
+            state
+                .acp_installation_state
+                .mark_installed(
+                    &agent.id,
+                    &version,
+                    DistributionType::Git,
+                    Some(checkout_dir_str.clone()),
+                    None,
+                )
+                .await
+                .map_err(|e| ServerError::Internal(format!("Failed to save state: {e}")))?;
+
+            Ok(serde_json::json!({
+                "success": true,
+                "agentId": agent.id,
+                "distributionType": dist_type,
+                "installedPath": checkout_dir_str,
+                "message": format!("Agent '{}' cloned successfully", agent.name)
+            }))
         }
         _ => Err(ServerError::BadRequest(format!(
             "Unknown distribution type: {dist_type}"
@@ -376,11 +526,143 @@ async fn install_agent(
     }
 }
 
-/// DELETE /api/acp/install - Uninstall an agent
+/// POST /api/acp/install/manifest - Install every agent in the manifest, in
+/// order, over one SSE stream. Each event's `type` is one of:
+///   - `progress`: `completed`/`total`, the current agent with its phase and
+///     percent, and `overallPercent` for a single progress bar
+///   - `agentInstalled` / `agentFailed`: per-agent outcome
+///   - `summary`: the final event, listing installed and failed agents
+///
+/// A failed agent does not stop the others unless `stopOnError` is set; the
+/// remaining agents are then reported as `skipped` in the summary.
+async fn install_manifest(
+    State(state): State<AppState>,
+    Json(req): Json<ManifestInstallRequest>,
+) -> Result<Sse<SseStream>, ServerError> {
+    if req.agents.is_empty() {
+        return Err(ServerError::BadRequest(
+            "Manifest must list at least one agent".to_string(),
+        ));
+    }
+    let registry = fetch_registry().await?;
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<Event, Infallible>>(64);
+    tokio::spawn(async move {
+        let total = req.agents.len();
+        let send = |payload: serde_json::Value| {
+            let _ = tx.try_send(Ok(Event::default().data(payload.to_string())));
+        };
+        let mut installed = Vec::new();
+        let mut failed = Vec::new();
+        let mut skipped = Vec::new();
+
+        for (index, entry) in req.agents.into_iter().enumerate() {
+            if req.stop_on_error && !failed.is_empty() {
+                skipped.push(entry.agent_id);
+                continue;
+            }
+            let completed = installed.len() + failed.len();
+            send(serde_json::json!({
+                "type": "progress",
+                "completed": completed,
+                "total": total,
+                "current": { "agentId": entry.agent_id, "index": index, "phase": "starting" },
+                "overallPercent": completed * 100 / total,
+            }));
+
+            let progress: crate::acp::InstallProgressFn = {
+                let tx = tx.clone();
+                let agent_id = entry.agent_id.clone();
+                std::sync::Arc::new(move |update: crate::acp::InstallProgress| {
+                    let within = update.percent.map_or(0, usize::from);
+                    let payload = serde_json::json!({
+                        "type": "progress",
+                        "completed": completed,
+                        "total": total,
+                        "current": {
+                            "agentId": agent_id,
+                            "index": index,
+                            "phase": update.phase,
+                            "percent": update.percent,
+                            "downloadedBytes": update.downloaded_bytes,
+                            "totalBytes": update.total_bytes,
+                        },
+                        "overallPercent": (completed * 100 + within) / total,
+                    });
+                    let _ = tx.try_send(Ok(Event::default().data(payload.to_string())));
+                })
+            };
+
+            let result = match registry.agents.iter().find(|a| a.id == entry.agent_id) {
+                Some(agent) => install_registry_agent(&state, agent, &entry, Some(progress)).await,
+                None => Err(ServerError::NotFound(format!(
+                    "Agent '{}' not found in registry",
+                    entry.agent_id
+                ))),
+            };
+            match result {
+                Ok(outcome) => {
+                    send(serde_json::json!({
+                        "type": "agentInstalled",
+                        "agentId": entry.agent_id,
+                        "index": index,
+                        "result": outcome,
+                    }));
+                    installed.push(entry.agent_id);
+                }
+                Err(error) => {
+                    let message = error.to_string();
+                    tracing::warn!(
+                        "[ACP Install] Manifest install of {} failed: {}",
+                        entry.agent_id,
+                        message
+                    );
+                    send(serde_json::json!({
+                        "type": "agentFailed",
+                        "agentId": entry.agent_id,
+                        "index": index,
+                        "error": message,
+                    }));
+                    failed.push(serde_json::json!({
+                        "agentId": entry.agent_id,
+                        "error": message,
+                    }));
+                }
+            }
+        }
+
+        // The summary is the one event a client must not miss.
+        let summary = serde_json::json!({
+            "type": "summary",
+            "success": failed.is_empty() && skipped.is_empty(),
+            "total": total,
+            "installed": installed,
+            "failed": failed,
+            "skipped": skipped,
+        });
+        let _ = tx
+            .send(Ok(Event::default().data(summary.to_string())))
+            .await;
+    });
+
+    let stream: SseStream = Box::pin(tokio_stream::wrappers::ReceiverStream::new(rx));
+    Ok(Sse::new(stream))
+}
+
+/// DELETE /api/acp/install - Uninstall an agent, or cancel an in-flight
+/// install when `?agentId=` is given in the query string.
 async fn uninstall_agent(
     State(state): State<AppState>,
-    Json(req): Json<InstallRequest>,
+    Query(query): Query<CancelInstallQuery>,
+    body: Bytes,
 ) -> Result<Json<serde_json::Value>, ServerError> {
+    if let Some(agent_id) = query.agent_id {
+        return cancel_install(&state, &agent_id).await;
+    }
+
+    let req: InstallRequest = serde_json::from_slice(&body)
+        .map_err(|e| ServerError::BadRequest(format!("Invalid request body: {e}")))?;
+
     tracing::info!("[ACP Install] Uninstalling agent: {}", req.agent_id);
 
     // Check if installed and get type
@@ -389,8 +671,11 @@ async fn uninstall_agent(
         .get_installed_info(&req.agent_id)
         .await
     {
-        if info.dist_type == DistributionType::Binary {
-            // Remove binary files
+        if matches!(
+            info.dist_type,
+            DistributionType::Binary | DistributionType::Git
+        ) {
+            // Remove binary files or the git checkout
             state
                 .acp_binary_manager
                 .uninstall(&req.agent_id)
@@ -413,6 +698,25 @@ async fn uninstall_agent(
     })))
 }
 
+async fn cancel_install(
+    state: &AppState,
+    agent_id: &str,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    if !state.acp_binary_manager.cancel_install(agent_id).await {
+        return Err(ServerError::NotFound(format!(
+            "No installation in progress for agent '{agent_id}'"
+        )));
+    }
+
+    tracing::info!("[ACP Install] Cancelling install of agent: {}", agent_id);
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "agentId": agent_id,
+        "cancelled": true,
+        "message": format!("Installation of '{agent_id}' is being cancelled")
+    })))
+}
+
 // ─── Helper Functions ──────────────────────────────────────────────────────
 
 /// Fetch the ACP registry from CDN
@@ -428,10 +732,15 @@ pub async fn fetch_registry() -> Result<AcpRegistry, ServerError> {
         )));
     }
 
-    let registry: AcpRegistry = response
+    let json: serde_json::Value = response
         .json()
         .await
         .map_err(|e| ServerError::Internal(format!("Failed to parse registry: {e}")))?;
+    let version = json.get("version").and_then(|v| v.as_str());
+    crate::acp::check_registry_version(version, crate::acp::registry_version_strict())
+        .map_err(ServerError::Internal)?;
+    let registry: AcpRegistry = serde_json::from_value(json)
+        .map_err(|e| ServerError::Internal(format!("Failed to parse registry: {e}")))?;
 
     Ok(registry)
 }
@@ -448,6 +757,9 @@ fn get_distribution_types(distribution: &serde_json::Value) -> Vec<String> {
     if distribution.get("binary").is_some() {
         types.push("binary".to_string());
     }
+    if distribution.get("git").is_some() {
+        types.push("git".to_string());
+    }
     types
 }
 
@@ -479,6 +791,14 @@ async fn get_agent_status(
                 resolved_distribution_type: Some("binary"),
             };
         }
+        if info.dist_type == DistributionType::Git {
+            return RegistryAgentStatus {
+                available: true,
+                installed: true,
+                uninstallable: true,
+                resolved_distribution_type: Some("git"),
+            };
+        }
     }
 
     let dist = &agent.distribution;
@@ -544,6 +864,51 @@ pub fn detect_platform() -> Option<String> {
     Some(platform.to_string())
 }
 
+/// GET /api/acp/capabilities - What this backend can install on the current machine
+async fn get_capabilities(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let rm = &state.acp_runtime_manager;
+    let npx_available = rm.is_runtime_available(&RuntimeType::Npx).await;
+    let uvx_available = rm.is_runtime_available(&RuntimeType::Uvx).await;
+    let platform = detect_platform();
+
+    let archive_formats = crate::acp::binary_manager::SUPPORTED_ARCHIVE_FORMATS
+        .iter()
+        .map(|(format, extensions)| {
+            serde_json::json!({
+                "format": format,
+                "extensions": extensions,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(serde_json::json!({
+        "archiveFormats": archive_formats,
+        "currentPlatform": AcpPaths::current_platform(),
+        "platform": platform,
+        "distributionTypes": [
+            { "type": "npx", "available": npx_available },
+            { "type": "uvx", "available": uvx_available },
+            { "type": "binary", "available": platform.is_some() },
+            { "type": "git", "available": shell_env::which("git").is_some() },
+        ],
+    })))
+}
+
+/// GET /api/acp/paths - Where ACP agent data lives on this machine
+async fn get_paths(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let paths = &state.acp_paths;
+    Json(serde_json::json!({
+        "baseDir": paths.base_dir(),
+        "source": paths.source(),
+        "registryCache": paths.registry_cache_path(),
+        "installedState": paths.installed_state_path(),
+        "downloads": paths.downloads_dir(),
+        "runtimes": paths.runtimes_dir(),
+    }))
+}
+
 /// POST /api/acp/registry - Force refresh registry cache
 async fn refresh_registry(
     State(_state): State<AppState>,
@@ -9,6 +9,7 @@ use axum::{
     Json, Router,
 };
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::sync::Arc;
 use tokio_stream::StreamExt as _;
@@ -16,15 +17,91 @@ use tokio_stream::StreamExt as _;
 use crate::acp;
 use crate::error::ServerError;
 use crate::state::AppState;
+use routa_core::acp::spawn_error::SpawnError;
 use routa_core::acp::terminal_manager::TerminalManager;
-use routa_core::acp::SessionLaunchOptions;
+use routa_core::acp::{provider_args, ProviderSettings, SessionLaunchOptions};
+use routa_core::client_gate::ClientInfo;
 use routa_core::models::agent::{Agent, AgentRole};
 use routa_core::orchestration::{OrchestratorConfig, RoutaOrchestrator, SpecialistConfig};
+use routa_core::role_providers::ProviderSource;
 use routa_core::storage::{LocalSessionProvider, SessionRecord};
 use routa_core::store::acp_session_store::{AcpSessionRow, CreateAcpSessionParams};
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/", get(acp_sse).post(acp_rpc))
+    Router::new().route("/", get(acp_sse).post(acp_rpc)).route(
+        "/providers/cache",
+        get(get_provider_cache).delete(clear_provider_cache),
+    )
+}
+
+/// Periodically kill ACP sessions idle for longer than
+/// `Settings::acp_session_ttl`. Holds only a weak reference to the state, so
+/// the task ends once the server's state is dropped.
+pub fn spawn_session_sweeper(state: &AppState) {
+    let Some(ttl) = state.settings.acp_session_ttl else {
+        return;
+    };
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let period =
+        routa_core::session_sweep::sweep_interval(state.settings.session_sweep_interval, ttl);
+    let state = Arc::downgrade(state);
+    runtime.spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let Some(state) = state.upgrade() else {
+                break;
+            };
+            let pruned = state.acp_manager.prune_idle_sessions(ttl).await;
+            if pruned.is_empty() {
+                continue;
+            }
+            tracing::info!(
+                "[ACP Route] Pruned {} idle session(s) (idle for more than {}s): {}",
+                pruned.len(),
+                ttl.as_secs(),
+                pruned.join(", ")
+            );
+        }
+    });
+}
+
+/// Fill the ACP warm pool for the working directory sessions in
+/// `workspace_id` default to, in the background. A no-op unless
+/// `ROUTA_ACP_WARM_POOL` configures a pool.
+pub fn prewarm_workspace(state: &AppState, workspace_id: &str) {
+    if state.settings.acp.warm_pool.sizes.is_empty() {
+        return;
+    }
+    let state = state.clone();
+    let workspace_id = workspace_id.to_string();
+    tokio::spawn(async move {
+        let cwd = resolve_session_cwd(&state, &workspace_id, None).await;
+        state.acp_manager.process_pool().prewarm(&cwd).await;
+    });
+}
+
+/// GET /api/acp/providers/cache — cached provider command lookups and their age.
+async fn get_provider_cache(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let cache = &state.command_availability;
+    Json(serde_json::json!({
+        "ttlMs": cache.ttl().as_millis() as u64,
+        "entries": cache.entries(),
+    }))
+}
+
+/// DELETE /api/acp/providers/cache — forget cached lookups so the next
+/// provider list checks every command again.
+async fn clear_provider_cache(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let cleared = state.command_availability.clear();
+    tracing::info!(
+        "[ACP Route] Cleared {} cached provider availability entries",
+        cleared
+    );
+    Json(serde_json::json!({ "cleared": cleared }))
 }
 
 fn has_explicit_cwd(value: Option<&str>) -> bool {
@@ -255,6 +332,32 @@ async fn acp_rpc(
                 .get("protocolVersion")
                 .and_then(|v| v.as_u64())
                 .unwrap_or(1);
+            // ACP sessions are created later by `session/new`, so the client
+            // is only attributed in the log here.
+            let client = ClientInfo::from_initialize_params(&params);
+            let client_label = client
+                .as_ref()
+                .map_or_else(|| "unknown".to_string(), ToString::to_string);
+            if let Err(message) = state.settings.client_version_gate.check(client.as_ref()) {
+                tracing::warn!(
+                    "[ACP Route] Rejected initialize from {}: {}",
+                    client_label,
+                    message
+                );
+                return Ok(AcpResponse::Json(Json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": -32600,
+                        "message": message
+                    }
+                }))));
+            }
+            tracing::info!(
+                "[ACP Route] Initialize from {} (protocol {})",
+                client_label,
+                protocol_version
+            );
 
             Ok(AcpResponse::Json(Json(serde_json::json!({
                 "jsonrpc": "2.0",
@@ -271,14 +374,23 @@ async fn acp_rpc(
         }
 
         "_providers/list" => {
-            use crate::shell_env;
-
             let presets = acp::get_presets();
+            let force_refresh = params
+                .get("forceRefresh")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let commands = presets
+                .iter()
+                .map(|preset| preset.command.clone())
+                .chain(["npx".to_string(), "uv".to_string()])
+                .collect();
+            let availability = command_availability(&state, commands, force_refresh).await;
+            let is_available = |command: &str| availability.get(command).copied().unwrap_or(false);
             let mut static_ids = std::collections::HashSet::new();
 
             let mut providers: Vec<serde_json::Value> = Vec::new();
             for preset in &presets {
-                let installed = shell_env::which(&preset.command).is_some();
+                let installed = is_available(&preset.command);
                 static_ids.insert(preset.name.clone());
 
                 providers.push(serde_json::json!({
@@ -293,8 +405,8 @@ async fn acp_rpc(
 
             // Merge registry agents (including those that overlap with static presets)
             // For overlapping agents, use a different ID to allow both versions to coexist
-            let npx_available = shell_env::which("npx").is_some();
-            let uvx_available = shell_env::which("uv").is_some();
+            let npx_available = is_available("npx");
+            let uvx_available = is_available("uv");
 
             if let Ok(response) =
                 reqwest::get("https://cdn.agentclientprotocol.com/registry/v1/latest/registry.json")
@@ -406,6 +518,32 @@ async fn acp_rpc(
                     }))));
                 }
             };
+            let provider_settings = match ProviderSettings::from_params(&params) {
+                Ok(value) => value,
+                Err(message) => {
+                    return Ok(AcpResponse::Json(Json(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": {
+                            "code": -32602,
+                            "message": message
+                        }
+                    }))));
+                }
+            };
+            let extra_args = match provider_args::extra_args_from_params(&params) {
+                Ok(value) => value,
+                Err(message) => {
+                    return Ok(AcpResponse::Json(Json(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": {
+                            "code": -32602,
+                            "message": message
+                        }
+                    }))));
+                }
+            };
             let requested_cwd = params
                 .get("cwd")
                 .and_then(|v| v.as_str())
@@ -433,6 +571,16 @@ async fn acp_rpc(
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_uppercase())
                 .or_else(|| specialist.as_ref().map(|s| s.role.as_str().to_string()));
+            // A custom launch names its own command, so only plain requests
+            // take the role's configured provider.
+            let (provider, provider_source) = if custom_provider_launch.is_some() {
+                (provider, ProviderSource::Request)
+            } else {
+                state
+                    .settings
+                    .role_providers
+                    .resolve(provider, role.as_deref())
+            };
             let model = params
                 .get("model")
                 .and_then(|v| v.as_str())
@@ -508,7 +656,7 @@ async fn acp_rpc(
                 parent_session_id
             );
 
-            let launch_options = SessionLaunchOptions {
+            let mut launch_options = SessionLaunchOptions {
                 specialist_id: specialist_id.clone(),
                 specialist_system_prompt: params
                     .get("systemPrompt")
@@ -518,8 +666,10 @@ async fn acp_rpc(
                     .map(str::to_string)
                     .or_else(|| specialist.as_ref().and_then(build_specialist_system_prompt)),
                 allowed_native_tools: derive_allowed_native_tools(specialist_id.as_deref()),
+                provider_args: extra_args,
                 ..SessionLaunchOptions::default()
             };
+            provider_settings.apply_to(&mut launch_options);
             let persisted_custom_provider_launch = custom_provider_launch.clone();
             let effective_provider = provider.clone().or_else(|| {
                 custom_provider_launch
@@ -659,14 +809,21 @@ async fn acp_rpc(
                     )
                     .await;
 
+                    let effective_settings = state
+                        .acp_manager
+                        .get_session(&session_id)
+                        .await
+                        .and_then(|session| session.provider_settings);
                     Ok(AcpResponse::Json(Json(serde_json::json!({
                         "jsonrpc": "2.0",
                         "id": id,
                         "result": {
                             "sessionId": session_id,
                             "provider": effective_provider.as_deref().unwrap_or("opencode"),
+                            "providerSource": provider_source.as_str(),
                             "role": role.as_deref().unwrap_or("CRAFTER"),
                             "routaAgentId": routa_agent_id,
+                            "providerSettings": effective_settings,
                         }
                     }))))
                 }
@@ -677,7 +834,8 @@ async fn acp_rpc(
                         "id": id,
                         "error": {
                             "code": -32000,
-                            "message": format!("Failed to create session: {}", e)
+                            "message": format!("Failed to create session: {}", e),
+                            "data": SpawnError::classify(e.clone()).to_json(),
                         }
                     }))))
                 }
@@ -698,6 +856,19 @@ async fn acp_rpc(
                     }))));
                 }
             };
+            let provider_settings = match ProviderSettings::from_params(&params) {
+                Ok(value) => value,
+                Err(message) => {
+                    return Ok(AcpResponse::Json(Json(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": {
+                            "code": -32602,
+                            "message": message
+                        }
+                    }))));
+                }
+            };
             let session_id = params.get("sessionId").and_then(|v| v.as_str());
 
             let session_id = match session_id {
@@ -724,6 +895,31 @@ async fn acp_rpc(
                 })
                 .unwrap_or_default();
 
+            if prompt_text.len() > state.settings.max_prompt_bytes {
+                tracing::warn!(
+                    "[ACP Route] session/prompt rejected: session={}, prompt_len={} exceeds limit {}",
+                    session_id,
+                    prompt_text.len(),
+                    state.settings.max_prompt_bytes
+                );
+                return Ok(AcpResponse::Json(Json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": -32602,
+                        "message": format!(
+                            "Prompt too large: {} bytes exceeds the maximum of {} bytes",
+                            prompt_text.len(),
+                            state.settings.max_prompt_bytes
+                        ),
+                        "data": {
+                            "promptBytes": prompt_text.len(),
+                            "maxPromptBytes": state.settings.max_prompt_bytes,
+                        }
+                    }
+                }))));
+            }
+
             tracing::info!(
                 "[ACP Route] session/prompt: session={}, prompt_len={}",
                 session_id,
@@ -814,7 +1010,7 @@ async fn acp_rpc(
                         .as_ref()
                         .map(|launch| launch.command.clone())
                 });
-                let launch_options = SessionLaunchOptions {
+                let mut launch_options = SessionLaunchOptions {
                     specialist_id: specialist_id.clone(),
                     specialist_system_prompt: params
                         .get("systemPrompt")
@@ -826,6 +1022,7 @@ async fn acp_rpc(
                     allowed_native_tools: derive_allowed_native_tools(specialist_id.as_deref()),
                     ..SessionLaunchOptions::default()
                 };
+                provider_settings.apply_to(&mut launch_options);
 
                 // Create the session
                 let create_result = if let Some(custom) = custom_provider_launch.clone() {
@@ -972,11 +1169,17 @@ async fn acp_rpc(
                             "id": id,
                             "error": {
                                 "code": -32000,
-                                "message": format!("Failed to auto-create session: {}", e)
+                                "message": format!("Failed to auto-create session: {}", e),
+                                "data": SpawnError::classify(e.clone()).to_json(),
                             }
                         }))));
                     }
                 }
+            } else if provider_settings != ProviderSettings::default() {
+                state
+                    .acp_manager
+                    .update_provider_settings(&session_id, &provider_settings)
+                    .await;
             }
 
             let session_record = state.acp_manager.get_session(&session_id).await;
@@ -1181,7 +1384,7 @@ async fn acp_rpc(
 
             // For ACP providers, use the traditional JSON response
             match state.acp_manager.prompt(&session_id, &prompt_text).await {
-                Ok(result) => {
+                Ok(mut result) => {
                     // Persist history and mark first_prompt_sent after turn completes
                     let _ = state
                         .acp_session_store
@@ -1194,6 +1397,15 @@ async fn acp_rpc(
                             .save_history(&session_id, &history)
                             .await;
                     }
+                    if let (Some(result), Some(settings)) = (
+                        result.as_object_mut(),
+                        session_record.and_then(|session| session.provider_settings),
+                    ) {
+                        result.insert(
+                            "providerSettings".to_string(),
+                            serde_json::to_value(settings).unwrap_or_default(),
+                        );
+                    }
                     Ok(AcpResponse::Json(Json(serde_json::json!({
                         "jsonrpc": "2.0",
                         "id": id,
@@ -1291,6 +1503,10 @@ async fn acp_rpc(
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
 
+            // Replayed to the client whichever way the agent is resumed, so a
+            // reload keeps the conversation even when the agent starts fresh.
+            let transcript = state.acp_manager.get_transcript(&session_id).await;
+
             if existing_session_alive {
                 return Ok(AcpResponse::Json(Json(serde_json::json!({
                     "jsonrpc": "2.0",
@@ -1300,7 +1516,8 @@ async fn acp_rpc(
                         "provider": provider,
                         "role": role.as_deref().unwrap_or("CRAFTER"),
                         "acpStatus": "ready",
-                        "resumeMode": "attached"
+                        "resumeMode": "attached",
+                        "transcript": transcript,
                     }
                 }))));
             }
@@ -1503,6 +1720,7 @@ async fn acp_rpc(
                             "resumeMode": resume_mode,
                             "resumeCapabilities": resume_capabilities,
                             "nativeResumeError": native_resume_error,
+                            "transcript": transcript,
                         }
                     }))))
                 }
@@ -1511,7 +1729,8 @@ async fn acp_rpc(
                     "id": id,
                     "error": {
                         "code": -32000,
-                        "message": format!("Failed to load session: {}", error)
+                        "message": format!("Failed to load session: {}", error),
+                        "data": SpawnError::classify(error.clone()).to_json(),
                     }
                 })))),
             }
@@ -1709,6 +1928,50 @@ async fn acp_rpc(
     }
 }
 
+/// Whether each of `commands` is on `PATH`, from `state.command_availability`
+/// where fresh; the rest are looked up off the async runtime and cached.
+/// `force_refresh` drops every cached result first.
+async fn command_availability(
+    state: &AppState,
+    commands: Vec<String>,
+    force_refresh: bool,
+) -> HashMap<String, bool> {
+    let cache = &state.command_availability;
+    if force_refresh {
+        cache.clear();
+    }
+    let mut availability = HashMap::new();
+    let mut missing = Vec::new();
+    for command in commands {
+        match cache.get(&command) {
+            Some(available) => {
+                availability.insert(command, available);
+            }
+            None => missing.push(command),
+        }
+    }
+    if missing.is_empty() {
+        return availability;
+    }
+
+    let looked_up = tokio::task::spawn_blocking(move || {
+        missing
+            .into_iter()
+            .map(|command| {
+                let available = crate::shell_env::which(&command).is_some();
+                (command, available)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+    for (command, available) in looked_up {
+        cache.insert(&command, available);
+        availability.insert(command, available);
+    }
+    availability
+}
+
 fn build_specialist_system_prompt(specialist: &SpecialistConfig) -> Option<String> {
     specialist.system_prompt_with_reminder()
 }
@@ -1974,10 +2237,11 @@ mod tests {
     use tokio::sync::broadcast;
 
     use super::{
-        acp_rpc, consolidate_replay_events, custom_provider_launch_from_row,
-        extract_custom_provider_launch, has_explicit_cwd, history_since_event_id,
-        resolve_session_cwd, should_attempt_native_resume, sse_event_id_from_rpc_message,
-        AcpResponse, CustomProviderLaunch,
+        acp_rpc, clear_provider_cache, command_availability, consolidate_replay_events,
+        custom_provider_launch_from_row, extract_custom_provider_launch, get_provider_cache,
+        has_explicit_cwd, history_since_event_id, resolve_session_cwd,
+        should_attempt_native_resume, sse_event_id_from_rpc_message, AcpResponse,
+        CustomProviderLaunch,
     };
     use routa_core::acp::terminal_manager::TerminalManager;
 
@@ -2229,6 +2493,104 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn session_new_rejects_extra_args_with_shell_metacharacters() {
+        let db = Database::open_in_memory().expect("db should open");
+        let state = Arc::new(AppStateInner::new(db));
+
+        let response = acp_rpc(
+            State(state),
+            Json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "session/new",
+                "params": {
+                    "workspaceId": "default",
+                    "provider": "opencode",
+                    "extraArgs": ["--model", "x; rm -rf ~"]
+                }
+            })),
+        )
+        .await
+        .expect("request should complete");
+
+        let value = json_response_value(response);
+        assert_eq!(value["error"]["code"].as_i64(), Some(-32602));
+        let message = value["error"]["message"].as_str().unwrap_or_default();
+        assert!(message.starts_with("Invalid extraArgs"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn initialize_rejects_clients_below_minimum_version() {
+        let db = Database::open_in_memory().expect("db should open");
+        let mut inner = AppStateInner::new(db);
+        inner.settings.client_version_gate =
+            routa_core::client_gate::ClientVersionGate::parse("routa-web=2.0.0");
+        let state = Arc::new(inner);
+        let initialize = |version: &str| {
+            Json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": 1,
+                    "clientInfo": { "name": "routa-web", "version": version }
+                }
+            }))
+        };
+
+        let outdated = acp_rpc(State(state.clone()), initialize("1.9.3"))
+            .await
+            .expect("request should complete");
+        let outdated = json_response_value(outdated);
+        assert_eq!(outdated["error"]["code"].as_i64(), Some(-32600));
+        assert!(outdated["error"]["message"]
+            .as_str()
+            .is_some_and(|message| message.contains("upgrade to 2.0.0")));
+
+        let current = acp_rpc(State(state), initialize("2.0.0"))
+            .await
+            .expect("request should complete");
+        let current = json_response_value(current);
+        assert_eq!(current["result"]["protocolVersion"].as_u64(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn session_prompt_rejects_oversized_prompt_before_spawn() {
+        let db = Database::open_in_memory().expect("db should open");
+        let mut inner = AppStateInner::new(db);
+        inner.settings.max_prompt_bytes = 8;
+        let state = Arc::new(inner);
+
+        let response = acp_rpc(
+            State(state.clone()),
+            Json(json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": "session/prompt",
+                "params": {
+                    "sessionId": "oversized-prompt-session",
+                    "prompt": [
+                        { "type": "text", "text": "hello" },
+                        { "type": "text", "text": "world" }
+                    ]
+                }
+            })),
+        )
+        .await
+        .expect("request should complete");
+
+        let value = json_response_value(response);
+        assert_eq!(value["error"]["code"].as_i64(), Some(-32602));
+        assert_eq!(value["error"]["data"]["promptBytes"].as_u64(), Some(11));
+        assert_eq!(value["error"]["data"]["maxPromptBytes"].as_u64(), Some(8));
+        assert!(state
+            .acp_manager
+            .get_session("oversized-prompt-session")
+            .await
+            .is_none());
+    }
+
     #[tokio::test]
     async fn session_respond_user_input_returns_explicit_no_pending_error() {
         let db = Database::open_in_memory().expect("db should open");
@@ -2421,4 +2783,42 @@ mod tests {
             Some("Persisted session not found: missing-session")
         );
     }
+
+    #[tokio::test]
+    async fn command_availability_is_cached_until_forced() {
+        let db = Database::open_in_memory().expect("db should open");
+        let state = Arc::new(AppStateInner::new(db));
+        let command = "routa-test-missing-provider-command".to_string();
+        state.command_availability.insert(&command, true);
+
+        let cached = command_availability(&state, vec![command.clone()], false).await;
+        assert_eq!(cached.get(&command), Some(&true));
+
+        let refreshed = command_availability(&state, vec![command.clone()], true).await;
+        assert_eq!(refreshed.get(&command), Some(&false));
+        assert_eq!(state.command_availability.get(&command), Some(false));
+    }
+
+    #[tokio::test]
+    async fn provider_cache_endpoints_list_and_clear_entries() {
+        let db = Database::open_in_memory().expect("db should open");
+        let state = Arc::new(AppStateInner::new(db));
+        state.command_availability.insert("opencode", true);
+        state.command_availability.insert("codex-acp", false);
+
+        let Json(listed) = get_provider_cache(State(state.clone())).await;
+        assert_eq!(listed["ttlMs"].as_u64(), Some(60_000));
+        let entries = listed["entries"].as_array().expect("entries");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["command"], "codex-acp");
+        assert_eq!(entries[0]["available"], false);
+        assert_eq!(entries[1]["command"], "opencode");
+        assert_eq!(entries[1]["expired"], false);
+        assert!(entries[1]["ageMs"].is_u64());
+
+        let Json(cleared) = clear_provider_cache(State(state.clone())).await;
+        assert_eq!(cleared["cleared"], 2);
+        assert_eq!(state.command_availability.get("opencode"), None);
+        assert!(state.command_availability.entries().is_empty());
+    }
 }
@@ -1,11 +1,14 @@
 use axum::{
     extract::{Query, State},
+    http::HeaderMap,
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::api::ndjson::{ndjson_response, wants_ndjson};
 use crate::error::ServerError;
 use crate::models::agent::{Agent, AgentRole, AgentStatus, ModelTier};
 use crate::state::AppState;
@@ -28,16 +31,19 @@ struct ListAgentsQuery {
     parent_id: Option<String>,
     #[allow(dead_code)]
     summary: Option<String>,
+    /// Stream agents as NDJSON instead of a buffered `{ "agents": [...] }` body.
+    stream: Option<bool>,
 }
 
 async fn list_agents(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Query(query): Query<ListAgentsQuery>,
-) -> Result<Json<serde_json::Value>, ServerError> {
+) -> Result<Response, ServerError> {
     // Next.js compatible: GET /api/agents?id=xxx returns single agent
     if let Some(id) = &query.id {
         let agent = state.agent_store.get(id).await?;
-        return Ok(Json(serde_json::json!(agent)));
+        return Ok(Json(serde_json::json!(agent)).into_response());
     }
 
     let workspace_id = query.workspace_id.as_deref().unwrap_or("default");
@@ -59,7 +65,11 @@ async fn list_agents(
         state.agent_store.list_by_workspace(workspace_id).await?
     };
 
-    Ok(Json(serde_json::json!({ "agents": agents })))
+    if wants_ndjson(&headers, query.stream) {
+        return ndjson_response(agents);
+    }
+
+    Ok(Json(serde_json::json!({ "agents": agents })).into_response())
 }
 
 /// GET /api/agents/{id} — REST-style single agent lookup
@@ -104,7 +114,7 @@ async fn create_agent(
 
     state.workspace_store.ensure_default().await?;
 
-    let agent = Agent::new(
+    let agent = Agent::try_new(
         uuid::Uuid::new_v4().to_string(),
         body.name,
         role,
@@ -112,7 +122,7 @@ async fn create_agent(
         body.parent_id,
         model_tier,
         body.metadata,
-    );
+    )?;
 
     state.agent_store.save(&agent).await?;
 
@@ -364,6 +364,12 @@ async fn create_canvas_from_specialist(
         } else {
             history_text
         };
+        let combined_output = state
+            .settings
+            .acp
+            .output_normalization
+            .apply(&combined_output)
+            .into_owned();
 
         let source =
             if let Some(value) = extract_canvas_source_from_specialist_output(&combined_output) {
@@ -1,10 +1,11 @@
 //! Clone API - /api/clone
 //!
-//! POST /api/clone - Clone a GitHub repository
+//! POST /api/clone - Clone a repository from GitHub, GitLab, Bitbucket, or a self-hosted server
 //! GET  /api/clone - List cloned repositories
 //! PATCH /api/clone - Switch branch
+//! GET  /api/clone/active - List clones in progress
 
-use axum::{routing::get, Json, Router};
+use axum::{extract::State, routing::get, Json, Router};
 use serde::Deserialize;
 
 use crate::error::ServerError;
@@ -12,7 +13,9 @@ use crate::git;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/", get(list_repos).post(clone_repo).patch(switch_branch))
+    Router::new()
+        .route("/", get(list_repos).post(clone_repo).patch(switch_branch))
+        .route("/active", get(list_active_clones))
 }
 
 /// Parse git clone error output and return a user-friendly message
@@ -91,6 +94,7 @@ struct CloneRequest {
 }
 
 async fn clone_repo(
+    State(state): State<AppState>,
     Json(body): Json<CloneRequest>,
 ) -> Result<Json<serde_json::Value>, ServerError> {
     let url = body
@@ -98,11 +102,17 @@ async fn clone_repo(
         .as_deref()
         .ok_or_else(|| ServerError::BadRequest("Missing 'url' field".into()))?;
 
-    let parsed = git::parse_github_url(url).ok_or_else(|| {
+    let parsed = git::parse_git_url(url).ok_or_else(|| {
         ServerError::BadRequest(
-            "Invalid GitHub URL. Expected: https://github.com/owner/repo or owner/repo".into(),
+            "Invalid repository URL. Expected: https://host/owner/repo, git@host:owner/repo.git, or owner/repo".into(),
         )
     })?;
+    let git_config = state
+        .settings
+        .clone_host_policy
+        .check(url)
+        .await?
+        .git_config_args();
 
     let repo_name = git::repo_to_dir_name(&parsed.owner, &parsed.repo);
     let base_dir = git::get_clone_base_dir();
@@ -116,8 +126,10 @@ async fn clone_repo(
         // Already cloned — pull latest
         tokio::task::spawn_blocking({
             let target_str = target_str.clone();
+            let git_config = git_config.clone();
             move || {
                 let _ = git::git_command()
+                    .args(&git_config)
                     .args(["pull", "--ff-only"])
                     .current_dir(&target_str)
                     .output();
@@ -144,14 +156,26 @@ async fn clone_repo(
     }
 
     // Clone the repository
-    let clone_url = format!("https://github.com/{}/{}.git", parsed.owner, parsed.repo);
+    let clone_url = parsed.https_url();
     let target_dir_str = target_dir.to_string_lossy().to_string();
+    let active = state
+        .clone_tracker
+        .start(&clone_url, &target_str)
+        .ok_or_else(|| {
+            ServerError::Conflict(format!(
+                "{}/{} is already being cloned",
+                parsed.owner, parsed.repo
+            ))
+        })?;
+    active.set_phase("cloning", None);
 
     let output = tokio::task::spawn_blocking({
         let clone_url = clone_url.clone();
         let target = target_dir_str.clone();
+        let git_config = git_config.clone();
         move || {
             git::git_command()
+                .args(&git_config)
                 .args(["clone", "--depth", "1", &clone_url, &target])
                 .output()
         }
@@ -167,17 +191,10 @@ async fn clone_repo(
         return Err(ServerError::Internal(error_msg));
     }
 
-    // Fetch all branches
-    let _ = tokio::task::spawn_blocking({
-        let ts = target_str.clone();
-        move || {
-            let _ = git::git_command()
-                .args(["fetch", "--all"])
-                .current_dir(&ts)
-                .output();
-        }
-    })
-    .await;
+    // Fetch all branches; a failure only warns, the clone itself succeeded
+    active.set_phase("fetching", None);
+    let fetch_warning =
+        super::clone_progress::fetch_all_with_retry(&target_str, None, &git_config, &[]).await;
 
     let info = tokio::task::spawn_blocking({
         let ts = target_str.clone();
@@ -193,9 +210,16 @@ async fn clone_repo(
         "branch": info.current,
         "branches": info.branches,
         "existed": false,
+        "fetchWarning": fetch_warning,
     })))
 }
 
+/// Clones currently running through either clone endpoint, with their
+/// latest phase and percent.
+async fn list_active_clones(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "clones": state.clone_tracker.list() }))
+}
+
 async fn list_repos() -> Result<Json<serde_json::Value>, ServerError> {
     let repos = tokio::task::spawn_blocking(git::list_cloned_repos)
         .await
@@ -0,0 +1,54 @@
+//! Blame API - /api/clone/blame
+//!
+//! GET /api/clone/blame?repoPath=...&file=... - Per-line provenance for a file
+
+use axum::{extract::Query, routing::get, Json, Router};
+use serde::Deserialize;
+
+use crate::api::repo_context::resolve_repo_dir_or_error;
+use crate::error::ServerError;
+use crate::git;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(get_blame))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlameQuery {
+    repo_path: Option<String>,
+    file: Option<String>,
+}
+
+async fn get_blame(Query(query): Query<BlameQuery>) -> Result<Json<git::FileBlame>, ServerError> {
+    let repo_path = query
+        .repo_path
+        .ok_or_else(|| ServerError::BadRequest("Missing repoPath".into()))?;
+    let file = query
+        .file
+        .filter(|file| !file.trim().is_empty())
+        .ok_or_else(|| ServerError::BadRequest("Missing file".into()))?;
+    let repo_path = resolve_repo_dir_or_error(&repo_path, "repoPath ")?
+        .to_string_lossy()
+        .to_string();
+
+    let blame = tokio::task::spawn_blocking(move || git::get_file_blame(&repo_path, &file))
+        .await
+        .map_err(|e| ServerError::Internal(e.to_string()))?
+        .map_err(|message| {
+            if message.contains("not found") {
+                ServerError::NotFound(message)
+            } else if message.contains("repository root")
+                || message.contains("not allowed")
+                || message.contains("too large")
+                || message.contains("cannot be empty")
+            {
+                ServerError::BadRequest(message)
+            } else {
+                ServerError::Internal(message)
+            }
+        })?;
+
+    Ok(Json(blame))
+}
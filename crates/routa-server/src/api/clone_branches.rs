@@ -1,7 +1,11 @@
 //! Branch Management API - /api/clone/branches
 //!
-//! GET   /api/clone/branches?repoPath=... - Get branch info
+//! GET   /api/clone/branches?repoPath=...&remote=... - Get branch info
 //! POST  /api/clone/branches - Fetch remote branches then return all
+//!
+//! `remote` selects which remote's branches are listed and compared against
+//! (defaults to `origin`). When POSTed, only that remote is fetched; without
+//! it every remote is fetched.
 //! PATCH /api/clone/branches - Checkout a branch
 //! DELETE /api/clone/branches - Delete a local branch
 
@@ -27,6 +31,25 @@ pub fn router() -> Router<AppState> {
 #[serde(rename_all = "camelCase")]
 struct BranchQuery {
     repo_path: Option<String>,
+    remote: Option<String>,
+}
+
+/// Resolve the requested remote (default `origin`) and reject names that are
+/// not configured, except for the implicit default.
+fn resolve_remote(repo_path: &str, remote: Option<String>) -> Result<String, ServerError> {
+    match remote.filter(|remote| !remote.trim().is_empty()) {
+        Some(remote) => {
+            git::validate_remote(repo_path, &remote).map_err(|message| {
+                if message.contains("not found") {
+                    ServerError::NotFound(message)
+                } else {
+                    ServerError::BadRequest(message)
+                }
+            })?;
+            Ok(remote)
+        }
+        None => Ok(git::DEFAULT_REMOTE.to_string()),
+    }
 }
 
 async fn get_branches(
@@ -39,23 +62,27 @@ async fn get_branches(
         .to_string_lossy()
         .to_string();
 
-    let (current, local, remote, status) = tokio::task::spawn_blocking({
+    let remote_name = query.remote;
+
+    let (remote_name, current, local, remote, status) = tokio::task::spawn_blocking({
         let rp = repo_path.clone();
         move || {
+            let remote_name = resolve_remote(&rp, remote_name)?;
             let current = git::get_current_branch(&rp).unwrap_or_else(|| "unknown".into());
             let local = git::list_local_branches(&rp);
-            let remote = git::list_remote_branches(&rp);
-            let status = git::get_branch_status(&rp, &current);
-            (current, local, remote, status)
+            let remote = git::list_remote_branches_for(&rp, &remote_name);
+            let status = git::get_branch_status_for_remote(&rp, &current, &remote_name);
+            Ok::<_, ServerError>((remote_name, current, local, remote, status))
         }
     })
     .await
-    .map_err(|e| ServerError::Internal(e.to_string()))?;
+    .map_err(|e| ServerError::Internal(e.to_string()))??;
 
     Ok(Json(serde_json::json!({
         "current": current,
         "local": local,
         "remote": remote,
+        "remoteName": remote_name,
         "status": status,
     })))
 }
@@ -64,6 +91,7 @@ async fn get_branches(
 #[serde(rename_all = "camelCase")]
 struct FetchBranchesBody {
     repo_path: Option<String>,
+    remote: Option<String>,
 }
 
 async fn fetch_branches(
@@ -76,24 +104,33 @@ async fn fetch_branches(
         .to_string_lossy()
         .to_string();
 
-    let (current, local, remote, status) = tokio::task::spawn_blocking({
+    let requested_remote = body.remote;
+
+    let (remote_name, current, local, remote, status) = tokio::task::spawn_blocking({
         let rp = repo_path.clone();
         move || {
-            git::fetch_remote(&rp);
+            let fetch_all = requested_remote.is_none();
+            let remote_name = resolve_remote(&rp, requested_remote)?;
+            if fetch_all {
+                git::fetch_remote(&rp);
+            } else {
+                git::fetch_from_remote(&rp, &remote_name);
+            }
             let current = git::get_current_branch(&rp).unwrap_or_else(|| "unknown".into());
             let local = git::list_local_branches(&rp);
-            let remote = git::list_remote_branches(&rp);
-            let status = git::get_branch_status(&rp, &current);
-            (current, local, remote, status)
+            let remote = git::list_remote_branches_for(&rp, &remote_name);
+            let status = git::get_branch_status_for_remote(&rp, &current, &remote_name);
+            Ok::<_, ServerError>((remote_name, current, local, remote, status))
         }
     })
     .await
-    .map_err(|e| ServerError::Internal(e.to_string()))?;
+    .map_err(|e| ServerError::Internal(e.to_string()))??;
 
     Ok(Json(serde_json::json!({
         "current": current,
         "local": local,
         "remote": remote,
+        "remoteName": remote_name,
         "status": status,
     })))
 }
@@ -0,0 +1,56 @@
+//! Commit Log API - /api/clone/log
+//!
+//! GET /api/clone/log?repoPath=...&limit=...&since=...&author=...&path=...
+//!   Commit history with per-file added/removed line counts
+
+use axum::{extract::Query, routing::get, Json, Router};
+use serde::Deserialize;
+
+use crate::api::repo_context::resolve_repo_dir_or_error;
+use crate::error::ServerError;
+use crate::git;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(get_log))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogQuery {
+    repo_path: Option<String>,
+    limit: Option<usize>,
+    since: Option<String>,
+    author: Option<String>,
+    path: Option<String>,
+}
+
+async fn get_log(Query(query): Query<LogQuery>) -> Result<Json<serde_json::Value>, ServerError> {
+    let repo_path = query
+        .repo_path
+        .ok_or_else(|| ServerError::BadRequest("Missing repoPath".into()))?;
+    let repo_path = resolve_repo_dir_or_error(&repo_path, "repoPath ")?
+        .to_string_lossy()
+        .to_string();
+
+    let filter = git::CommitLogFilter {
+        limit: query.limit,
+        since: query.since,
+        author: query.author,
+        path: query.path,
+    };
+
+    let commits =
+        tokio::task::spawn_blocking(move || git::get_commit_log_with_files(&repo_path, &filter))
+            .await
+            .map_err(|e| ServerError::Internal(e.to_string()))?
+            .map_err(|message| {
+                if message.contains("repository root") || message.contains("not allowed") {
+                    ServerError::BadRequest(message)
+                } else {
+                    ServerError::Internal(message)
+                }
+            })?;
+
+    Ok(Json(serde_json::json!({ "commits": commits })))
+}
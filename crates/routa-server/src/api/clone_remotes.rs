@@ -0,0 +1,125 @@
+//! Remotes API - /api/clone/remotes
+//!
+//! GET    /api/clone/remotes?repoPath=... - List configured git remotes
+//! POST   /api/clone/remotes - Add a remote (`repoPath`, `name`, `url`)
+//! DELETE /api/clone/remotes - Remove a remote (`repoPath`, `name`)
+
+use axum::{extract::Query, routing::get, Json, Router};
+use serde::Deserialize;
+
+use crate::api::repo_context::resolve_repo_dir_or_error;
+use crate::error::ServerError;
+use crate::git;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route(
+        "/",
+        get(list_remotes).post(add_remote).delete(remove_remote),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemotesQuery {
+    repo_path: Option<String>,
+}
+
+async fn list_remotes(
+    Query(query): Query<RemotesQuery>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let repo_path = query
+        .repo_path
+        .ok_or_else(|| ServerError::BadRequest("Missing repoPath".into()))?;
+    let repo_path = resolve_repo_dir_or_error(&repo_path, "repoPath ")?
+        .to_string_lossy()
+        .to_string();
+
+    let remotes = tokio::task::spawn_blocking(move || git::list_remotes(&repo_path))
+        .await
+        .map_err(|e| ServerError::Internal(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({ "remotes": remotes })))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddRemoteBody {
+    repo_path: Option<String>,
+    name: Option<String>,
+    url: Option<String>,
+}
+
+async fn add_remote(
+    Json(body): Json<AddRemoteBody>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let repo_path = body
+        .repo_path
+        .ok_or_else(|| ServerError::BadRequest("Missing repoPath".into()))?;
+    let name = body
+        .name
+        .ok_or_else(|| ServerError::BadRequest("Missing name".into()))?;
+    let url = body
+        .url
+        .ok_or_else(|| ServerError::BadRequest("Missing url".into()))?;
+    let repo_path = resolve_repo_dir_or_error(&repo_path, "repoPath ")?
+        .to_string_lossy()
+        .to_string();
+
+    let remotes = tokio::task::spawn_blocking(move || {
+        git::add_remote(&repo_path, &name, &url)?;
+        Ok::<_, String>(git::list_remotes(&repo_path))
+    })
+    .await
+    .map_err(|e| ServerError::Internal(e.to_string()))?
+    .map_err(remote_error)?;
+
+    Ok(Json(
+        serde_json::json!({ "success": true, "remotes": remotes }),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoveRemoteBody {
+    repo_path: Option<String>,
+    name: Option<String>,
+}
+
+async fn remove_remote(
+    Json(body): Json<RemoveRemoteBody>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let repo_path = body
+        .repo_path
+        .ok_or_else(|| ServerError::BadRequest("Missing repoPath".into()))?;
+    let name = body
+        .name
+        .ok_or_else(|| ServerError::BadRequest("Missing name".into()))?;
+    let repo_path = resolve_repo_dir_or_error(&repo_path, "repoPath ")?
+        .to_string_lossy()
+        .to_string();
+
+    let remotes = tokio::task::spawn_blocking(move || {
+        git::remove_remote(&repo_path, &name)?;
+        Ok::<_, String>(git::list_remotes(&repo_path))
+    })
+    .await
+    .map_err(|e| ServerError::Internal(e.to_string()))?
+    .map_err(remote_error)?;
+
+    Ok(Json(
+        serde_json::json!({ "success": true, "remotes": remotes }),
+    ))
+}
+
+fn remote_error(message: String) -> ServerError {
+    if message.contains("not found") {
+        ServerError::NotFound(message)
+    } else if message.contains("already exists") {
+        ServerError::Conflict(message)
+    } else if message.starts_with("Invalid") {
+        ServerError::BadRequest(message)
+    } else {
+        ServerError::Internal(message)
+    }
+}
@@ -1,17 +1,70 @@
-//! File Search API - /api/files/search
+//! File API - /api/files
 //!
 //! GET /api/files/search?q=query&repoPath=/path/to/repo&limit=20
-//!   Search files in a repository using fuzzy matching
-
-use axum::{extract::Query, routing::get, Json, Router};
+//!   Search files in a repository using fuzzy matching. `limit` defaults to
+//!   `ROUTA_FILE_SEARCH_DEFAULT_LIMIT` and is clamped to `ROUTA_FILE_SEARCH_MAX_LIMIT`.
+//!   The walk stops after `ROUTA_FILE_SEARCH_TIMEOUT_MS` (partial results are
+//!   flagged `timedOut`) or when the client goes away.
+//!   `includeHidden` (default `true`) controls dot-prefixed files and
+//!   directories. `.git` and the other names in the ignore list are skipped
+//!   either way; `includeHidden=false` additionally skips every other dotfile.
+//!   `respectGitignore` (default `true`) also skips what the repo's
+//!   `.gitignore` files, `.git/info/exclude`, and the global excludes file
+//!   ignore.
+//! GET /api/files/search/stream?q=query&repoPath=/path/to/repo&limit=20&minScore=1
+//!   SSE variant of `search`: `match` events as files are found and score at
+//!   least `minScore` (up to `limit` of them, in walk order rather than by
+//!   score), then a `done` event with the same totals and flags as `search`
+//! GET /api/files/grep?q=text&repoPath=/path/to/repo&limit=100&context=2
+//!   Search file contents: matching lines with 1-based line and column numbers
+//!   and `context` lines around them. Takes `caseSensitive` (default `false`),
+//!   `includeHidden`, and `respectGitignore`; binary files are skipped and
+//!   only the first 1 MiB of each file is searched
+//! GET /api/files/read?repoPath=/path/to/repo&path=src/main.rs
+//!   Read a file with its detected MIME type and language
+//! POST /api/files/read-batch  { repoPath, paths: [...] }
+//!   Read several files at once; each entry is `ok`, `binary`, `oversize`
+//!   (over the overall content cap), or `error`
+//! GET /api/files/tail?repoPath=/path/to/repo&file=logs/app.log&lines=100
+//!   Last N lines of a file (capped at 1000 lines / 1 MiB)
+//! GET /api/files/tail/stream?repoPath=/path/to/repo&file=logs/app.log&lines=100
+//!   SSE: the initial tail, then appended lines as the file grows
+
+use axum::{
+    extract::{Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use base64::Engine as _;
+use routa_core::file_types::{self, FileKind};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+mod batch;
+mod grep;
+mod search_stream;
+mod tail;
 
 use crate::error::ServerError;
 use crate::state::AppState;
 
+/// Text content beyond this is truncated.
+const MAX_TEXT_BYTES: u64 = 1024 * 1024;
+/// Images up to this size are returned inline as base64.
+const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
 pub fn router() -> Router<AppState> {
-    Router::new().route("/search", get(search_files))
+    Router::new()
+        .route("/search", get(search_files))
+        .route("/search/stream", get(search_stream::search_files_stream))
+        .route("/grep", get(grep::grep_files))
+        .route("/read", get(read_file))
+        .route("/read-batch", post(batch::read_file_batch))
+        .route("/tail", get(tail::tail_file))
+        .route("/tail/stream", get(tail::tail_file_stream))
 }
 
 #[derive(Debug, Deserialize)]
@@ -20,6 +73,8 @@ struct SearchQuery {
     q: Option<String>,
     repo_path: Option<String>,
     limit: Option<usize>,
+    include_hidden: Option<bool>,
+    respect_gitignore: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,14 +84,46 @@ struct FileMatch {
     full_path: String,
     name: String,
     score: i32,
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    mime_type: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<&'static str>,
+}
+
+impl FileMatch {
+    fn new(repo_dir: &Path, file_path: String, score: i32) -> Self {
+        let full_path = repo_dir.join(&file_path).to_string_lossy().to_string();
+        let name = Path::new(&file_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.clone());
+        let file_type = file_types::detect_from_path(Path::new(&file_path));
+        FileMatch {
+            path: file_path,
+            full_path,
+            name,
+            score,
+            mime_type: file_type.as_ref().map(|t| t.mime_type),
+            language: file_type.and_then(|t| t.language),
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct SearchResult {
     files: Vec<FileMatch>,
     total: usize,
     query: String,
     scanned: usize,
+    /// Effective limit after applying the default and maximum.
+    limit: usize,
+    /// Whether the requested limit exceeded the maximum and was reduced.
+    limit_clamped: bool,
+    /// The walk hit the scanned-file cap; results cover only part of the repo.
+    truncated: bool,
+    /// The walk hit its deadline; results are scored from the files seen so far.
+    timed_out: bool,
 }
 
 const IGNORE_PATTERNS: &[&str] = &[
@@ -101,58 +188,151 @@ fn should_ignore(name: &str) -> bool {
     IGNORE_PATTERNS.contains(&name)
 }
 
-fn walk_directory(dir: &Path, root: &Path, max_files: usize) -> Vec<String> {
-    let mut files = Vec::new();
-    walk_recursive(dir, root, &mut files, max_files);
-    files
+/// Maximum number of files collected by a single walk.
+const MAX_SCANNED_FILES: usize = 10000;
+
+/// Bounds on a directory walk: file count, wall-clock deadline, and a flag
+/// the request handler sets if it is dropped (e.g. the client disconnected).
+struct WalkControl {
+    max_files: usize,
+    /// Traverse and return dot-prefixed entries not covered by the ignore list.
+    include_hidden: bool,
+    /// Skip what `.gitignore`, `.git/info/exclude`, and the global excludes
+    /// file ignore, on top of the ignore list.
+    respect_gitignore: bool,
+    deadline: Option<Instant>,
+    cancelled: Arc<AtomicBool>,
 }
 
-fn walk_recursive(dir: &Path, root: &Path, files: &mut Vec<String>, max_files: usize) {
-    if files.len() >= max_files {
-        return;
+impl WalkControl {
+    fn new(max_files: usize) -> Self {
+        Self {
+            max_files,
+            include_hidden: true,
+            respect_gitignore: true,
+            deadline: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
     }
-    let entries = match std::fs::read_dir(dir) {
-        Ok(e) => e,
-        Err(_) => return,
-    };
-    for entry in entries.flatten() {
-        if files.len() >= max_files {
+
+    fn include_hidden(mut self, include_hidden: bool) -> Self {
+        self.include_hidden = include_hidden;
+        self
+    }
+
+    fn respect_gitignore(mut self, respect_gitignore: bool) -> Self {
+        self.respect_gitignore = respect_gitignore;
+        self
+    }
+
+    fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+}
+
+/// Sets the walk's cancellation flag when the handler future is dropped.
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Default)]
+struct Walk {
+    files: Vec<String>,
+    /// The walk stopped at `max_files`.
+    truncated: bool,
+    /// The walk stopped at the deadline or was cancelled.
+    timed_out: bool,
+}
+
+fn walk_directory(dir: &Path, root: &Path, control: &WalkControl) -> Walk {
+    walk_directory_with(dir, root, control, &mut |_| {})
+}
+
+/// Like [`walk_directory`], calling `visit` with each file's relative path as
+/// soon as it is found.
+fn walk_directory_with(
+    dir: &Path,
+    root: &Path,
+    control: &WalkControl,
+    visit: &mut dyn FnMut(&str),
+) -> Walk {
+    let mut walk = Walk::default();
+    walk_entries(dir, root, &mut walk, control, visit);
+    walk
+}
+
+fn walk_entries(
+    dir: &Path,
+    root: &Path,
+    walk: &mut Walk,
+    control: &WalkControl,
+    visit: &mut dyn FnMut(&str),
+) {
+    let mut builder = ignore::WalkBuilder::new(dir);
+    builder
+        .hidden(!control.include_hidden)
+        .parents(false)
+        .ignore(false)
+        .git_ignore(control.respect_gitignore)
+        .git_exclude(control.respect_gitignore)
+        .git_global(control.respect_gitignore)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(|entry| !should_ignore(&entry.file_name().to_string_lossy()));
+
+    for entry in builder.build().flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        if walk.files.len() >= control.max_files {
+            walk.truncated = true;
             return;
         }
-        let name = entry.file_name().to_string_lossy().to_string();
-        if should_ignore(&name) {
-            continue;
+        if control.cancelled.load(Ordering::Relaxed)
+            || control
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            walk.timed_out = true;
+            return;
         }
-        let path = entry.path();
-        if path.is_dir() {
-            walk_recursive(&path, root, files, max_files);
-        } else if path.is_file() {
-            if let Ok(rel) = path.strip_prefix(root) {
-                files.push(rel.to_string_lossy().to_string());
-            }
+        if let Ok(rel) = path.strip_prefix(root) {
+            let rel = rel.to_string_lossy().to_string();
+            visit(&rel);
+            walk.files.push(rel);
         }
     }
 }
 
 async fn search_files(
+    State(state): State<AppState>,
     Query(params): Query<SearchQuery>,
 ) -> Result<Json<SearchResult>, ServerError> {
     let query = params.q.unwrap_or_default();
     let repo_path = params
         .repo_path
         .ok_or_else(|| ServerError::BadRequest("Missing repoPath parameter".into()))?;
-    let limit = params.limit.unwrap_or(20);
-
-    let repo_dir = PathBuf::from(&repo_path);
-    if !repo_dir.exists() {
-        return Err(ServerError::NotFound(
-            "Repository path does not exist".into(),
-        ));
-    }
-
-    let files = tokio::task::spawn_blocking({
+    let (limit, limit_clamped) = state.settings.file_search_limits.resolve(params.limit);
+    let repo_dir = existing_repo_dir(repo_path)?;
+
+    let control = WalkControl::new(MAX_SCANNED_FILES)
+        .include_hidden(params.include_hidden.unwrap_or(true))
+        .respect_gitignore(params.respect_gitignore.unwrap_or(true))
+        .with_timeout(state.settings.file_search_limits.walk_timeout);
+    let _cancel_guard = CancelOnDrop(control.cancelled.clone());
+    let Walk {
+        files,
+        truncated,
+        timed_out,
+    } = tokio::task::spawn_blocking({
         let repo_dir = repo_dir.clone();
-        move || walk_directory(&repo_dir, &repo_dir, 10000)
+        move || walk_directory(&repo_dir, &repo_dir, &control)
     })
     .await
     .map_err(|e| ServerError::Internal(e.to_string()))?;
@@ -163,25 +343,17 @@ async fn search_files(
         let default_files: Vec<FileMatch> = files
             .into_iter()
             .take(limit)
-            .map(|file_path| {
-                let full_path = repo_dir.join(&file_path).to_string_lossy().to_string();
-                let name = Path::new(&file_path)
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_else(|| file_path.clone());
-                FileMatch {
-                    path: file_path,
-                    full_path,
-                    name,
-                    score: 0,
-                }
-            })
+            .map(|file_path| FileMatch::new(&repo_dir, file_path, 0))
             .collect();
         return Ok(Json(SearchResult {
             files: default_files,
             total: scanned,
             query: String::new(),
             scanned,
+            limit,
+            limit_clamped,
+            truncated,
+            timed_out,
         }));
     }
 
@@ -190,17 +362,7 @@ async fn search_files(
         .filter_map(|file_path| {
             let score = fuzzy_match(&query, &file_path);
             if score > 0 {
-                let full_path = repo_dir.join(&file_path).to_string_lossy().to_string();
-                let name = Path::new(&file_path)
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_else(|| file_path.clone());
-                Some(FileMatch {
-                    path: file_path,
-                    full_path,
-                    name,
-                    score,
-                })
+                Some(FileMatch::new(&repo_dir, file_path, score))
             } else {
                 None
             }
@@ -216,9 +378,149 @@ async fn search_files(
         total,
         query,
         scanned,
+        limit,
+        limit_clamped,
+        truncated,
+        timed_out,
     }))
 }
 
+fn existing_repo_dir(repo_path: String) -> Result<PathBuf, ServerError> {
+    let repo_dir = PathBuf::from(repo_path);
+    if !repo_dir.exists() {
+        return Err(ServerError::NotFound(
+            "Repository path does not exist".into(),
+        ));
+    }
+    Ok(repo_dir)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadQuery {
+    repo_path: Option<String>,
+    path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadResult {
+    path: String,
+    size: u64,
+    mime_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<&'static str>,
+    kind: FileKind,
+    /// `utf-8` for text, `base64` for inline images, absent when not returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encoding: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    truncated: bool,
+}
+
+/// Resolve `relative` inside `repo_dir`, rejecting paths that escape it.
+fn resolve_file_in_repo(repo_dir: &Path, relative: &str) -> Result<PathBuf, ServerError> {
+    let root = repo_dir
+        .canonicalize()
+        .map_err(|_| ServerError::NotFound("Repository path does not exist".into()))?;
+    let file = root
+        .join(relative)
+        .canonicalize()
+        .map_err(|_| ServerError::NotFound(format!("File not found: {relative}")))?;
+    if !file.starts_with(&root) {
+        return Err(ServerError::BadRequest(
+            "path must stay inside repoPath".into(),
+        ));
+    }
+    if !file.is_file() {
+        return Err(ServerError::BadRequest(format!("Not a file: {relative}")));
+    }
+    Ok(file)
+}
+
+fn read_file_with_type(file: &Path, relative: String) -> Result<ReadResult, ServerError> {
+    use std::io::Read;
+
+    let size = std::fs::metadata(file)
+        .map_err(|e| ServerError::Internal(e.to_string()))?
+        .len();
+    let limit = MAX_TEXT_BYTES.max(MAX_IMAGE_BYTES);
+    let mut bytes = Vec::new();
+    std::fs::File::open(file)
+        .and_then(|handle| handle.take(limit).read_to_end(&mut bytes))
+        .map_err(|e| ServerError::Internal(e.to_string()))?;
+
+    let file_type = file_types::detect(file, &bytes);
+    let (encoding, content, truncated) = match file_type.kind {
+        FileKind::Text => {
+            bytes.truncate(MAX_TEXT_BYTES as usize);
+            let text = String::from_utf8_lossy(&bytes).to_string();
+            (Some("utf-8"), Some(text), size > MAX_TEXT_BYTES)
+        }
+        FileKind::Image if size <= MAX_IMAGE_BYTES => (
+            Some("base64"),
+            Some(base64::engine::general_purpose::STANDARD.encode(&bytes)),
+            false,
+        ),
+        FileKind::Image | FileKind::Binary => (None, None, false),
+    };
+
+    Ok(ReadResult {
+        path: relative,
+        size,
+        mime_type: file_type.mime_type,
+        language: file_type.language,
+        kind: file_type.kind,
+        encoding,
+        content,
+        truncated,
+    })
+}
+
+/// Read a UTF-8 text file inside `repo_dir`, rejecting binary files and
+/// files over [`MAX_TEXT_BYTES`] instead of truncating them.
+pub(crate) fn read_text_file_in_repo(
+    repo_dir: &Path,
+    relative: &str,
+) -> Result<String, ServerError> {
+    let file = resolve_file_in_repo(repo_dir, relative)?;
+    let size = std::fs::metadata(&file)
+        .map_err(|e| ServerError::Internal(e.to_string()))?
+        .len();
+    if size > MAX_TEXT_BYTES {
+        return Err(ServerError::BadRequest(format!(
+            "File too large: {relative} is {size} bytes (max {MAX_TEXT_BYTES})"
+        )));
+    }
+    let bytes = std::fs::read(&file).map_err(|e| ServerError::Internal(e.to_string()))?;
+    if file_types::detect(&file, &bytes).kind != FileKind::Text {
+        return Err(ServerError::BadRequest(format!(
+            "Not a text file: {relative}"
+        )));
+    }
+    String::from_utf8(bytes)
+        .map_err(|_| ServerError::BadRequest(format!("File is not valid UTF-8: {relative}")))
+}
+
+async fn read_file(Query(params): Query<ReadQuery>) -> Result<Json<ReadResult>, ServerError> {
+    let repo_path = params
+        .repo_path
+        .ok_or_else(|| ServerError::BadRequest("Missing repoPath parameter".into()))?;
+    let relative = params
+        .path
+        .filter(|p| !p.trim().is_empty())
+        .ok_or_else(|| ServerError::BadRequest("Missing path parameter".into()))?;
+
+    tokio::task::spawn_blocking(move || {
+        let file = resolve_file_in_repo(Path::new(&repo_path), &relative)?;
+        read_file_with_type(&file, relative)
+    })
+    .await
+    .map_err(|e| ServerError::Internal(e.to_string()))?
+    .map(Json)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,14 +561,180 @@ mod tests {
         fs::write(root.join(".git/config"), "ignored").expect("write git config");
         fs::write(root.join("node_modules/pkg/index.js"), "ignored").expect("write node_modules");
 
-        let files = walk_directory(root, root, 1);
-        assert_eq!(files.len(), 1);
-        assert!(files[0].starts_with("src") && files[0].contains("a.rs"));
+        let walk = walk_directory(root, root, &WalkControl::new(1));
+        assert_eq!(walk.files.len(), 1);
+        assert!(walk.files[0].starts_with("src") && walk.files[0].contains("a.rs"));
+        assert!(walk.truncated);
 
-        let all = walk_directory(root, root, 10);
+        let all = walk_directory(root, root, &WalkControl::new(10)).files;
         assert!(all.iter().any(|p| p.contains("src") && p.contains("a.rs")));
         assert!(all.iter().any(|p| p.contains("src") && p.contains("b.rs")));
         assert!(!all.iter().any(|p| p.contains(".git")));
         assert!(!all.iter().any(|p| p.contains("node_modules")));
     }
+
+    #[test]
+    fn read_file_returns_type_and_encoding() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}\n").expect("write rs");
+        std::fs::write(dir.path().join("logo.png"), b"\x89PNG\r\n\x1a\n\0\0").expect("write png");
+        std::fs::write(dir.path().join("NOTES"), "plain text\n").expect("write text");
+
+        let rust = read_file_with_type(&dir.path().join("main.rs"), "main.rs".into()).unwrap();
+        assert_eq!(rust.mime_type, "text/x-rust");
+        assert_eq!(rust.language, Some("rust"));
+        assert_eq!(rust.content.as_deref(), Some("fn main() {}\n"));
+
+        let png = read_file_with_type(&dir.path().join("logo.png"), "logo.png".into()).unwrap();
+        assert_eq!(png.mime_type, "image/png");
+        assert_eq!(png.encoding, Some("base64"));
+        assert_eq!(png.content.as_deref(), Some("iVBORw0KGgoAAA=="));
+
+        let notes = read_file_with_type(&dir.path().join("NOTES"), "NOTES".into()).unwrap();
+        assert_eq!(notes.mime_type, "text/plain");
+        assert_eq!(notes.encoding, Some("utf-8"));
+    }
+
+    #[test]
+    fn read_text_file_in_repo_rejects_binary_and_oversized_files() {
+        let dir = tempdir().expect("tempdir");
+        fs::write(dir.path().join("notes.md"), "# Notes\n").expect("write md");
+        fs::write(dir.path().join("blob.bin"), [0u8, 159, 146, 150, 0, 1]).expect("write bin");
+        fs::write(
+            dir.path().join("big.txt"),
+            "a".repeat(MAX_TEXT_BYTES as usize + 1),
+        )
+        .expect("write big");
+
+        assert_eq!(
+            read_text_file_in_repo(dir.path(), "notes.md").unwrap(),
+            "# Notes\n"
+        );
+        assert!(read_text_file_in_repo(dir.path(), "blob.bin").is_err());
+        assert!(read_text_file_in_repo(dir.path(), "big.txt").is_err());
+        assert!(read_text_file_in_repo(dir.path(), "../outside.md").is_err());
+    }
+
+    #[test]
+    fn resolve_file_in_repo_rejects_escapes() {
+        let parent = tempfile::tempdir().expect("tempdir");
+        let repo = parent.path().join("repo");
+        std::fs::create_dir(&repo).expect("create repo");
+        std::fs::write(parent.path().join("secret.txt"), "nope").expect("write secret");
+
+        assert!(matches!(
+            resolve_file_in_repo(&repo, "../secret.txt"),
+            Err(ServerError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn walk_directory_stops_when_cancelled_or_past_deadline() {
+        let temp = tempdir().expect("tempdir should be created");
+        let root = temp.path();
+        fs::write(root.join("a.rs"), "a").expect("write a.rs");
+
+        let control = WalkControl::new(10);
+        control.cancelled.store(true, Ordering::Relaxed);
+        let walk = walk_directory(root, root, &control);
+        assert!(walk.files.is_empty());
+        assert!(walk.timed_out);
+
+        let expired = WalkControl::new(10).with_timeout(Duration::ZERO);
+        assert!(walk_directory(root, root, &expired).timed_out);
+
+        let walk = walk_directory(root, root, &WalkControl::new(10));
+        assert_eq!(walk.files, vec!["a.rs".to_string()]);
+        assert!(!walk.timed_out && !walk.truncated);
+    }
+
+    #[test]
+    fn walk_directory_include_hidden_still_skips_git() {
+        let temp = tempdir().expect("tempdir should be created");
+        let root = temp.path();
+        fs::create_dir_all(root.join(".github/workflows")).expect("create .github");
+        fs::create_dir_all(root.join(".git")).expect("create .git");
+        fs::write(root.join(".github/workflows/ci.yml"), "ci").expect("write ci.yml");
+        fs::write(root.join(".env.example"), "KEY=").expect("write .env.example");
+        fs::write(root.join(".git/HEAD"), "ref").expect("write HEAD");
+        fs::write(root.join("main.rs"), "fn main() {}").expect("write main.rs");
+
+        let mut visible = walk_directory(root, root, &WalkControl::new(10)).files;
+        visible.sort();
+        let ci = Path::new(".github")
+            .join("workflows")
+            .join("ci.yml")
+            .to_string_lossy()
+            .to_string();
+        assert_eq!(
+            visible,
+            vec![".env.example".to_string(), ci, "main.rs".to_string()]
+        );
+
+        let control = WalkControl::new(10).include_hidden(false);
+        assert_eq!(
+            walk_directory(root, root, &control).files,
+            vec!["main.rs".to_string()]
+        );
+    }
+
+    #[test]
+    fn walk_directory_honors_gitignore_unless_disabled() {
+        let temp = tempdir().expect("tempdir should be created");
+        let root = temp.path();
+        fs::create_dir_all(root.join("generated/out")).expect("create generated");
+        fs::create_dir_all(root.join("pkg")).expect("create pkg");
+        fs::write(root.join(".gitignore"), "generated/\n*.log\n!keep.log\n").expect("write");
+        fs::write(root.join("pkg/.gitignore"), "local.txt\n").expect("write nested");
+        fs::write(root.join("generated/out/bundle.js"), "x").expect("write bundle");
+        fs::write(root.join("debug.log"), "x").expect("write debug.log");
+        fs::write(root.join("keep.log"), "x").expect("write keep.log");
+        fs::write(root.join("pkg/local.txt"), "x").expect("write local.txt");
+        fs::write(root.join("pkg/lib.rs"), "x").expect("write lib.rs");
+
+        let control = WalkControl::new(10).include_hidden(false);
+        let files: Vec<_> = walk_directory(root, root, &control)
+            .files
+            .iter()
+            .map(|path| path.replace('\\', "/"))
+            .collect();
+        assert_eq!(files, vec!["keep.log", "pkg/lib.rs"]);
+
+        let control = WalkControl::new(10)
+            .include_hidden(false)
+            .respect_gitignore(false);
+        assert_eq!(walk_directory(root, root, &control).files.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn search_files_honors_include_hidden() {
+        let temp = tempdir().expect("tempdir should be created");
+        fs::write(temp.path().join(".env.example"), "KEY=").expect("write .env.example");
+        fs::write(temp.path().join("main.rs"), "fn main() {}").expect("write main.rs");
+        let db = crate::Database::open_in_memory().expect("in-memory db should open");
+        let state: AppState = Arc::new(crate::AppStateInner::new(db));
+
+        let search = |include_hidden| {
+            search_files(
+                State(state.clone()),
+                Query(SearchQuery {
+                    q: None,
+                    repo_path: Some(temp.path().to_string_lossy().to_string()),
+                    limit: None,
+                    include_hidden,
+                    respect_gitignore: None,
+                }),
+            )
+        };
+        let paths = |result: SearchResult| {
+            let mut paths: Vec<_> = result.files.into_iter().map(|file| file.path).collect();
+            paths.sort();
+            paths
+        };
+
+        let Json(all) = search(None).await.expect("search should succeed");
+        assert_eq!(paths(all), vec![".env.example", "main.rs"]);
+        let Json(visible) = search(Some(false)).await.expect("search should succeed");
+        assert_eq!(paths(visible), vec!["main.rs"]);
+    }
 }
@@ -0,0 +1,205 @@
+//! Batch reads for `POST /api/files/read-batch`.
+//!
+//! Each path is read like `/api/files/read` and reported on its own: files
+//! that cannot be read become `error` entries, binary files (and images too
+//! large to inline) become `binary` entries without content, and once the
+//! returned content reaches `MAX_BATCH_CONTENT_BYTES` the remaining files are
+//! `oversize` entries. Paths that are absolute or climb out of `repoPath`
+//! reject the whole request.
+
+use std::path::{Component, Path};
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use super::{read_file_with_type, resolve_file_in_repo, ReadResult};
+use crate::error::ServerError;
+
+/// Upper bound on the number of paths in one request.
+const MAX_BATCH_FILES: usize = 200;
+/// Upper bound on the content returned across all files.
+const MAX_BATCH_CONTENT_BYTES: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ReadBatchRequest {
+    repo_path: Option<String>,
+    #[serde(default)]
+    paths: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+enum BatchEntry {
+    Ok(ReadResult),
+    Binary(ReadResult),
+    #[serde(rename_all = "camelCase")]
+    Oversize {
+        path: String,
+        size: u64,
+    },
+    Error {
+        path: String,
+        error: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ReadBatchResult {
+    files: Vec<BatchEntry>,
+    /// Content bytes returned across all `ok` entries.
+    total_bytes: usize,
+    /// Whether any file was left out for exceeding the overall cap.
+    truncated: bool,
+}
+
+/// POST /api/files/read-batch
+pub(super) async fn read_file_batch(
+    Json(request): Json<ReadBatchRequest>,
+) -> Result<Json<ReadBatchResult>, ServerError> {
+    let repo_path = request
+        .repo_path
+        .filter(|p| !p.trim().is_empty())
+        .ok_or_else(|| ServerError::BadRequest("Missing repoPath".into()))?;
+    if request.paths.is_empty() {
+        return Err(ServerError::BadRequest("paths must not be empty".into()));
+    }
+    if request.paths.len() > MAX_BATCH_FILES {
+        return Err(ServerError::BadRequest(format!(
+            "Too many paths: {} (max {MAX_BATCH_FILES})",
+            request.paths.len()
+        )));
+    }
+    if let Some(path) = request.paths.iter().find(|path| !stays_inside(path)) {
+        return Err(ServerError::BadRequest(format!(
+            "path must stay inside repoPath: {path}"
+        )));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        read_batch(
+            Path::new(&repo_path),
+            request.paths,
+            MAX_BATCH_CONTENT_BYTES,
+        )
+    })
+    .await
+    .map_err(|e| ServerError::Internal(e.to_string()))?
+    .map(Json)
+}
+
+/// Whether `relative` is a relative path that never climbs above its root.
+/// Symlinks are checked when the file is resolved.
+fn stays_inside(relative: &str) -> bool {
+    let mut depth = 0usize;
+    for component in Path::new(relative).components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => match depth.checked_sub(1) {
+                Some(parent) => depth = parent,
+                None => return false,
+            },
+            Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+    true
+}
+
+fn read_batch(
+    repo_dir: &Path,
+    paths: Vec<String>,
+    max_content_bytes: usize,
+) -> Result<ReadBatchResult, ServerError> {
+    if !repo_dir.is_dir() {
+        return Err(ServerError::NotFound(
+            "Repository path does not exist".into(),
+        ));
+    }
+
+    let mut files = Vec::with_capacity(paths.len());
+    let mut total_bytes = 0usize;
+    let mut truncated = false;
+    for path in paths {
+        let read = resolve_file_in_repo(repo_dir, &path)
+            .and_then(|file| read_file_with_type(&file, path.clone()));
+        let entry = match read {
+            Err(error) => BatchEntry::Error {
+                path,
+                error: error.to_string(),
+            },
+            Ok(result) => match result.content.as_ref().map(String::len) {
+                None => BatchEntry::Binary(result),
+                Some(len) if total_bytes + len > max_content_bytes => {
+                    truncated = true;
+                    BatchEntry::Oversize {
+                        path,
+                        size: result.size,
+                    }
+                }
+                Some(len) => {
+                    total_bytes += len;
+                    BatchEntry::Ok(result)
+                }
+            },
+        };
+        files.push(entry);
+    }
+
+    Ok(ReadBatchResult {
+        files,
+        total_bytes,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_inside_rejects_escapes() {
+        assert!(stays_inside("src/main.rs"));
+        assert!(stays_inside("./src/../README.md"));
+        assert!(!stays_inside("../secret"));
+        assert!(!stays_inside("src/../../secret"));
+        assert!(!stays_inside("/etc/passwd"));
+    }
+
+    #[test]
+    fn read_batch_marks_each_file_and_caps_total_content() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        std::fs::write(root.join("a.txt"), "aaaa").expect("write a");
+        std::fs::write(root.join("b.txt"), "bbbbbbbb").expect("write b");
+        std::fs::write(root.join("c.txt"), "cc").expect("write c");
+        std::fs::write(root.join("blob.bin"), [0u8, 159, 146, 150, 0, 1]).expect("write bin");
+
+        let result = read_batch(
+            root,
+            vec![
+                "a.txt".into(),
+                "missing.txt".into(),
+                "blob.bin".into(),
+                "b.txt".into(),
+                "c.txt".into(),
+            ],
+            8,
+        )
+        .expect("batch read");
+        let value = serde_json::to_value(&result).expect("serialize");
+        let statuses = value["files"]
+            .as_array()
+            .expect("files")
+            .iter()
+            .map(|entry| entry["status"].as_str().unwrap_or_default().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(statuses, ["ok", "error", "binary", "oversize", "ok"]);
+        assert_eq!(value["files"][0]["content"], "aaaa");
+        assert_eq!(value["files"][3]["size"], 8);
+        assert_eq!(value["totalBytes"], 6);
+        assert_eq!(value["truncated"], true);
+    }
+}
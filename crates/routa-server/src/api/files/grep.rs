@@ -0,0 +1,346 @@
+//! Content search for `GET /api/files/grep`.
+//!
+//! Files come from the same walk as `search` (ignore list, `includeHidden`,
+//! `respectGitignore`, walk deadline, cancellation on disconnect); binary
+//! files are skipped. Only the first `MAX_GREP_FILE_BYTES` of a file are
+//! scanned, and the search stops once `limit` matching lines are found.
+//! Matching is a case-insensitive substring match unless `caseSensitive=true`.
+
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use routa_core::file_types::{self, FileKind};
+use serde::{Deserialize, Serialize};
+
+use super::{existing_repo_dir, walk_directory_with, CancelOnDrop, WalkControl, MAX_SCANNED_FILES};
+use crate::error::ServerError;
+use crate::state::AppState;
+
+/// Matching lines returned when the request gives no `limit`.
+const DEFAULT_GREP_MATCHES: usize = 100;
+/// Upper bound on `limit`.
+const MAX_GREP_MATCHES: usize = 1000;
+/// Bytes read from each file; the rest of a larger file is not searched.
+const MAX_GREP_FILE_BYTES: u64 = 1024 * 1024;
+/// Context lines on either side of a match when the request gives none.
+const DEFAULT_CONTEXT_LINES: usize = 2;
+const MAX_CONTEXT_LINES: usize = 10;
+/// Lines are cut to this many characters (minified files have huge lines).
+const MAX_LINE_CHARS: usize = 500;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GrepQuery {
+    q: Option<String>,
+    repo_path: Option<String>,
+    limit: Option<usize>,
+    case_sensitive: Option<bool>,
+    /// Lines of context before and after each match.
+    context: Option<usize>,
+    include_hidden: Option<bool>,
+    respect_gitignore: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GrepMatch {
+    path: String,
+    /// 1-based line number.
+    line: usize,
+    /// 1-based character column of the first occurrence on the line.
+    column: usize,
+    text: String,
+    before: Vec<String>,
+    after: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GrepResult {
+    matches: Vec<GrepMatch>,
+    query: String,
+    /// Files whose content was searched (binary files excluded).
+    files_scanned: usize,
+    files_matched: usize,
+    limit: usize,
+    limit_clamped: bool,
+    /// The search stopped at `limit` matches or the scanned-file cap.
+    truncated: bool,
+    /// The walk hit its deadline; results cover only the files seen so far.
+    timed_out: bool,
+}
+
+struct GrepOptions<'a> {
+    query: &'a str,
+    case_sensitive: bool,
+    context: usize,
+    limit: usize,
+}
+
+/// Walk `repo_dir` and collect matching lines. Stops the walk once `limit`
+/// matches are found.
+fn grep_repo(repo_dir: &Path, options: &GrepOptions<'_>, control: &WalkControl) -> GrepResult {
+    let needle = if options.case_sensitive {
+        options.query.to_string()
+    } else {
+        fold_case(options.query).0
+    };
+    let mut matches = Vec::new();
+    let mut files_scanned = 0;
+    let mut files_matched = 0;
+    let mut limit_reached = false;
+    let walk = walk_directory_with(repo_dir, repo_dir, control, &mut |file_path| {
+        if limit_reached {
+            return;
+        }
+        let Some(content) = read_text_prefix(&repo_dir.join(file_path)) else {
+            return;
+        };
+        files_scanned += 1;
+        let before = matches.len();
+        grep_content(file_path, &content, &needle, options, &mut matches);
+        if matches.len() > before {
+            files_matched += 1;
+        }
+        if matches.len() >= options.limit {
+            matches.truncate(options.limit);
+            limit_reached = true;
+            control.cancelled.store(true, Ordering::Relaxed);
+        }
+    });
+    GrepResult {
+        matches,
+        query: options.query.to_string(),
+        files_scanned,
+        files_matched,
+        limit: options.limit,
+        limit_clamped: false,
+        truncated: limit_reached || walk.truncated,
+        timed_out: walk.timed_out && !limit_reached,
+    }
+}
+
+/// Up to `MAX_GREP_FILE_BYTES` of a text file; `None` for binary or
+/// unreadable files.
+fn read_text_prefix(path: &Path) -> Option<String> {
+    if file_types::detect_from_path(path).is_some_and(|file_type| !file_type.is_text()) {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(MAX_GREP_FILE_BYTES)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    if file_types::detect(path, &bytes).kind != FileKind::Text {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn grep_content(
+    path: &str,
+    content: &str,
+    needle: &str,
+    options: &GrepOptions<'_>,
+    matches: &mut Vec<GrepMatch>,
+) {
+    let lines: Vec<&str> = content.lines().collect();
+    for (index, line) in lines.iter().enumerate() {
+        if matches.len() >= options.limit {
+            return;
+        }
+        let Some(column) = find_column(line, needle, options.case_sensitive) else {
+            continue;
+        };
+        let context_start = index.saturating_sub(options.context);
+        let context_end = (index + 1 + options.context).min(lines.len());
+        matches.push(GrepMatch {
+            path: path.to_string(),
+            line: index + 1,
+            column,
+            text: clip_line(line),
+            before: lines[context_start..index]
+                .iter()
+                .map(|l| clip_line(l))
+                .collect(),
+            after: lines[index + 1..context_end]
+                .iter()
+                .map(|l| clip_line(l))
+                .collect(),
+        });
+    }
+}
+
+/// `text` lower-cased char by char, with the index of the original character
+/// each byte of the result came from. Lower-casing can change the number of
+/// characters (`İ` becomes two), so offsets in the folded text do not carry
+/// over to the original directly.
+fn fold_case(text: &str) -> (String, Vec<usize>) {
+    let mut folded = String::with_capacity(text.len());
+    let mut origin = Vec::with_capacity(text.len());
+    for (index, c) in text.chars().enumerate() {
+        for lower in c.to_lowercase() {
+            folded.push(lower);
+            origin.extend(std::iter::repeat_n(index, lower.len_utf8()));
+        }
+    }
+    (folded, origin)
+}
+
+/// 1-based character column of the first occurrence of `needle` in `line`.
+/// Without `case_sensitive`, `needle` must already be folded with `fold_case`.
+fn find_column(line: &str, needle: &str, case_sensitive: bool) -> Option<usize> {
+    if case_sensitive {
+        return line
+            .find(needle)
+            .map(|offset| line[..offset].chars().count() + 1);
+    }
+    let (folded, origin) = fold_case(line);
+    folded.find(needle).map(|offset| origin[offset] + 1)
+}
+
+fn clip_line(line: &str) -> String {
+    match line.char_indices().nth(MAX_LINE_CHARS) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+/// GET /api/files/grep
+pub(super) async fn grep_files(
+    State(state): State<AppState>,
+    Query(params): Query<GrepQuery>,
+) -> Result<Json<GrepResult>, ServerError> {
+    let query = params
+        .q
+        .filter(|q| !q.trim().is_empty())
+        .ok_or_else(|| ServerError::BadRequest("Missing q parameter".into()))?;
+    let repo_path = params
+        .repo_path
+        .ok_or_else(|| ServerError::BadRequest("Missing repoPath parameter".into()))?;
+    let requested = params.limit.unwrap_or(DEFAULT_GREP_MATCHES).max(1);
+    let limit = requested.min(MAX_GREP_MATCHES);
+    let context = params
+        .context
+        .unwrap_or(DEFAULT_CONTEXT_LINES)
+        .min(MAX_CONTEXT_LINES);
+    let case_sensitive = params.case_sensitive.unwrap_or(false);
+    let repo_dir = existing_repo_dir(repo_path)?;
+
+    let control = WalkControl::new(MAX_SCANNED_FILES)
+        .include_hidden(params.include_hidden.unwrap_or(true))
+        .respect_gitignore(params.respect_gitignore.unwrap_or(true))
+        .with_timeout(state.settings.file_search_limits.walk_timeout);
+    let _cancel_guard = CancelOnDrop(control.cancelled.clone());
+    let mut result = tokio::task::spawn_blocking(move || {
+        let options = GrepOptions {
+            query: &query,
+            case_sensitive,
+            context,
+            limit,
+        };
+        grep_repo(&repo_dir, &options, &control)
+    })
+    .await
+    .map_err(|e| ServerError::Internal(e.to_string()))?;
+    result.limit_clamped = requested > limit;
+    Ok(Json(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn options(query: &str, limit: usize) -> GrepOptions<'_> {
+        GrepOptions {
+            query,
+            case_sensitive: false,
+            context: 1,
+            limit,
+        }
+    }
+
+    fn repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).expect("create src");
+        fs::create_dir_all(root.join("node_modules/pkg")).expect("create node_modules");
+        fs::write(
+            root.join("src/main.rs"),
+            "use std::io;\n\nfn main() {\n    let todo = TODO_LIST;\n}\n",
+        )
+        .expect("write main.rs");
+        fs::write(root.join("src/lib.rs"), "// todo: docs\n").expect("write lib.rs");
+        fs::write(root.join("logo.png"), b"\x89PNG\r\n\x1a\ntodo").expect("write png");
+        fs::write(root.join("blob.dat"), b"todo\0\0\0").expect("write blob");
+        fs::write(root.join("node_modules/pkg/index.js"), "todo").expect("write index.js");
+        dir
+    }
+
+    #[test]
+    fn finds_lines_with_context_and_skips_binaries_and_ignored_dirs() {
+        let dir = repo();
+        let result = grep_repo(dir.path(), &options("todo", 10), &WalkControl::new(10));
+
+        let found: Vec<_> = result
+            .matches
+            .iter()
+            .map(|m| (m.path.replace('\\', "/"), m.line, m.column))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("src/lib.rs".to_string(), 1, 4),
+                ("src/main.rs".to_string(), 4, 9),
+            ]
+        );
+        let main = &result.matches[1];
+        assert_eq!(main.text, "    let todo = TODO_LIST;");
+        assert_eq!(main.before, vec!["fn main() {".to_string()]);
+        assert_eq!(main.after, vec!["}".to_string()]);
+        assert_eq!((result.files_scanned, result.files_matched), (2, 2));
+        assert!(!result.truncated && !result.timed_out);
+    }
+
+    #[test]
+    fn case_sensitive_search_and_match_limit() {
+        let dir = repo();
+        let sensitive = GrepOptions {
+            case_sensitive: true,
+            ..options("TODO", 10)
+        };
+        let result = grep_repo(dir.path(), &sensitive, &WalkControl::new(10));
+        assert_eq!(result.matches.len(), 1);
+        assert_eq!(result.matches[0].line, 4);
+
+        let result = grep_repo(dir.path(), &options("todo", 1), &WalkControl::new(10));
+        assert_eq!(result.matches.len(), 1);
+        assert!(result.truncated);
+        assert!(!result.timed_out, "stopping at the limit is not a timeout");
+    }
+
+    #[test]
+    fn columns_count_original_characters_when_folding_changes_length() {
+        let needle = fold_case("TODO").0;
+        assert_eq!(find_column("İİ todo", &needle, false), Some(4));
+        assert_eq!(find_column("ÀB TODO", &needle, false), Some(4));
+        assert_eq!(find_column("İİ todo", "todo", true), Some(4));
+        assert_eq!(find_column("İİ todo", "TODO", true), None);
+    }
+
+    #[test]
+    fn long_lines_are_clipped() {
+        let line = "x".repeat(MAX_LINE_CHARS + 20);
+        let clipped = clip_line(&line);
+        assert_eq!(clipped.chars().count(), MAX_LINE_CHARS + 1);
+        assert!(clipped.ends_with('…'));
+        assert_eq!(clip_line("short"), "short");
+    }
+}
@@ -0,0 +1,188 @@
+//! Streaming fuzzy file search for `/api/files/search/stream`.
+//!
+//! The walk runs on a blocking thread and scores each file as it is found,
+//! sending matches over a channel so the first results reach the client
+//! before the walk finishes. Limits, the ignore list, `includeHidden`, the
+//! walk deadline, and cancellation on disconnect are the same as for the
+//! batch `search`.
+
+use std::convert::Infallible;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use serde::Deserialize;
+
+use super::{
+    existing_repo_dir, fuzzy_match, walk_directory_with, CancelOnDrop, FileMatch, Walk,
+    WalkControl, MAX_SCANNED_FILES,
+};
+use crate::error::ServerError;
+use crate::state::AppState;
+
+/// Matches buffered between the walk and the SSE writer.
+const MATCH_CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct StreamSearchQuery {
+    q: Option<String>,
+    repo_path: Option<String>,
+    limit: Option<usize>,
+    include_hidden: Option<bool>,
+    respect_gitignore: Option<bool>,
+    /// Lowest fuzzy score worth sending; at least 1.
+    min_score: Option<i32>,
+}
+
+/// Walk `repo_dir`, passing up to `limit` files that score at least
+/// `min_score` against `query` (every file when `query` is blank) to `send`,
+/// which returns `false` once nobody is listening. Returns the walk and the
+/// number of files that qualified.
+fn stream_matches(
+    repo_dir: &Path,
+    query: &str,
+    min_score: i32,
+    limit: usize,
+    control: &WalkControl,
+    send: &mut dyn FnMut(FileMatch) -> bool,
+) -> (Walk, usize) {
+    let query = query.trim();
+    let mut total = 0;
+    let walk = walk_directory_with(repo_dir, repo_dir, control, &mut |file_path| {
+        let score = if query.is_empty() {
+            0
+        } else {
+            fuzzy_match(query, file_path)
+        };
+        if !query.is_empty() && score < min_score {
+            return;
+        }
+        total += 1;
+        if total <= limit && !send(FileMatch::new(repo_dir, file_path.to_string(), score)) {
+            control.cancelled.store(true, Ordering::Relaxed);
+        }
+    });
+    (walk, total)
+}
+
+pub(super) async fn search_files_stream(
+    State(state): State<AppState>,
+    Query(params): Query<StreamSearchQuery>,
+) -> Result<Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>>, ServerError> {
+    let query = params.q.unwrap_or_default();
+    let repo_path = params
+        .repo_path
+        .ok_or_else(|| ServerError::BadRequest("Missing repoPath parameter".into()))?;
+    let (limit, limit_clamped) = state.settings.file_search_limits.resolve(params.limit);
+    let min_score = params.min_score.unwrap_or(1).max(1);
+    let repo_dir = existing_repo_dir(repo_path)?;
+
+    let control = WalkControl::new(MAX_SCANNED_FILES)
+        .include_hidden(params.include_hidden.unwrap_or(true))
+        .respect_gitignore(params.respect_gitignore.unwrap_or(true))
+        .with_timeout(state.settings.file_search_limits.walk_timeout);
+    let cancel_guard = CancelOnDrop(control.cancelled.clone());
+    let (tx, mut rx) = tokio::sync::mpsc::channel(MATCH_CHANNEL_CAPACITY);
+    let walker = tokio::task::spawn_blocking({
+        let query = query.clone();
+        move || {
+            stream_matches(&repo_dir, &query, min_score, limit, &control, &mut |file| {
+                tx.blocking_send(file).is_ok()
+            })
+        }
+    });
+
+    let stream = async_stream::stream! {
+        // Dropping the stream (client gone) stops the walk.
+        let _cancel_guard = cancel_guard;
+        while let Some(file) = rx.recv().await {
+            let payload = serde_json::json!({ "type": "match", "file": file });
+            yield Ok(Event::default().data(payload.to_string()));
+        }
+        let payload = match walker.await {
+            Ok((walk, total)) => serde_json::json!({
+                "type": "done",
+                "query": query.trim(),
+                "total": total,
+                "scanned": walk.files.len(),
+                "limit": limit,
+                "limitClamped": limit_clamped,
+                "truncated": walk.truncated,
+                "timedOut": walk.timed_out,
+            }),
+            Err(e) => serde_json::json!({ "type": "error", "error": e.to_string() }),
+        };
+        yield Ok(Event::default().data(payload.to_string()));
+    };
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).expect("create src");
+        fs::create_dir_all(root.join("node_modules/pkg")).expect("create node_modules");
+        fs::write(root.join("src/main.rs"), "").expect("write main.rs");
+        fs::write(root.join("src/mod.rs"), "").expect("write mod.rs");
+        fs::write(root.join("README.md"), "").expect("write README.md");
+        fs::write(root.join("node_modules/pkg/main.js"), "").expect("write main.js");
+        dir
+    }
+
+    #[test]
+    fn stream_matches_sends_qualifying_files_up_to_the_limit() {
+        let dir = repo();
+        let mut sent = Vec::new();
+        let (walk, total) = stream_matches(
+            dir.path(),
+            "main",
+            1,
+            10,
+            &WalkControl::new(10),
+            &mut |file| {
+                sent.push(file.path);
+                true
+            },
+        );
+        assert_eq!(sent, vec!["src/main.rs".to_string()]);
+        assert_eq!(total, 1);
+        assert_eq!(walk.files.len(), 3, "node_modules is skipped");
+
+        let mut sent = 0;
+        let (_, total) = stream_matches(dir.path(), " ", 1, 2, &WalkControl::new(10), &mut |_| {
+            sent += 1;
+            true
+        });
+        assert_eq!((sent, total), (2, 3), "blank query lists files up to limit");
+    }
+
+    #[test]
+    fn stream_matches_respects_min_score_and_stops_when_receiver_is_gone() {
+        let dir = repo();
+        let (_, total) = stream_matches(
+            dir.path(),
+            "rs",
+            1000,
+            10,
+            &WalkControl::new(10),
+            &mut |_| true,
+        );
+        assert_eq!(total, 0);
+
+        let control = WalkControl::new(10);
+        let (walk, _) = stream_matches(dir.path(), " ", 1, 10, &control, &mut |_| false);
+        assert!(control.cancelled.load(Ordering::Relaxed));
+        assert!(walk.timed_out);
+        assert_eq!(walk.files.len(), 1);
+    }
+}
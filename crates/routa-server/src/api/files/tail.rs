@@ -0,0 +1,369 @@
+//! Log tailing for `/api/files/tail` and `/api/files/tail/stream`.
+//!
+//! The initial tail reads at most `MAX_TAIL_BYTES` from the end of the file.
+//! The stream polls the file for growth: appended bytes are emitted as whole
+//! lines, a shrinking file (truncation) or a new inode at the same path
+//! (rotation) emits a `reset` event and restarts from the beginning, and a
+//! missing file emits `missing` until it reappears. Output that never ends
+//! a line (progress bars, binary logs) is flushed as a line once it reaches
+//! `MAX_PARTIAL_LINE_BYTES`.
+//!
+//! Polling is used instead of filesystem notifications on purpose: rotation
+//! and truncation have to be detected by comparing inode and size anyway,
+//! notification backends differ per platform and miss changes on network
+//! and container-mounted filesystems, and a stat every `POLL_INTERVAL` per
+//! open stream is cheap. Each poll runs on the blocking pool.
+
+use std::convert::Infallible;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use axum::{
+    extract::Query,
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use super::resolve_file_in_repo;
+use crate::error::ServerError;
+
+const DEFAULT_TAIL_LINES: usize = 100;
+const MAX_TAIL_LINES: usize = 1000;
+/// Upper bound on bytes read for the initial tail.
+const MAX_TAIL_BYTES: u64 = 1024 * 1024;
+/// Upper bound on bytes read per poll, so a burst of output is spread over polls.
+const MAX_CHUNK_BYTES: u64 = 256 * 1024;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Longest incomplete last line kept between polls before it is flushed.
+const MAX_PARTIAL_LINE_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct TailQuery {
+    repo_path: Option<String>,
+    file: Option<String>,
+    lines: Option<usize>,
+}
+
+impl TailQuery {
+    /// Validate the query and resolve the file inside the repository.
+    fn resolve(self) -> Result<(PathBuf, String, usize), ServerError> {
+        let repo_path = self
+            .repo_path
+            .ok_or_else(|| ServerError::BadRequest("Missing repoPath parameter".into()))?;
+        let relative = self
+            .file
+            .filter(|f| !f.trim().is_empty())
+            .ok_or_else(|| ServerError::BadRequest("Missing file parameter".into()))?;
+        let lines = self.lines.unwrap_or(DEFAULT_TAIL_LINES).min(MAX_TAIL_LINES);
+        let file = resolve_file_in_repo(Path::new(&repo_path), &relative)?;
+        Ok((file, relative, lines))
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct TailResult {
+    file: String,
+    lines: Vec<String>,
+    /// File size at the time of reading; the stream continues from here.
+    size: u64,
+    /// Only the last `MAX_TAIL_BYTES` were read, so earlier lines may exist.
+    truncated: bool,
+}
+
+/// Read the last `lines` lines of `path`, looking at no more than
+/// `MAX_TAIL_BYTES` from the end.
+fn read_tail(path: &Path, relative: String, lines: usize) -> std::io::Result<TailResult> {
+    let mut handle = std::fs::File::open(path)?;
+    let size = handle.metadata()?.len();
+    let start = size.saturating_sub(MAX_TAIL_BYTES);
+    handle.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    handle.take(size - start).read_to_end(&mut bytes)?;
+
+    let text = String::from_utf8_lossy(&bytes);
+    let mut all: Vec<&str> = text.lines().collect();
+    if start > 0 && !all.is_empty() {
+        // The first line was cut by the byte window.
+        all.remove(0);
+    }
+    let skip = all.len().saturating_sub(lines);
+    Ok(TailResult {
+        file: relative,
+        lines: all[skip..].iter().map(|line| line.to_string()).collect(),
+        size,
+        truncated: start > 0,
+    })
+}
+
+pub(super) async fn tail_file(
+    Query(params): Query<TailQuery>,
+) -> Result<Json<TailResult>, ServerError> {
+    tokio::task::spawn_blocking(move || {
+        let (file, relative, lines) = params.resolve()?;
+        read_tail(&file, relative, lines).map_err(|e| ServerError::Internal(e.to_string()))
+    })
+    .await
+    .map_err(|e| ServerError::Internal(e.to_string()))?
+    .map(Json)
+}
+
+/// Identity of the file currently at a path, used to detect rotation.
+#[cfg(unix)]
+fn file_id(metadata: &std::fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &std::fs::Metadata) -> Option<u64> {
+    None
+}
+
+/// Follows a file from a byte offset, buffering any incomplete last line.
+struct Follower {
+    path: PathBuf,
+    offset: u64,
+    id: Option<u64>,
+    /// Raw bytes after the last newline; decoded only once a line is complete
+    /// so a character split across reads is not mangled.
+    partial: Vec<u8>,
+    missing: bool,
+}
+
+enum Poll {
+    Lines(Vec<String>),
+    Reset,
+    Missing,
+    Idle,
+}
+
+impl Follower {
+    fn new(path: PathBuf, offset: u64) -> Self {
+        let id = std::fs::metadata(&path).ok().and_then(|m| file_id(&m));
+        Self {
+            path,
+            offset,
+            id,
+            partial: Vec::new(),
+            missing: false,
+        }
+    }
+
+    fn poll(&mut self) -> Poll {
+        let metadata = match std::fs::metadata(&self.path) {
+            Ok(metadata) => metadata,
+            Err(_) if self.missing => return Poll::Idle,
+            Err(_) => {
+                self.missing = true;
+                return Poll::Missing;
+            }
+        };
+        let id = file_id(&metadata);
+        let rotated = self.missing || id != self.id;
+        if rotated || metadata.len() < self.offset {
+            self.missing = false;
+            self.id = id;
+            self.offset = 0;
+            self.partial.clear();
+            return Poll::Reset;
+        }
+        if metadata.len() == self.offset {
+            return Poll::Idle;
+        }
+
+        let mut bytes = Vec::new();
+        let read = std::fs::File::open(&self.path).and_then(|mut handle| {
+            handle.seek(SeekFrom::Start(self.offset))?;
+            handle.take(MAX_CHUNK_BYTES).read_to_end(&mut bytes)
+        });
+        let Ok(read) = read else {
+            return Poll::Idle;
+        };
+        self.offset += read as u64;
+        self.partial.extend_from_slice(&bytes);
+
+        let mut lines = Vec::new();
+        if let Some(last_newline) = self.partial.iter().rposition(|&b| b == b'\n') {
+            let rest = self.partial.split_off(last_newline + 1);
+            let complete = std::mem::replace(&mut self.partial, rest);
+            lines.extend(
+                String::from_utf8_lossy(&complete)
+                    .lines()
+                    .map(str::to_string),
+            );
+        }
+        if self.partial.len() >= MAX_PARTIAL_LINE_BYTES {
+            let rest = self.partial.split_off(utf8_boundary(&self.partial));
+            let flushed = std::mem::replace(&mut self.partial, rest);
+            lines.push(String::from_utf8_lossy(&flushed).into_owned());
+        }
+        if lines.is_empty() {
+            Poll::Idle
+        } else {
+            Poll::Lines(lines)
+        }
+    }
+}
+
+/// Length of the longest prefix of `bytes` that does not end partway through
+/// a UTF-8 encoded character.
+fn utf8_boundary(bytes: &[u8]) -> usize {
+    let len = bytes.len();
+    for back in 1..=len.min(4) {
+        let byte = bytes[len - back];
+        if byte & 0xC0 != 0x80 {
+            let width = match byte {
+                0xF0..=0xFF => 4,
+                0xE0..=0xEF => 3,
+                0xC0..=0xDF => 2,
+                _ => 1,
+            };
+            return if width > back { len - back } else { len };
+        }
+    }
+    len
+}
+
+pub(super) async fn tail_file_stream(
+    Query(params): Query<TailQuery>,
+) -> Result<Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>>, ServerError> {
+    let (file, tail) = tokio::task::spawn_blocking(move || {
+        let (file, relative, lines) = params.resolve()?;
+        let tail =
+            read_tail(&file, relative, lines).map_err(|e| ServerError::Internal(e.to_string()))?;
+        Ok::<_, ServerError>((file, tail))
+    })
+    .await
+    .map_err(|e| ServerError::Internal(e.to_string()))??;
+
+    let mut follower = Follower::new(file, tail.size);
+    let initial = serde_json::json!({
+        "type": "tail",
+        "file": tail.file,
+        "lines": tail.lines,
+        "truncated": tail.truncated,
+    });
+    let stream = async_stream::stream! {
+        yield Ok(Event::default().data(initial.to_string()));
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let polled = tokio::task::spawn_blocking(move || {
+                let poll = follower.poll();
+                (follower, poll)
+            })
+            .await;
+            let poll = match polled {
+                Ok((returned, poll)) => {
+                    follower = returned;
+                    poll
+                }
+                Err(_) => break,
+            };
+            let payload = match poll {
+                Poll::Lines(lines) => serde_json::json!({ "type": "lines", "lines": lines }),
+                Poll::Reset => serde_json::json!({ "type": "reset" }),
+                Poll::Missing => serde_json::json!({ "type": "missing" }),
+                Poll::Idle => continue,
+            };
+            yield Ok(Event::default().data(payload.to_string()));
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn read_tail_returns_last_lines() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "one\ntwo\nthree\n").expect("write log");
+
+        let tail = read_tail(&path, "app.log".into(), 2).unwrap();
+        assert_eq!(tail.lines, vec!["two", "three"]);
+        assert_eq!(tail.size, 14);
+        assert!(!tail.truncated);
+    }
+
+    #[test]
+    fn follower_emits_appended_lines_and_resets_on_truncation() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "old\n").expect("write log");
+
+        let mut follower = Follower::new(path.clone(), 4);
+        assert!(matches!(follower.poll(), Poll::Idle));
+
+        let mut handle = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open log");
+        handle.write_all(b"new\npart").expect("append");
+        match follower.poll() {
+            Poll::Lines(lines) => assert_eq!(lines, vec!["new"]),
+            _ => panic!("expected appended lines"),
+        }
+        handle.write_all(b"ial\n").expect("append");
+        match follower.poll() {
+            Poll::Lines(lines) => assert_eq!(lines, vec!["partial"]),
+            _ => panic!("expected completed line"),
+        }
+
+        std::fs::File::create(&path).expect("truncate log");
+        assert!(matches!(follower.poll(), Poll::Reset));
+        assert!(matches!(follower.poll(), Poll::Idle));
+    }
+
+    #[test]
+    fn follower_decodes_characters_split_across_reads() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "").expect("write log");
+        let mut follower = Follower::new(path.clone(), 0);
+
+        let mut handle = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open log");
+        let line = "café ✓\n".as_bytes();
+        handle.write_all(&line[..4]).expect("append");
+        assert!(matches!(follower.poll(), Poll::Idle));
+        handle.write_all(&line[4..]).expect("append");
+        match follower.poll() {
+            Poll::Lines(lines) => assert_eq!(lines, vec!["café ✓"]),
+            _ => panic!("expected completed line"),
+        }
+    }
+
+    #[test]
+    fn follower_flushes_a_line_that_never_ends() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("progress.log");
+        std::fs::write(&path, "").expect("write log");
+        let mut follower = Follower::new(path.clone(), 0);
+
+        let mut output = "#".repeat(MAX_PARTIAL_LINE_BYTES - 1).into_bytes();
+        // End on the first byte of a two-byte character.
+        output.push("é".as_bytes()[0]);
+        std::fs::write(&path, &output).expect("append");
+        match follower.poll() {
+            Poll::Lines(lines) => {
+                assert_eq!(lines.len(), 1);
+                assert_eq!(lines[0].len(), MAX_PARTIAL_LINE_BYTES - 1);
+            }
+            _ => panic!("expected the long line to be flushed"),
+        }
+        assert_eq!(follower.partial, &"é".as_bytes()[..1]);
+        assert_eq!(utf8_boundary("ab✓".as_bytes()), 5);
+        assert_eq!(utf8_boundary(&"ab✓".as_bytes()[..4]), 2);
+    }
+}
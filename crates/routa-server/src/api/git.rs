@@ -153,7 +153,8 @@ fn server_error_message(error: ServerError) -> String {
         | ServerError::BadRequest(message)
         | ServerError::Conflict(message)
         | ServerError::Internal(message)
-        | ServerError::NotImplemented(message) => message,
+        | ServerError::NotImplemented(message)
+        | ServerError::Unauthorized(message) => message,
     }
 }
 
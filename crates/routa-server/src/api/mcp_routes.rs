@@ -2,21 +2,66 @@
 //!
 //! Uses the official rmcp `StreamableHttpService` for session management,
 //! SSE framing, and JSON-RPC transport behavior.
+//!
+//! A POST body that is a JSON array is treated as a JSON-RPC batch: each
+//! element is dispatched through the service on its own and the responses are
+//! returned as one JSON array, leaving out notifications.
+//!
+//! Notes in the session's workspace are exposed as MCP resources at
+//! `routa://note/{id}` (`resources/list`, `resources/read`).
+//!
+//! A session's workspace comes from `workspaceId` in the `initialize` params,
+//! else the `Routa-Workspace-Id` header, else the `wsId` query parameter, else
+//! `default`; tool calls that omit `workspaceId` use it. A workspace named in
+//! the `initialize` params must already exist.
+//!
+//! `clientInfo` from `initialize` is recorded on the session for logs and
+//! `GET /api/mcp/sessions`; clients older than `ROUTA_MIN_CLIENT_VERSION`
+//! are rejected at `initialize`.
+//!
+//! Sessions a client abandons without a DELETE are evicted once idle for
+//! longer than `ROUTA_MCP_SESSION_TTL_SECS` (default 30 minutes, `0` disables).
+//!
+//! Results of idempotent list tools are cached for `ROUTA_MCP_TOOL_CACHE_TTL_MS`
+//! (default 5 seconds, `0` disables) and invalidated by MCP mutations that touch
+//! the same data; pass `"noCache": true` in a tool's arguments to skip the cache.
+//!
+//! GET /api/mcp/sessions - List active MCP sessions (requires `ROUTA_ADMIN_TOKEN`
+//!   as a bearer token; disabled while that variable is unset)
 
 mod rmcp_service;
+mod session_registry;
+mod skill_tools;
 mod tool_catalog;
 mod tool_executor;
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use axum::{
     body::Body,
-    http::{header::ACCEPT, HeaderValue, Request, Response},
+    extract::Query,
+    http::{
+        header::{ACCEPT, AUTHORIZATION},
+        HeaderMap, HeaderValue, Method, Request, Response, StatusCode,
+    },
     response::IntoResponse,
     routing::get,
-    Router,
+    Json, Router,
+};
+use rmcp::transport::streamable_http_server::session::{
+    local::LocalSessionManager, SessionManager,
 };
+use routa_core::client_gate::ClientInfo;
+use routa_core::session_sweep;
 use serde::Deserialize;
 
+use crate::error::ServerError;
 use crate::state::AppState;
+use session_registry::McpSessionRegistry;
+
+const SESSION_ID_HEADER: &str = "mcp-session-id";
+const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
 
 #[derive(Debug, Deserialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -26,53 +71,428 @@ pub(super) struct McpRequestQuery {
     mcp_profile: Option<String>,
 }
 
+/// `workspaceId` from the params of an `initialize` request, attached to the
+/// request's extensions so the rmcp handler (which only sees the typed
+/// params) can scope the session to it.
+#[derive(Debug, Clone)]
+pub(super) struct InitializeWorkspace(pub(super) String);
+
+impl InitializeWorkspace {
+    fn from_params(params: &serde_json::Value) -> Option<Self> {
+        params
+            .get("workspaceId")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| Self(value.to_string()))
+    }
+}
+
+/// The params of `bytes` when it is a single `initialize` request.
+fn initialize_params(bytes: &[u8]) -> Option<serde_json::Value> {
+    let mut message = serde_json::from_slice::<serde_json::Value>(bytes).ok()?;
+    if message.get("method").and_then(|m| m.as_str()) != Some("initialize") {
+        return None;
+    }
+    message.get_mut("params").map(serde_json::Value::take)
+}
+
 pub fn router(state: AppState) -> Router<AppState> {
-    let service = rmcp_service::build_service(state);
-
-    Router::new().route(
-        "/",
-        get({
-            let service = service.clone();
-            move |request| handle_get(service, request)
-        })
-        .post({
-            let service = service.clone();
-            move |request| handle_post(service, request)
-        })
-        .delete(move |request| handle_delete(service, request)),
-    )
+    let admin_token = state.settings.admin_token.clone();
+    let session_ttl = state.settings.mcp_session_ttl;
+    let sweep_interval = state.settings.session_sweep_interval;
+    let session_manager = Arc::new(LocalSessionManager::default());
+    let service = rmcp_service::build_service(state, session_manager.clone());
+    let sessions = Arc::new(McpSessionRegistry::default());
+    if let Some(ttl) = session_ttl {
+        let period = session_sweep::sweep_interval(sweep_interval, ttl);
+        spawn_session_reaper(&sessions, session_manager, ttl, period);
+    }
+
+    Router::new()
+        .route(
+            "/",
+            get({
+                let service = service.clone();
+                let sessions = sessions.clone();
+                move |request| handle_get(service, sessions, request)
+            })
+            .post({
+                let service = service.clone();
+                let sessions = sessions.clone();
+                move |request| handle_post(service, sessions, request)
+            })
+            .delete({
+                let sessions = sessions.clone();
+                move |request| handle_delete(service, sessions, request)
+            }),
+        )
+        .route(
+            "/sessions",
+            get(move |headers| list_sessions(sessions, admin_token, headers)),
+        )
+}
+
+/// Every `period`, close sessions idle for longer than `ttl`, both in the
+/// registry and in rmcp. Holds only a weak reference to the registry, so the
+/// task ends once the router is dropped.
+fn spawn_session_reaper(
+    sessions: &Arc<McpSessionRegistry>,
+    session_manager: Arc<LocalSessionManager>,
+    ttl: Duration,
+    period: Duration,
+) {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let sessions = Arc::downgrade(sessions);
+    runtime.spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let Some(sessions) = sessions.upgrade() else {
+                break;
+            };
+            let mut pruned = 0;
+            for session_id in sessions.idle_longer_than(ttl) {
+                let client = sessions.client(&session_id);
+                if !sessions.remove_if_idle(&session_id, ttl) {
+                    continue;
+                }
+                pruned += 1;
+                let _ = session_manager
+                    .close_session(&Arc::<str>::from(session_id.as_str()))
+                    .await;
+                tracing::info!(
+                    "[MCP Route] Session closed: {} (idle for more than {}s, client: {})",
+                    session_id,
+                    ttl.as_secs(),
+                    client_label(client.as_ref())
+                );
+            }
+            if pruned > 0 {
+                tracing::info!("[MCP Route] Pruned {} idle session(s)", pruned);
+            }
+        }
+    });
 }
 
 async fn handle_get(
     service: rmcp_service::SharedMcpHttpService,
+    sessions: Arc<McpSessionRegistry>,
     request: Request<Body>,
 ) -> impl IntoResponse {
-    with_exposed_headers(
-        service
-            .handle(ensure_accept_header(request, &["text/event-stream"]))
-            .await,
-    )
+    let request = ensure_accept_header(request, &["text/event-stream"]);
+    let tracked = TrackedRequest::from_request(&request);
+    let response = service.handle(request).await;
+    tracked.record(&sessions, &response);
+    with_exposed_headers(response)
 }
 
 async fn handle_post(
     service: rmcp_service::SharedMcpHttpService,
+    sessions: Arc<McpSessionRegistry>,
     request: Request<Body>,
-) -> impl IntoResponse {
-    with_exposed_headers(
-        service
-            .handle(ensure_accept_header(
-                request,
-                &["application/json", "text/event-stream"],
+) -> axum::response::Response {
+    let request = ensure_accept_header(request, &["application/json", "text/event-stream"]);
+    let (parts, body) = request.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_POST_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            return (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("Failed to read request body: {error}"),
+            )
+                .into_response()
+        }
+    };
+    if bytes.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'[') {
+        return match serde_json::from_slice::<Vec<serde_json::Value>>(&bytes) {
+            Ok(messages) => handle_batch(&service, &sessions, &parts, messages).await,
+            Err(error) => Json(jsonrpc_error(
+                serde_json::Value::Null,
+                -32700,
+                &format!("Parse error: {error}"),
             ))
-            .await,
-    )
+            .into_response(),
+        };
+    }
+
+    let mut parts = parts;
+    if let Some(params) = initialize_params(&bytes) {
+        if let Some(workspace) = InitializeWorkspace::from_params(&params) {
+            parts.extensions.insert(workspace);
+        }
+        if let Some(client) = ClientInfo::from_initialize_params(&params) {
+            parts.extensions.insert(client);
+        }
+    }
+    let request = Request::from_parts(parts, Body::from(bytes));
+    let tracked = TrackedRequest::from_request(&request);
+    let response = service.handle(request).await;
+    tracked.record(&sessions, &response);
+    with_exposed_headers(response).into_response()
+}
+
+/// Upper bound on a POST body read before dispatch.
+const MAX_POST_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Dispatch each batch element as its own POST with the original headers and
+/// collect the responses. Notifications and client responses produce no
+/// element; an all-notification batch gets `202 Accepted` with no body.
+async fn handle_batch(
+    service: &rmcp_service::SharedMcpHttpService,
+    sessions: &McpSessionRegistry,
+    parts: &axum::http::request::Parts,
+    messages: Vec<serde_json::Value>,
+) -> axum::response::Response {
+    if messages.is_empty() {
+        return Json(jsonrpc_error(
+            serde_json::Value::Null,
+            -32600,
+            "Invalid Request: empty batch",
+        ))
+        .into_response();
+    }
+
+    let mut responses = Vec::new();
+    for message in messages {
+        let id = message.get("id").filter(|id| !id.is_null()).cloned();
+        let method = message.get("method").and_then(|m| m.as_str());
+        if method == Some("initialize") {
+            responses.push(jsonrpc_error(
+                id.unwrap_or_default(),
+                -32600,
+                "Invalid Request: initialize must not be part of a batch",
+            ));
+            continue;
+        }
+        let expects_response = method.is_some() && id.is_some();
+
+        let mut request = Request::new(Body::from(message.to_string()));
+        *request.method_mut() = parts.method.clone();
+        *request.uri_mut() = parts.uri.clone();
+        *request.version_mut() = parts.version;
+        *request.headers_mut() = parts.headers.clone();
+        let tracked = TrackedRequest::from_request(&request);
+        let response = service.handle(request).await;
+        tracked.record(sessions, &response);
+
+        if let (true, Some(id)) = (expects_response, id) {
+            responses.push(batch_element_response(id, response).await);
+        }
+    }
+
+    if responses.is_empty() {
+        return with_exposed_headers(StatusCode::ACCEPTED.into_response());
+    }
+    with_exposed_headers(Json(responses).into_response())
+}
+
+/// The JSON-RPC response for request `id` in a single-request `response`,
+/// which is an SSE stream (or JSON) on success and plain text on failure.
+async fn batch_element_response<B>(
+    id: serde_json::Value,
+    response: Response<B>,
+) -> serde_json::Value
+where
+    B: axum::body::HttpBody<Data = axum::body::Bytes> + Send + 'static,
+    B::Error: Into<axum::BoxError>,
+{
+    use tokio_stream::StreamExt;
+
+    let status = response.status();
+    let mut stream = Body::new(response.into_body()).into_data_stream();
+    let mut text = String::new();
+    while let Some(chunk) = stream.next().await {
+        let Ok(chunk) = chunk else {
+            break;
+        };
+        text.push_str(&String::from_utf8_lossy(&chunk));
+        // The stream may stay open after the response; stop once it arrives.
+        if status.is_success() {
+            if let Some(message) = find_response_message(&text, &id) {
+                return message;
+            }
+        }
+    }
+
+    if !status.is_success() {
+        let code = if status.is_client_error() {
+            -32600
+        } else {
+            -32603
+        };
+        return jsonrpc_error(id, code, text.trim());
+    }
+    find_response_message(&text, &id)
+        .unwrap_or_else(|| jsonrpc_error(id, -32603, "No response received for request"))
+}
+
+/// Find the response to `id` in an SSE or plain JSON body.
+fn find_response_message(body: &str, id: &serde_json::Value) -> Option<serde_json::Value> {
+    let is_response = |message: &serde_json::Value| {
+        message.get("id") == Some(id)
+            && (message.get("result").is_some() || message.get("error").is_some())
+    };
+    if let Ok(message) = serde_json::from_str::<serde_json::Value>(body) {
+        return is_response(&message).then_some(message);
+    }
+    body.split("\n\n").find_map(|event| {
+        let data = event
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(str::trim_start)
+            .collect::<Vec<_>>()
+            .join("\n");
+        serde_json::from_str::<serde_json::Value>(&data)
+            .ok()
+            .filter(is_response)
+    })
+}
+
+fn jsonrpc_error(id: serde_json::Value, code: i64, message: &str) -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
 }
 
 async fn handle_delete(
     service: rmcp_service::SharedMcpHttpService,
+    sessions: Arc<McpSessionRegistry>,
     request: Request<Body>,
 ) -> impl IntoResponse {
-    with_exposed_headers(service.handle(request).await)
+    let tracked = TrackedRequest::from_request(&request);
+    let response = service.handle(request).await;
+    tracked.record(&sessions, &response);
+    with_exposed_headers(response)
+}
+
+/// Session headers of an incoming MCP request, used to keep the session
+/// registry in sync with rmcp once the response is known.
+struct TrackedRequest {
+    method: Method,
+    session_id: Option<String>,
+    protocol_version: Option<String>,
+    workspace_id: String,
+    mcp_profile: Option<String>,
+    client: Option<ClientInfo>,
+}
+
+impl TrackedRequest {
+    fn from_request(request: &Request<Body>) -> Self {
+        // Resolved the same way as `RequestScope` in the rmcp service.
+        let query = Query::<McpRequestQuery>::try_from_uri(request.uri())
+            .map(|query| query.0)
+            .unwrap_or_default();
+        Self {
+            method: request.method().clone(),
+            session_id: header_string(request.headers(), SESSION_ID_HEADER),
+            protocol_version: header_string(request.headers(), PROTOCOL_VERSION_HEADER),
+            workspace_id: request
+                .extensions()
+                .get::<InitializeWorkspace>()
+                .map(|workspace| workspace.0.clone())
+                .or_else(|| header_string(request.headers(), "routa-workspace-id"))
+                .or(query.ws_id)
+                .unwrap_or_else(|| "default".to_string()),
+            mcp_profile: query.mcp_profile,
+            client: request.extensions().get::<ClientInfo>().cloned(),
+        }
+    }
+
+    fn record<B>(self, sessions: &McpSessionRegistry, response: &Response<B>) {
+        let Some(session_id) = self.session_id else {
+            if let Some(created) = header_string(response.headers(), SESSION_ID_HEADER) {
+                tracing::info!(
+                    "[MCP Route] Session created: {} (workspace: {}, client: {})",
+                    created,
+                    self.workspace_id,
+                    client_label(self.client.as_ref())
+                );
+                sessions.record_created(
+                    &created,
+                    self.workspace_id,
+                    self.mcp_profile,
+                    self.protocol_version,
+                    self.client,
+                );
+            }
+            return;
+        };
+        let closed = response.status() == StatusCode::NOT_FOUND
+            || (self.method == Method::DELETE && response.status().is_success());
+        if closed {
+            let client = sessions.client(&session_id);
+            sessions.remove(&session_id);
+            tracing::info!(
+                "[MCP Route] Session closed: {} ({}, client: {})",
+                session_id,
+                if self.method == Method::DELETE {
+                    "deleted by client"
+                } else {
+                    "unknown to transport"
+                },
+                client_label(client.as_ref())
+            );
+        } else {
+            sessions.touch(&session_id, self.protocol_version);
+        }
+    }
+}
+
+fn client_label(client: Option<&ClientInfo>) -> String {
+    client.map_or_else(|| "unknown".to_string(), ToString::to_string)
+}
+
+fn header_string(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// GET /api/mcp/sessions - Active MCP sessions, least recently active first.
+async fn list_sessions(
+    sessions: Arc<McpSessionRegistry>,
+    admin_token: Option<String>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let Some(admin_token) = admin_token else {
+        return Err(ServerError::Unauthorized(
+            "Set ROUTA_ADMIN_TOKEN to enable this endpoint".to_string(),
+        ));
+    };
+    if !admin_authorized(&admin_token, &headers) {
+        return Err(ServerError::Unauthorized(
+            "Missing or invalid admin token".to_string(),
+        ));
+    }
+    let sessions = sessions.list();
+    Ok(Json(serde_json::json!({
+        "total": sessions.len(),
+        "sessions": sessions,
+    })))
+}
+
+/// Whether `headers` carry `Authorization: Bearer <expected>`. Operator
+/// endpoints are refused outright when no admin token is configured, since
+/// CORS lets any web page reach the server.
+fn admin_authorized(expected: &str, headers: &HeaderMap) -> bool {
+    header_string(headers, AUTHORIZATION.as_str())
+        .and_then(|value| value.strip_prefix("Bearer ").map(str::to_string))
+        .is_some_and(|token| constant_time_eq(token.trim().as_bytes(), expected.as_bytes()))
+}
+
+/// Compare without exiting at the first differing byte, so response times
+/// don't reveal how much of a guessed token was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
 }
 
 fn ensure_accept_header(mut request: Request<Body>, required: &[&str]) -> Request<Body> {
@@ -117,6 +537,27 @@ pub fn build_tool_list_public() -> Vec<serde_json::Value> {
     tool_catalog::build_tool_list_public()
 }
 
+/// Tools generated from skills (empty unless skill tools are enabled).
+pub fn build_skill_tool_list_public(state: &AppState) -> Vec<serde_json::Value> {
+    skill_tools::skill_tool_defs(state)
+}
+
+/// The tool list, including skill tools, with tools disabled by the server
+/// configuration removed.
+pub fn build_enabled_tool_list_public(state: &AppState) -> Vec<serde_json::Value> {
+    let mut tools = tool_catalog::build_tool_list_public();
+    tools.extend(skill_tools::skill_tool_defs(state));
+    tool_catalog::filter_enabled_tools(state, tools)
+}
+
+/// The tools an MCP session with `profile` sees in `tools/list`.
+pub fn build_listed_tools_public(
+    state: &AppState,
+    profile: Option<&str>,
+) -> Vec<serde_json::Value> {
+    tool_catalog::listed_tools(state, profile)
+}
+
 pub async fn execute_tool_public(
     state: &AppState,
     name: &str,
@@ -125,13 +566,25 @@ pub async fn execute_tool_public(
     tool_executor::execute_tool_public(state, name, args).await
 }
 
-pub(super) async fn execute_tool_for_profile_public(
+pub(super) async fn execute_tool_for_session_public(
     state: &AppState,
     name: &str,
     args: &serde_json::Value,
     mcp_profile: Option<&str>,
+    verified_workspace: Option<&str>,
 ) -> serde_json::Value {
-    tool_executor::execute_tool_for_profile_public(state, name, args, mcp_profile).await
+    tool_executor::execute_tool_for_session_public(
+        state,
+        name,
+        args,
+        mcp_profile,
+        verified_workspace,
+    )
+    .await
+}
+
+pub(super) async fn workspace_exists_public(state: &AppState, workspace_id: &str) -> bool {
+    tool_executor::workspace_exists_public(state, workspace_id).await
 }
 
 pub fn normalize_tool_name_public(name: &str) -> &str {
@@ -151,18 +604,35 @@ pub(super) fn inject_workspace_id(args: &mut serde_json::Value, workspace_id: &s
     }
 }
 
+/// In-memory state with the default workspace, for the MCP tests here and
+/// in the submodules.
+#[cfg(test)]
+async fn test_state() -> AppState {
+    let db = crate::db::Database::open(":memory:").expect("open in-memory database");
+    let state: AppState = Arc::new(crate::state::AppStateInner::new(db));
+    state
+        .workspace_store
+        .ensure_default()
+        .await
+        .expect("ensure default workspace");
+    state
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use axum::{
         body::Body,
-        http::{header::ACCEPT, Request},
+        http::{
+            header::{ACCEPT, AUTHORIZATION},
+            HeaderMap, Request,
+        },
     };
 
     use super::{
-        build_tool_list_public, ensure_accept_header, execute_tool_public, inject_workspace_id,
-        normalize_tool_name_public,
+        admin_authorized, build_tool_list_public, ensure_accept_header, inject_workspace_id,
+        list_sessions, normalize_tool_name_public, McpSessionRegistry, ServerError,
     };
 
     #[test]
@@ -211,6 +681,25 @@ mod tests {
         assert!(accept.contains("text/event-stream"));
     }
 
+    #[test]
+    fn admin_token_must_match_exactly() {
+        let mut headers = HeaderMap::new();
+        assert!(!admin_authorized("secret", &headers));
+        headers.insert(AUTHORIZATION, "Bearer secreT".parse().unwrap());
+        assert!(!admin_authorized("secret", &headers));
+        headers.insert(AUTHORIZATION, "Bearer secret-and-more".parse().unwrap());
+        assert!(!admin_authorized("secret", &headers));
+        headers.insert(AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert!(admin_authorized("secret", &headers));
+    }
+
+    #[tokio::test]
+    async fn session_listing_is_refused_without_an_admin_token() {
+        let sessions = Arc::new(McpSessionRegistry::default());
+        let result = list_sessions(sessions, None, HeaderMap::new()).await;
+        assert!(matches!(result, Err(ServerError::Unauthorized(_))));
+    }
+
     #[test]
     fn build_tool_list_public_contains_expected_tool() {
         let tools = build_tool_list_public();
@@ -234,18 +723,4 @@ mod tests {
             "create_card"
         );
     }
-
-    #[tokio::test]
-    async fn execute_tool_public_returns_error_for_unknown_tool() {
-        let db = crate::db::Database::open(":memory:").expect("open in-memory database");
-        let state: crate::state::AppState = Arc::new(crate::state::AppStateInner::new(db));
-        state
-            .workspace_store
-            .ensure_default()
-            .await
-            .expect("ensure default workspace");
-
-        let result = execute_tool_public(&state, "unknown_tool_name", &serde_json::json!({})).await;
-        assert_eq!(result.get("isError").and_then(|v| v.as_bool()), Some(true));
-    }
 }
@@ -2,33 +2,78 @@ use axum::{extract::Query, http::request::Parts};
 use rmcp::{
     handler::server::ServerHandler,
     model::{
-        CallToolRequestParams, CallToolResult, Implementation, InitializeRequestParams,
-        InitializeResult, ListToolsResult, PaginatedRequestParams, ServerCapabilities, ServerInfo,
-        Tool,
+        AnnotateAble, CallToolRequestParams, CallToolResult, Implementation,
+        InitializeRequestParams, InitializeResult, ListResourcesResult, ListToolsResult,
+        LoggingLevel, LoggingMessageNotificationParam, PaginatedRequestParams, RawResource,
+        ReadResourceRequestParams, ReadResourceResult, ResourceContents, ResourcesCapability,
+        ServerCapabilities, ServerInfo, Tool,
     },
-    service::{RequestContext, RoleServer},
+    service::{Peer, RequestContext, RoleServer},
     transport::{
         streamable_http_server::session::local::LocalSessionManager, StreamableHttpServerConfig,
         StreamableHttpService,
     },
     ErrorData as McpError,
 };
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+use tokio::sync::{broadcast, mpsc, RwLock};
 
 use crate::state::AppState;
+use routa_core::client_gate::ClientInfo;
 
-use super::tool_catalog;
 use super::{
-    execute_tool_for_profile_public, inject_workspace_id, normalize_tool_name_public,
-    McpRequestQuery,
+    execute_tool_for_session_public, inject_workspace_id, normalize_tool_name_public,
+    workspace_exists_public, InitializeWorkspace, McpRequestQuery,
 };
+use super::{skill_tools, tool_catalog};
 
 pub(super) type SharedMcpHttpService =
     Arc<StreamableHttpService<RoutaMcpHttpServer, LocalSessionManager>>;
 
+/// URI prefix of notes exposed as MCP resources: `routa://note/{id}`.
+const NOTE_URI_PREFIX: &str = "routa://note/";
+
+/// Capacity of a session's notification queue; notifications beyond it are
+/// dropped rather than blocking the tool call that produced them.
+const SESSION_NOTIFICATION_CAPACITY: usize = 64;
+
+/// One instance is created per MCP session (stateful mode), so `session`
+/// holds context resolved once at `initialize` and reused by every call.
 #[derive(Clone)]
 pub(super) struct RoutaMcpHttpServer {
     state: AppState,
+    session: Arc<RwLock<Option<McpSessionData>>>,
+    /// Shared by every session: state-changing tool calls, fanned out to the
+    /// sessions scoped to the same workspace.
+    workspace_changes: broadcast::Sender<WorkspaceChange>,
+}
+
+/// Per-session context cached on the server instance.
+#[derive(Debug, Clone)]
+struct McpSessionData {
+    scope: RequestScope,
+    /// `WorkspaceStore::generation` at which `scope.workspace_id` was last
+    /// confirmed to exist; `None` when it has not been (or no longer is).
+    verified_generation: Option<u64>,
+    /// Queue drained onto the session's GET SSE stream; `None` for sessions
+    /// that never sent `initialize` (e.g. after a server restart).
+    notifier: Option<mpsc::Sender<SessionNotification>>,
+}
+
+/// A server-initiated notification for one session.
+#[derive(Debug, Clone)]
+enum SessionNotification {
+    /// `notifications/tools/list_changed`
+    ToolListChanged,
+    /// `notifications/message` from the `routa.state` logger, carrying the
+    /// tool that changed workspace state.
+    StateChanged(WorkspaceChange),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct WorkspaceChange {
+    workspace_id: String,
+    tool: String,
 }
 
 #[derive(Debug, Clone)]
@@ -46,15 +91,20 @@ impl RequestScope {
             .unwrap_or_default();
 
         let workspace_id = parts
-            .and_then(|parts| {
+            .and_then(|parts| parts.extensions.get::<InitializeWorkspace>())
+            .map(|workspace| workspace.0.clone())
+            .or_else(|| {
                 parts
-                    .headers
-                    .get("routa-workspace-id")
-                    .and_then(|value| value.to_str().ok())
+                    .and_then(|parts| {
+                        parts
+                            .headers
+                            .get("routa-workspace-id")
+                            .and_then(|value| value.to_str().ok())
+                    })
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .map(str::to_string)
             })
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-            .map(str::to_string)
             .or(query.ws_id)
             .unwrap_or_else(|| "default".to_string());
 
@@ -66,15 +116,157 @@ impl RequestScope {
 }
 
 impl RoutaMcpHttpServer {
-    pub(super) fn new(state: AppState) -> Self {
-        Self { state }
+    pub(super) fn new(
+        state: AppState,
+        workspace_changes: broadcast::Sender<WorkspaceChange>,
+    ) -> Self {
+        Self {
+            state,
+            session: Arc::new(RwLock::new(None)),
+            workspace_changes,
+        }
+    }
+
+    /// Return the cached session context, establishing it from `context`
+    /// when the session skipped `initialize` (e.g. after a server restart).
+    /// The workspace is re-verified whenever a workspace has been deleted
+    /// since it was last checked.
+    async fn session_data(&self, context: &RequestContext<RoleServer>) -> McpSessionData {
+        let generation = self.state.workspace_store.generation();
+        if let Some(data) = self.session.read().await.as_ref() {
+            if data.verified_generation == Some(generation) {
+                return data.clone();
+            }
+        }
+
+        let mut session = self.session.write().await;
+        let scope = session
+            .as_ref()
+            .map(|data| data.scope.clone())
+            .unwrap_or_else(|| RequestScope::from_context(context));
+        let notifier = session.as_ref().and_then(|data| data.notifier.clone());
+        let verified_generation = workspace_exists_public(&self.state, &scope.workspace_id)
+            .await
+            .then_some(generation);
+        let data = McpSessionData {
+            scope,
+            verified_generation,
+            notifier,
+        };
+        *session = Some(data.clone());
+        data
+    }
+
+    /// Create the session's notification queue and the task draining it onto
+    /// the peer, which rmcp delivers on the session's GET SSE stream. The
+    /// drain ends once every sender is gone or the peer stops accepting
+    /// notifications.
+    async fn open_notifier(
+        &self,
+        peer: Peer<RoleServer>,
+        workspace_id: &str,
+    ) -> mpsc::Sender<SessionNotification> {
+        let (sender, mut receiver) = mpsc::channel(SESSION_NOTIFICATION_CAPACITY);
+        tokio::spawn(async move {
+            while let Some(notification) = receiver.recv().await {
+                let sent = match notification {
+                    SessionNotification::ToolListChanged => peer.notify_tool_list_changed().await,
+                    SessionNotification::StateChanged(change) => {
+                        peer.notify_logging_message(LoggingMessageNotificationParam {
+                            level: LoggingLevel::Info,
+                            logger: Some("routa.state".to_string()),
+                            data: serde_json::json!({
+                                "event": "state_changed",
+                                "workspaceId": change.workspace_id,
+                                "tool": change.tool,
+                            }),
+                        })
+                        .await
+                    }
+                };
+                if sent.is_err() {
+                    break;
+                }
+            }
+        });
+        self.relay_workspace_changes(sender.clone(), workspace_id.to_string());
+        if let Some(data) = self.session.write().await.as_mut() {
+            data.notifier = Some(sender.clone());
+        }
+        sender
+    }
+
+    /// Queue a notification for every state-changing tool call in
+    /// `workspace_id`, from any session.
+    fn relay_workspace_changes(
+        &self,
+        sender: mpsc::Sender<SessionNotification>,
+        workspace_id: String,
+    ) {
+        let mut changes = self.workspace_changes.subscribe();
+        let session = Arc::downgrade(&self.session);
+        tokio::spawn(async move {
+            loop {
+                let change = match changes.recv().await {
+                    Ok(change) => change,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if change.workspace_id != workspace_id {
+                    continue;
+                }
+                if !queue(&session, &sender, SessionNotification::StateChanged(change)) {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Send `notifications/tools/list_changed` whenever the skill set changes.
+    /// The watcher ends once the session is gone or its queue is closed.
+    fn notify_on_skill_changes(&self, sender: mpsc::Sender<SessionNotification>) {
+        let mut changes = self.state.skill_registry.subscribe_changes();
+        changes.mark_unchanged();
+        let session = Arc::downgrade(&self.session);
+        tokio::spawn(async move {
+            while changes.changed().await.is_ok() {
+                if !queue(&session, &sender, SessionNotification::ToolListChanged) {
+                    break;
+                }
+            }
+        });
     }
 }
 
-pub(super) fn build_service(state: AppState) -> SharedMcpHttpService {
+/// Queue `notification` while the session is alive. A full queue drops the
+/// notification; returns `false` once the session or its queue is gone.
+fn queue(
+    session: &Weak<RwLock<Option<McpSessionData>>>,
+    sender: &mpsc::Sender<SessionNotification>,
+    notification: SessionNotification,
+) -> bool {
+    if session.upgrade().is_none() {
+        return false;
+    }
+    !matches!(
+        sender.try_send(notification),
+        Err(mpsc::error::TrySendError::Closed(_))
+    )
+}
+
+pub(super) fn build_service(
+    state: AppState,
+    session_manager: Arc<LocalSessionManager>,
+) -> SharedMcpHttpService {
+    let (workspace_changes, _) = broadcast::channel(SESSION_NOTIFICATION_CAPACITY);
     Arc::new(StreamableHttpService::new(
-        move || Ok(RoutaMcpHttpServer::new(state.clone())),
-        Arc::new(LocalSessionManager::default()),
+        move || {
+            Ok(RoutaMcpHttpServer::new(
+                state.clone(),
+                workspace_changes.clone(),
+            ))
+        },
+        session_manager,
         StreamableHttpServerConfig {
             stateful_mode: true,
             ..Default::default()
@@ -92,11 +284,36 @@ impl ServerHandler for RoutaMcpHttpServer {
         request: InitializeRequestParams,
         context: RequestContext<RoleServer>,
     ) -> Result<InitializeResult, McpError> {
+        let client = ClientInfo {
+            name: request.client_info.name.clone(),
+            version: Some(request.client_info.version.clone()).filter(|v| !v.is_empty()),
+        };
+        if let Err(message) = self.state.settings.client_version_gate.check(Some(&client)) {
+            tracing::warn!("[MCP] Rejected initialize from {client}: {message}");
+            return Err(McpError::invalid_request(message, None));
+        }
         if context.peer.peer_info().is_none() {
             context.peer.set_peer_info(request.clone());
         }
 
-        let scope = RequestScope::from_context(&context);
+        let session = self.session_data(&context).await;
+        let requested_workspace = context
+            .extensions
+            .get::<Parts>()
+            .and_then(|parts| parts.extensions.get::<InitializeWorkspace>());
+        if requested_workspace.is_some() && session.verified_generation.is_none() {
+            return Err(McpError::invalid_params(
+                format!("Workspace not found: {}", session.scope.workspace_id),
+                None,
+            ));
+        }
+        let scope = session.scope;
+        let notifier = self
+            .open_notifier(context.peer.clone(), &scope.workspace_id)
+            .await;
+        if scope.mcp_profile.is_none() && skill_tools::skill_tools_enabled(&self.state) {
+            self.notify_on_skill_changes(notifier);
+        }
         Ok(server_info(
             scope.mcp_profile.as_deref(),
             request.protocol_version,
@@ -108,8 +325,8 @@ impl ServerHandler for RoutaMcpHttpServer {
         _request: Option<PaginatedRequestParams>,
         context: RequestContext<RoleServer>,
     ) -> Result<ListToolsResult, McpError> {
-        let scope = RequestScope::from_context(&context);
-        let tools = tool_catalog::build_tool_list_for_profile(scope.mcp_profile.as_deref())
+        let scope = self.session_data(&context).await.scope;
+        let tools = tool_catalog::listed_tools(&self.state, scope.mcp_profile.as_deref())
             .into_iter()
             .map(tool_from_value)
             .collect::<Result<Vec<_>, _>>()?;
@@ -121,12 +338,74 @@ impl ServerHandler for RoutaMcpHttpServer {
         })
     }
 
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let scope = self.session_data(&context).await.scope;
+        let notes = self
+            .state
+            .note_store
+            .list_by_workspace(&scope.workspace_id)
+            .await
+            .map_err(|err| McpError::internal_error(err.to_string(), None))?;
+        let resources = notes
+            .into_iter()
+            .map(|note| {
+                let mut resource = RawResource::new(note_uri(&note.id), note.title);
+                resource.mime_type = Some("text/markdown".to_string());
+                resource.size = u32::try_from(note.content.len()).ok();
+                resource.no_annotation()
+            })
+            .collect();
+
+        Ok(ListResourcesResult {
+            resources,
+            next_cursor: None,
+            meta: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let Some(note_id) = parse_note_uri(&request.uri) else {
+            return Err(McpError::invalid_params(
+                format!(
+                    "Malformed resource URI '{}': expected {NOTE_URI_PREFIX}{{id}}",
+                    request.uri
+                ),
+                None,
+            ));
+        };
+        let scope = self.session_data(&context).await.scope;
+        match self
+            .state
+            .note_store
+            .get(note_id, &scope.workspace_id)
+            .await
+        {
+            Ok(Some(note)) => Ok(ReadResourceResult {
+                contents: vec![ResourceContents::text(note.content, request.uri)],
+            }),
+            Ok(None) => Err(McpError::resource_not_found(
+                format!("Note not found: {note_id}"),
+                None,
+            )),
+            Err(err) => Err(McpError::internal_error(err.to_string(), None)),
+        }
+    }
+
     async fn call_tool(
         &self,
         request: CallToolRequestParams,
         context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
-        let scope = RequestScope::from_context(&context);
+        let session = self.session_data(&context).await;
+        let scope = &session.scope;
         let requested_tool_name = request.name.to_string();
         let normalized_tool_name = normalize_tool_name_public(&requested_tool_name).to_string();
 
@@ -146,13 +425,42 @@ impl ServerHandler for RoutaMcpHttpServer {
             .unwrap_or_else(|| serde_json::json!({}));
         inject_workspace_id(&mut arguments, &scope.workspace_id);
 
-        let result = execute_tool_for_profile_public(
+        let result = execute_tool_for_session_public(
             &self.state,
             &normalized_tool_name,
             &arguments,
             scope.mcp_profile.as_deref(),
+            session
+                .verified_generation
+                .map(|_| scope.workspace_id.as_str()),
         )
         .await;
+        let succeeded = result.get("error").is_none()
+            && result.get("isError").and_then(|v| v.as_bool()) != Some(true);
+        if succeeded && !tool_catalog::tool_is_read_only(&normalized_tool_name) {
+            let workspace_id = arguments
+                .get("workspaceId")
+                .and_then(|v| v.as_str())
+                .unwrap_or(&scope.workspace_id);
+            let _ = self.workspace_changes.send(WorkspaceChange {
+                workspace_id: workspace_id.to_string(),
+                tool: normalized_tool_name.clone(),
+            });
+        }
+        if let Some(error) = result.get("error").filter(|error| {
+            error.get("code").and_then(|code| code.as_i64())
+                == Some(crate::models::validation::INVALID_PARAMS_CODE)
+        }) {
+            let message = error
+                .get("message")
+                .and_then(|message| message.as_str())
+                .unwrap_or("Invalid params")
+                .to_string();
+            return Err(McpError::invalid_params(
+                message,
+                error.get("data").cloned(),
+            ));
+        }
         serde_json::from_value(result).map_err(|err| {
             McpError::internal_error(
                 format!("Failed to encode MCP tool result for '{normalized_tool_name}': {err}"),
@@ -162,13 +470,37 @@ impl ServerHandler for RoutaMcpHttpServer {
     }
 }
 
+fn note_uri(note_id: &str) -> String {
+    format!("{NOTE_URI_PREFIX}{note_id}")
+}
+
+/// The note id in a `routa://note/{id}` URI; `None` for anything else,
+/// including empty ids and ids with path, query or fragment parts.
+fn parse_note_uri(uri: &str) -> Option<&str> {
+    let id = uri.strip_prefix(NOTE_URI_PREFIX)?;
+    let malformed = id.is_empty()
+        || id
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '/' | '?' | '#'));
+    (!malformed).then_some(id)
+}
+
 fn server_info(
     profile: Option<&str>,
     protocol_version: rmcp::model::ProtocolVersion,
 ) -> ServerInfo {
+    let mut capabilities = ServerCapabilities::builder()
+        .enable_tools()
+        .enable_tool_list_changed()
+        .enable_logging()
+        .build();
+    capabilities.resources = Some(ResourcesCapability {
+        list_changed: Some(false),
+        ..Default::default()
+    });
     ServerInfo {
         protocol_version,
-        capabilities: ServerCapabilities::builder().enable_tools().build(),
+        capabilities,
         server_info: Implementation {
             name: server_name(profile).to_string(),
             version: "0.1.0".to_string(),
@@ -197,3 +529,26 @@ fn tool_from_value(value: serde_json::Value) -> Result<Tool, McpError> {
         McpError::internal_error(format!("Invalid MCP tool definition: {err}"), None)
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{note_uri, parse_note_uri};
+
+    #[test]
+    fn note_uris_round_trip_and_malformed_ones_are_rejected() {
+        assert_eq!(parse_note_uri(&note_uri("spec")), Some("spec"));
+        assert_eq!(parse_note_uri("routa://note/task-1"), Some("task-1"));
+
+        for malformed in [
+            "routa://note/",
+            "routa://note/a/b",
+            "routa://note/a?rev=1",
+            "routa://note/a#top",
+            "routa://note/a b",
+            "routa://task/a",
+            "note/a",
+        ] {
+            assert_eq!(parse_note_uri(malformed), None, "{malformed}");
+        }
+    }
+}
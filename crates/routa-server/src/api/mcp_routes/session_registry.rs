@@ -0,0 +1,334 @@
+//! Bookkeeping for active MCP sessions, for `GET /api/mcp/sessions`.
+//!
+//! rmcp's `LocalSessionManager` does not expose its sessions, so they are
+//! tracked from the transport headers: a response carrying a new
+//! `Mcp-Session-Id` creates an entry, later requests refresh it, and a
+//! DELETE (or a 404 for an unknown id) removes it. Sessions idle past the
+//! TTL are evicted by the reaper in `mcp_routes`.
+//!
+//! Every MCP request touches its session, so entries are split across
+//! `SHARD_COUNT` locks by a hash of the session id: requests for different
+//! sessions rarely contend, and listing takes one shard's read lock at a time.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use routa_core::client_gate::ClientInfo;
+use serde::Serialize;
+
+/// Number of leading session-id characters shown to operators.
+const VISIBLE_ID_CHARS: usize = 8;
+/// Number of independently locked partitions of the session map.
+const SHARD_COUNT: usize = 16;
+
+type Shard = RwLock<HashMap<String, McpSessionEntry>>;
+
+#[derive(Debug, Clone)]
+struct McpSessionEntry {
+    workspace_id: String,
+    mcp_profile: Option<String>,
+    protocol_version: Option<String>,
+    /// `clientInfo` sent with `initialize`.
+    client: Option<ClientInfo>,
+    created_at: DateTime<Utc>,
+    last_activity: DateTime<Utc>,
+    /// Monotonic twin of `last_activity`, used for idle expiry.
+    last_seen: Instant,
+}
+
+/// Public view of a tracked session; the id is truncated.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSessionSummary {
+    pub session_id: String,
+    pub workspace_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcp_profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client: Option<ClientInfo>,
+    pub created_at: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    pub idle_seconds: i64,
+}
+
+#[derive(Debug)]
+pub struct McpSessionRegistry {
+    shards: Vec<Shard>,
+}
+
+impl Default for McpSessionRegistry {
+    fn default() -> Self {
+        Self::with_shards(SHARD_COUNT)
+    }
+}
+
+impl McpSessionRegistry {
+    fn with_shards(count: usize) -> Self {
+        Self {
+            shards: (0..count.max(1)).map(|_| Shard::default()).collect(),
+        }
+    }
+
+    fn shard(&self, session_id: &str) -> &Shard {
+        let mut hasher = DefaultHasher::new();
+        session_id.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+
+    pub fn record_created(
+        &self,
+        session_id: &str,
+        workspace_id: String,
+        mcp_profile: Option<String>,
+        protocol_version: Option<String>,
+        client: Option<ClientInfo>,
+    ) {
+        let now = Utc::now();
+        if let Ok(mut sessions) = self.shard(session_id).write() {
+            sessions.insert(
+                session_id.to_string(),
+                McpSessionEntry {
+                    workspace_id,
+                    mcp_profile,
+                    protocol_version,
+                    client,
+                    created_at: now,
+                    last_activity: now,
+                    last_seen: Instant::now(),
+                },
+            );
+        }
+    }
+
+    /// Refresh `last_activity`, and the protocol version once the client sends it.
+    pub fn touch(&self, session_id: &str, protocol_version: Option<String>) {
+        if let Ok(mut sessions) = self.shard(session_id).write() {
+            if let Some(entry) = sessions.get_mut(session_id) {
+                entry.last_activity = Utc::now();
+                entry.last_seen = Instant::now();
+                if protocol_version.is_some() {
+                    entry.protocol_version = protocol_version;
+                }
+            }
+        }
+    }
+
+    /// The client that opened `session_id`, for log lines.
+    pub fn client(&self, session_id: &str) -> Option<ClientInfo> {
+        self.shard(session_id)
+            .read()
+            .ok()?
+            .get(session_id)
+            .and_then(|entry| entry.client.clone())
+    }
+
+    pub fn remove(&self, session_id: &str) {
+        if let Ok(mut sessions) = self.shard(session_id).write() {
+            sessions.remove(session_id);
+        }
+    }
+
+    /// Ids of sessions not seen for longer than `ttl`. Only takes read locks.
+    pub fn idle_longer_than(&self, ttl: Duration) -> Vec<String> {
+        self.shards
+            .iter()
+            .filter_map(|shard| shard.read().ok())
+            .flat_map(|sessions| {
+                sessions
+                    .iter()
+                    .filter(|(_, entry)| entry.last_seen.elapsed() > ttl)
+                    .map(|(id, _)| id.clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Remove `session_id` if it is still idle for longer than `ttl`, so a
+    /// request that arrived since `idle_longer_than` keeps the session alive.
+    pub fn remove_if_idle(&self, session_id: &str, ttl: Duration) -> bool {
+        let Ok(mut sessions) = self.shard(session_id).write() else {
+            return false;
+        };
+        if sessions
+            .get(session_id)
+            .is_some_and(|entry| entry.last_seen.elapsed() > ttl)
+        {
+            sessions.remove(session_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Tracked sessions, least recently active first.
+    pub fn list(&self) -> Vec<McpSessionSummary> {
+        let now = Utc::now();
+        let mut summaries = self
+            .shards
+            .iter()
+            .filter_map(|shard| shard.read().ok())
+            .flat_map(|sessions| {
+                sessions
+                    .iter()
+                    .map(|(id, entry)| McpSessionSummary {
+                        session_id: truncate_session_id(id),
+                        workspace_id: entry.workspace_id.clone(),
+                        mcp_profile: entry.mcp_profile.clone(),
+                        protocol_version: entry.protocol_version.clone(),
+                        client: entry.client.clone(),
+                        created_at: entry.created_at,
+                        last_activity: entry.last_activity,
+                        idle_seconds: (now - entry.last_activity).num_seconds(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        summaries.sort_by_key(|summary| summary.last_activity);
+        summaries
+    }
+}
+
+fn truncate_session_id(session_id: &str) -> String {
+    if session_id.chars().count() <= VISIBLE_ID_CHARS {
+        return session_id.to_string();
+    }
+    let visible: String = session_id.chars().take(VISIBLE_ID_CHARS).collect();
+    format!("{visible}…")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_lifecycle_and_truncates_ids() {
+        let registry = McpSessionRegistry::default();
+        registry.record_created(
+            "0123456789abcdef",
+            "ws-1".to_string(),
+            None,
+            Some("2025-03-26".to_string()),
+            Some(ClientInfo {
+                name: "cursor".to_string(),
+                version: Some("1.4.0".to_string()),
+            }),
+        );
+        registry.touch("0123456789abcdef", Some("2025-06-18".to_string()));
+
+        let sessions = registry.list();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, "01234567…");
+        assert_eq!(sessions[0].workspace_id, "ws-1");
+        assert_eq!(sessions[0].protocol_version.as_deref(), Some("2025-06-18"));
+        assert_eq!(
+            registry
+                .client("0123456789abcdef")
+                .map(|client| client.to_string()),
+            Some("cursor 1.4.0".to_string())
+        );
+        assert_eq!(
+            serde_json::to_value(&sessions[0]).expect("serialize summary")["client"],
+            serde_json::json!({ "name": "cursor", "version": "1.4.0" })
+        );
+
+        registry.remove("0123456789abcdef");
+        assert!(registry.list().is_empty());
+    }
+
+    #[test]
+    fn evicts_only_sessions_idle_past_the_ttl() {
+        let registry = McpSessionRegistry::default();
+        registry.record_created("stale", "ws-1".to_string(), None, None, None);
+        std::thread::sleep(Duration::from_millis(20));
+        registry.record_created("fresh", "ws-1".to_string(), None, None, None);
+
+        let ttl = Duration::from_millis(10);
+        assert_eq!(registry.idle_longer_than(ttl), vec!["stale".to_string()]);
+
+        registry.touch("stale", None);
+        assert!(!registry.remove_if_idle("stale", ttl));
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(registry.remove_if_idle("stale", ttl));
+        assert!(!registry.remove_if_idle("stale", ttl));
+        assert_eq!(registry.list().len(), 1);
+    }
+
+    /// 8 threads each initializing, touching, and listing sessions, with
+    /// every second session closed again.
+    fn run_concurrent_workload(registry: &std::sync::Arc<McpSessionRegistry>, sessions: usize) {
+        let threads: Vec<_> = (0..8)
+            .map(|thread| {
+                let registry = registry.clone();
+                std::thread::spawn(move || {
+                    for i in 0..sessions {
+                        let id = format!("session-{thread}-{i}");
+                        registry.record_created(&id, "ws-1".to_string(), None, None, None);
+                        for _ in 0..10 {
+                            registry.touch(&id, Some("2025-06-18".to_string()));
+                        }
+                        if i % 20 == 0 {
+                            assert!(!registry.list().is_empty());
+                        }
+                        if i % 2 == 1 {
+                            registry.remove(&id);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().expect("worker thread");
+        }
+    }
+
+    #[test]
+    fn concurrent_initialize_touch_and_list_keep_every_session() {
+        let registry = std::sync::Arc::new(McpSessionRegistry::default());
+        run_concurrent_workload(&registry, 200);
+
+        let sessions = registry.list();
+        assert_eq!(sessions.len(), 8 * 100);
+        assert!(sessions
+            .iter()
+            .all(|s| s.protocol_version.as_deref() == Some("2025-06-18")));
+        // Sessions spread over the shards instead of piling into one lock.
+        let used_shards = registry
+            .shards
+            .iter()
+            .filter(|shard| !shard.read().unwrap().is_empty())
+            .count();
+        assert!(used_shards > SHARD_COUNT / 2, "{used_shards}");
+    }
+
+    #[test]
+    fn sharding_is_not_slower_than_a_single_lock_under_concurrency() {
+        // Best of three, to keep scheduler noise out of the comparison.
+        let time = |shards: usize| {
+            (0..3)
+                .map(|_| {
+                    let registry = std::sync::Arc::new(McpSessionRegistry::with_shards(shards));
+                    let started = Instant::now();
+                    run_concurrent_workload(&registry, 500);
+                    started.elapsed()
+                })
+                .min()
+                .unwrap()
+        };
+        let single = time(1);
+        let sharded = time(SHARD_COUNT);
+        println!("single lock: {single:?}, {SHARD_COUNT} shards: {sharded:?}");
+        // Generous margin: this guards against sharding making things worse,
+        // not a specific speed-up, which depends on the machine's cores.
+        assert!(
+            sharded <= single.mul_f64(1.5),
+            "single lock: {single:?}, {SHARD_COUNT} shards: {sharded:?}"
+        );
+    }
+}
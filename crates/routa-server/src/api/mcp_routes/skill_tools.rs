@@ -0,0 +1,356 @@
+//! Skill-backed MCP tools.
+//!
+//! With `ROUTA_MCP_SKILL_TOOLS=1`, every discovered skill whose frontmatter
+//! declares an `entry` script and an object `input-schema` is listed as a
+//! `skill_<name>` tool (default profile only). Calling the tool runs the entry
+//! script in a subprocess with a cleared environment, the skill directory as
+//! its working directory, the tool arguments as JSON on stdin, and a timeout;
+//! stdout becomes the tool result. Skills without a valid schema are skipped.
+//!
+//! The subprocess is confined only by process limits: the default address
+//! space cap, a CPU time cap equal to the timeout, and on Linux
+//! no-new-privileges (see `routa_core::acp::resource_limits`). It is not
+//! isolated from the filesystem or network; it can read and write whatever
+//! the server user can, so only enable skill tools for trusted skills.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use routa_core::acp::ResourceLimits;
+use routa_core::skills::SkillDefinition;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use super::tool_executor::{tool_result_error, tool_result_text};
+use crate::state::AppState;
+
+const SKILL_TOOL_PREFIX: &str = "skill_";
+
+const RUN_TIMEOUT: Duration = Duration::from_secs(60);
+/// Output beyond this many bytes (per stream) is dropped.
+const MAX_OUTPUT_BYTES: u64 = 64 * 1024;
+
+pub(super) fn skill_tools_enabled(state: &AppState) -> bool {
+    state
+        .mcp_tool_config
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .skill_tools
+}
+
+/// MCP tool name for a skill: `skill_` plus the name with anything outside
+/// `[A-Za-z0-9_-]` replaced by `_`.
+fn tool_name(skill_name: &str) -> String {
+    let sanitized: String = skill_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{SKILL_TOOL_PREFIX}{sanitized}")
+}
+
+/// The skill's input schema, if it declares an entry and an object schema.
+fn tool_schema(skill: &SkillDefinition) -> Result<&serde_json::Value, String> {
+    if skill.entry.is_none() {
+        return Err(format!("Skill '{}' declares no entry script", skill.name));
+    }
+    let schema = skill
+        .input_schema
+        .as_ref()
+        .ok_or_else(|| format!("Skill '{}' declares no input-schema", skill.name))?;
+    if schema.get("type").and_then(|value| value.as_str()) != Some("object") {
+        return Err(format!(
+            "Skill '{}' input-schema must have type: object",
+            skill.name
+        ));
+    }
+    Ok(schema)
+}
+
+/// Tool definitions for skills that can run as tools; empty when disabled.
+pub(super) fn skill_tool_defs(state: &AppState) -> Vec<serde_json::Value> {
+    if !skill_tools_enabled(state) {
+        return Vec::new();
+    }
+    state
+        .skill_registry
+        .list_skills_filtered(&Default::default())
+        .iter()
+        .filter_map(|skill| match tool_schema(skill) {
+            Ok(schema) => Some(serde_json::json!({
+                "name": tool_name(&skill.name),
+                "description": skill.description,
+                "inputSchema": schema,
+            })),
+            Err(reason) if skill.entry.is_some() || skill.input_schema.is_some() => {
+                tracing::warn!("[MCP] Not exposing skill as tool: {reason}");
+                None
+            }
+            Err(_) => None,
+        })
+        .collect()
+}
+
+/// Resolve the entry script, which must stay inside the skill directory.
+fn resolve_entry(skill: &SkillDefinition) -> Result<(PathBuf, PathBuf), String> {
+    let entry = skill.entry.as_deref().unwrap_or_default();
+    let skill_dir = Path::new(&skill.source)
+        .parent()
+        .and_then(|dir| dir.canonicalize().ok())
+        .ok_or_else(|| format!("Skill directory for '{}' is unavailable", skill.name))?;
+    let script = skill_dir
+        .join(entry)
+        .canonicalize()
+        .map_err(|_| format!("Entry script not found: {entry}"))?;
+    if !script.starts_with(&skill_dir) || !script.is_file() {
+        return Err(format!(
+            "Entry script must be a file inside the skill directory: {entry}"
+        ));
+    }
+    Ok((skill_dir, script))
+}
+
+/// Interpreter for well-known script extensions; other files run directly.
+fn command_for(script: &Path) -> tokio::process::Command {
+    let interpreter = match script.extension().and_then(|ext| ext.to_str()) {
+        Some("py") => Some("python3"),
+        Some("js") | Some("mjs") | Some("cjs") => Some("node"),
+        Some("sh") => Some("sh"),
+        _ => None,
+    };
+    match interpreter {
+        Some(interpreter) => {
+            let mut command = tokio::process::Command::new(interpreter);
+            command.arg(script);
+            command
+        }
+        None => tokio::process::Command::new(script),
+    }
+}
+
+/// Read `reader` to EOF, keeping the first `MAX_OUTPUT_BYTES`. The rest is
+/// drained and dropped so a chatty skill never blocks on a full pipe.
+async fn read_capped<R: tokio::io::AsyncRead + Unpin>(reader: Option<R>) -> String {
+    let mut bytes = Vec::new();
+    if let Some(mut reader) = reader {
+        let mut buf = [0u8; 8 * 1024];
+        while let Ok(n) = reader.read(&mut buf).await {
+            if n == 0 {
+                break;
+            }
+            let room = (MAX_OUTPUT_BYTES as usize).saturating_sub(bytes.len());
+            bytes.extend_from_slice(&buf[..n.min(room)]);
+        }
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+async fn run_skill(
+    skill: &SkillDefinition,
+    args: &serde_json::Value,
+    workspace_id: &str,
+) -> Result<String, String> {
+    let (skill_dir, script) = resolve_entry(skill)?;
+    let mut command = command_for(&script);
+    ResourceLimits {
+        max_cpu_secs: Some(RUN_TIMEOUT.as_secs()),
+        ..ResourceLimits::default()
+    }
+    .apply_without_new_privileges(&mut command);
+    let mut child = command
+        .current_dir(&skill_dir)
+        .env_clear()
+        .env("PATH", routa_core::shell_env::full_path())
+        .env("HOME", &skill_dir)
+        .env("ROUTA_SKILL_NAME", &skill.name)
+        .env("ROUTA_WORKSPACE_ID", workspace_id)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start skill '{}': {e}", skill.name))?;
+
+    let stdin = child.stdin.take();
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    // Write the arguments while draining output: a skill that prints before
+    // reading all of its input would otherwise deadlock on two full pipes.
+    let write_args = async move {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(args.to_string().as_bytes()).await;
+        }
+    };
+    let run = async {
+        let (_, stdout, stderr, status) = tokio::join!(
+            write_args,
+            read_capped(stdout),
+            read_capped(stderr),
+            child.wait()
+        );
+        (stdout, stderr, status)
+    };
+    let (stdout, stderr, status) = tokio::time::timeout(RUN_TIMEOUT, run).await.map_err(|_| {
+        format!(
+            "Skill '{}' timed out after {}s",
+            skill.name,
+            RUN_TIMEOUT.as_secs()
+        )
+    })?;
+    let status = status.map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(stdout)
+    } else {
+        Err(format!(
+            "Skill '{}' exited with {status}: {}",
+            skill.name,
+            if stderr.trim().is_empty() {
+                stdout.trim()
+            } else {
+                stderr.trim()
+            }
+        ))
+    }
+}
+
+/// Run a `skill_*` tool. Returns `None` for names that are not skill tools.
+pub(super) async fn execute(
+    state: &AppState,
+    name: &str,
+    args: &serde_json::Value,
+    workspace_id: &str,
+) -> Option<serde_json::Value> {
+    if !name.starts_with(SKILL_TOOL_PREFIX) {
+        return None;
+    }
+    if !skill_tools_enabled(state) {
+        return Some(tool_result_error(
+            "Skill tools are disabled (set ROUTA_MCP_SKILL_TOOLS=1)",
+        ));
+    }
+    let skill = state
+        .skill_registry
+        .list_skills()
+        .into_iter()
+        .find(|skill| tool_name(&skill.name) == name)?;
+    if let Err(reason) = tool_schema(&skill) {
+        return Some(tool_result_error(&reason));
+    }
+    Some(match run_skill(&skill, args, workspace_id).await {
+        Ok(output) => tool_result_text(&output),
+        Err(message) => tool_result_error(&message),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_name_sanitizes_skill_names() {
+        assert_eq!(tool_name("code-review"), "skill_code-review");
+        assert_eq!(tool_name("my skill.v2"), "skill_my_skill_v2");
+    }
+
+    fn skill(source: &str, entry: Option<&str>) -> SkillDefinition {
+        SkillDefinition {
+            name: "lint".to_string(),
+            description: "Lint".to_string(),
+            short_description: None,
+            content: String::new(),
+            source: source.to_string(),
+            license: None,
+            compatibility: None,
+            tags: Vec::new(),
+            metadata: Default::default(),
+            entry: entry.map(str::to_string),
+            input_schema: Some(serde_json::json!({ "type": "object" })),
+        }
+    }
+
+    #[test]
+    fn tool_schema_requires_entry_and_object_schema() {
+        let mut skill = skill("SKILL.md", None);
+        assert!(tool_schema(&skill).is_err());
+
+        skill.entry = Some("run.sh".to_string());
+        assert!(tool_schema(&skill).is_ok());
+
+        skill.input_schema = Some(serde_json::json!({ "type": "string" }));
+        assert!(tool_schema(&skill).is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn output_past_the_cap_is_drained_and_dropped() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("SKILL.md"), "").expect("write SKILL.md");
+        std::fs::write(
+            temp.path().join("run.sh"),
+            format!("head -c {} /dev/zero | tr '\\0' a\n", MAX_OUTPUT_BYTES * 4),
+        )
+        .expect("write run.sh");
+        let source = temp.path().join("SKILL.md");
+        let skill = skill(&source.to_string_lossy(), Some("run.sh"));
+
+        let output = tokio::time::timeout(
+            Duration::from_secs(10),
+            run_skill(&skill, &serde_json::json!({}), "default"),
+        )
+        .await
+        .expect("skill should finish instead of blocking on a full pipe")
+        .expect("skill should succeed");
+        assert_eq!(output.len() as u64, MAX_OUTPUT_BYTES);
+        assert!(output.bytes().all(|b| b == b'a'));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn large_input_is_written_while_output_is_drained() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("SKILL.md"), "").expect("write SKILL.md");
+        // Fill the stdout pipe before reading any input.
+        std::fs::write(
+            temp.path().join("run.sh"),
+            "head -c 262144 /dev/zero | tr '\\0' a\nwc -c >/dev/null\n",
+        )
+        .expect("write run.sh");
+        let source = temp.path().join("SKILL.md");
+        let skill = skill(&source.to_string_lossy(), Some("run.sh"));
+        let args = serde_json::json!({ "blob": "x".repeat(256 * 1024) });
+
+        let output =
+            tokio::time::timeout(Duration::from_secs(10), run_skill(&skill, &args, "default"))
+                .await
+                .expect("skill should finish instead of deadlocking on stdin")
+                .expect("skill should succeed");
+        assert_eq!(output.len() as u64, MAX_OUTPUT_BYTES);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn skills_run_without_new_privileges() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("SKILL.md"), "").expect("write SKILL.md");
+        std::fs::write(
+            temp.path().join("run.sh"),
+            "grep NoNewPrivs /proc/self/status\n",
+        )
+        .expect("write run.sh");
+        let source = temp.path().join("SKILL.md");
+        let skill = skill(&source.to_string_lossy(), Some("run.sh"));
+
+        let output = run_skill(&skill, &serde_json::json!({}), "default")
+            .await
+            .expect("skill should succeed");
+        assert!(
+            output.split_whitespace().eq(["NoNewPrivs:", "1"]),
+            "{output}"
+        );
+    }
+}
@@ -1,7 +1,35 @@
+use super::tool_executor::WORKSPACE_SCOPED_TOOLS;
+use crate::state::AppState;
+
 pub(super) fn build_tool_list_public() -> Vec<serde_json::Value> {
     build_tool_list_inner()
 }
 
+/// Drop tools disabled by the server's MCP tool configuration.
+pub(super) fn filter_enabled_tools(
+    state: &AppState,
+    tools: Vec<serde_json::Value>,
+) -> Vec<serde_json::Value> {
+    tools
+        .into_iter()
+        .filter(|tool| {
+            tool.get("name")
+                .and_then(|value| value.as_str())
+                .is_some_and(|name| state.mcp_tool_enabled(name))
+        })
+        .collect()
+}
+
+/// The tools `tools/list` returns for a session with `profile`: the profile's
+/// catalog, plus skill tools when no profile is set, minus disabled tools.
+pub(super) fn listed_tools(state: &AppState, profile: Option<&str>) -> Vec<serde_json::Value> {
+    let mut tools = build_tool_list_for_profile(profile);
+    if profile.is_none() {
+        tools.extend(super::skill_tools::skill_tool_defs(state));
+    }
+    filter_enabled_tools(state, tools)
+}
+
 pub(super) fn build_tool_list_for_profile(profile: Option<&str>) -> Vec<serde_json::Value> {
     let tools = build_tool_list_inner();
     match profile {
@@ -126,7 +154,9 @@ fn build_tool_list_inner() -> Vec<serde_json::Value> {
         tool_def("list_agents", "List all agents in the workspace", serde_json::json!({
             "type": "object",
             "properties": {
-                "workspaceId": { "type": "string", "description": "Workspace ID (default if omitted)" }
+                "workspaceId": { "type": "string", "description": "Workspace ID (default if omitted)" },
+                "limit": { "type": "integer", "minimum": 1, "maximum": 500, "description": "Maximum agents to return (default: 50)" },
+                "offset": { "type": "integer", "minimum": 0, "description": "Number of agents to skip (default: 0)" }
             }
         })),
         tool_def("create_agent", "Create a new agent (ROUTA=coordinator, CRAFTER=implementor, GATE=verifier, DEVELOPER=solo)", serde_json::json!({
@@ -166,7 +196,9 @@ fn build_tool_list_inner() -> Vec<serde_json::Value> {
         tool_def("list_tasks", "List all tasks in the workspace with status and assignments", serde_json::json!({
             "type": "object",
             "properties": {
-                "workspaceId": { "type": "string" }
+                "workspaceId": { "type": "string" },
+                "limit": { "type": "integer", "minimum": 1, "maximum": 500, "description": "Maximum tasks to return (default: 50)" },
+                "offset": { "type": "integer", "minimum": 0, "description": "Number of tasks to skip (default: 0)" }
             }
         })),
         tool_def("create_task", "Create a new task in the task store. Returns a taskId for delegation.", serde_json::json!({
@@ -192,6 +224,22 @@ fn build_tool_list_inner() -> Vec<serde_json::Value> {
             },
             "required": ["taskId", "status", "agentId"]
         })),
+        tool_def("delete_task", "Delete a task from the workspace. Fails if the task does not exist there.", serde_json::json!({
+            "type": "object",
+            "properties": {
+                "taskId": { "type": "string", "description": "ID of the task to delete" },
+                "workspaceId": { "type": "string", "description": "Workspace the task belongs to" }
+            },
+            "required": ["taskId"]
+        })),
+        tool_def("task_diff", "Get the combined diff of a task's changes: the commits on its worktree branch (or its issue/<id> branch) since it forked from the base branch, as structured per-file patches. Large diffs are truncated.", serde_json::json!({
+            "type": "object",
+            "properties": {
+                "taskId": { "type": "string", "description": "ID of the task whose changes to show" },
+                "workspaceId": { "type": "string", "description": "Workspace the task belongs to" }
+            },
+            "required": ["taskId"]
+        })),
         tool_def("update_task", "Atomically update structured task fields. Use this for story-readiness fields such as scope, acceptance criteria, verification commands, and test cases. agentId is optional for Kanban sessions.", serde_json::json!({
             "type": "object",
             "properties": {
@@ -207,6 +255,15 @@ fn build_tool_list_inner() -> Vec<serde_json::Value> {
             },
             "required": ["taskId"]
         })),
+        tool_def("move_task", "Move a task to another workspace. References to agents, dependencies, boards, worktrees, or codebases outside the target workspace are cleared and reported.", serde_json::json!({
+            "type": "object",
+            "properties": {
+                "taskId": { "type": "string", "description": "Task ID" },
+                "fromWorkspaceId": { "type": "string", "description": "Workspace the task currently belongs to" },
+                "toWorkspaceId": { "type": "string", "description": "Destination workspace" }
+            },
+            "required": ["taskId", "fromWorkspaceId", "toWorkspaceId"]
+        })),
         tool_def("get_my_task", "Get the task(s) assigned to the calling agent, including objective, scope, and acceptance criteria.", serde_json::json!({
             "type": "object",
             "properties": {
@@ -288,17 +345,28 @@ fn build_tool_list_inner() -> Vec<serde_json::Value> {
                 "type": { "type": "string", "enum": ["spec", "task", "general"], "description": "Filter by type" }
             }
         })),
-        tool_def("create_note", "Create a new note in the workspace for agent collaboration.", serde_json::json!({
+        tool_def("create_note", "Create a new note in the workspace for agent collaboration. With mode='append' and an existing noteId, the content is appended under a timestamped separator instead of replacing it.", serde_json::json!({
             "type": "object",
             "properties": {
                 "noteId": { "type": "string" },
                 "title": { "type": "string", "description": "Note title" },
                 "content": { "type": "string", "description": "Note content" },
                 "workspaceId": { "type": "string" },
-                "type": { "type": "string", "enum": ["spec", "task", "general"] }
+                "type": { "type": "string", "enum": ["spec", "task", "general"] },
+                "mode": { "type": "string", "enum": ["replace", "append"], "description": "How to treat an existing note (default: replace)" }
             },
             "required": ["title"]
         })),
+        tool_def("create_note_from_file", "Create a note from a text file in a repository. The file path is recorded in the note metadata; binary files and files over 1 MiB are rejected.", serde_json::json!({
+            "type": "object",
+            "properties": {
+                "repoPath": { "type": "string", "description": "Repository root the file is read from" },
+                "file": { "type": "string", "description": "File path relative to repoPath" },
+                "title": { "type": "string", "description": "Note title (default: the file path)" },
+                "workspaceId": { "type": "string" }
+            },
+            "required": ["repoPath", "file"]
+        })),
         tool_def("read_note", "Read the content of a note. Use noteId='spec' for the workspace spec note.", serde_json::json!({
             "type": "object",
             "properties": {
@@ -324,6 +392,23 @@ fn build_tool_list_inner() -> Vec<serde_json::Value> {
             },
             "required": ["noteId", "content"]
         })),
+        tool_def("move_note", "Move a note to another workspace. Linked tasks, parent notes, or assigned agents outside the target workspace are cleared and reported.", serde_json::json!({
+            "type": "object",
+            "properties": {
+                "noteId": { "type": "string", "description": "Note ID" },
+                "fromWorkspaceId": { "type": "string", "description": "Workspace the note currently belongs to" },
+                "toWorkspaceId": { "type": "string", "description": "Destination workspace" }
+            },
+            "required": ["noteId", "fromWorkspaceId", "toWorkspaceId"]
+        })),
+        tool_def("global_search", "Search task and note titles/content across all workspaces. Each result includes its workspaceId.", serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string", "description": "Text to search for" },
+                "limit": { "type": "integer", "description": "Maximum number of results (default: 50, max: 200)" }
+            },
+            "required": ["query"]
+        })),
         // ── Workspace tools ──────────────────────────────────────────────
         tool_def("list_workspaces", "List all workspaces with their id, title, status, and branch.", serde_json::json!({
             "type": "object",
@@ -335,9 +420,60 @@ fn build_tool_list_inner() -> Vec<serde_json::Value> {
                 "workspaceId": { "type": "string", "description": "Workspace ID" }
             }
         })),
-        tool_def("list_skills", "List all discovered skills", serde_json::json!({
+        tool_def("get_workspace", "Get a workspace's resolved settings: title, defaultCwd, defaultProvider, env, and the active branch of its default codebase.", serde_json::json!({
             "type": "object",
-            "properties": {}
+            "properties": {
+                "workspaceId": { "type": "string", "description": "Workspace ID" }
+            }
+        })),
+        tool_def("update_workspace", "Update a workspace's title and settings. Settings are partial: omitted keys are kept, null clears a setting, and env is merged per variable (null removes one). Returns the resolved settings.", serde_json::json!({
+            "type": "object",
+            "properties": {
+                "workspaceId": { "type": "string", "description": "Workspace ID" },
+                "title": { "type": "string", "description": "New workspace title" },
+                "settings": {
+                    "type": "object",
+                    "description": "Partial settings to apply",
+                    "properties": {
+                        "defaultCwd": { "type": ["string", "null"], "description": "Absolute path of an existing directory new sessions start in" },
+                        "defaultProvider": { "type": ["string", "null"], "description": "Provider new sessions use when none is given" },
+                        "env": { "type": ["object", "null"], "description": "Environment variables for new sessions", "additionalProperties": { "type": ["string", "null"] } }
+                    },
+                    "additionalProperties": false
+                }
+            }
+        })),
+        tool_def("get_workspace_branch", "Get the active branch recorded for a workspace codebase and the branch currently checked out in its repository.", serde_json::json!({
+            "type": "object",
+            "properties": {
+                "workspaceId": { "type": "string", "description": "Workspace ID" },
+                "codebaseId": { "type": "string", "description": "Codebase ID (default: the workspace's default codebase)" }
+            }
+        })),
+        tool_def("set_workspace_branch", "Check out a branch in a workspace codebase and record it as the workspace's active branch.", serde_json::json!({
+            "type": "object",
+            "properties": {
+                "workspaceId": { "type": "string", "description": "Workspace ID" },
+                "codebaseId": { "type": "string", "description": "Codebase ID (default: the workspace's default codebase)" },
+                "branch": { "type": "string", "description": "Branch to check out" },
+                "create": { "type": "boolean", "description": "Create the branch when it does not exist (default: false)" }
+            },
+            "required": ["branch"]
+        })),
+        tool_def("git_status", "Get the structured git status of a repository: current branch, upstream and ahead/behind counts, staged, unstaged, untracked and conflicted files, and whether the working tree is dirty.", serde_json::json!({
+            "type": "object",
+            "properties": {
+                "repoPath": { "type": "string", "description": "Repository path (default: the workspace's default codebase)" },
+                "workspaceId": { "type": "string", "description": "Workspace ID" },
+                "codebaseId": { "type": "string", "description": "Codebase ID, when repoPath is not given" }
+            }
+        })),
+        tool_def("list_skills", "List discovered skills, optionally filtered by provider compatibility and tag. When filters are given the response echoes them alongside the matching skills.", serde_json::json!({
+            "type": "object",
+            "properties": {
+                "compatibility": { "type": "string", "description": "Only skills that work with this provider (e.g. claude, opencode). Skills without declared compatibility always match." },
+                "tag": { "type": "string", "description": "Only skills tagged with this value" }
+            }
         })),
         tool_def("list_specialists", "List all available specialist configurations (roles, model tiers, descriptions).", serde_json::json!({
             "type": "object",
@@ -519,11 +655,101 @@ fn build_tool_list_inner() -> Vec<serde_json::Value> {
     ]
 }
 
-fn tool_def(name: &str, description: &str, input_schema: serde_json::Value) -> serde_json::Value {
+fn tool_def(
+    name: &str,
+    description: &str,
+    mut input_schema: serde_json::Value,
+) -> serde_json::Value {
+    if WORKSPACE_SCOPED_TOOLS.contains(&name) {
+        if let Some(properties) = input_schema
+            .get_mut("properties")
+            .and_then(|properties| properties.as_object_mut())
+        {
+            properties.insert(
+                "createIfMissing".to_string(),
+                serde_json::json!({
+                    "type": "boolean",
+                    "description": "Create workspaceId if it does not exist instead of failing (default: false)"
+                }),
+            );
+        }
+    }
     serde_json::json!({
         "name": name,
         "description": description,
         "inputSchema": input_schema,
+        "annotations": tool_annotations(name),
+    })
+}
+
+/// Tools that only read state.
+pub(super) fn tool_is_read_only(name: &str) -> bool {
+    matches!(
+        name,
+        "list_agents"
+            | "read_agent_conversation"
+            | "get_agent_status"
+            | "get_agent_summary"
+            | "list_tasks"
+            | "get_my_task"
+            | "task_diff"
+            | "list_artifacts"
+            | "get_artifact"
+            | "list_notes"
+            | "read_note"
+            | "global_search"
+            | "list_workspaces"
+            | "get_workspace_info"
+            | "get_workspace"
+            | "get_workspace_branch"
+            | "git_status"
+            | "list_skills"
+            | "list_specialists"
+            | "read_canvas_sdk_resource"
+            | "read_specialist_spec_resource"
+            | "list_boards"
+            | "get_board"
+            | "search_cards"
+            | "list_cards_by_column"
+    )
+}
+
+/// MCP tool annotations (`readOnlyHint`, `destructiveHint`, `idempotentHint`)
+/// so clients can tell side-effecting tools apart and warn before destructive
+/// calls. "Destructive" means the call deletes or overwrites existing data;
+/// tools that only add data are not.
+fn tool_annotations(name: &str) -> serde_json::Value {
+    let read_only = tool_is_read_only(name);
+    let destructive = matches!(
+        name,
+        "delete_task"
+            | "delete_card"
+            | "delete_column"
+            | "set_note_content"
+            | "update_task"
+            | "update_card"
+    );
+    let idempotent = read_only
+        || matches!(
+            name,
+            "update_task_status"
+                | "update_task"
+                | "move_task"
+                | "set_note_content"
+                | "move_note"
+                | "set_workspace_branch"
+                | "update_workspace"
+                | "unsubscribe_from_events"
+                | "move_card"
+                | "update_card"
+                | "delete_task"
+                | "delete_card"
+                | "delete_column"
+        );
+    serde_json::json!({
+        "readOnlyHint": read_only,
+        "destructiveHint": destructive,
+        "idempotentHint": idempotent,
     })
 }
 
@@ -531,7 +757,10 @@ fn tool_def(name: &str, description: &str, input_schema: serde_json::Value) -> s
 mod tests {
     use std::collections::HashSet;
 
-    use super::{build_tool_list_for_profile, tool_allowed_for_profile};
+    use super::{
+        build_tool_list_for_profile, build_tool_list_inner, tool_allowed_for_profile,
+        WORKSPACE_SCOPED_TOOLS,
+    };
 
     #[test]
     fn kanban_profile_only_allows_kanban_tools() {
@@ -597,4 +826,52 @@ mod tests {
         assert!(!properties.contains_key("verificationVerdict"));
         assert!(!properties.contains_key("assignedTo"));
     }
+
+    #[test]
+    fn workspace_scoped_tools_declare_create_if_missing() {
+        let tools = build_tool_list_inner();
+        for name in WORKSPACE_SCOPED_TOOLS {
+            let tool = tools
+                .iter()
+                .find(|tool| tool["name"] == *name)
+                .unwrap_or_else(|| panic!("{name} is not in the catalog"));
+            assert_eq!(
+                tool["inputSchema"]["properties"]["createIfMissing"]["type"], "boolean",
+                "{name}"
+            );
+        }
+        let list_skills = tools
+            .iter()
+            .find(|tool| tool["name"] == "list_skills")
+            .unwrap();
+        assert!(list_skills["inputSchema"]["properties"]
+            .get("createIfMissing")
+            .is_none());
+    }
+
+    #[test]
+    fn every_tool_carries_consistent_annotations() {
+        for tool in build_tool_list_inner() {
+            let name = tool["name"].as_str().unwrap();
+            let annotations = &tool["annotations"];
+            let read_only = annotations["readOnlyHint"].as_bool().unwrap();
+            let destructive = annotations["destructiveHint"].as_bool().unwrap();
+            let idempotent = annotations["idempotentHint"].as_bool().unwrap();
+            assert!(!(read_only && destructive), "{name}");
+            assert!(!read_only || idempotent, "{name}");
+        }
+
+        let tools = build_tool_list_inner();
+        let annotations_of = |name: &str| {
+            tools
+                .iter()
+                .find(|tool| tool["name"] == name)
+                .map(|tool| tool["annotations"].clone())
+                .unwrap()
+        };
+        assert_eq!(annotations_of("list_tasks")["readOnlyHint"], true);
+        assert_eq!(annotations_of("create_task")["readOnlyHint"], false);
+        assert_eq!(annotations_of("create_task")["idempotentHint"], false);
+        assert_eq!(annotations_of("delete_card")["destructiveHint"], true);
+    }
 }
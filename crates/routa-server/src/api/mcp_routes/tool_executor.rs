@@ -3,24 +3,180 @@ mod delegation;
 mod events_kanban;
 mod notes_workspace;
 
+use crate::models::workspace::Workspace;
 use crate::rpc::RpcRouter;
 use crate::state::AppState;
 
+/// Tools that create or list entities inside `workspaceId`; these are
+/// rejected when the workspace does not exist instead of writing into a
+/// phantom workspace. Their schemas declare `createIfMissing`, which creates
+/// the workspace instead.
+pub(super) const WORKSPACE_SCOPED_TOOLS: &[&str] = &[
+    "list_agents",
+    "create_agent",
+    "list_tasks",
+    "create_task",
+    "list_notes",
+    "create_note",
+    "create_note_from_file",
+    "list_boards",
+    "create_board",
+    "create_card",
+    "search_cards",
+    "list_cards_by_column",
+    "get_workspace",
+    "update_workspace",
+    "get_workspace_branch",
+    "set_workspace_branch",
+];
+
+/// Argument that makes a cacheable read tool skip the result cache.
+const CACHE_BYPASS_ARG: &str = "noCache";
+
+/// Kinds of data the cacheable read tools depend on and mutations touch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolDomain {
+    Agents,
+    Tasks,
+    Artifacts,
+    Notes,
+    Workspaces,
+    Skills,
+    Kanban,
+}
+
+const ALL_DOMAINS: &[ToolDomain] = &[
+    ToolDomain::Agents,
+    ToolDomain::Tasks,
+    ToolDomain::Artifacts,
+    ToolDomain::Notes,
+    ToolDomain::Workspaces,
+    ToolDomain::Skills,
+    ToolDomain::Kanban,
+];
+
+/// Idempotent read tools whose results are cached, with the data they read.
+/// Tools not listed here (conversations, live agent status, diffs, search)
+/// always run.
+fn cached_tool_reads(name: &str) -> Option<&'static [ToolDomain]> {
+    use ToolDomain::*;
+    Some(match name {
+        "list_agents" => &[Agents],
+        "list_tasks" => &[Tasks],
+        "list_artifacts" => &[Artifacts],
+        "list_notes" => &[Notes],
+        "list_workspaces" => &[Workspaces],
+        "get_workspace_info" => ALL_DOMAINS,
+        "get_workspace" => &[Workspaces],
+        "list_skills" | "list_specialists" => &[Skills],
+        // Kanban cards are tasks, so boards change with them.
+        "list_boards" | "get_board" => &[Kanban, Tasks],
+        _ => return None,
+    })
+}
+
+/// Data a mutating tool may change. Unknown mutating tools are assumed to
+/// touch everything.
+fn mutated_domains(name: &str) -> &'static [ToolDomain] {
+    use ToolDomain::*;
+    match name {
+        "create_agent" | "send_message_to_agent" => &[Agents],
+        "delegate_task_to_agent" | "report_to_parent" => &[Agents, Tasks, Kanban],
+        "create_task"
+        | "update_task"
+        | "update_task_status"
+        | "delete_task"
+        | "move_task"
+        | "decompose_tasks"
+        | "request_previous_lane_handoff"
+        | "submit_lane_handoff" => &[Tasks, Kanban],
+        "provide_artifact" => &[Artifacts, Tasks],
+        "create_note"
+        | "create_note_from_file"
+        | "set_note_content"
+        | "append_to_note"
+        | "move_note" => &[Notes],
+        "create_board" | "create_column" | "delete_column" => &[Kanban],
+        "set_workspace_branch" | "update_workspace" => &[Workspaces],
+        "create_card" | "update_card" | "move_card" | "delete_card" => &[Kanban, Tasks],
+        "subscribe_to_events" | "unsubscribe_from_events" => &[],
+        _ => ALL_DOMAINS,
+    }
+}
+
+/// Cache key for `name` called with `args`: the tool name plus the arguments
+/// with object keys sorted and the bypass flag removed.
+fn tool_cache_key(name: &str, args: &serde_json::Value) -> String {
+    fn canonical(value: &serde_json::Value, out: &mut String) {
+        match value {
+            serde_json::Value::Object(map) => {
+                let mut keys = map
+                    .keys()
+                    .filter(|key| key.as_str() != CACHE_BYPASS_ARG)
+                    .collect::<Vec<_>>();
+                keys.sort();
+                out.push('{');
+                for (index, key) in keys.into_iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                    out.push(':');
+                    canonical(&map[key], out);
+                }
+                out.push('}');
+            }
+            serde_json::Value::Array(items) => {
+                out.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    canonical(item, out);
+                }
+                out.push(']');
+            }
+            other => out.push_str(&other.to_string()),
+        }
+    }
+
+    let mut key = format!("{name}:");
+    canonical(args, &mut key);
+    key
+}
+
 pub(super) async fn execute_tool_public(
     state: &AppState,
     name: &str,
     args: &serde_json::Value,
 ) -> serde_json::Value {
-    execute_tool(state, normalize_tool_name(name), args, None).await
+    execute_tool(state, normalize_tool_name(name), args, None, None).await
 }
 
-pub(super) async fn execute_tool_for_profile_public(
+/// Execute a tool on behalf of an MCP session. `verified_workspace` is the
+/// session's workspace when it is already known to exist, letting
+/// workspace-scoped tools skip the store lookup.
+pub(super) async fn execute_tool_for_session_public(
     state: &AppState,
     name: &str,
     args: &serde_json::Value,
     mcp_profile: Option<&str>,
+    verified_workspace: Option<&str>,
 ) -> serde_json::Value {
-    execute_tool(state, normalize_tool_name(name), args, mcp_profile).await
+    execute_tool(
+        state,
+        normalize_tool_name(name),
+        args,
+        mcp_profile,
+        verified_workspace,
+    )
+    .await
+}
+
+pub(super) async fn workspace_exists_public(state: &AppState, workspace_id: &str) -> bool {
+    ensure_workspace_exists(state, workspace_id, false)
+        .await
+        .is_ok()
 }
 
 pub(super) fn normalize_tool_name_public(name: &str) -> &str {
@@ -32,12 +188,76 @@ async fn execute_tool(
     name: &str,
     args: &serde_json::Value,
     mcp_profile: Option<&str>,
+    verified_workspace: Option<&str>,
 ) -> serde_json::Value {
+    if !state.mcp_tool_enabled(name) {
+        return tool_result_error(&format!("Tool disabled by server configuration: {name}"));
+    }
+
     let workspace_id = args
         .get("workspaceId")
         .and_then(|v| v.as_str())
         .unwrap_or("default");
 
+    if WORKSPACE_SCOPED_TOOLS.contains(&name) && verified_workspace != Some(workspace_id) {
+        let create_if_missing = args
+            .get("createIfMissing")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if let Err(message) = ensure_workspace_exists(state, workspace_id, create_if_missing).await
+        {
+            return tool_result_error(&message);
+        }
+    }
+
+    let cache = &state.mcp_tool_cache;
+    let cache_key = cached_tool_reads(name)
+        .filter(|_| cache.is_enabled())
+        .filter(|_| {
+            !args
+                .get(CACHE_BYPASS_ARG)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        })
+        .map(|_| tool_cache_key(name, args));
+    if let Some(hit) = cache_key.as_deref().and_then(|key| cache.get(key)) {
+        return hit;
+    }
+
+    let result = dispatch_tool(state, name, args, workspace_id, mcp_profile).await;
+
+    // Failed mutations may still have written partially, so invalidate
+    // regardless of the outcome. Any tool may create its workspace.
+    let mut touched = if super::tool_catalog::tool_is_read_only(name) {
+        Vec::new()
+    } else {
+        mutated_domains(name).to_vec()
+    };
+    if args.get("createIfMissing").and_then(|v| v.as_bool()) == Some(true) {
+        touched.push(ToolDomain::Workspaces);
+    }
+    if !touched.is_empty() {
+        cache.invalidate(|tool| {
+            cached_tool_reads(tool)
+                .is_some_and(|reads| reads.iter().any(|domain| touched.contains(domain)))
+        });
+    }
+    if let Some(key) = cache_key {
+        if result.get("isError").and_then(|v| v.as_bool()) != Some(true) {
+            cache.insert(name, key, result.clone());
+        }
+    }
+
+    result
+}
+
+async fn dispatch_tool(
+    state: &AppState,
+    name: &str,
+    args: &serde_json::Value,
+    workspace_id: &str,
+    mcp_profile: Option<&str>,
+) -> serde_json::Value {
     if let Some(result) = agents_tasks::execute(state, name, args, workspace_id, mcp_profile).await
     {
         return result;
@@ -51,10 +271,87 @@ async fn execute_tool(
     if let Some(result) = events_kanban::execute(state, name, args, workspace_id).await {
         return result;
     }
+    if let Some(result) = super::skill_tools::execute(state, name, args, workspace_id).await {
+        return result;
+    }
 
     tool_result_error(&format!("Unknown tool: {name}"))
 }
 
+/// Verify `workspace_id` refers to a real workspace. The `default` workspace
+/// is always materialized on demand; other ids are only created when
+/// `create_if_missing` is set.
+async fn ensure_workspace_exists(
+    state: &AppState,
+    workspace_id: &str,
+    create_if_missing: bool,
+) -> Result<(), String> {
+    if workspace_id == "default" {
+        return state
+            .workspace_store
+            .ensure_default()
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string());
+    }
+
+    match state.workspace_store.get(workspace_id).await {
+        Ok(Some(_)) => return Ok(()),
+        Ok(None) => {}
+        Err(e) => return Err(e.to_string()),
+    }
+
+    if create_if_missing {
+        let workspace = Workspace::new(workspace_id.to_string(), workspace_id.to_string(), None);
+        return state
+            .workspace_store
+            .save(&workspace)
+            .await
+            .map_err(|e| e.to_string());
+    }
+
+    let valid = state
+        .workspace_store
+        .list()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|workspace| workspace.id)
+        .collect::<Vec<_>>();
+    Err(format!(
+        "Workspace not found: {workspace_id}. Valid workspaces: {}. Pass createIfMissing: true to create it.",
+        if valid.is_empty() {
+            "(none)".to_string()
+        } else {
+            valid.join(", ")
+        }
+    ))
+}
+
+/// Read and validate `fromWorkspaceId` / `toWorkspaceId` for the move tools.
+/// Both workspaces must exist and differ.
+async fn move_workspace_ids(
+    state: &AppState,
+    args: &serde_json::Value,
+) -> Result<(String, String), String> {
+    let from = args
+        .get("fromWorkspaceId")
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| "Missing fromWorkspaceId".to_string())?;
+    let to = args
+        .get("toWorkspaceId")
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| "Missing toWorkspaceId".to_string())?;
+    if from == to {
+        return Err("fromWorkspaceId and toWorkspaceId must differ".to_string());
+    }
+    ensure_workspace_exists(state, from, false).await?;
+    ensure_workspace_exists(state, to, false).await?;
+    Ok((from.to_string(), to.to_string()))
+}
+
 fn normalize_tool_name(name: &str) -> &str {
     name.strip_prefix("routa-coordination_")
         .or_else(|| name.strip_prefix("kanban-planning-mcp_"))
@@ -102,6 +399,55 @@ pub(super) fn tool_result_json(value: &serde_json::Value) -> serde_json::Value {
     })
 }
 
+/// Page size for list tools called without `limit`.
+const DEFAULT_TOOL_LIST_LIMIT: usize = 50;
+
+/// One page of `items` under `key`, sliced by the optional `limit` (default
+/// 50, capped at `MAX_PAGE_LIMIT`) and `offset` arguments, with the `total`
+/// item count and whether more follow.
+pub(super) fn tool_result_page<T: serde::Serialize>(
+    items: Vec<T>,
+    key: &str,
+    args: &serde_json::Value,
+) -> serde_json::Value {
+    let limit = args
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_TOOL_LIST_LIMIT, |limit| limit as usize)
+        .clamp(1, crate::api::pagination::MAX_PAGE_LIMIT);
+    let offset = args
+        .get("offset")
+        .and_then(|v| v.as_u64())
+        .map_or(0, |offset| offset as usize);
+    let total = items.len();
+    let page = items
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .collect::<Vec<_>>();
+    let has_more = offset.saturating_add(page.len()) < total;
+    let mut body = serde_json::json!({
+        "total": total,
+        "offset": offset,
+        "limit": limit,
+        "hasMore": has_more,
+    });
+    body[key] = serde_json::to_value(page).unwrap_or_default();
+    tool_result_json(&body)
+}
+
+/// Tool result for rejected arguments. The `error` member carries a JSON-RPC
+/// `-32602` payload that the MCP transport surfaces as an invalid-params error.
+pub(super) fn tool_result_invalid_params(
+    error: &crate::models::validation::ValidationError,
+) -> serde_json::Value {
+    serde_json::json!({
+        "isError": true,
+        "content": [{ "type": "text", "text": error.to_string() }],
+        "error": error.to_json_rpc_error()
+    })
+}
+
 pub(super) fn tool_result_error(msg: &str) -> serde_json::Value {
     serde_json::json!({
         "isError": true,
@@ -111,7 +457,12 @@ pub(super) fn tool_result_error(msg: &str) -> serde_json::Value {
 
 #[cfg(test)]
 mod tests {
-    use super::normalize_tool_name_public;
+    use super::{execute_tool_public, normalize_tool_name_public, tool_cache_key};
+    use crate::api::mcp_routes::test_state;
+
+    fn result_text(result: &serde_json::Value) -> &str {
+        result["content"][0]["text"].as_str().unwrap_or_default()
+    }
 
     #[test]
     fn normalize_tool_name_supports_compat_prefixes() {
@@ -125,4 +476,168 @@ mod tests {
         );
         assert_eq!(normalize_tool_name_public("list_tasks"), "list_tasks");
     }
+
+    #[test]
+    fn tool_cache_key_ignores_key_order_and_bypass_flag() {
+        let a = serde_json::json!({ "workspaceId": "default", "status": "PENDING" });
+        let b =
+            serde_json::json!({ "status": "PENDING", "noCache": true, "workspaceId": "default" });
+        assert_eq!(
+            tool_cache_key("list_tasks", &a),
+            tool_cache_key("list_tasks", &b)
+        );
+        assert_ne!(
+            tool_cache_key("list_tasks", &a),
+            tool_cache_key("list_agents", &a)
+        );
+    }
+
+    #[tokio::test]
+    async fn mutations_invalidate_cached_reads() {
+        let state = test_state().await;
+        let args = serde_json::json!({ "workspaceId": "default" });
+
+        let before = execute_tool_public(&state, "list_agents", &args).await;
+        let created = execute_tool_public(
+            &state,
+            "create_agent",
+            &serde_json::json!({ "workspaceId": "default", "name": "cached-agent", "role": "CRAFTER" }),
+        )
+        .await;
+        assert_eq!(created["isError"], false, "{created}");
+
+        let after = execute_tool_public(&state, "list_agents", &args).await;
+        assert!(!result_text(&before).contains("cached-agent"));
+        assert!(result_text(&after).contains("cached-agent"));
+    }
+
+    #[test]
+    fn tool_result_page_slices_and_reports_total() {
+        let items = (0..5).collect::<Vec<_>>();
+        let page = |args: serde_json::Value| -> serde_json::Value {
+            serde_json::from_str(result_text(&super::tool_result_page(
+                items.clone(),
+                "items",
+                &args,
+            )))
+            .expect("page JSON")
+        };
+
+        let first = page(serde_json::json!({ "limit": 2 }));
+        assert_eq!(first["items"], serde_json::json!([0, 1]));
+        assert_eq!(first["total"], 5);
+        assert_eq!(first["hasMore"], true);
+
+        let last = page(serde_json::json!({ "limit": 2, "offset": 4 }));
+        assert_eq!(last["items"], serde_json::json!([4]));
+        assert_eq!(last["hasMore"], false);
+
+        let default = page(serde_json::json!({}));
+        assert_eq!(default["limit"], 50);
+        assert_eq!(default["items"].as_array().map(Vec::len), Some(5));
+        assert_eq!(default["hasMore"], false);
+    }
+
+    #[tokio::test]
+    async fn no_cache_flag_bypasses_cached_reads() {
+        let state = test_state().await;
+        let args = serde_json::json!({ "workspaceId": "default" });
+        execute_tool_public(&state, "list_notes", &args).await;
+
+        // Written behind the MCP layer, so no invalidation happens.
+        let note = crate::models::note::Note::new(
+            "direct-note".to_string(),
+            "Direct note".to_string(),
+            String::new(),
+            "default".to_string(),
+            None,
+        );
+        state.note_store.save(&note).await.expect("save note");
+
+        let cached = execute_tool_public(&state, "list_notes", &args).await;
+        assert!(!result_text(&cached).contains("direct-note"));
+        let fresh = execute_tool_public(
+            &state,
+            "list_notes",
+            &serde_json::json!({ "workspaceId": "default", "noCache": true }),
+        )
+        .await;
+        assert!(result_text(&fresh).contains("direct-note"));
+    }
+
+    #[tokio::test]
+    async fn execute_tool_public_returns_error_for_unknown_tool() {
+        let state = test_state().await;
+
+        let result = execute_tool_public(&state, "unknown_tool_name", &serde_json::json!({})).await;
+        assert_eq!(result.get("isError").and_then(|v| v.as_bool()), Some(true));
+    }
+
+    #[tokio::test]
+    async fn execute_tool_public_rejects_unknown_workspace() {
+        let state = test_state().await;
+
+        let result = execute_tool_public(
+            &state,
+            "create_note",
+            &serde_json::json!({ "title": "phantom", "workspaceId": "does-not-exist" }),
+        )
+        .await;
+        assert_eq!(result.get("isError").and_then(|v| v.as_bool()), Some(true));
+        let text = result["content"][0]["text"].as_str().unwrap_or_default();
+        assert!(text.contains("Workspace not found: does-not-exist"));
+        assert!(text.contains("default"));
+        assert!(state
+            .note_store
+            .list_by_workspace("does-not-exist")
+            .await
+            .expect("list notes")
+            .is_empty());
+
+        let created = execute_tool_public(
+            &state,
+            "list_notes",
+            &serde_json::json!({ "workspaceId": "does-not-exist", "createIfMissing": true }),
+        )
+        .await;
+        assert_eq!(
+            created.get("isError").and_then(|v| v.as_bool()),
+            Some(false)
+        );
+        assert!(state
+            .workspace_store
+            .get("does-not-exist")
+            .await
+            .expect("get workspace")
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn execute_tool_public_rejects_disabled_tool() {
+        let state = test_state().await;
+        state
+            .mcp_tool_config
+            .write()
+            .expect("tool config lock")
+            .disabled
+            .insert("create_agent".to_string());
+
+        let result = execute_tool_public(
+            &state,
+            "create_agent",
+            &serde_json::json!({ "name": "blocked", "role": "CRAFTER" }),
+        )
+        .await;
+        assert_eq!(result.get("isError").and_then(|v| v.as_bool()), Some(true));
+        let text = result["content"][0]["text"].as_str().unwrap_or_default();
+        assert!(text.contains("disabled"));
+
+        let listed = crate::api::mcp_routes::build_enabled_tool_list_public(&state);
+        assert!(!listed
+            .iter()
+            .any(|tool| tool.get("name").and_then(|v| v.as_str()) == Some("create_agent")));
+        assert!(listed
+            .iter()
+            .any(|tool| tool.get("name").and_then(|v| v.as_str()) == Some("list_agents")));
+    }
 }
@@ -1,6 +1,9 @@
 use crate::state::AppState;
 
-use super::{rpc_tool_result, tool_result_error, tool_result_json, tool_result_text};
+use super::{
+    rpc_tool_result, tool_result_error, tool_result_invalid_params, tool_result_json,
+    tool_result_page, tool_result_text,
+};
 
 pub(super) async fn execute(
     state: &AppState,
@@ -11,16 +14,11 @@ pub(super) async fn execute(
 ) -> Option<serde_json::Value> {
     let result = match name {
         "list_agents" => match state.agent_store.list_by_workspace(workspace_id).await {
-            Ok(agents) => {
-                tool_result_text(&serde_json::to_string_pretty(&agents).unwrap_or_default())
-            }
+            Ok(agents) => tool_result_page(agents, "agents", args),
             Err(e) => tool_result_error(&e.to_string()),
         },
         "create_agent" => {
-            let name_val = args
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unnamed");
+            let name_val = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
             let role_str = args
                 .get("role")
                 .and_then(|v| v.as_str())
@@ -32,7 +30,7 @@ pub(super) async fn execute(
             let role = crate::models::agent::AgentRole::from_str(role_str);
             match role {
                 Some(r) => {
-                    let agent = crate::models::agent::Agent::new(
+                    let agent = match crate::models::agent::Agent::try_new(
                         uuid::Uuid::new_v4().to_string(),
                         name_val.to_string(),
                         r,
@@ -40,7 +38,10 @@ pub(super) async fn execute(
                         parent_id,
                         None,
                         None,
-                    );
+                    ) {
+                        Ok(agent) => agent,
+                        Err(errors) => return Some(tool_result_invalid_params(&errors)),
+                    };
                     match state.agent_store.save(&agent).await {
                         Ok(_) => tool_result_json(&serde_json::json!({
                             "success": true,
@@ -129,22 +130,17 @@ pub(super) async fn execute(
             }
         }
         "list_tasks" => match state.task_store.list_by_workspace(workspace_id).await {
-            Ok(tasks) => {
-                tool_result_text(&serde_json::to_string_pretty(&tasks).unwrap_or_default())
-            }
+            Ok(tasks) => tool_result_page(tasks, "tasks", args),
             Err(e) => tool_result_error(&e.to_string()),
         },
         "create_task" => {
-            let title = args
-                .get("title")
-                .and_then(|v| v.as_str())
-                .unwrap_or("Untitled");
+            let title = args.get("title").and_then(|v| v.as_str()).unwrap_or("");
             let objective = args.get("objective").and_then(|v| v.as_str()).unwrap_or("");
             let session_id = args
                 .get("sessionId")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
-            let mut task = crate::models::task::Task::new(
+            let mut task = match crate::models::task::Task::try_new(
                 uuid::Uuid::new_v4().to_string(),
                 title.to_string(),
                 objective.to_string(),
@@ -158,7 +154,10 @@ pub(super) async fn execute(
                 None,
                 None,
                 None,
-            );
+            ) {
+                Ok(task) => task,
+                Err(errors) => return Some(tool_result_invalid_params(&errors)),
+            };
             if let Some(source) = args
                 .get("creationSource")
                 .and_then(|v| v.as_str())
@@ -208,6 +207,74 @@ pub(super) async fn execute(
                 None => tool_result_error(&format!("Invalid status: {status_str}")),
             }
         }
+        "delete_task" => {
+            let task_id = args.get("taskId").and_then(|v| v.as_str()).unwrap_or("");
+            let mut errors = crate::models::validation::ValidationError::new();
+            errors.require_non_empty("taskId", task_id);
+            if !errors.is_empty() {
+                return Some(tool_result_invalid_params(&errors));
+            }
+            match state
+                .task_store
+                .delete_in_workspace(task_id, workspace_id)
+                .await
+            {
+                Ok(true) => tool_result_text(&format!(
+                    "Deleted task {task_id} from workspace {workspace_id}"
+                )),
+                Ok(false) => tool_result_error(&format!(
+                    "Task not found: {task_id} in workspace {workspace_id}"
+                )),
+                Err(e) => tool_result_error(&e.to_string()),
+            }
+        }
+        "task_diff" => {
+            let task_id = args.get("taskId").and_then(|v| v.as_str()).unwrap_or("");
+            let mut errors = crate::models::validation::ValidationError::new();
+            errors.require_non_empty("taskId", task_id);
+            if !errors.is_empty() {
+                return Some(tool_result_invalid_params(&errors));
+            }
+            let task = match state.task_store.get(task_id).await {
+                Ok(Some(task)) if task.workspace_id == workspace_id => task,
+                Ok(_) => {
+                    return Some(tool_result_error(&format!(
+                        "Task not found: {task_id} in workspace {workspace_id}"
+                    )))
+                }
+                Err(e) => return Some(tool_result_error(&e.to_string())),
+            };
+            let Some((repo_path, base, head)) = task_change_range(state, &task).await else {
+                return Some(tool_result_text(&format!(
+                    "No changes are associated with task {task_id} yet: it has no worktree and no {} branch exists in its codebases.",
+                    task_branch_name(&task.id)
+                )));
+            };
+            let diff = tokio::task::spawn_blocking(move || {
+                crate::git::get_range_diff(&repo_path, &base, &head, TASK_DIFF_MAX_PATCH_BYTES)
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result);
+            match diff {
+                Ok(diff) if diff.files.is_empty() => tool_result_text(&format!(
+                    "No changes are associated with task {task_id} yet: branch {} has no changes relative to {}.",
+                    diff.head, diff.base
+                )),
+                Ok(diff) => tool_result_json(&serde_json::json!({
+                    "taskId": task_id,
+                    "base": diff.base,
+                    "head": diff.head,
+                    "commits": diff.commits,
+                    "files": diff.files,
+                    "additions": diff.additions,
+                    "deletions": diff.deletions,
+                    "truncated": !diff.truncated_files.is_empty(),
+                    "truncatedFiles": diff.truncated_files,
+                })),
+                Err(e) => tool_result_error(&e),
+            }
+        }
         "update_task" => {
             let blocked_fields =
                 super::super::tool_catalog::protected_update_task_fields_for_profile(
@@ -287,6 +354,98 @@ pub(super) async fn execute(
                 Err(e) => tool_result_error(&e.to_string()),
             }
         }
+        "move_task" => {
+            let task_id = args.get("taskId").and_then(|v| v.as_str()).unwrap_or("");
+            let (from, to) = match super::move_workspace_ids(state, args).await {
+                Ok(ids) => ids,
+                Err(message) => return Some(tool_result_error(&message)),
+            };
+            let mut task = match state.task_store.get(task_id).await {
+                Ok(Some(task)) if task.workspace_id == from => task,
+                Ok(_) => {
+                    return Some(tool_result_error(&format!(
+                        "Task not found in workspace {from}: {task_id}"
+                    )))
+                }
+                Err(e) => return Some(tool_result_error(&e.to_string())),
+            };
+
+            // Drop references to entities that do not live in the target workspace.
+            let mut cleared = serde_json::Map::new();
+            if let Some(agent_id) = task.assigned_to.clone() {
+                let in_target = matches!(
+                    state.agent_store.get(&agent_id).await,
+                    Ok(Some(agent)) if agent.workspace_id == to
+                );
+                if !in_target {
+                    task.assigned_to = None;
+                    cleared.insert("assignedTo".into(), serde_json::json!(agent_id));
+                }
+            }
+            let mut kept_dependencies = Vec::new();
+            let mut cleared_dependencies = Vec::new();
+            for dependency in std::mem::take(&mut task.dependencies) {
+                match state.task_store.get(&dependency).await {
+                    Ok(Some(dep)) if dep.workspace_id == to => kept_dependencies.push(dependency),
+                    _ => cleared_dependencies.push(dependency),
+                }
+            }
+            task.dependencies = kept_dependencies;
+            if !cleared_dependencies.is_empty() {
+                cleared.insert(
+                    "dependencies".into(),
+                    serde_json::json!(cleared_dependencies),
+                );
+            }
+            if let Some(board_id) = task.board_id.clone() {
+                let in_target = matches!(
+                    state.kanban_store.get(&board_id).await,
+                    Ok(Some(board)) if board.workspace_id == to
+                );
+                if !in_target {
+                    task.board_id = None;
+                    task.column_id = None;
+                    cleared.insert("boardId".into(), serde_json::json!(board_id));
+                }
+            }
+            if let Some(worktree_id) = task.worktree_id.clone() {
+                let in_target = matches!(
+                    state.worktree_store.get(&worktree_id).await,
+                    Ok(Some(worktree)) if worktree.workspace_id == to
+                );
+                if !in_target {
+                    task.worktree_id = None;
+                    cleared.insert("worktreeId".into(), serde_json::json!(worktree_id));
+                }
+            }
+            let mut kept_codebases = Vec::new();
+            let mut cleared_codebases = Vec::new();
+            for codebase_id in std::mem::take(&mut task.codebase_ids) {
+                match state.codebase_store.get(&codebase_id).await {
+                    Ok(Some(codebase)) if codebase.workspace_id == to => {
+                        kept_codebases.push(codebase_id)
+                    }
+                    _ => cleared_codebases.push(codebase_id),
+                }
+            }
+            task.codebase_ids = kept_codebases;
+            if !cleared_codebases.is_empty() {
+                cleared.insert("codebaseIds".into(), serde_json::json!(cleared_codebases));
+            }
+
+            task.workspace_id = to.clone();
+            task.updated_at = chrono::Utc::now();
+            match state.task_store.save(&task).await {
+                Ok(_) => tool_result_json(&serde_json::json!({
+                    "success": true,
+                    "taskId": task_id,
+                    "fromWorkspaceId": from,
+                    "toWorkspaceId": to,
+                    "clearedReferences": cleared
+                })),
+                Err(e) => tool_result_error(&e.to_string()),
+            }
+        }
         "get_my_task" => {
             let agent_id = args.get("agentId").and_then(|v| v.as_str()).unwrap_or("");
             match state.task_store.list_by_assignee(agent_id).await {
@@ -385,6 +544,53 @@ pub(super) async fn execute(
     Some(result)
 }
 
+/// Total patch bytes `task_diff` returns before truncating the remaining files.
+const TASK_DIFF_MAX_PATCH_BYTES: usize = 200 * 1024;
+
+/// Branch `auto_create_worktree` creates for a task.
+fn task_branch_name(task_id: &str) -> String {
+    format!("issue/{}", &task_id[..task_id.len().min(8)])
+}
+
+/// Where a task's changes live, as `(repo_path, base_ref, head_branch)`: its
+/// worktree's branch when it has one, otherwise the conventional task branch
+/// in one of its codebases (or the workspace default codebase).
+async fn task_change_range(
+    state: &AppState,
+    task: &crate::models::task::Task,
+) -> Option<(String, String, String)> {
+    if let Some(worktree_id) = task.worktree_id.as_deref() {
+        if let Ok(Some(worktree)) = state.worktree_store.get(worktree_id).await {
+            let repo_path = match state.codebase_store.get(&worktree.codebase_id).await {
+                Ok(Some(codebase)) => codebase.repo_path,
+                _ => worktree.worktree_path,
+            };
+            let base = crate::git::resolve_base_ref(&repo_path, Some(&worktree.base_branch))?;
+            return Some((repo_path, base, worktree.branch));
+        }
+    }
+
+    let mut codebases = Vec::new();
+    for codebase_id in &task.codebase_ids {
+        if let Ok(Some(codebase)) = state.codebase_store.get(codebase_id).await {
+            codebases.push(codebase);
+        }
+    }
+    if codebases.is_empty() {
+        if let Ok(Some(codebase)) = state.codebase_store.get_default(&task.workspace_id).await {
+            codebases.push(codebase);
+        }
+    }
+    let branch = task_branch_name(&task.id);
+    codebases.into_iter().find_map(|codebase| {
+        if !crate::git::branch_exists(&codebase.repo_path, &branch) {
+            return None;
+        }
+        let base = crate::git::resolve_base_ref(&codebase.repo_path, codebase.branch.as_deref())?;
+        Some((codebase.repo_path, base, branch.clone()))
+    })
+}
+
 fn parse_string_array_arg(args: &serde_json::Value, key: &str) -> Option<Vec<String>> {
     args.get(key).and_then(|value| {
         value.as_array().map(|values| {
@@ -410,3 +616,182 @@ fn updated_task_fields(args: &serde_json::Value) -> Vec<&'static str> {
     .filter_map(|(arg_key, field_name)| args.get(arg_key).map(|_| field_name))
     .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::api::mcp_routes::{execute_tool_public, test_state};
+
+    #[tokio::test]
+    async fn execute_tool_public_rejects_create_task_without_title() {
+        let state = test_state().await;
+
+        let result = execute_tool_public(
+            &state,
+            "create_task",
+            &serde_json::json!({ "objective": "" }),
+        )
+        .await;
+        assert_eq!(result.get("isError").and_then(|v| v.as_bool()), Some(true));
+        assert_eq!(result["error"]["code"].as_i64(), Some(-32602));
+        let fields = result["error"]["data"]["errors"]
+            .as_array()
+            .expect("field errors")
+            .iter()
+            .filter_map(|error| error["field"].as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec!["title", "objective"]);
+        assert!(state
+            .task_store
+            .list_by_workspace("default")
+            .await
+            .expect("list tasks")
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn execute_tool_public_moves_task_and_clears_foreign_references() {
+        let state = test_state().await;
+        state
+            .workspace_store
+            .save(&crate::models::workspace::Workspace::new(
+                "target".to_string(),
+                "Target".to_string(),
+                None,
+            ))
+            .await
+            .expect("save target workspace");
+
+        let mut dependency = crate::models::task::Task::new(
+            "dep".to_string(),
+            "Dependency".to_string(),
+            "Stays behind".to_string(),
+            "default".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        state.task_store.save(&dependency).await.expect("save dep");
+        dependency.id = "moving".to_string();
+        dependency.title = "Moving".to_string();
+        dependency.dependencies = vec!["dep".to_string()];
+        state.task_store.save(&dependency).await.expect("save task");
+
+        let result = execute_tool_public(
+            &state,
+            "move_task",
+            &serde_json::json!({
+                "taskId": "moving",
+                "fromWorkspaceId": "default",
+                "toWorkspaceId": "target"
+            }),
+        )
+        .await;
+        assert_eq!(result.get("isError").and_then(|v| v.as_bool()), Some(false));
+        let body: serde_json::Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap_or_default())
+                .expect("json body");
+        assert_eq!(
+            body["clearedReferences"]["dependencies"],
+            serde_json::json!(["dep"])
+        );
+
+        let moved = state
+            .task_store
+            .get("moving")
+            .await
+            .expect("get task")
+            .expect("task exists");
+        assert_eq!(moved.workspace_id, "target");
+        assert!(moved.dependencies.is_empty());
+    }
+
+    #[tokio::test]
+    async fn delete_task_only_removes_tasks_in_the_given_workspace() {
+        let state = test_state().await;
+        let task = crate::models::task::Task::new(
+            "stale".to_string(),
+            "Stale".to_string(),
+            "Exploratory leftover".to_string(),
+            "default".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        state.task_store.save(&task).await.expect("save task");
+
+        let wrong_workspace = execute_tool_public(
+            &state,
+            "delete_task",
+            &serde_json::json!({ "taskId": "stale", "workspaceId": "other" }),
+        )
+        .await;
+        assert_eq!(
+            wrong_workspace.get("isError").and_then(|v| v.as_bool()),
+            Some(true)
+        );
+
+        let deleted = execute_tool_public(
+            &state,
+            "delete_task",
+            &serde_json::json!({ "taskId": "stale" }),
+        )
+        .await;
+        assert_eq!(
+            deleted.get("isError").and_then(|v| v.as_bool()),
+            Some(false)
+        );
+        assert!(state
+            .task_store
+            .get("stale")
+            .await
+            .expect("get task")
+            .is_none());
+
+        let missing = execute_tool_public(
+            &state,
+            "delete_task",
+            &serde_json::json!({ "taskId": "stale" }),
+        )
+        .await;
+        let text = missing["content"][0]["text"].as_str().unwrap_or_default();
+        assert!(text.contains("Task not found: stale"));
+    }
+
+    #[tokio::test]
+    async fn task_diff_reports_when_no_changes_are_associated() {
+        let state = test_state().await;
+        let task = crate::models::task::Task::new(
+            "fresh-task".to_string(),
+            "Fresh".to_string(),
+            "Nothing done yet".to_string(),
+            "default".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        state.task_store.save(&task).await.expect("save task");
+
+        let result = execute_tool_public(
+            &state,
+            "task_diff",
+            &serde_json::json!({ "taskId": "fresh-task" }),
+        )
+        .await;
+        assert_eq!(result.get("isError").and_then(|v| v.as_bool()), Some(false));
+        let text = result["content"][0]["text"].as_str().unwrap_or_default();
+        assert!(text.contains("No changes are associated with task fresh-task"));
+        assert!(text.contains("issue/fresh-ta"));
+    }
+}
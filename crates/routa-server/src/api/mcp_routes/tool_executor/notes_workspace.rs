@@ -1,8 +1,10 @@
+use crate::models::codebase::Codebase;
+use crate::models::workspace::{Workspace, WorkspaceSettings};
 use crate::state::AppState;
 use routa_core::models::read_canvas_sdk_resource;
 use routa_core::models::read_feature_tree_spec_resource;
 
-use super::{tool_result_error, tool_result_json, tool_result_text};
+use super::{tool_result_error, tool_result_invalid_params, tool_result_json, tool_result_text};
 
 pub(super) async fn execute(
     state: &AppState,
@@ -18,14 +20,44 @@ pub(super) async fn execute(
             Err(e) => tool_result_error(&e.to_string()),
         },
         "create_note" => {
-            let title = args
-                .get("title")
-                .and_then(|v| v.as_str())
-                .unwrap_or("Untitled");
+            let title = args.get("title").and_then(|v| v.as_str()).unwrap_or("");
             let content = args.get("content").and_then(|v| v.as_str()).unwrap_or("");
-            let note_id = args
-                .get("noteId")
-                .and_then(|v| v.as_str())
+            let requested_id = args.get("noteId").and_then(|v| v.as_str());
+            let append = match args.get("mode").and_then(|v| v.as_str()) {
+                None | Some("replace") => false,
+                Some("append") => true,
+                Some(other) => {
+                    return Some(tool_result_error(&format!(
+                        "Invalid mode: {other}. Expected 'replace' or 'append'"
+                    )))
+                }
+            };
+
+            if let (true, Some(note_id)) = (append, requested_id) {
+                match state.note_store.get(note_id, workspace_id).await {
+                    Ok(Some(mut note)) => {
+                        note.content = append_note_entry(&note.content, content);
+                        if !title.trim().is_empty() {
+                            note.title = title.to_string();
+                        }
+                        note.updated_at = chrono::Utc::now();
+                        return Some(match state.note_store.save(&note).await {
+                            Ok(_) => tool_result_json(&serde_json::json!({
+                                "success": true,
+                                "noteId": note_id,
+                                "title": note.title,
+                                "mode": "append",
+                                "created": false
+                            })),
+                            Err(e) => tool_result_error(&e.to_string()),
+                        });
+                    }
+                    Ok(None) => {}
+                    Err(e) => return Some(tool_result_error(&e.to_string())),
+                }
+            }
+
+            let note_id = requested_id
                 .map(|s| s.to_string())
                 .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
             let session_id = args
@@ -37,7 +69,7 @@ pub(super) async fn execute(
                 .and_then(|v| v.as_str())
                 .unwrap_or("general");
             let note_type = crate::models::note::NoteType::from_str(note_type_str);
-            let note = crate::models::note::Note::new_with_session(
+            let note = match crate::models::note::Note::try_new_with_session(
                 note_id.clone(),
                 title.to_string(),
                 content.to_string(),
@@ -47,8 +79,18 @@ pub(super) async fn execute(
                     note_type,
                     ..Default::default()
                 }),
-            );
+            ) {
+                Ok(note) => note,
+                Err(errors) => return Some(tool_result_invalid_params(&errors)),
+            };
             match state.note_store.save(&note).await {
+                Ok(_) if append => tool_result_json(&serde_json::json!({
+                    "success": true,
+                    "noteId": note_id,
+                    "title": title,
+                    "mode": "append",
+                    "created": true
+                })),
                 Ok(_) => tool_result_json(&serde_json::json!({
                     "success": true,
                     "noteId": note_id,
@@ -57,6 +99,68 @@ pub(super) async fn execute(
                 Err(e) => tool_result_error(&e.to_string()),
             }
         }
+        "create_note_from_file" => {
+            let repo_path = args.get("repoPath").and_then(|v| v.as_str()).unwrap_or("");
+            let file = args.get("file").and_then(|v| v.as_str()).unwrap_or("");
+            if repo_path.trim().is_empty() || file.trim().is_empty() {
+                return Some(tool_result_error("repoPath and file are required"));
+            }
+            let title = args
+                .get("title")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|title| !title.is_empty())
+                .map(str::to_string)
+                .unwrap_or_else(|| file.to_string());
+
+            let content = {
+                let repo_path = std::path::PathBuf::from(repo_path);
+                let file = file.to_string();
+                match tokio::task::spawn_blocking(move || {
+                    crate::api::files::read_text_file_in_repo(&repo_path, &file)
+                })
+                .await
+                {
+                    Ok(Ok(content)) => content,
+                    Ok(Err(e)) => return Some(tool_result_error(&e.to_string())),
+                    Err(e) => return Some(tool_result_error(&e.to_string())),
+                }
+            };
+
+            let note_id = uuid::Uuid::new_v4().to_string();
+            let session_id = args
+                .get("sessionId")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let source = std::collections::HashMap::from([
+                ("sourceRepoPath".to_string(), repo_path.to_string()),
+                ("sourcePath".to_string(), file.to_string()),
+            ]);
+            let note = match crate::models::note::Note::try_new_with_session(
+                note_id.clone(),
+                title.clone(),
+                content,
+                workspace_id.to_string(),
+                session_id,
+                Some(crate::models::note::NoteMetadata {
+                    custom: Some(source),
+                    ..Default::default()
+                }),
+            ) {
+                Ok(note) => note,
+                Err(errors) => return Some(tool_result_invalid_params(&errors)),
+            };
+            match state.note_store.save(&note).await {
+                Ok(_) => tool_result_json(&serde_json::json!({
+                    "success": true,
+                    "noteId": note_id,
+                    "title": title,
+                    "sourcePath": file,
+                    "bytes": note.content.len()
+                })),
+                Err(e) => tool_result_error(&e.to_string()),
+            }
+        }
         "read_note" => {
             let note_id = args.get("noteId").and_then(|v| v.as_str()).unwrap_or("");
             match state.note_store.get(note_id, workspace_id).await {
@@ -142,6 +246,135 @@ pub(super) async fn execute(
                 Err(e) => tool_result_error(&e.to_string()),
             }
         }
+        "move_note" => {
+            let note_id = args.get("noteId").and_then(|v| v.as_str()).unwrap_or("");
+            let (from, to) = match super::move_workspace_ids(state, args).await {
+                Ok(ids) => ids,
+                Err(message) => return Some(tool_result_error(&message)),
+            };
+            let mut note = match state.note_store.get(note_id, &from).await {
+                Ok(Some(note)) => note,
+                Ok(None) => {
+                    return Some(tool_result_error(&format!(
+                        "Note not found in workspace {from}: {note_id}"
+                    )))
+                }
+                Err(e) => return Some(tool_result_error(&e.to_string())),
+            };
+            match state.note_store.get(note_id, &to).await {
+                Ok(None) => {}
+                Ok(Some(_)) => {
+                    return Some(tool_result_error(&format!(
+                        "Note {note_id} already exists in workspace {to}"
+                    )))
+                }
+                Err(e) => return Some(tool_result_error(&e.to_string())),
+            }
+
+            // Drop references to entities that do not live in the target workspace.
+            let mut cleared = serde_json::Map::new();
+            if let Some(task_id) = note.metadata.linked_task_id.clone() {
+                let in_target = matches!(
+                    state.task_store.get(&task_id).await,
+                    Ok(Some(task)) if task.workspace_id == to
+                );
+                if !in_target {
+                    note.metadata.linked_task_id = None;
+                    cleared.insert("linkedTaskId".into(), serde_json::json!(task_id));
+                }
+            }
+            if let Some(parent_id) = note.metadata.parent_note_id.clone() {
+                if !matches!(state.note_store.get(&parent_id, &to).await, Ok(Some(_))) {
+                    note.metadata.parent_note_id = None;
+                    cleared.insert("parentNoteId".into(), serde_json::json!(parent_id));
+                }
+            }
+            if let Some(agent_ids) = note.metadata.assigned_agent_ids.take() {
+                let mut kept = Vec::new();
+                let mut dropped = Vec::new();
+                for agent_id in agent_ids {
+                    match state.agent_store.get(&agent_id).await {
+                        Ok(Some(agent)) if agent.workspace_id == to => kept.push(agent_id),
+                        _ => dropped.push(agent_id),
+                    }
+                }
+                if !dropped.is_empty() {
+                    cleared.insert("assignedAgentIds".into(), serde_json::json!(dropped));
+                }
+                note.metadata.assigned_agent_ids = (!kept.is_empty()).then_some(kept);
+            }
+
+            note.workspace_id = to.clone();
+            note.updated_at = chrono::Utc::now();
+            if let Err(e) = state.note_store.save(&note).await {
+                return Some(tool_result_error(&e.to_string()));
+            }
+            match state.note_store.delete(note_id, &from).await {
+                Ok(_) => tool_result_json(&serde_json::json!({
+                    "success": true,
+                    "noteId": note_id,
+                    "fromWorkspaceId": from,
+                    "toWorkspaceId": to,
+                    "clearedReferences": cleared
+                })),
+                Err(e) => tool_result_error(&e.to_string()),
+            }
+        }
+        "global_search" => {
+            let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
+            if query.trim().is_empty() {
+                return Some(tool_result_error("Missing query"));
+            }
+            let limit = routa_core::store::clamp_search_limit(
+                args.get("limit")
+                    .and_then(|v| v.as_u64())
+                    .map(|v| v as usize),
+            );
+            let tasks = match state.task_store.search(query, None, limit).await {
+                Ok(tasks) => tasks,
+                Err(e) => return Some(tool_result_error(&e.to_string())),
+            };
+            let notes = match state.note_store.search(query, None, limit).await {
+                Ok(notes) => notes,
+                Err(e) => return Some(tool_result_error(&e.to_string())),
+            };
+
+            let mut results = tasks
+                .into_iter()
+                .map(|task| {
+                    (
+                        task.updated_at,
+                        serde_json::json!({
+                            "type": "task",
+                            "id": task.id,
+                            "title": task.title,
+                            "status": task.status.as_str(),
+                            "workspaceId": task.workspace_id,
+                        }),
+                    )
+                })
+                .chain(notes.into_iter().map(|note| {
+                    (
+                        note.updated_at,
+                        serde_json::json!({
+                            "type": "note",
+                            "id": note.id,
+                            "title": note.title,
+                            "workspaceId": note.workspace_id,
+                        }),
+                    )
+                }))
+                .collect::<Vec<_>>();
+            results.sort_by(|a, b| b.0.cmp(&a.0));
+            let truncated = results.len() > limit;
+            results.truncate(limit);
+
+            tool_result_json(&serde_json::json!({
+                "query": query,
+                "results": results.into_iter().map(|(_, value)| value).collect::<Vec<_>>(),
+                "truncated": truncated
+            }))
+        }
         "list_workspaces" => match state.workspace_store.list().await {
             Ok(ws) => tool_result_text(&serde_json::to_string_pretty(&ws).unwrap_or_default()),
             Err(e) => tool_result_error(&e.to_string()),
@@ -179,9 +412,177 @@ pub(super) async fn execute(
             Ok(None) => tool_result_error(&format!("Workspace not found: {workspace_id}")),
             Err(e) => tool_result_error(&e.to_string()),
         },
+        "get_workspace" => match state.workspace_store.get(workspace_id).await {
+            Ok(Some(ws)) => tool_result_json(&workspace_settings_json(state, &ws).await),
+            Ok(None) => tool_result_error(&format!("Workspace not found: {workspace_id}")),
+            Err(e) => tool_result_error(&e.to_string()),
+        },
+        "update_workspace" => {
+            let mut ws = match state.workspace_store.get(workspace_id).await {
+                Ok(Some(ws)) => ws,
+                Ok(None) => {
+                    return Some(tool_result_error(&format!(
+                        "Workspace not found: {workspace_id}"
+                    )))
+                }
+                Err(e) => return Some(tool_result_error(&e.to_string())),
+            };
+            let mut errors = crate::models::validation::ValidationError::new();
+            let title = match args.get("title") {
+                None | Some(serde_json::Value::Null) => None,
+                Some(serde_json::Value::String(title)) => {
+                    errors.require_non_empty("title", title);
+                    Some(title.trim().to_string())
+                }
+                Some(_) => {
+                    errors.push("title", "title must be a string");
+                    None
+                }
+            };
+            let mut settings = WorkspaceSettings::from_workspace(&ws);
+            if let Some(patch) = args.get("settings").filter(|v| !v.is_null()) {
+                if let Err(invalid) = settings.apply_patch(patch) {
+                    errors.errors.extend(invalid.errors);
+                }
+            }
+            if let Some(cwd) = settings.default_cwd.as_deref() {
+                let changed = WorkspaceSettings::from_workspace(&ws)
+                    .default_cwd
+                    .as_deref()
+                    != Some(cwd);
+                if changed && !std::path::Path::new(cwd).is_dir() {
+                    errors.push(
+                        "defaultCwd",
+                        format!("defaultCwd is not a directory: {cwd}"),
+                    );
+                }
+            }
+            if !errors.is_empty() {
+                return Some(tool_result_invalid_params(&errors));
+            }
+
+            if let Some(title) = title {
+                ws.title = title;
+            }
+            settings.store(&mut ws);
+            ws.updated_at = chrono::Utc::now();
+            if let Err(e) = state.workspace_store.save(&ws).await {
+                return Some(tool_result_error(&e.to_string()));
+            }
+            tool_result_json(&workspace_settings_json(state, &ws).await)
+        }
+        "get_workspace_branch" => match workspace_codebase(state, args, workspace_id).await {
+            Ok(codebase) => tool_result_json(&workspace_branch_json(&codebase).await),
+            Err(e) => tool_result_error(&e),
+        },
+        "set_workspace_branch" => {
+            let branch = args
+                .get("branch")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .trim();
+            let mut errors = crate::models::validation::ValidationError::new();
+            errors.require_non_empty("branch", branch);
+            if !errors.is_empty() {
+                return Some(tool_result_invalid_params(&errors));
+            }
+            if branch.starts_with('-') || branch.chars().any(char::is_whitespace) {
+                return Some(tool_result_error(&format!("Invalid branch name: {branch}")));
+            }
+            let create = args
+                .get("create")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let mut codebase = match workspace_codebase(state, args, workspace_id).await {
+                Ok(codebase) => codebase,
+                Err(e) => return Some(tool_result_error(&e)),
+            };
+
+            let checkout = tokio::task::spawn_blocking({
+                let repo_path = codebase.repo_path.clone();
+                let branch = branch.to_string();
+                move || {
+                    if create {
+                        crate::git::checkout_branch(&repo_path, &branch)
+                            .then_some(())
+                            .ok_or_else(|| format!("Failed to checkout branch '{branch}'"))
+                    } else {
+                        crate::git::checkout_existing_branch(&repo_path, &branch)
+                    }
+                }
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result);
+            if let Err(e) = checkout {
+                return Some(tool_result_error(&format!(
+                    "Failed to checkout {branch} in {}: {e}",
+                    codebase.repo_path
+                )));
+            }
+            if let Err(e) = state
+                .codebase_store
+                .update(&codebase.id, Some(branch), None, None, None, None)
+                .await
+            {
+                return Some(tool_result_error(&e.to_string()));
+            }
+            codebase.branch = Some(branch.to_string());
+            tool_result_json(&workspace_branch_json(&codebase).await)
+        }
+        "git_status" => {
+            let repo_path = match args.get("repoPath").and_then(|v| v.as_str()) {
+                Some(path) if !path.trim().is_empty() => {
+                    crate::api::repo_context::normalize_local_repo_path(path)
+                }
+                _ => match workspace_codebase(state, args, workspace_id).await {
+                    Ok(codebase) => std::path::PathBuf::from(codebase.repo_path),
+                    Err(e) => return Some(tool_result_error(&e)),
+                },
+            };
+            let status = tokio::task::spawn_blocking(move || {
+                crate::api::repo_context::validate_local_git_repo_path(&repo_path)
+                    .map_err(|e| e.to_string())?;
+                let repo_path = repo_path.to_string_lossy().to_string();
+                crate::git::get_structured_status(&repo_path).map(|status| (repo_path, status))
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result);
+            match status {
+                Ok((repo_path, status)) => {
+                    let mut value = serde_json::to_value(status).unwrap_or_default();
+                    value["repoPath"] = serde_json::json!(repo_path);
+                    tool_result_json(&value)
+                }
+                Err(e) => tool_result_error(&e),
+            }
+        }
         "list_skills" => {
-            let skills = state.skill_registry.list_skills();
-            tool_result_text(&serde_json::to_string_pretty(&skills).unwrap_or_default())
+            let filter = routa_core::skills::SkillFilter {
+                compatibility: args
+                    .get("compatibility")
+                    .and_then(|v| v.as_str())
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string),
+                tag: args
+                    .get("tag")
+                    .and_then(|v| v.as_str())
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string),
+            };
+            if filter.compatibility.is_none() && filter.tag.is_none() {
+                let skills = state.skill_registry.list_skills();
+                return Some(tool_result_text(
+                    &serde_json::to_string_pretty(&skills).unwrap_or_default(),
+                ));
+            }
+            let skills = state.skill_registry.list_skills_filtered(&filter);
+            tool_result_json(&serde_json::json!({
+                "filter": filter,
+                "count": skills.len(),
+                "skills": skills,
+            }))
         }
         "list_specialists" => tool_result_json(&serde_json::json!({
             "specialists": [
@@ -228,3 +629,251 @@ pub(super) async fn execute(
 
     Some(result)
 }
+
+/// Append `entry` to `existing` under a timestamped separator.
+fn append_note_entry(existing: &str, entry: &str) -> String {
+    let header = format!("--- {} ---", chrono::Utc::now().to_rfc3339());
+    if existing.trim().is_empty() {
+        format!("{header}\n{entry}")
+    } else {
+        format!("{}\n\n{header}\n{entry}", existing.trim_end())
+    }
+}
+
+/// The codebase a branch tool acts on: `codebaseId` when given, else the
+/// workspace's default codebase, else its first one.
+async fn workspace_codebase(
+    state: &AppState,
+    args: &serde_json::Value,
+    workspace_id: &str,
+) -> Result<Codebase, String> {
+    if let Some(codebase_id) = args.get("codebaseId").and_then(|v| v.as_str()) {
+        return match state.codebase_store.get(codebase_id).await {
+            Ok(Some(codebase)) if codebase.workspace_id == workspace_id => Ok(codebase),
+            Ok(_) => Err(format!(
+                "Codebase not found in workspace {workspace_id}: {codebase_id}"
+            )),
+            Err(e) => Err(e.to_string()),
+        };
+    }
+    if let Some(codebase) = state
+        .codebase_store
+        .get_default(workspace_id)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        return Ok(codebase);
+    }
+    state
+        .codebase_store
+        .list_by_workspace(workspace_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Workspace {workspace_id} has no codebase"))
+}
+
+/// A workspace's settings as returned by `get_workspace`/`update_workspace`,
+/// with the active branch of its default codebase.
+async fn workspace_settings_json(state: &AppState, ws: &Workspace) -> serde_json::Value {
+    let settings = WorkspaceSettings::from_workspace(ws);
+    let active_branch = workspace_codebase(state, &serde_json::json!({}), &ws.id)
+        .await
+        .ok()
+        .and_then(|codebase| codebase.branch);
+    serde_json::json!({
+        "workspaceId": ws.id,
+        "title": ws.title,
+        "status": ws.status,
+        "defaultCwd": settings.default_cwd,
+        "defaultProvider": settings.default_provider,
+        "env": settings.env,
+        "activeBranch": active_branch,
+        "updatedAt": ws.updated_at,
+    })
+}
+
+/// The branch recorded for `codebase` alongside the one checked out in its repo.
+async fn workspace_branch_json(codebase: &Codebase) -> serde_json::Value {
+    let repo_path = codebase.repo_path.clone();
+    let current_branch =
+        tokio::task::spawn_blocking(move || crate::git::get_current_branch(&repo_path))
+            .await
+            .ok()
+            .flatten();
+    serde_json::json!({
+        "workspaceId": codebase.workspace_id,
+        "codebaseId": codebase.id,
+        "repoPath": codebase.repo_path,
+        "activeBranch": codebase.branch,
+        "currentBranch": current_branch,
+        "inSync": codebase.branch.is_some() && codebase.branch == current_branch,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::mcp_routes::{execute_tool_public, test_state};
+
+    #[tokio::test]
+    async fn create_note_replaces_by_default_and_appends_in_append_mode() {
+        let state = test_state().await;
+
+        for content in ["first", "second"] {
+            execute_tool_public(
+                &state,
+                "create_note",
+                &serde_json::json!({ "noteId": "log", "title": "Log", "content": content }),
+            )
+            .await;
+        }
+        let note = state
+            .note_store
+            .get("log", "default")
+            .await
+            .expect("get note")
+            .expect("note exists");
+        assert_eq!(note.content, "second");
+
+        let appended = execute_tool_public(
+            &state,
+            "create_note",
+            &serde_json::json!({ "noteId": "log", "content": "third", "mode": "append" }),
+        )
+        .await;
+        assert_eq!(
+            appended.get("isError").and_then(|v| v.as_bool()),
+            Some(false)
+        );
+        let note = state
+            .note_store
+            .get("log", "default")
+            .await
+            .expect("get note")
+            .expect("note exists");
+        assert!(note.content.starts_with("second\n\n--- "));
+        assert!(note.content.ends_with("---\nthird"));
+        assert_eq!(note.title, "Log");
+
+        execute_tool_public(
+            &state,
+            "create_note",
+            &serde_json::json!({
+                "noteId": "journal",
+                "title": "Journal",
+                "content": "day one",
+                "mode": "append"
+            }),
+        )
+        .await;
+        let journal = state
+            .note_store
+            .get("journal", "default")
+            .await
+            .expect("get note")
+            .expect("note created");
+        assert_eq!(journal.content, "day one");
+    }
+
+    #[tokio::test]
+    async fn global_search_spans_workspaces_and_annotates_results() {
+        let state = test_state().await;
+        state
+            .workspace_store
+            .save(&crate::models::workspace::Workspace::new(
+                "other".to_string(),
+                "Other".to_string(),
+                None,
+            ))
+            .await
+            .expect("save other workspace");
+
+        execute_tool_public(
+            &state,
+            "create_note",
+            &serde_json::json!({ "noteId": "n1", "title": "Release plan", "content": "ship it" }),
+        )
+        .await;
+        execute_tool_public(
+            &state,
+            "create_task",
+            &serde_json::json!({
+                "title": "Prepare release",
+                "objective": "Tag 100% of crates",
+                "workspaceId": "other"
+            }),
+        )
+        .await;
+
+        let result = execute_tool_public(
+            &state,
+            "global_search",
+            &serde_json::json!({ "query": "release" }),
+        )
+        .await;
+        let text = result["content"][0]["text"].as_str().expect("text content");
+        let body: serde_json::Value = serde_json::from_str(text).expect("json body");
+        let results = body["results"].as_array().expect("results array");
+        assert_eq!(results.len(), 2);
+        let mut workspaces = results
+            .iter()
+            .map(|r| r["workspaceId"].as_str().unwrap_or_default().to_string())
+            .collect::<Vec<_>>();
+        workspaces.sort();
+        assert_eq!(workspaces, vec!["default", "other"]);
+
+        let escaped = execute_tool_public(
+            &state,
+            "global_search",
+            &serde_json::json!({ "query": "0%" }),
+        )
+        .await;
+        let text = escaped["content"][0]["text"]
+            .as_str()
+            .expect("text content");
+        let body: serde_json::Value = serde_json::from_str(text).expect("json body");
+        assert_eq!(body["results"].as_array().map(Vec::len), Some(1));
+    }
+
+    #[tokio::test]
+    async fn create_note_from_file_records_source_and_rejects_escapes() {
+        let state = test_state().await;
+        let repo = tempfile::tempdir().expect("tempdir");
+        std::fs::write(repo.path().join("README.md"), "# Demo\n").expect("write readme");
+        let repo_path = repo.path().to_string_lossy().to_string();
+
+        let result = execute_tool_public(
+            &state,
+            "create_note_from_file",
+            &serde_json::json!({ "repoPath": repo_path, "file": "README.md" }),
+        )
+        .await;
+        let text = result["content"][0]["text"].as_str().expect("text content");
+        let body: serde_json::Value = serde_json::from_str(text).expect("json body");
+        let note = state
+            .note_store
+            .get(body["noteId"].as_str().expect("note id"), "default")
+            .await
+            .expect("get note")
+            .expect("note created");
+        assert_eq!(note.title, "README.md");
+        assert_eq!(note.content, "# Demo\n");
+        assert_eq!(
+            note.metadata
+                .custom
+                .as_ref()
+                .and_then(|custom| custom.get("sourcePath"))
+                .map(String::as_str),
+            Some("README.md")
+        );
+
+        let escaped = execute_tool_public(
+            &state,
+            "create_note_from_file",
+            &serde_json::json!({ "repoPath": repo_path, "file": "../../etc/passwd" }),
+        )
+        .await;
+        assert_eq!(escaped["isError"].as_bool(), Some(true));
+    }
+}
@@ -1,34 +1,119 @@
 //! MCP Tools API - /api/mcp/tools
 //!
-//! GET  /api/mcp/tools - List all MCP tool definitions
-//! POST /api/mcp/tools - Execute a specific tool by name
+//! GET   /api/mcp/tools - List enabled MCP tool definitions
+//! GET   /api/mcp/tools/schema - Export the tool catalog as one codegen-ready document
+//! POST  /api/mcp/tools - Execute a specific tool by name
+//! PATCH /api/mcp/tools - Update which tools are enabled
+//! POST  /api/mcp/tools/{name}/test - Validate and run a tool, returning a transcript
+
+use std::time::Instant;
 
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::HeaderMap,
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use serde::Deserialize;
 
 use crate::error::ServerError;
+use crate::models::validation::ValidationError;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
-    Router::new().route(
-        "/",
-        get(list_tools)
-            .post(execute_tool)
-            .patch(update_tools_config),
-    )
+    Router::new()
+        .route(
+            "/",
+            get(list_tools)
+                .post(execute_tool)
+                .patch(update_tools_config),
+        )
+        .route("/schema", get(tool_schema))
+        .route("/{name}/test", post(test_tool))
 }
 
-async fn list_tools(State(_state): State<AppState>) -> Json<serde_json::Value> {
+async fn list_tools(State(state): State<AppState>) -> Json<serde_json::Value> {
     Json(serde_json::json!({
-        "tools": super::mcp_routes::build_tool_list_public()
+        "tools": super::mcp_routes::build_enabled_tool_list_public(&state)
     }))
 }
 
+/// Version of the `/api/mcp/tools/schema` document layout.
+const TOOL_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolSchemaQuery {
+    /// MCP profile to export the catalog for (e.g. `kanban-planning`).
+    mcp_profile: Option<String>,
+}
+
+/// GET /api/mcp/tools/schema — The tool catalog as one document for client
+/// code generation.
+async fn tool_schema(
+    State(state): State<AppState>,
+    Query(query): Query<ToolSchemaQuery>,
+) -> Json<serde_json::Value> {
+    let profile = query
+        .mcp_profile
+        .as_deref()
+        .map(str::trim)
+        .filter(|profile| !profile.is_empty());
+    Json(tool_schema_document(
+        super::mcp_routes::build_listed_tools_public(&state, profile),
+        profile,
+    ))
+}
+
+/// Wrap tool definitions, as returned by `tools/list`, in the schema
+/// document. Every tool carries `outputSchema`: its own when it declares
+/// one, otherwise `null`, in which case results are plain `CallToolResult`s
+/// described under `definitions`.
+fn tool_schema_document(tools: Vec<serde_json::Value>, profile: Option<&str>) -> serde_json::Value {
+    let tools: Vec<serde_json::Value> = tools
+        .into_iter()
+        .map(|tool| {
+            let field =
+                |key: &str, default: serde_json::Value| tool.get(key).cloned().unwrap_or(default);
+            serde_json::json!({
+                "name": field("name", serde_json::Value::Null),
+                "description": field("description", serde_json::json!("")),
+                "inputSchema": field("inputSchema", serde_json::json!({ "type": "object" })),
+                "outputSchema": field("outputSchema", serde_json::Value::Null),
+                "annotations": field("annotations", serde_json::json!({})),
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "schemaVersion": TOOL_SCHEMA_VERSION,
+        "server": { "name": "routa", "version": env!("CARGO_PKG_VERSION") },
+        "mcpProfile": profile,
+        "toolCount": tools.len(),
+        "tools": tools,
+        "definitions": {
+            "CallToolResult": {
+                "type": "object",
+                "properties": {
+                    "content": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "type": { "type": "string" },
+                                "text": { "type": "string" }
+                            },
+                            "required": ["type"]
+                        }
+                    },
+                    "structuredContent": { "type": "object" },
+                    "isError": { "type": "boolean" }
+                },
+                "required": ["content"]
+            }
+        }
+    })
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ExecuteToolRequest {
@@ -44,25 +129,22 @@ struct ExecuteToolQuery {
     ws_id: Option<String>,
 }
 
-async fn execute_tool(
-    State(state): State<AppState>,
-    headers: HeaderMap,
-    Query(query): Query<ExecuteToolQuery>,
-    Json(body): Json<ExecuteToolRequest>,
-) -> Result<Json<serde_json::Value>, ServerError> {
-    let name = body
-        .name
-        .as_deref()
-        .ok_or_else(|| ServerError::BadRequest("Tool name is required".into()))?;
-
-    let mut args = body.args.unwrap_or(serde_json::json!({}));
+/// Resolve the workspace from the args, the body, the `routa-workspace-id`
+/// header, then `?wsId=`, and inject it into `args`.
+fn resolve_workspace_args(
+    args: Option<serde_json::Value>,
+    body_workspace_id: Option<String>,
+    headers: &HeaderMap,
+    query: ExecuteToolQuery,
+) -> serde_json::Value {
+    let mut args = args.unwrap_or(serde_json::json!({}));
     let workspace_id = args
         .get("workspaceId")
         .and_then(|value| value.as_str())
         .map(str::trim)
         .filter(|value| !value.is_empty())
         .map(str::to_string)
-        .or(body.workspace_id)
+        .or(body_workspace_id)
         .or_else(|| {
             headers
                 .get("routa-workspace-id")
@@ -75,34 +157,286 @@ async fn execute_tool(
     if let Some(workspace_id) = workspace_id.as_deref() {
         super::mcp_routes::inject_workspace_id(&mut args, workspace_id);
     }
+    args
+}
 
+/// Definition of an enabled tool, or a `BadRequest` naming why it can't run.
+fn find_enabled_tool(state: &AppState, name: &str) -> Result<serde_json::Value, ServerError> {
     let normalized_name = super::mcp_routes::normalize_tool_name_public(name);
-    let known_tool = super::mcp_routes::build_tool_list_public()
-        .iter()
-        .filter_map(|tool| tool.get("name").and_then(|value| value.as_str()))
-        .any(|tool_name| tool_name == normalized_name);
-
-    if !known_tool {
-        return Err(ServerError::BadRequest(format!("Unknown tool: {name}")));
+    let definition = super::mcp_routes::build_tool_list_public()
+        .into_iter()
+        .chain(super::mcp_routes::build_skill_tool_list_public(state))
+        .find(|tool| tool.get("name").and_then(|value| value.as_str()) == Some(normalized_name))
+        .ok_or_else(|| ServerError::BadRequest(format!("Unknown tool: {name}")))?;
+    if !state.mcp_tool_enabled(normalized_name) {
+        return Err(ServerError::BadRequest(format!(
+            "Tool disabled by server configuration: {name}"
+        )));
     }
+    Ok(definition)
+}
+
+async fn execute_tool(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ExecuteToolQuery>,
+    Json(body): Json<ExecuteToolRequest>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let name = body
+        .name
+        .as_deref()
+        .ok_or_else(|| ServerError::BadRequest("Tool name is required".into()))?;
+
+    let args = resolve_workspace_args(body.args, body.workspace_id, &headers, query);
+    find_enabled_tool(&state, name)?;
 
+    let normalized_name = super::mcp_routes::normalize_tool_name_public(name);
     let result = super::mcp_routes::execute_tool_public(&state, normalized_name, &args).await;
     Ok(Json(result))
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[allow(dead_code)]
+struct TestToolRequest {
+    args: Option<serde_json::Value>,
+    workspace_id: Option<String>,
+    /// Validate the arguments without running the tool.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// POST /api/mcp/tools/{name}/test — Exercise a tool without an MCP client.
+///
+/// The arguments are checked against the tool's `inputSchema` first; the tool
+/// only runs when they are valid and `dryRun` is not set. The response carries
+/// the `tools/call` request and response an MCP client would have seen, the
+/// timing, and any validation issues.
+async fn test_tool(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Query(query): Query<ExecuteToolQuery>,
+    Json(body): Json<TestToolRequest>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let definition = find_enabled_tool(&state, &name)?;
+    let normalized_name = super::mcp_routes::normalize_tool_name_public(&name);
+    let args = resolve_workspace_args(body.args, body.workspace_id, &headers, query);
+    let issues = validate_tool_args(&definition["inputSchema"], &args);
+
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": { "name": normalized_name, "arguments": args },
+    });
+    let mut transcript = vec![serde_json::json!({ "direction": "request", "message": request })];
+
+    let executed = issues.is_empty() && !body.dry_run;
+    let started = Instant::now();
+    let result = if executed {
+        let result = super::mcp_routes::execute_tool_public(&state, normalized_name, &args).await;
+        let response = match result.get("error") {
+            Some(error) => serde_json::json!({ "jsonrpc": "2.0", "id": 1, "error": error }),
+            None => serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": result }),
+        };
+        transcript.push(serde_json::json!({ "direction": "response", "message": response }));
+        Some(result)
+    } else {
+        None
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let is_error = result.as_ref().map(|result| {
+        result.get("error").is_some()
+            || result
+                .get("isError")
+                .and_then(|value| value.as_bool())
+                .unwrap_or(false)
+    });
+
+    Ok(Json(serde_json::json!({
+        "tool": normalized_name,
+        "dryRun": body.dry_run,
+        "valid": issues.is_empty(),
+        "issues": issues.errors,
+        "executed": executed,
+        "isError": is_error,
+        "result": result,
+        "durationMs": duration_ms,
+        "transcript": transcript,
+    })))
+}
+
+/// Check `args` against the top level of a tool `inputSchema`: required
+/// properties, declared property types, and `additionalProperties: false`.
+fn validate_tool_args(schema: &serde_json::Value, args: &serde_json::Value) -> ValidationError {
+    let mut issues = ValidationError::new();
+    let Some(args) = args.as_object() else {
+        issues.push("arguments", "arguments must be an object");
+        return issues;
+    };
+    let properties = schema.get("properties").and_then(|value| value.as_object());
+
+    for field in schema
+        .get("required")
+        .and_then(|value| value.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|value| value.as_str())
+    {
+        if args.get(field).is_none_or(|value| value.is_null()) {
+            issues.push(field, format!("{field} is required"));
+        }
+    }
+
+    for (field, value) in args {
+        let Some(property) = properties.and_then(|properties| properties.get(field)) else {
+            if schema.get("additionalProperties") == Some(&serde_json::Value::Bool(false))
+                && field != "workspaceId"
+            {
+                issues.push(field, format!("{field} is not a known argument"));
+            }
+            continue;
+        };
+        let Some(expected) = property.get("type") else {
+            continue;
+        };
+        let matches = match expected {
+            serde_json::Value::String(kind) => json_type_matches(kind, value),
+            serde_json::Value::Array(kinds) => kinds
+                .iter()
+                .filter_map(|kind| kind.as_str())
+                .any(|kind| json_type_matches(kind, value)),
+            _ => true,
+        };
+        if !matches {
+            issues.push(field, format!("{field} must be of type {expected}"));
+        }
+    }
+    issues
+}
+
+fn json_type_matches(kind: &str, value: &serde_json::Value) -> bool {
+    match kind {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct UpdateToolsConfigRequest {
     enabled: Option<Vec<String>>,
     disabled: Option<Vec<String>>,
 }
 
-/// PATCH /api/mcp/tools — Update tool enable/disable config (no-op stub; config not persisted)
+/// PATCH /api/mcp/tools — Update tool enable/disable config.
+///
+/// `enabled` replaces the allow-list (an empty list re-enables every tool);
+/// `disabled` replaces the deny-list. Omitted fields are left unchanged.
+/// Changes are held in memory and reset to the environment on restart.
 async fn update_tools_config(
-    Json(_body): Json<UpdateToolsConfigRequest>,
+    State(state): State<AppState>,
+    Json(body): Json<UpdateToolsConfigRequest>,
 ) -> Json<serde_json::Value> {
-    // Tool configuration is stateless in the embedded Rust backend.
-    // This endpoint exists for API parity; changes take effect transiently.
-    Json(serde_json::json!({ "updated": true }))
+    let mut config = state
+        .mcp_tool_config
+        .write()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(enabled) = body.enabled {
+        config.enabled = (!enabled.is_empty()).then(|| enabled.into_iter().collect());
+    }
+    if let Some(disabled) = body.disabled {
+        config.disabled = disabled.into_iter().collect();
+    }
+
+    let mut enabled = config
+        .enabled
+        .as_ref()
+        .map(|names| names.iter().cloned().collect::<Vec<_>>());
+    if let Some(names) = enabled.as_mut() {
+        names.sort();
+    }
+    let mut disabled = config.disabled.iter().cloned().collect::<Vec<_>>();
+    disabled.sort();
+
+    Json(serde_json::json!({
+        "updated": true,
+        "enabled": enabled,
+        "disabled": disabled,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use routa_core::{db::Database, state::AppStateInner};
+
+    use super::{tool_schema_document, validate_tool_args};
+    use crate::api::mcp_routes::build_listed_tools_public;
+
+    #[test]
+    fn tool_schema_matches_the_listed_tools() {
+        let state = Arc::new(AppStateInner::new(
+            Database::open_in_memory().expect("db should open"),
+        ));
+        for profile in [None, Some("kanban-planning")] {
+            let listed = build_listed_tools_public(&state, profile);
+            let document = tool_schema_document(listed.clone(), profile);
+            let tools = document["tools"].as_array().expect("tools");
+
+            assert_eq!(document["toolCount"], listed.len());
+            assert_eq!(document["mcpProfile"].as_str(), profile);
+            for (exported, listed) in tools.iter().zip(&listed) {
+                assert_eq!(exported["name"], listed["name"]);
+                assert_eq!(exported["description"], listed["description"]);
+                assert_eq!(exported["inputSchema"], listed["inputSchema"]);
+                assert_eq!(exported["annotations"], listed["annotations"]);
+                assert!(exported.get("outputSchema").is_some());
+            }
+        }
+        let document = tool_schema_document(build_listed_tools_public(&state, None), None);
+        assert!(document["tools"]
+            .as_array()
+            .expect("tools")
+            .iter()
+            .any(|tool| tool["name"] == "list_agents"));
+    }
+
+    #[test]
+    fn validate_tool_args_reports_missing_and_mistyped_fields() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "title": { "type": "string" },
+                "limit": { "type": "integer" },
+                "labels": { "type": ["array", "null"] }
+            },
+            "required": ["title"],
+            "additionalProperties": false
+        });
+
+        let valid = validate_tool_args(
+            &schema,
+            &serde_json::json!({ "title": "x", "labels": null, "workspaceId": "default" }),
+        );
+        assert!(valid.is_empty());
+
+        let invalid = validate_tool_args(
+            &schema,
+            &serde_json::json!({ "limit": "ten", "extra": true }),
+        );
+        let mut fields: Vec<_> = invalid.errors.iter().map(|e| e.field.as_str()).collect();
+        fields.sort_unstable();
+        assert_eq!(fields, ["extra", "limit", "title"]);
+
+        assert!(!validate_tool_args(&schema, &serde_json::json!("nope")).is_empty());
+    }
 }
@@ -8,9 +8,12 @@ pub mod agents;
 pub mod background_tasks;
 pub mod canvas;
 pub mod clone;
+pub mod clone_blame;
 pub mod clone_branches;
 pub mod clone_local;
+pub mod clone_log;
 pub mod clone_progress;
+pub mod clone_remotes;
 pub mod codebases;
 pub mod debug;
 pub mod feature_explorer;
@@ -31,7 +34,9 @@ pub mod mcp_server_mgmt;
 pub mod mcp_servers;
 pub mod mcp_tools;
 pub mod memory;
+pub(crate) mod ndjson;
 pub mod notes;
+pub(crate) mod pagination;
 pub mod polling;
 pub mod provider_models;
 pub mod providers;
@@ -99,8 +104,11 @@ pub fn api_router(state: AppState) -> Router<AppState> {
         .nest("/api/test-mcp", test_mcp::router())
         .nest("/api/clone", clone::router())
         .nest("/api/clone/local", clone_local::router())
+        .nest("/api/clone/blame", clone_blame::router())
+        .nest("/api/clone/log", clone_log::router())
         .nest("/api/clone/progress", clone_progress::router())
         .nest("/api/clone/branches", clone_branches::router())
+        .nest("/api/clone/remotes", clone_remotes::router())
         .nest("/api/files", files::router())
         .nest("/api/fitness", fitness::router())
         .nest("/api/rpc", rpc::router())
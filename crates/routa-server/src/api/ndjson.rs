@@ -0,0 +1,123 @@
+//! Newline-delimited JSON responses for large list endpoints.
+//!
+//! List handlers keep returning a buffered JSON array by default. When the
+//! client sends `Accept: application/x-ndjson` or `?stream=true`, the items
+//! are instead written one per line, each line its own body chunk, so a
+//! client can process items as they arrive instead of parsing one large
+//! array. The handler still loads the (paginated) list from the store first;
+//! this is a wire format, not a store cursor.
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, StatusCode},
+    response::Response,
+};
+use serde::Serialize;
+
+use crate::error::ServerError;
+
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Returns `true` when the request asked for an NDJSON stream, either via
+/// the `stream` query flag or an `Accept` header listing `application/x-ndjson`.
+pub fn wants_ndjson(headers: &HeaderMap, stream: Option<bool>) -> bool {
+    if let Some(stream) = stream {
+        return stream;
+    }
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media| {
+            media
+                .split(';')
+                .next()
+                .is_some_and(|mime| mime.trim().eq_ignore_ascii_case(NDJSON_CONTENT_TYPE))
+        })
+}
+
+/// Respond with `items` as NDJSON. Every item is serialized before the
+/// response starts, so a serialization error is a 500 rather than a 200 with
+/// a truncated body.
+pub fn ndjson_response<T, I>(items: I) -> Result<Response, ServerError>
+where
+    I: IntoIterator<Item = T>,
+    T: Serialize,
+{
+    let lines = items
+        .into_iter()
+        .map(|item| {
+            let mut line = serde_json::to_vec(&item)?;
+            line.push(b'\n');
+            Ok(line)
+        })
+        .collect::<Result<Vec<_>, serde_json::Error>>()
+        .map_err(|e| ServerError::Internal(format!("Failed to serialize NDJSON item: {e}")))?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)
+        .body(Body::from_stream(tokio_stream::iter(
+            lines.into_iter().map(Ok::<_, std::convert::Infallible>),
+        )))
+        .map_err(|e| ServerError::Internal(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn wants_ndjson_honors_query_flag_over_accept_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static(NDJSON_CONTENT_TYPE),
+        );
+
+        assert!(wants_ndjson(&headers, None));
+        assert!(!wants_ndjson(&headers, Some(false)));
+        assert!(wants_ndjson(&HeaderMap::new(), Some(true)));
+        assert!(!wants_ndjson(&HeaderMap::new(), None));
+    }
+
+    #[test]
+    fn wants_ndjson_matches_media_type_in_accept_list() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static("application/json;q=0.9, Application/X-NDJSON; q=1"),
+        );
+        assert!(wants_ndjson(&headers, None));
+
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        assert!(!wants_ndjson(&headers, None));
+    }
+
+    #[tokio::test]
+    async fn ndjson_response_writes_one_line_per_item() {
+        let response = ndjson_response(vec![
+            serde_json::json!({ "id": 1 }),
+            serde_json::json!({ "id": 2 }),
+        ])
+        .expect("response");
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            NDJSON_CONTENT_TYPE
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        assert_eq!(&body[..], b"{\"id\":1}\n{\"id\":2}\n");
+    }
+
+    #[test]
+    fn ndjson_response_fails_before_streaming_on_unserializable_item() {
+        // JSON object keys must be strings.
+        let bad = std::collections::BTreeMap::from([((1, 2), "tuple key")]);
+        let error = ndjson_response(vec![std::collections::BTreeMap::new(), bad]).unwrap_err();
+        assert!(matches!(error, ServerError::Internal(_)), "{error:?}");
+    }
+}
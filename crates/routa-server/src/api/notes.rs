@@ -1,6 +1,10 @@
 use axum::{
     extract::{Query, State},
-    response::sse::{Event, KeepAlive, Sse},
+    http::HeaderMap,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::get,
     Json, Router,
 };
@@ -8,6 +12,8 @@ use serde::Deserialize;
 use std::convert::Infallible;
 use tokio_stream::StreamExt as _;
 
+use crate::api::ndjson::{ndjson_response, wants_ndjson};
+use crate::api::pagination::{paginate, with_next_cursor_header, PageQuery};
 use crate::error::ServerError;
 use crate::models::note::{Note, NoteMetadata, NoteType};
 use crate::state::AppState;
@@ -21,6 +27,7 @@ pub fn router() -> Router<AppState> {
                 .delete(delete_note_query),
         )
         .route("/events", get(note_events_sse))
+        .route("/search", get(search_notes))
         .route(
             "/{workspace_id}/{note_id}",
             get(get_note).delete(delete_note_path),
@@ -34,17 +41,60 @@ struct ListNotesQuery {
     #[serde(rename = "type")]
     note_type: Option<String>,
     note_id: Option<String>,
+    /// Stream notes as NDJSON instead of a buffered `{ "notes": [...] }` body.
+    stream: Option<bool>,
+    /// Page size; see [`crate::api::pagination`].
+    limit: Option<usize>,
+    offset: Option<usize>,
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchNotesQuery {
+    q: Option<String>,
+    workspace_id: Option<String>,
+    /// Search every workspace instead of only `workspaceId`.
+    include_all_workspaces: Option<bool>,
+    limit: Option<usize>,
+}
+
+/// GET /api/notes/search?q=...&workspaceId=...&includeAllWorkspaces=true&limit=...
+async fn search_notes(
+    State(state): State<AppState>,
+    Query(query): Query<SearchNotesQuery>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let q = query
+        .q
+        .filter(|q| !q.trim().is_empty())
+        .ok_or_else(|| ServerError::BadRequest("Missing q".into()))?;
+    let all_workspaces = query.include_all_workspaces.unwrap_or(false);
+    let workspace_id = query.workspace_id.as_deref().unwrap_or("default");
+    let limit = routa_core::store::clamp_search_limit(query.limit);
+
+    let notes = state
+        .note_store
+        .search(&q, (!all_workspaces).then_some(workspace_id), limit)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "notes": notes,
+        "query": q,
+        "includeAllWorkspaces": all_workspaces,
+        "limit": limit,
+    })))
 }
 
 async fn list_notes(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Query(query): Query<ListNotesQuery>,
-) -> Result<Json<serde_json::Value>, ServerError> {
+) -> Result<Response, ServerError> {
     let workspace_id = query.workspace_id.as_deref().unwrap_or("default");
 
     if let Some(note_id) = &query.note_id {
         let note = state.note_store.get(note_id, workspace_id).await?;
-        return Ok(Json(serde_json::json!({ "note": note })));
+        return Ok(Json(serde_json::json!({ "note": note })).into_response());
     }
 
     let notes = if let Some(type_str) = &query.note_type {
@@ -56,8 +106,25 @@ async fn list_notes(
     } else {
         state.note_store.list_by_workspace(workspace_id).await?
     };
+    let page = paginate(
+        notes,
+        &PageQuery {
+            limit: query.limit,
+            offset: query.offset,
+            cursor: query.cursor.clone(),
+        },
+        |note| (note.created_at, note.id.as_str()),
+    )?;
+
+    if wants_ndjson(&headers, query.stream) {
+        let next_cursor = page.next_cursor.clone();
+        let response = ndjson_response(page.items)?;
+        return Ok(with_next_cursor_header(response, next_cursor.as_deref()));
+    }
 
-    Ok(Json(serde_json::json!({ "notes": notes })))
+    let mut body = serde_json::json!({ "notes": page.items });
+    page.merge_into(&mut body);
+    Ok(Json(body).into_response())
 }
 
 async fn get_note(
@@ -104,13 +171,14 @@ async fn create_or_update_note(
         ..Default::default()
     });
 
-    let note = Note::new(
+    let note = Note::try_new_with_session(
         note_id,
         body.title,
         body.content.unwrap_or_default(),
         workspace_id,
+        None,
         Some(metadata),
-    );
+    )?;
 
     state.note_store.save(&note).await?;
     Ok(Json(serde_json::json!({ "note": note })))
@@ -0,0 +1,225 @@
+//! Offset and cursor pagination for list endpoints.
+//!
+//! List handlers return every item unless the request carries `limit`,
+//! `offset`, or `cursor`. `offset` is fine for small, quiet collections, but
+//! items shift under it when rows are inserted or deleted between requests.
+//! Cursors are preferred for large, active collections: `nextCursor` is an
+//! opaque token encoding the sort key (creation time + id) of the last item
+//! returned, and the next page starts strictly after that key no matter what
+//! changed in between.
+//!
+//! Items are ordered newest first, by `(created_at, id)` descending.
+
+use axum::{http::HeaderValue, response::Response};
+use base64::Engine as _;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::error::ServerError;
+
+/// Page size when `cursor` or `offset` is given without a `limit`.
+pub const DEFAULT_PAGE_LIMIT: usize = 100;
+/// Upper bound on a caller-supplied `limit`.
+pub const MAX_PAGE_LIMIT: usize = 500;
+/// Response header carrying `nextCursor` for NDJSON list responses.
+pub const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
+/// Pagination params shared by list endpoints (`limit`, `offset`, `cursor`).
+#[derive(Debug, Default, Clone)]
+pub struct PageQuery {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// `nextCursor` from the previous page.
+    pub cursor: Option<String>,
+}
+
+impl PageQuery {
+    fn is_requested(&self) -> bool {
+        self.limit.is_some() || self.offset.is_some() || self.cursor.is_some()
+    }
+}
+
+/// One page of items. `next_cursor` is `None` on the last page; `paginated`
+/// is `false` when the request asked for no pagination and `items` is the
+/// full, untouched list.
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub paginated: bool,
+}
+
+impl<T> Page<T> {
+    /// Add `nextCursor` to a JSON object body when the request was paginated.
+    pub fn merge_into(&self, body: &mut serde_json::Value) {
+        if let (true, Some(body)) = (self.paginated, body.as_object_mut()) {
+            body.insert(
+                "nextCursor".to_string(),
+                serde_json::json!(self.next_cursor),
+            );
+        }
+    }
+}
+
+/// NDJSON bodies have nowhere to carry `nextCursor`, so it goes in a header.
+pub fn with_next_cursor_header(mut response: Response, next_cursor: Option<&str>) -> Response {
+    if let Some(value) = next_cursor.and_then(|cursor| HeaderValue::from_str(cursor).ok()) {
+        response.headers_mut().insert(NEXT_CURSOR_HEADER, value);
+    }
+    response
+}
+
+fn encode_cursor(created_at: &DateTime<Utc>, id: &str) -> String {
+    let key = format!(
+        "{}|{id}",
+        created_at.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    );
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(key)
+}
+
+fn decode_cursor(cursor: &str) -> Result<(DateTime<Utc>, String), ServerError> {
+    let invalid = || ServerError::BadRequest("Invalid cursor".to_string());
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .map_err(|_| invalid())?;
+    let key = String::from_utf8(bytes).map_err(|_| invalid())?;
+    let (created_at, id) = key.split_once('|').ok_or_else(invalid)?;
+    let created_at = DateTime::parse_from_rfc3339(created_at)
+        .map_err(|_| invalid())?
+        .with_timezone(&Utc);
+    Ok((created_at, id.to_string()))
+}
+
+/// Apply `query` to `items`, keyed by `key` (creation time and id).
+pub fn paginate<T, F>(mut items: Vec<T>, query: &PageQuery, key: F) -> Result<Page<T>, ServerError>
+where
+    F: Fn(&T) -> (DateTime<Utc>, &str),
+{
+    if !query.is_requested() {
+        return Ok(Page {
+            items,
+            next_cursor: None,
+            paginated: false,
+        });
+    }
+    if query.cursor.is_some() && query.offset.is_some() {
+        return Err(ServerError::BadRequest(
+            "Use either cursor or offset, not both".to_string(),
+        ));
+    }
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_LIMIT)
+        .clamp(1, MAX_PAGE_LIMIT);
+    items.sort_by(|a, b| {
+        let (a_at, a_id) = key(a);
+        let (b_at, b_id) = key(b);
+        (b_at, b_id).cmp(&(a_at, a_id))
+    });
+
+    let start = match query.cursor.as_deref() {
+        Some(cursor) => {
+            let (after_at, after_id) = decode_cursor(cursor)?;
+            items.partition_point(|item| {
+                let (at, id) = key(item);
+                (at, id) >= (after_at, after_id.as_str())
+            })
+        }
+        None => query.offset.unwrap_or(0).min(items.len()),
+    };
+
+    let mut page: Vec<T> = items.drain(start..).take(limit + 1).collect();
+    let has_more = page.len() > limit;
+    page.truncate(limit);
+    let next_cursor = if has_more {
+        page.last().map(|item| {
+            let (at, id) = key(item);
+            encode_cursor(&at, id)
+        })
+    } else {
+        None
+    };
+
+    Ok(Page {
+        items: page,
+        next_cursor,
+        paginated: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn item(seconds: i64, id: &str) -> (DateTime<Utc>, String) {
+        (Utc.timestamp_opt(seconds, 0).unwrap(), id.to_string())
+    }
+
+    fn key(item: &(DateTime<Utc>, String)) -> (DateTime<Utc>, &str) {
+        (item.0, item.1.as_str())
+    }
+
+    fn ids(page: &Page<(DateTime<Utc>, String)>) -> Vec<&str> {
+        page.items.iter().map(|item| item.1.as_str()).collect()
+    }
+
+    #[test]
+    fn returns_everything_without_page_params() {
+        let items = vec![item(1, "a"), item(2, "b")];
+        let page = paginate(items, &PageQuery::default(), key).unwrap();
+        assert!(!page.paginated);
+        assert_eq!(ids(&page), ["a", "b"]);
+        let mut body = serde_json::json!({});
+        page.merge_into(&mut body);
+        assert_eq!(body, serde_json::json!({}));
+    }
+
+    #[test]
+    fn cursor_pages_stay_stable_when_items_are_inserted() {
+        let mut items = vec![item(1, "a"), item(2, "b"), item(2, "c"), item(3, "d")];
+        let query = PageQuery {
+            limit: Some(2),
+            ..PageQuery::default()
+        };
+        let first = paginate(items.clone(), &query, key).unwrap();
+        assert_eq!(ids(&first), ["d", "c"]);
+
+        // A newer item arrives between requests; offset paging would repeat "c".
+        items.push(item(4, "e"));
+        let query = PageQuery {
+            limit: Some(2),
+            cursor: first.next_cursor.clone(),
+            ..PageQuery::default()
+        };
+        let second = paginate(items, &query, key).unwrap();
+        assert_eq!(ids(&second), ["b", "a"]);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[test]
+    fn offset_paging_and_invalid_requests() {
+        let items = vec![item(1, "a"), item(2, "b"), item(3, "c")];
+        let query = PageQuery {
+            limit: Some(1),
+            offset: Some(1),
+            cursor: None,
+        };
+        let page = paginate(items.clone(), &query, key).unwrap();
+        assert_eq!(ids(&page), ["b"]);
+        assert!(page.next_cursor.is_some());
+
+        let invalid = PageQuery {
+            cursor: Some("not a cursor".to_string()),
+            ..PageQuery::default()
+        };
+        assert!(paginate(items.clone(), &invalid, key).is_err());
+
+        let both = PageQuery {
+            offset: Some(0),
+            cursor: Some(encode_cursor(&items[0].0, "a")),
+            ..PageQuery::default()
+        };
+        assert!(paginate(items, &both, key).is_err());
+    }
+}
@@ -381,6 +381,7 @@ async fn delete_session(
 
     // Always delete from the database
     state.acp_session_store.delete(&session_id).await?;
+    state.acp_manager.remove_transcript(&session_id).await;
 
     // If neither memory nor DB had the session, return 404
     if !in_memory_found {
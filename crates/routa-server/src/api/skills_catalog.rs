@@ -360,8 +360,7 @@ async fn download_and_install_skills(
 
     let cursor = std::io::Cursor::new(&zip_bytes);
     let mut archive = zip::ZipArchive::new(cursor).map_err(|e| format!("Zip: {e}"))?;
-    archive
-        .extract(tmp_dir.path())
+    routa_core::archive::extract_zip(&mut archive, tmp_dir.path(), || Ok(()))
         .map_err(|e| format!("Extract: {e}"))?;
 
     let top_dirs: Vec<_> = std::fs::read_dir(tmp_dir.path())
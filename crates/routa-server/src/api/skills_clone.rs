@@ -2,8 +2,13 @@
 //!
 //! POST /api/skills/clone - Clone a skill repo and import skills
 //! GET  /api/skills/clone?repoPath=... - Discover skills from a path
+//! GET  /api/skills/clone?url=...      - Dry run: discover skills in a remote repo without importing
 
-use axum::{extract::Query, routing::get, Json, Router};
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
 use serde::Deserialize;
 use std::path::Path;
 
@@ -26,6 +31,7 @@ struct CloneSkillsRequest {
 }
 
 async fn clone_skills(
+    State(state): State<AppState>,
     Json(body): Json<CloneSkillsRequest>,
 ) -> Result<Json<serde_json::Value>, ServerError> {
     let url = body
@@ -33,11 +39,17 @@ async fn clone_skills(
         .as_deref()
         .ok_or_else(|| ServerError::BadRequest("Missing 'url' field".into()))?;
 
-    let parsed = git::parse_github_url(url).ok_or_else(|| {
+    let parsed = git::parse_git_url(url).ok_or_else(|| {
         ServerError::BadRequest(
-            "Invalid GitHub URL. Expected: https://github.com/owner/repo or owner/repo".into(),
+            "Invalid repository URL. Expected: https://host/owner/repo, git@host:owner/repo.git, or owner/repo".into(),
         )
     })?;
+    let git_config = state
+        .settings
+        .clone_host_policy
+        .check(url)
+        .await?
+        .git_config_args();
 
     let repo_name = git::repo_to_dir_name(&parsed.owner, &parsed.repo);
     let base_dir = git::get_clone_base_dir();
@@ -51,12 +63,14 @@ async fn clone_skills(
     tokio::task::spawn_blocking(move || {
         if target_path.exists() {
             let _ = git::git_command()
+                .args(&git_config)
                 .args(["pull", "--ff-only"])
                 .current_dir(&target_path)
                 .output();
         } else {
-            let clone_url = format!("https://github.com/{}/{}.git", parsed.owner, parsed.repo);
+            let clone_url = parsed.https_url();
             let _ = git::git_command()
+                .args(&git_config)
                 .args([
                     "clone",
                     "--depth",
@@ -110,14 +124,21 @@ async fn clone_skills(
 #[serde(rename_all = "camelCase")]
 struct DiscoverQuery {
     repo_path: Option<String>,
+    /// Remote repo to inspect without importing (dry run).
+    url: Option<String>,
 }
 
 async fn discover_skills(
+    State(state): State<AppState>,
     Query(query): Query<DiscoverQuery>,
 ) -> Result<Json<serde_json::Value>, ServerError> {
-    let repo_path = query
-        .repo_path
-        .ok_or_else(|| ServerError::BadRequest("Missing 'repoPath' query parameter".into()))?;
+    if let Some(url) = query.url.as_deref() {
+        return discover_remote_skills(&state, url).await;
+    }
+
+    let repo_path = query.repo_path.ok_or_else(|| {
+        ServerError::BadRequest("Missing 'repoPath' or 'url' query parameter".into())
+    })?;
 
     let rp = Path::new(&repo_path);
     if !rp.exists() {
@@ -138,3 +159,77 @@ async fn discover_skills(
         })).collect::<Vec<_>>(),
     })))
 }
+
+/// Shallow-clone `url` into a temp dir, report what `clone_skills` would
+/// import, and remove the clone. Nothing is written to `.agents/skills`.
+async fn discover_remote_skills(
+    state: &AppState,
+    url: &str,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let parsed = git::parse_git_url(url).ok_or_else(|| {
+        ServerError::BadRequest(
+            "Invalid repository URL. Expected: https://host/owner/repo, git@host:owner/repo.git, or owner/repo".into(),
+        )
+    })?;
+    let git_config = state
+        .settings
+        .clone_host_policy
+        .check(url)
+        .await?
+        .git_config_args();
+
+    let clone_url = parsed.https_url();
+    let discovered = tokio::task::spawn_blocking(move || {
+        let temp_dir = tempfile::tempdir()
+            .map_err(|e| ServerError::Internal(format!("Failed to create temp dir: {e}")))?;
+        let output = git::git_command()
+            .args(&git_config)
+            .args(["clone", "--depth", "1", &clone_url])
+            .arg(temp_dir.path())
+            .output()
+            .map_err(|e| ServerError::Internal(format!("Failed to run git: {e}")))?;
+        if !output.status.success() {
+            return Err(ServerError::BadRequest(format!(
+                "Failed to clone {clone_url}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        // `source` paths point into the temp clone, which is gone once this
+        // returns; report them relative to the repo root instead.
+        let mut discovered = git::discover_skills_from_path(temp_dir.path());
+        for skill in &mut discovered {
+            if let Ok(relative) = Path::new(&skill.source).strip_prefix(temp_dir.path()) {
+                skill.source = relative.to_string_lossy().to_string();
+            }
+        }
+        Ok(discovered)
+    })
+    .await
+    .map_err(|e| ServerError::Internal(e.to_string()))??;
+
+    let local_skills_base = std::env::current_dir()
+        .unwrap_or_default()
+        .join(LOCAL_SKILLS_DIR);
+    let skills: Vec<serde_json::Value> = discovered
+        .iter()
+        .map(|s| {
+            serde_json::json!({
+                "name": s.name,
+                "description": s.description,
+                "license": s.license,
+                "compatibility": s.compatibility,
+                "source": s.source,
+                "collision": local_skills_base.join(&s.name).exists(),
+            })
+        })
+        .collect();
+    let collisions = skills.iter().filter(|s| s["collision"] == true).count();
+
+    Ok(Json(serde_json::json!({
+        "dryRun": true,
+        "source": url,
+        "skills": skills,
+        "count": skills.len(),
+        "collisions": collisions,
+    })))
+}
@@ -1,22 +1,131 @@
 //! Skill Upload API - /api/skills/upload
 //!
-//! POST /api/skills/upload - Upload and extract a skill zip file
+//! POST /api/skills/upload                  - Upload and extract a skill zip file
+//! GET  /api/skills/upload                  - Upload history, newest first
+//! POST /api/skills/upload/{sha256}/extract - Re-extract a previously uploaded archive
+//!
+//! Uploaded archives are kept content-addressed under
+//! `.agents/skill-archives/<sha256>.zip`, with every upload recorded in
+//! `index.json` next to them. Uploading an archive that was already stored is
+//! recorded but not extracted again.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-use axum::{routing::post, Router};
+use axum::{extract::Path as AxumPath, routing::post, Json, Router};
 use axum_extra::extract::Multipart;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::error::ServerError;
 use crate::state::AppState;
 
 const SKILLS_DIR: &str = ".agents/skills";
+const ARCHIVES_DIR: &str = ".agents/skill-archives";
+const INDEX_FILE: &str = "index.json";
+
+/// Serializes index read-modify-write cycles across concurrent uploads.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/", post(upload_skill))
+    Router::new()
+        .route("/", post(upload_skill).get(list_uploads))
+        .route("/{sha256}/extract", post(reextract_upload))
+}
+
+/// One upload as recorded in the archive index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadRecord {
+    sha256: String,
+    file_name: String,
+    size: u64,
+    uploaded_at: String,
+    /// The archive was already stored, so extraction was skipped.
+    deduplicated: bool,
+}
+
+struct ArchiveStore {
+    dir: PathBuf,
+}
+
+impl ArchiveStore {
+    fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn archive_path(&self, sha256: &str) -> PathBuf {
+        self.dir.join(format!("{sha256}.zip"))
+    }
+
+    fn index(&self) -> Vec<UploadRecord> {
+        std::fs::read_to_string(self.dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    /// Store `data` under its digest. Returns the digest and whether an
+    /// upload of the same archive was already recorded.
+    fn put(&self, data: &[u8]) -> Result<(String, bool), String> {
+        let sha256 = sha256_hex(data);
+        let path = self.archive_path(&sha256);
+        let processed = path.is_file() && self.index().iter().any(|r| r.sha256 == sha256);
+        if !path.is_file() {
+            std::fs::create_dir_all(&self.dir)
+                .map_err(|e| format!("Failed to create archive dir: {e}"))?;
+            write_atomic(&path, data)?;
+        }
+        Ok((sha256, processed))
+    }
+
+    fn record(&self, record: UploadRecord) -> Result<(), String> {
+        let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut index = self.index();
+        index.push(record);
+        let raw = serde_json::to_vec_pretty(&index)
+            .map_err(|e| format!("Failed to serialize archive index: {e}"))?;
+        write_atomic(&self.dir.join(INDEX_FILE), &raw)
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn is_sha256(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit())
 }
 
-async fn upload_skill(
-    mut multipart: Multipart,
-) -> Result<axum::Json<serde_json::Value>, ServerError> {
+fn write_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, data)
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+fn extract_archive(archive: &Path, dest: &Path) -> Result<(), String> {
+    let file = std::fs::File::open(archive).map_err(|e| format!("Failed to open zip: {e}"))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Failed to read zip: {e}"))?;
+    routa_core::archive::extract_zip(&mut zip, dest, || Ok(()))
+}
+
+async fn extract_blocking(archive: PathBuf, dest: PathBuf) -> Result<(), ServerError> {
+    tokio::task::spawn_blocking(move || extract_archive(&archive, &dest))
+        .await
+        .map_err(|e| ServerError::Internal(e.to_string()))?
+        .map_err(|e| ServerError::BadRequest(format!("Failed to extract archive: {e}")))
+}
+
+fn working_dirs() -> (PathBuf, ArchiveStore) {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    (
+        cwd.join(SKILLS_DIR),
+        ArchiveStore::new(cwd.join(ARCHIVES_DIR)),
+    )
+}
+
+async fn upload_skill(mut multipart: Multipart) -> Result<Json<serde_json::Value>, ServerError> {
     let mut file_name = String::new();
     let mut file_data: Option<Vec<u8>> = None;
 
@@ -40,41 +149,142 @@ async fn upload_skill(
         ));
     }
 
-    let cwd = std::env::current_dir().unwrap_or_default();
-    let skills_dir = cwd.join(SKILLS_DIR);
-    std::fs::create_dir_all(&skills_dir)
-        .map_err(|e| ServerError::Internal(format!("Failed to create skills dir: {e}")))?;
-
-    // Write zip to temp file
-    let temp_zip = skills_dir.join(format!("_upload_{}.zip", chrono::Utc::now().timestamp()));
-    std::fs::write(&temp_zip, &data)
-        .map_err(|e| ServerError::Internal(format!("Failed to write zip: {e}")))?;
-
-    // Extract using unzip command
-    let result = tokio::task::spawn_blocking({
-        let zip_path = temp_zip.to_string_lossy().to_string();
-        let dest = skills_dir.to_string_lossy().to_string();
-        move || {
-            std::process::Command::new("unzip")
-                .args(["-o", &zip_path, "-d", &dest])
-                .output()
-        }
-    })
-    .await
-    .map_err(|e| ServerError::Internal(e.to_string()))?;
-
-    // Clean up temp zip
-    let _ = std::fs::remove_file(&temp_zip);
-
-    match result {
-        Ok(output) if output.status.success() => Ok(axum::Json(serde_json::json!({
-            "success": true,
-            "message": format!("Extracted {} to {}/", file_name, SKILLS_DIR),
-        }))),
-        Ok(output) => Err(ServerError::Internal(format!(
-            "Unzip failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ))),
-        Err(e) => Err(ServerError::Internal(format!("Unzip command failed: {e}"))),
+    let (skills_dir, store) = working_dirs();
+    let (sha256, deduplicated) = store.put(&data).map_err(ServerError::Internal)?;
+
+    let message = if deduplicated {
+        format!("{file_name} was already extracted to {SKILLS_DIR}/ (sha256 {sha256})")
+    } else {
+        extract_blocking(store.archive_path(&sha256), skills_dir).await?;
+        format!("Extracted {file_name} to {SKILLS_DIR}/")
+    };
+    let record = UploadRecord {
+        sha256,
+        file_name,
+        size: data.len() as u64,
+        uploaded_at: chrono::Utc::now().to_rfc3339(),
+        deduplicated,
+    };
+    store
+        .record(record.clone())
+        .map_err(ServerError::Internal)?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": message,
+        "sha256": record.sha256,
+        "deduplicated": record.deduplicated,
+    })))
+}
+
+async fn list_uploads() -> Json<serde_json::Value> {
+    let (_, store) = working_dirs();
+    let mut uploads = store.index();
+    uploads.reverse();
+    Json(serde_json::json!({ "uploads": uploads }))
+}
+
+async fn reextract_upload(
+    AxumPath(sha256): AxumPath<String>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let sha256 = sha256.to_ascii_lowercase();
+    if !is_sha256(&sha256) {
+        return Err(ServerError::BadRequest(format!(
+            "Invalid sha256 digest: {sha256}"
+        )));
+    }
+    let (skills_dir, store) = working_dirs();
+    let archive = store.archive_path(&sha256);
+    if !archive.is_file() {
+        return Err(ServerError::NotFound(format!(
+            "No uploaded archive with sha256 {sha256}"
+        )));
+    }
+    extract_blocking(archive, skills_dir).await?;
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": format!("Re-extracted {sha256} to {SKILLS_DIR}/"),
+        "sha256": sha256,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill_zip(body: &str) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer
+            .start_file("demo/SKILL.md", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut writer, body.as_bytes()).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn upload(store: &ArchiveStore, file_name: &str, data: &[u8]) -> UploadRecord {
+        let (sha256, deduplicated) = store.put(data).unwrap();
+        let record = UploadRecord {
+            sha256,
+            file_name: file_name.to_string(),
+            size: data.len() as u64,
+            uploaded_at: chrono::Utc::now().to_rfc3339(),
+            deduplicated,
+        };
+        store.record(record.clone()).unwrap();
+        record
+    }
+
+    #[test]
+    fn repeated_uploads_are_deduplicated_and_recorded() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = ArchiveStore::new(temp.path().join("archives"));
+        let data = skill_zip("# Demo");
+
+        let first = upload(&store, "demo.zip", &data);
+        assert!(!first.deduplicated);
+        assert!(is_sha256(&first.sha256));
+        assert_eq!(
+            std::fs::read(store.archive_path(&first.sha256)).unwrap(),
+            data
+        );
+
+        let second = upload(&store, "demo-copy.zip", &data);
+        assert!(second.deduplicated);
+        assert_eq!(second.sha256, first.sha256);
+
+        let other = upload(&store, "other.zip", &skill_zip("# Other"));
+        assert!(!other.deduplicated);
+        assert_ne!(other.sha256, first.sha256);
+
+        let index = store.index();
+        let names: Vec<_> = index.iter().map(|r| r.file_name.as_str()).collect();
+        assert_eq!(names, ["demo.zip", "demo-copy.zip", "other.zip"]);
+    }
+
+    #[test]
+    fn unrecorded_archive_is_not_treated_as_processed() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = ArchiveStore::new(temp.path().join("archives"));
+        let data = skill_zip("# Demo");
+
+        // Stored, but extraction failed before the upload was recorded.
+        let (_, processed) = store.put(&data).unwrap();
+        assert!(!processed);
+        let (_, processed) = store.put(&data).unwrap();
+        assert!(!processed);
+    }
+
+    #[test]
+    fn stored_archive_extracts_into_skills_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = ArchiveStore::new(temp.path().join("archives"));
+        let record = upload(&store, "demo.zip", &skill_zip("# Demo"));
+
+        let skills = temp.path().join("skills");
+        extract_archive(&store.archive_path(&record.sha256), &skills).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(skills.join("demo/SKILL.md")).unwrap(),
+            "# Demo"
+        );
     }
 }
@@ -125,6 +125,24 @@ pub struct ListTasksQuery {
     pub session_id: Option<String>,
     pub status: Option<String>,
     pub assigned_to: Option<String>,
+    /// Stream tasks as NDJSON instead of a buffered `{ "tasks": [...] }` body.
+    pub stream: Option<bool>,
+    /// Page size; see [`crate::api::pagination`]. Cursors are preferred over
+    /// `offset` for large, active boards.
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+/// Query params for `GET /api/tasks/search`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchTasksQuery {
+    pub q: Option<String>,
+    pub workspace_id: Option<String>,
+    /// Search every workspace instead of only `workspaceId`.
+    pub include_all_workspaces: Option<bool>,
+    pub limit: Option<usize>,
 }
 
 /// Query params for task file change
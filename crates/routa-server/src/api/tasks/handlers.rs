@@ -1,5 +1,7 @@
 use axum::{
     extract::{Query, State},
+    http::HeaderMap,
+    response::{IntoResponse, Response},
     routing::get,
     Json, Router,
 };
@@ -10,14 +12,16 @@ use routa_core::models::artifact::{Artifact, ArtifactType};
 
 use super::changes;
 use super::dto::{
-    CreateTaskArtifactRequest, CreateTaskRequest, ListTasksQuery, UpdateStatusRequest,
-    UpdateTaskRequest,
+    CreateTaskArtifactRequest, CreateTaskRequest, ListTasksQuery, SearchTasksQuery,
+    UpdateStatusRequest, UpdateTaskRequest,
 };
 use super::evidence::{
     build_task_run_ledger, ensure_transition_artifacts, serialize_task_with_evidence,
     serialize_tasks_batch,
 };
 
+use crate::api::ndjson::{ndjson_response, wants_ndjson};
+use crate::api::pagination::{paginate, with_next_cursor_header, PageQuery};
 use crate::api::tasks_automation::{
     auto_create_worktree, resolve_codebase, trigger_assigned_task_agent,
 };
@@ -51,6 +55,7 @@ pub fn router() -> Router<AppState> {
         .route("/{id}/runs", get(list_task_runs))
         .route("/{id}/status", axum::routing::post(update_task_status))
         .route("/ready", get(find_ready_tasks))
+        .route("/search", get(search_tasks))
 }
 
 async fn emit_kanban_workspace_event(
@@ -193,8 +198,9 @@ async fn create_task_artifact(
 
 async fn list_tasks(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Query(query): Query<ListTasksQuery>,
-) -> Result<Json<serde_json::Value>, ServerError> {
+) -> Result<Response, ServerError> {
     let workspace_id = query.workspace_id.as_deref().unwrap_or("default");
 
     let tasks = if let Some(session_id) = &query.session_id {
@@ -212,11 +218,57 @@ async fn list_tasks(
     } else {
         state.task_store.list_by_workspace(workspace_id).await?
     };
+    let page = paginate(
+        tasks,
+        &PageQuery {
+            limit: query.limit,
+            offset: query.offset,
+            cursor: query.cursor.clone(),
+        },
+        |task| (task.created_at, task.id.as_str()),
+    )?;
 
     // Use batch serialization to avoid N+1 queries
+    let serialized_tasks = serialize_tasks_batch(&state, &page.items).await?;
+
+    if wants_ndjson(&headers, query.stream) {
+        let response = ndjson_response(serialized_tasks)?;
+        return Ok(with_next_cursor_header(
+            response,
+            page.next_cursor.as_deref(),
+        ));
+    }
+
+    let mut body = serde_json::json!({ "tasks": serialized_tasks });
+    page.merge_into(&mut body);
+    Ok(Json(body).into_response())
+}
+
+/// GET /api/tasks/search?q=...&workspaceId=...&includeAllWorkspaces=true&limit=...
+async fn search_tasks(
+    State(state): State<AppState>,
+    Query(query): Query<SearchTasksQuery>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let q = query
+        .q
+        .filter(|q| !q.trim().is_empty())
+        .ok_or_else(|| ServerError::BadRequest("Missing q".into()))?;
+    let all_workspaces = query.include_all_workspaces.unwrap_or(false);
+    let workspace_id = query.workspace_id.as_deref().unwrap_or("default");
+    let limit = routa_core::store::clamp_search_limit(query.limit);
+
+    let tasks = state
+        .task_store
+        .search(&q, (!all_workspaces).then_some(workspace_id), limit)
+        .await?;
     let serialized_tasks = serialize_tasks_batch(&state, &tasks).await?;
 
-    Ok(Json(serde_json::json!({ "tasks": serialized_tasks })))
+    Ok(Json(serde_json::json!({
+        "tasks": serialized_tasks,
+        "query": q,
+        "includeAllWorkspaces": all_workspaces,
+        "limit": limit,
+    })))
 }
 
 async fn get_task(
@@ -22,6 +22,7 @@ pub fn router() -> Router<AppState> {
                 .patch(update_workspace),
         )
         .route("/{id}/archive", post(archive_workspace))
+        .route("/{id}/clear", post(clear_workspace))
 }
 
 #[derive(Debug, Deserialize)]
@@ -55,6 +56,7 @@ async fn get_workspace(
         .get(&id)
         .await?
         .ok_or_else(|| ServerError::NotFound(format!("Workspace {id} not found")))?;
+    super::acp_routes::prewarm_workspace(&state, &id);
     let codebases = state
         .codebase_store
         .list_by_workspace(&id)
@@ -155,3 +157,180 @@ async fn delete_workspace(
     state.workspace_store.delete(&id).await?;
     Ok(Json(serde_json::json!({ "deleted": true })))
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClearWorkspaceQuery {
+    /// `tasks`, `notes`, or `all`.
+    scope: Option<String>,
+    confirm: Option<bool>,
+    /// Also delete the workspace's agents. Off by default, even for `all`.
+    include_agents: Option<bool>,
+}
+
+/// Bulk-delete tasks and/or notes in one workspace, keeping the workspace.
+async fn clear_workspace(
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Query(query): Query<ClearWorkspaceQuery>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let (clear_tasks, clear_notes) = match query.scope.as_deref() {
+        Some("tasks") => (true, false),
+        Some("notes") => (false, true),
+        Some("all") => (true, true),
+        Some(other) => {
+            return Err(ServerError::BadRequest(format!(
+                "Invalid scope '{other}'. Expected tasks, notes, or all"
+            )))
+        }
+        None => {
+            return Err(ServerError::BadRequest(
+                "scope is required (tasks, notes, or all)".to_string(),
+            ))
+        }
+    };
+    if query.confirm != Some(true) {
+        return Err(ServerError::BadRequest(
+            "Clearing a workspace requires confirm=true".to_string(),
+        ));
+    }
+
+    state
+        .workspace_store
+        .get(&id)
+        .await?
+        .ok_or_else(|| ServerError::NotFound(format!("Workspace {id} not found")))?;
+
+    let tasks = if clear_tasks {
+        state.task_store.delete_by_workspace(&id).await?
+    } else {
+        0
+    };
+    let notes = if clear_notes {
+        state.note_store.delete_by_workspace(&id).await?
+    } else {
+        0
+    };
+    let agents = if query.include_agents == Some(true) {
+        state.agent_store.delete_by_workspace(&id).await?
+    } else {
+        0
+    };
+
+    Ok(Json(serde_json::json!({
+        "workspaceId": id,
+        "removed": { "tasks": tasks, "notes": notes, "agents": agents },
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use routa_core::models::note::Note;
+    use routa_core::models::task::Task;
+    use routa_core::{AppStateInner, Database};
+    use std::sync::Arc;
+
+    async fn seed(state: &AppState, workspace_id: &str) {
+        state
+            .workspace_store
+            .save(&Workspace::new(
+                workspace_id.to_string(),
+                workspace_id.to_string(),
+                None,
+            ))
+            .await
+            .unwrap();
+        for i in 0..2 {
+            let task = Task::new(
+                format!("{workspace_id}-task-{i}"),
+                "Scratch".to_string(),
+                "Scratch objective".to_string(),
+                workspace_id.to_string(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            state.task_store.save(&task).await.unwrap();
+        }
+        let note = Note::new(
+            format!("{workspace_id}-note"),
+            "Scratch".to_string(),
+            "notes".to_string(),
+            workspace_id.to_string(),
+            None,
+        );
+        state.note_store.save(&note).await.unwrap();
+    }
+
+    fn clear_query(scope: &str, confirm: bool) -> Query<ClearWorkspaceQuery> {
+        Query(ClearWorkspaceQuery {
+            scope: Some(scope.to_string()),
+            confirm: Some(confirm),
+            include_agents: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn clear_workspace_leaves_other_workspaces_untouched() {
+        let db = Database::open_in_memory().expect("in-memory db should open");
+        let state: AppState = Arc::new(AppStateInner::new(db));
+        seed(&state, "ws-a").await;
+        seed(&state, "ws-b").await;
+
+        let unconfirmed = clear_workspace(
+            State(state.clone()),
+            axum::extract::Path("ws-a".to_string()),
+            clear_query("all", false),
+        )
+        .await;
+        assert!(unconfirmed.is_err());
+
+        let Json(body) = clear_workspace(
+            State(state.clone()),
+            axum::extract::Path("ws-a".to_string()),
+            clear_query("all", true),
+        )
+        .await
+        .unwrap();
+        assert_eq!(body["removed"]["tasks"], 2);
+        assert_eq!(body["removed"]["notes"], 1);
+        assert_eq!(body["removed"]["agents"], 0);
+
+        assert!(state
+            .task_store
+            .list_by_workspace("ws-a")
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(state
+            .note_store
+            .list_by_workspace("ws-a")
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            state
+                .task_store
+                .list_by_workspace("ws-b")
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            state
+                .note_store
+                .list_by_workspace("ws-b")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(state.workspace_store.get("ws-a").await.unwrap().is_some());
+    }
+}
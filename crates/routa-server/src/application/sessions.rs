@@ -43,7 +43,11 @@ impl SessionApplicationService {
                 Some(db_session) => entry.merge_db_state(db_session),
                 None => entry,
             };
-            return Ok(entry.to_detail_value());
+            let mut detail = entry.to_detail_value();
+            if let Some(context_size) = self.state.acp_manager.get_context_size(session_id).await {
+                detail["contextSize"] = serde_json::to_value(context_size).unwrap_or(Value::Null);
+            }
+            return Ok(detail);
         }
 
         let db_session =
@@ -762,6 +766,7 @@ mod tests {
             parent_session_id: parent_session_id.map(str::to_string),
             specialist_id: None,
             specialist_system_prompt: None,
+            provider_settings: None,
         }
     }
 
@@ -61,7 +61,7 @@ impl TaskApplicationService {
 
         let workspace_id = workspace_id.unwrap_or_else(|| "default".to_string());
 
-        let mut task = Task::new(
+        let mut task = Task::try_new(
             uuid::Uuid::new_v4().to_string(),
             title,
             objective,
@@ -73,7 +73,7 @@ impl TaskApplicationService {
             test_cases,
             dependencies,
             parallel_group,
-        );
+        )?;
         if task.creation_source.is_none() && task.session_id.is_some() {
             task.creation_source = Some(TaskCreationSource::Session);
         }
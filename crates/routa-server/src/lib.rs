@@ -44,7 +44,9 @@ pub mod feature_tree;
 // ── Server bootstrap ────────────────────────────────────────────────────
 
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 use axum::Router;
 use tower_http::cors::{Any, CorsLayer};
@@ -76,9 +78,13 @@ impl Default for ServerConfig {
 /// This is useful when you need to share the state between the HTTP server
 /// and other consumers (e.g. Tauri IPC commands, JSON-RPC router).
 pub async fn create_app_state(db_path: &str) -> Result<state::AppState, String> {
+    let settings = routa_core::settings::Settings::from_env();
+    // Fail fast when strict mode forbids the ACP data-dir fallback.
+    acp::AcpPaths::try_from_settings(&settings.acp)?;
+
     let db = db::Database::open(db_path).map_err(|e| format!("Failed to open database: {e}"))?;
 
-    let state: state::AppState = Arc::new(state::AppStateInner::new(db));
+    let state: state::AppState = Arc::new(state::AppStateInner::with_settings(db, settings));
 
     // Ensure default workspace exists
     state
@@ -280,6 +286,9 @@ pub async fn start_server_with_state(
         format!("http://{}:{}", config.host, config.port),
     );
 
+    api::acp_routes::spawn_session_sweeper(&state);
+    api::acp_routes::prewarm_workspace(&state, "default");
+
     // Build router
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -289,6 +298,7 @@ pub async fn start_server_with_state(
     let mut app = Router::new()
         .merge(api::api_router(state.clone()))
         .route("/api/health", axum::routing::get(health_check))
+        .route("/healthz", axum::routing::get(healthz))
         .layer(cors.clone())
         .layer(TraceLayer::new_for_http())
         .with_state(state);
@@ -419,6 +429,111 @@ pub async fn start_server_with_state(
     Ok(local_addr)
 }
 
+/// A named runtime self-check reported by `/healthz`. Returns `(ok, detail)`
+/// and may block; probes run on the blocking pool.
+pub type HealthProbe = fn() -> (bool, String);
+
+/// How long `/healthz` reuses probe results, so load-balancer polling does
+/// not spawn a process per request.
+const HEALTH_PROBE_TTL: Duration = Duration::from_secs(60);
+
+type HealthChecks = serde_json::Map<String, serde_json::Value>;
+
+/// Probe results from the last `/healthz` run that actually probed.
+struct CachedHealth {
+    /// `PROBE_GENERATION` when the probes ran; registering a probe bumps it.
+    generation: usize,
+    checked_at: Instant,
+    checked_at_utc: chrono::DateTime<chrono::Utc>,
+    checks: HealthChecks,
+}
+
+static PROBE_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+fn health_probes() -> &'static Mutex<Vec<(&'static str, HealthProbe)>> {
+    static PROBES: OnceLock<Mutex<Vec<(&'static str, HealthProbe)>>> = OnceLock::new();
+    PROBES.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn health_cache() -> &'static tokio::sync::Mutex<Option<CachedHealth>> {
+    static CACHE: OnceLock<tokio::sync::Mutex<Option<CachedHealth>>> = OnceLock::new();
+    CACHE.get_or_init(|| tokio::sync::Mutex::new(None))
+}
+
+/// Register a probe for `/healthz`, replacing any existing probe with the
+/// same name. Embedders use this for subsystems the server crate does not
+/// own (e.g. the desktop PTY support).
+pub fn register_health_probe(name: &'static str, probe: HealthProbe) {
+    let mut probes = health_probes().lock().unwrap_or_else(|e| e.into_inner());
+    probes.retain(|(existing, _)| *existing != name);
+    probes.push((name, probe));
+    PROBE_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Run every registered probe. A probe that panics is reported as failed.
+async fn run_health_probes() -> HealthChecks {
+    let probes = health_probes()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    let mut checks = HealthChecks::new();
+    for (name, probe) in probes {
+        let check = match tokio::task::spawn_blocking(probe).await {
+            Ok((ok, detail)) => serde_json::json!({ "ok": ok, "detail": detail }),
+            Err(error) => serde_json::json!({
+                "ok": false,
+                "detail": format!("Probe did not complete: {error}"),
+            }),
+        };
+        checks.insert(name.to_string(), check);
+    }
+    checks
+}
+
+async fn healthz() -> (axum::http::StatusCode, axum::Json<serde_json::Value>) {
+    // Holding the lock while probing means concurrent requests wait for one
+    // run instead of starting their own.
+    let mut cache = health_cache().lock().await;
+    let generation = PROBE_GENERATION.load(Ordering::SeqCst);
+    let cached = match cache.take() {
+        Some(cached)
+            if cached.generation == generation
+                && cached.checked_at.elapsed() < HEALTH_PROBE_TTL =>
+        {
+            cached
+        }
+        _ => {
+            let checks = run_health_probes().await;
+            CachedHealth {
+                generation,
+                checked_at: Instant::now(),
+                checked_at_utc: chrono::Utc::now(),
+                checks,
+            }
+        }
+    };
+    let cached = cache.insert(cached);
+
+    let healthy = cached
+        .checks
+        .values()
+        .all(|check| check["ok"].as_bool().unwrap_or(false));
+    let status = if healthy {
+        axum::http::StatusCode::OK
+    } else {
+        axum::http::StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        axum::Json(serde_json::json!({
+            "status": if healthy { "ok" } else { "degraded" },
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "checkedAt": cached.checked_at_utc.to_rfc3339(),
+            "checks": cached.checks,
+        })),
+    )
+}
+
 async fn health_check() -> axum::Json<serde_json::Value> {
     axum::Json(serde_json::json!({
         "status": "ok",
@@ -430,7 +545,8 @@ async fn health_check() -> axum::Json<serde_json::Value> {
 
 #[cfg(test)]
 mod tests {
-    use super::resolve_static_target;
+    use super::{healthz, register_health_probe, resolve_static_target};
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[test]
     fn resolves_workspace_overview_placeholder() {
@@ -532,4 +648,34 @@ mod tests {
         assert_eq!(target, "canvas/__placeholder__/__next._tree.txt");
         assert_eq!(content_type, "text/x-component; charset=utf-8");
     }
+
+    #[tokio::test]
+    async fn healthz_caches_probes_and_fails_panicked_ones() {
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+        fn counting_probe() -> (bool, String) {
+            RUNS.fetch_add(1, Ordering::SeqCst);
+            (true, "counted".to_string())
+        }
+        fn panicking_probe() -> (bool, String) {
+            panic!("probe blew up");
+        }
+
+        register_health_probe("test-counting", counting_probe);
+        let (status, _) = healthz().await;
+        assert_eq!(status, axum::http::StatusCode::OK);
+        let (status, body) = healthz().await;
+        assert_eq!(status, axum::http::StatusCode::OK);
+        assert_eq!(body.0["checks"]["test-counting"]["ok"], true);
+        assert_eq!(
+            RUNS.load(Ordering::SeqCst),
+            1,
+            "second hit should be cached"
+        );
+
+        register_health_probe("test-panicking", panicking_probe);
+        let (status, body) = healthz().await;
+        assert_eq!(status, axum::http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body.0["checks"]["test-panicking"]["ok"], false);
+        assert_eq!(RUNS.load(Ordering::SeqCst), 2);
+    }
 }
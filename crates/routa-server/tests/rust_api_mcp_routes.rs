@@ -646,3 +646,569 @@ async fn api_mcp_kanban_profile_blocks_update_task_workflow_metadata() {
     assert_ne!(get_body["task"]["status"], json!("COMPLETED"));
     assert!(get_body["task"]["verificationVerdict"].is_null());
 }
+
+#[tokio::test]
+async fn api_mcp_batch_dispatches_each_request_and_skips_notifications() {
+    let fixture = ApiFixture::new().await;
+    let (session_id, _) = fixture.initialize_session(None).await;
+    fixture.complete_initialization(None, &session_id).await;
+
+    let response = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            json!([
+                {
+                    "jsonrpc": "2.0",
+                    "id": "batch-list",
+                    "method": "tools/list",
+                    "params": {}
+                },
+                {
+                    "jsonrpc": "2.0",
+                    "method": "notifications/cancelled",
+                    "params": { "requestId": "unknown", "reason": "test" }
+                },
+                {
+                    "jsonrpc": "2.0",
+                    "id": 7,
+                    "method": "tools/call",
+                    "params": { "name": "list_workspaces", "arguments": {} }
+                }
+            ]),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = read_json(response, "batch response").await;
+    let responses = body.as_array().expect("batch should return an array");
+    assert_eq!(responses.len(), 2, "notifications get no element: {body}");
+
+    let list = responses
+        .iter()
+        .find(|message| message["id"] == json!("batch-list"))
+        .expect("tools/list response");
+    assert!(list["result"]["tools"]
+        .as_array()
+        .is_some_and(|tools| !tools.is_empty()));
+
+    let call = responses
+        .iter()
+        .find(|message| message["id"] == json!(7))
+        .expect("tools/call response");
+    assert_eq!(call["result"]["isError"], json!(false), "{call}");
+}
+
+#[tokio::test]
+async fn api_mcp_state_changes_are_pushed_to_other_sessions_get_stream() {
+    let fixture = ApiFixture::new().await;
+    let (watcher, _) = fixture.initialize_session(None).await;
+    fixture.complete_initialization(None, &watcher).await;
+    let (actor, _) = fixture.initialize_session(None).await;
+    fixture.complete_initialization(None, &actor).await;
+
+    let mut stream = fixture.get_mcp(Some(&watcher)).await;
+    assert_eq!(stream.status(), StatusCode::OK);
+
+    let response = fixture
+        .post_mcp(
+            None,
+            Some(&actor),
+            json!({
+                "jsonrpc": "2.0",
+                "id": "create",
+                "method": "tools/call",
+                "params": {
+                    "name": "create_task",
+                    "arguments": { "title": "Live", "objective": "Show up on the dashboard" }
+                }
+            }),
+        )
+        .await;
+    let call = read_first_sse_json(response, "create_task response").await;
+    assert_eq!(call["result"]["isError"], json!(false), "{call}");
+
+    let mut received = String::new();
+    let notification = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        loop {
+            let chunk = stream
+                .chunk()
+                .await
+                .expect("read GET stream")
+                .expect("GET stream should stay open");
+            received.push_str(&String::from_utf8_lossy(&chunk));
+            if let Some(event) = received
+                .split_inclusive("\n\n")
+                .find(|event| event.ends_with("\n\n") && event.contains("routa.state"))
+            {
+                return first_sse_json(event, "state notification");
+            }
+        }
+    })
+    .await
+    .expect("state notification should arrive on the GET stream");
+
+    assert_eq!(notification["method"], json!("notifications/message"));
+    assert_eq!(notification["params"]["data"]["tool"], json!("create_task"));
+    assert_eq!(
+        notification["params"]["data"]["workspaceId"],
+        json!("default")
+    );
+}
+
+#[tokio::test]
+async fn api_mcp_exposes_notes_as_resources() {
+    let fixture = ApiFixture::new().await;
+    let (session_id, initialize_json) = fixture.initialize_session(None).await;
+    assert_eq!(
+        initialize_json["result"]["capabilities"]["resources"],
+        json!({ "listChanged": false })
+    );
+    fixture.complete_initialization(None, &session_id).await;
+
+    let create = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            json!({
+                "jsonrpc": "2.0",
+                "id": "create-note",
+                "method": "tools/call",
+                "params": {
+                    "name": "create_note",
+                    "arguments": { "noteId": "design", "title": "Design", "content": "# Plan" }
+                }
+            }),
+        )
+        .await;
+    let created = read_first_sse_json(create, "create_note response").await;
+    assert_eq!(created["result"]["isError"], json!(false), "{created}");
+
+    let list = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            json!({ "jsonrpc": "2.0", "id": "list", "method": "resources/list", "params": {} }),
+        )
+        .await;
+    let listed = read_first_sse_json(list, "resources/list response").await;
+    let resources = listed["result"]["resources"]
+        .as_array()
+        .expect("resources array");
+    assert!(
+        resources
+            .iter()
+            .any(|resource| resource["uri"] == json!("routa://note/design")
+                && resource["name"] == json!("Design")),
+        "{listed}"
+    );
+
+    let read = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            json!({
+                "jsonrpc": "2.0",
+                "id": "read",
+                "method": "resources/read",
+                "params": { "uri": "routa://note/design" }
+            }),
+        )
+        .await;
+    let read = read_first_sse_json(read, "resources/read response").await;
+    assert_eq!(read["result"]["contents"][0]["text"], json!("# Plan"));
+
+    let malformed = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            json!({
+                "jsonrpc": "2.0",
+                "id": "bad",
+                "method": "resources/read",
+                "params": { "uri": "routa://note/design/../x" }
+            }),
+        )
+        .await;
+    let malformed = read_first_sse_json(malformed, "malformed resources/read").await;
+    assert_eq!(malformed["error"]["code"], json!(-32602), "{malformed}");
+}
+
+#[tokio::test]
+async fn api_mcp_initialize_scopes_session_to_workspace_param() {
+    let fixture = ApiFixture::new().await;
+    let create_workspace = fixture
+        .client
+        .post(fixture.endpoint("/api/workspaces"))
+        .json(&json!({ "title": "MCP scoped workspace" }))
+        .send()
+        .await
+        .expect("create workspace");
+    assert_eq!(create_workspace.status(), StatusCode::OK);
+    let workspace_id = read_json(create_workspace, "create workspace").await["workspace"]["id"]
+        .as_str()
+        .expect("workspace id")
+        .to_string();
+
+    let initialize = |workspace_id: String| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": "init",
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": {},
+                "clientInfo": { "name": "routa-server-test", "version": "1.0.0" },
+                "workspaceId": workspace_id
+            }
+        })
+    };
+
+    let missing = fixture
+        .post_mcp(None, None, initialize("no-such-workspace".to_string()))
+        .await;
+    let missing = read_text(missing, "initialize with unknown workspace").await;
+    assert!(
+        missing.contains("Workspace not found: no-such-workspace"),
+        "{missing}"
+    );
+
+    let response = fixture
+        .post_mcp(None, None, initialize(workspace_id.clone()))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let session_id = response
+        .headers()
+        .get("mcp-session-id")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .expect("initialize response should include mcp-session-id");
+    let initialized = read_first_sse_json(response, "initialize response").await;
+    assert!(initialized.get("result").is_some(), "{initialized}");
+    fixture.complete_initialization(None, &session_id).await;
+
+    let create = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            json!({
+                "jsonrpc": "2.0",
+                "id": "create-note",
+                "method": "tools/call",
+                "params": {
+                    "name": "create_note",
+                    "arguments": { "noteId": "scoped", "title": "Scoped", "content": "x" }
+                }
+            }),
+        )
+        .await;
+    let created = read_first_sse_json(create, "create_note response").await;
+    assert_eq!(created["result"]["isError"], json!(false), "{created}");
+
+    let list_notes = |workspace_id: &str| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": "list-notes",
+            "method": "tools/call",
+            "params": {
+                "name": "list_notes",
+                "arguments": { "workspaceId": workspace_id }
+            }
+        })
+    };
+    let scoped = fixture
+        .post_mcp(None, Some(&session_id), list_notes(&workspace_id))
+        .await;
+    let scoped = read_first_sse_json(scoped, "list_notes in session workspace").await;
+    assert!(
+        scoped["result"]["content"][0]["text"]
+            .as_str()
+            .is_some_and(|text| text.contains("\"scoped\"")),
+        "{scoped}"
+    );
+    let default = fixture
+        .post_mcp(None, Some(&session_id), list_notes("default"))
+        .await;
+    let default = read_first_sse_json(default, "list_notes in default workspace").await;
+    assert!(
+        default["result"]["content"][0]["text"]
+            .as_str()
+            .is_some_and(|text| !text.contains("\"scoped\"")),
+        "{default}"
+    );
+}
+
+#[tokio::test]
+async fn api_mcp_workspace_branch_tools_checkout_and_record_branch() {
+    let fixture = ApiFixture::new().await;
+    let temp = tempfile::tempdir().expect("tempdir");
+    let repo = temp.path().join("repo");
+    std::fs::create_dir_all(&repo).expect("repo dir");
+    for args in [
+        &["init", "-b", "main"][..],
+        &["config", "user.name", "Routa Test"],
+        &["config", "user.email", "routa-test@example.com"],
+        &["commit", "--allow-empty", "-m", "initial"],
+        &["branch", "feature/pinned"],
+    ] {
+        let output = std::process::Command::new("git")
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .current_dir(&repo)
+            .output()
+            .expect("run git");
+        assert!(output.status.success(), "git {args:?} failed");
+    }
+
+    let create_workspace = fixture
+        .client
+        .post(fixture.endpoint("/api/workspaces"))
+        .json(&json!({ "title": "Branch workspace" }))
+        .send()
+        .await
+        .expect("create workspace");
+    let workspace_id = read_json(create_workspace, "create workspace").await["workspace"]["id"]
+        .as_str()
+        .expect("workspace id")
+        .to_string();
+    let add_codebase = fixture
+        .client
+        .post(fixture.endpoint(&format!("/api/workspaces/{workspace_id}/codebases")))
+        .json(&json!({ "repoPath": repo.to_string_lossy(), "branch": "main" }))
+        .send()
+        .await
+        .expect("add codebase");
+    assert_eq!(add_codebase.status(), StatusCode::CREATED);
+
+    let (session_id, _) = fixture.initialize_session(None).await;
+    fixture.complete_initialization(None, &session_id).await;
+    let call = |id: &str, name: &str, arguments: Value| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments }
+        })
+    };
+    let tool_json = |body: &Value| -> Value {
+        serde_json::from_str(
+            body["result"]["content"][0]["text"]
+                .as_str()
+                .unwrap_or_else(|| panic!("tool text payload: {body}")),
+        )
+        .expect("parse tool payload")
+    };
+
+    let set = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            call(
+                "set-branch",
+                "set_workspace_branch",
+                json!({ "workspaceId": workspace_id, "branch": "feature/pinned" }),
+            ),
+        )
+        .await;
+    let set = read_first_sse_json(set, "set_workspace_branch response").await;
+    assert_eq!(set["result"]["isError"], json!(false), "{set}");
+    let set = tool_json(&set);
+    assert_eq!(set["activeBranch"], "feature/pinned");
+    assert_eq!(set["currentBranch"], "feature/pinned");
+
+    let get = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            call(
+                "get-branch",
+                "get_workspace_branch",
+                json!({ "workspaceId": workspace_id }),
+            ),
+        )
+        .await;
+    let get = tool_json(&read_first_sse_json(get, "get_workspace_branch response").await);
+    assert_eq!(get["activeBranch"], "feature/pinned");
+    assert_eq!(get["inSync"], true);
+
+    let missing = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            call(
+                "set-missing-branch",
+                "set_workspace_branch",
+                json!({ "workspaceId": workspace_id, "branch": "no-such-branch" }),
+            ),
+        )
+        .await;
+    let missing = read_first_sse_json(missing, "set_workspace_branch missing").await;
+    assert_eq!(missing["result"]["isError"], json!(true), "{missing}");
+}
+
+#[tokio::test]
+async fn api_mcp_workspace_settings_tools_update_and_resolve_settings() {
+    let fixture = ApiFixture::new().await;
+    let temp = tempfile::tempdir().expect("tempdir");
+    let cwd = temp.path().to_string_lossy().to_string();
+
+    let create_workspace = fixture
+        .client
+        .post(fixture.endpoint("/api/workspaces"))
+        .json(&json!({ "title": "Settings workspace" }))
+        .send()
+        .await
+        .expect("create workspace");
+    let workspace_id = read_json(create_workspace, "create workspace").await["workspace"]["id"]
+        .as_str()
+        .expect("workspace id")
+        .to_string();
+
+    let (session_id, _) = fixture.initialize_session(None).await;
+    fixture.complete_initialization(None, &session_id).await;
+    let call = |id: &str, name: &str, arguments: Value| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments }
+        })
+    };
+    let tool_json = |body: &Value| -> Value {
+        serde_json::from_str(
+            body["result"]["content"][0]["text"]
+                .as_str()
+                .unwrap_or_else(|| panic!("tool text payload: {body}")),
+        )
+        .expect("parse tool payload")
+    };
+
+    let update = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            call(
+                "update-workspace",
+                "update_workspace",
+                json!({
+                    "workspaceId": workspace_id,
+                    "title": "Renamed",
+                    "settings": {
+                        "defaultCwd": cwd,
+                        "defaultProvider": "claude",
+                        "env": { "RUST_LOG": "debug" }
+                    }
+                }),
+            ),
+        )
+        .await;
+    let update = read_first_sse_json(update, "update_workspace response").await;
+    assert_eq!(update["result"]["isError"], json!(false), "{update}");
+    let update = tool_json(&update);
+    assert_eq!(update["title"], "Renamed");
+    assert_eq!(update["defaultCwd"], json!(cwd));
+    assert_eq!(update["env"], json!({ "RUST_LOG": "debug" }));
+
+    let clear = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            call(
+                "clear-provider",
+                "update_workspace",
+                json!({ "workspaceId": workspace_id, "settings": { "defaultProvider": null } }),
+            ),
+        )
+        .await;
+    let clear = read_first_sse_json(clear, "update_workspace clear").await;
+    assert_eq!(clear["result"]["isError"], json!(false), "{clear}");
+
+    let get = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            call(
+                "get-workspace",
+                "get_workspace",
+                json!({ "workspaceId": workspace_id }),
+            ),
+        )
+        .await;
+    let get = tool_json(&read_first_sse_json(get, "get_workspace response").await);
+    assert_eq!(get["title"], "Renamed");
+    assert_eq!(get["defaultCwd"], json!(cwd));
+    assert_eq!(get["defaultProvider"], Value::Null);
+    assert_eq!(get["env"]["RUST_LOG"], "debug");
+    assert_eq!(get["activeBranch"], Value::Null);
+
+    let invalid = fixture
+        .post_mcp(
+            None,
+            Some(&session_id),
+            call(
+                "invalid-settings",
+                "update_workspace",
+                json!({
+                    "workspaceId": workspace_id,
+                    "settings": { "defaultCwd": "relative", "theme": "dark" }
+                }),
+            ),
+        )
+        .await;
+    let invalid = read_first_sse_json(invalid, "update_workspace invalid").await;
+    assert_eq!(invalid["error"]["code"], json!(-32602), "{invalid}");
+}
+
+#[tokio::test]
+async fn api_mcp_git_status_reports_structured_status() {
+    let fixture = ApiFixture::new().await;
+    let temp = tempfile::tempdir().expect("tempdir");
+    let repo = temp.path().join("repo");
+    let plain = temp.path().join("plain");
+    std::fs::create_dir_all(&repo).expect("repo dir");
+    std::fs::create_dir_all(&plain).expect("plain dir");
+    let output = std::process::Command::new("git")
+        .args(["init", "-b", "main"])
+        .current_dir(&repo)
+        .output()
+        .expect("git init");
+    assert!(output.status.success());
+    std::fs::write(repo.join("notes.txt"), "draft\n").expect("write file");
+
+    let (session_id, _) = fixture.initialize_session(None).await;
+    fixture.complete_initialization(None, &session_id).await;
+    let git_status = |id: &str, path: &std::path::Path| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": {
+                "name": "git_status",
+                "arguments": { "repoPath": path.to_string_lossy() }
+            }
+        })
+    };
+
+    let response = fixture
+        .post_mcp(None, Some(&session_id), git_status("status", &repo))
+        .await;
+    let body = read_first_sse_json(response, "git_status response").await;
+    assert_eq!(body["result"]["isError"], json!(false), "{body}");
+    let status: Value = serde_json::from_str(
+        body["result"]["content"][0]["text"]
+            .as_str()
+            .expect("git_status text payload"),
+    )
+    .expect("parse git_status payload");
+    assert_eq!(status["branch"], "main");
+    assert_eq!(status["untracked"], json!(["notes.txt"]));
+    assert_eq!(status["staged"], json!([]));
+    assert_eq!(status["dirty"], true);
+
+    let response = fixture
+        .post_mcp(None, Some(&session_id), git_status("not-git", &plain))
+        .await;
+    let body = read_first_sse_json(response, "git_status on plain dir").await;
+    assert_eq!(body["result"]["isError"], json!(true), "{body}");
+    assert!(body.to_string().contains("not a git repository"), "{body}");
+}
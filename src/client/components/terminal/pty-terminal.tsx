@@ -27,6 +27,10 @@ interface PtyTerminalProps {
   cwd?: string;
   /** Environment variables */
   env?: Record<string, string>;
+  /** Session label shown in terminal lists (default: the command) */
+  label?: string;
+  /** Bytes of output kept for repainting after a reload (default: 256 KiB) */
+  scrollbackBytes?: number;
   /** Callback when terminal exits */
   onExit?: (exitCode: number) => void;
   /** Initial rows (default: 24) */
@@ -66,7 +70,9 @@ export function PtyTerminal({
   args,
   cwd,
   env,
-  onExit: _onExit,
+  label,
+  scrollbackBytes,
+  onExit,
   rows = 24,
   cols = 80,
 }: PtyTerminalProps) {
@@ -74,8 +80,10 @@ export function PtyTerminal({
   const terminalRef = useRef<XTerminal | null>(null);
   const fitAddonRef = useRef<XFitAddon | null>(null);
   const sessionIdRef = useRef<string | null>(null);
-  const readLoopRef = useRef<number | null>(null);
+  const unlistenOutputRef = useRef<(() => void) | null>(null);
+  const unlistenExitRef = useRef<(() => void) | null>(null);
   const [initialized, setInitialized] = useState(false);
+  const [exitCode, setExitCode] = useState<number | null>(null);
   const [error, setError] = useState<string | null>(null);
 
   // Check if we're in Tauri
@@ -123,6 +131,8 @@ export function PtyTerminal({
         env,
         rows,
         cols,
+        label,
+        scrollbackBytes,
       });
       sessionIdRef.current = sessionId;
 
@@ -147,28 +157,46 @@ export function PtyTerminal({
         }
       });
 
-      // Start read loop
-      const readLoop = async () => {
-        if (!sessionIdRef.current) return;
-        try {
-          const data = await bridge.invoke<string | null>("pty_read", {
-            sessionId: sessionIdRef.current,
-          });
-          if (data) {
-            terminal.write(data);
+      // PTY output is pushed as events; subscribing replays what the shell
+      // printed before we started listening.
+      unlistenOutputRef.current = bridge.events.listen(
+        `pty://${sessionId}/data`,
+        (payload) => {
+          if (typeof payload === "string") {
+            terminal.write(payload);
           }
-        } catch {
-          // Session may have ended
-        }
-        readLoopRef.current = requestAnimationFrame(readLoop);
-      };
-      readLoopRef.current = requestAnimationFrame(readLoop);
+        },
+      );
+      await bridge.invoke("pty_subscribe", { sessionId });
+
+      // The backend drops the session once its process exits.
+      unlistenExitRef.current = bridge.events.listen(
+        `pty://${sessionId}/exit`,
+        (payload) => {
+          const code = (payload as { exitCode?: number } | null)?.exitCode ?? 0;
+          sessionIdRef.current = null;
+          terminal.write(`\r\n[Process exited (code ${code})]\r\n`);
+          setExitCode(code);
+          onExit?.(code);
+        },
+      );
 
       setInitialized(true);
     } catch (err) {
       setError(err instanceof Error ? err.message : String(err));
     }
-  }, [command, args, cwd, env, isTauri, rows, cols]);
+  }, [command, args, cwd, env, label, scrollbackBytes, isTauri, rows, cols, onExit]);
+
+  const restart = useCallback(() => {
+    unlistenOutputRef.current?.();
+    unlistenExitRef.current?.();
+    terminalRef.current?.dispose();
+    terminalRef.current = null;
+    fitAddonRef.current = null;
+    setExitCode(null);
+    setInitialized(false);
+    void initTerminal();
+  }, [initTerminal]);
 
   // Initialize on mount
   useEffect(() => {
@@ -179,9 +207,8 @@ export function PtyTerminal({
   // Cleanup on unmount
   useEffect(() => {
     return () => {
-      if (readLoopRef.current) {
-        cancelAnimationFrame(readLoopRef.current);
-      }
+      unlistenOutputRef.current?.();
+      unlistenExitRef.current?.();
       if (sessionIdRef.current && isTauri) {
         const bridge = getPlatformBridge();
         bridge.invoke("pty_kill", { sessionId: sessionIdRef.current }).catch(() => {});
@@ -232,13 +259,30 @@ export function PtyTerminal({
       {/* Header */}
       <div className="px-3 py-1.5 bg-[#161b22] border-b border-gray-700 flex items-center gap-2">
         <TerminalIcon1 className="w-3.5 h-3.5 text-green-400 shrink-0" fill="none" viewBox="0 0 24 24" stroke="currentColor" strokeWidth={2}/>
-        <span className="w-1.5 h-1.5 rounded-full bg-green-500 animate-pulse shrink-0" />
+        <span
+          className={`w-1.5 h-1.5 rounded-full shrink-0 ${
+            exitCode === null ? "bg-green-500 animate-pulse" : "bg-gray-500"
+          }`}
+        />
         <span className="text-xs font-mono text-gray-300 truncate flex-1">
           {command || "Terminal"} {args?.join(" ") || ""}
         </span>
         <span className="text-[10px] text-gray-500 shrink-0">
-          {initialized ? "connected" : "connecting..."}
+          {exitCode !== null
+            ? `Process exited (code ${exitCode})`
+            : initialized
+              ? "connected"
+              : "connecting..."}
         </span>
+        {exitCode !== null && (
+          <button
+            type="button"
+            onClick={restart}
+            className="text-[10px] px-1.5 py-0.5 rounded border border-gray-600 text-gray-300 hover:bg-gray-700 shrink-0"
+          >
+            Restart
+          </button>
+        )}
       </div>
 
       {/* Terminal container */}